@@ -195,6 +195,7 @@ pub fn create_pool_instr(
 
 pub fn open_position_instr(
     config: &ClientConfig,
+    amm_config: Pubkey,
     pool_account_key: Pubkey,
     token_vault_0: Pubkey,
     token_vault_1: Pubkey,
@@ -267,6 +268,7 @@ pub fn open_position_instr(
             position_nft_account: nft_ata_token_account,
             metadata_account: metadata_account_key,
             pool_state: pool_account_key,
+            amm_config,
             protocol_position: protocol_position_key,
             tick_array_lower,
             tick_array_upper,
@@ -295,6 +297,7 @@ pub fn open_position_instr(
             tick_array_upper_start_index,
             with_metadata,
             base_flag: None,
+            deadline: None,
         })
         .instructions()?;
     Ok(instructions)
@@ -302,6 +305,7 @@ pub fn open_position_instr(
 
 pub fn open_position_with_token22_nft_instr(
     config: &ClientConfig,
+    amm_config: Pubkey,
     pool_account_key: Pubkey,
     token_vault_0: Pubkey,
     token_vault_1: Pubkey,
@@ -369,6 +373,7 @@ pub fn open_position_with_token22_nft_instr(
             position_nft_mint: nft_mint_key,
             position_nft_account: nft_ata_token_account,
             pool_state: pool_account_key,
+            amm_config,
             protocol_position: protocol_position_key,
             tick_array_lower,
             tick_array_upper,
@@ -403,6 +408,7 @@ pub fn open_position_with_token22_nft_instr(
 
 pub fn increase_liquidity_instr(
     config: &ClientConfig,
+    amm_config: Pubkey,
     pool_account_key: Pubkey,
     token_vault_0: Pubkey,
     token_vault_1: Pubkey,
@@ -463,6 +469,7 @@ pub fn increase_liquidity_instr(
             nft_owner: program.payer(),
             nft_account: nft_ata_token_account,
             pool_state: pool_account_key,
+            amm_config,
             protocol_position: protocol_position_key,
             personal_position: personal_position_key,
             tick_array_lower,
@@ -482,6 +489,7 @@ pub fn increase_liquidity_instr(
             amount_0_max,
             amount_1_max,
             base_flag: None,
+            deadline: None,
         })
         .instructions()?;
     Ok(instructions)
@@ -567,6 +575,7 @@ pub fn decrease_liquidity_instr(
             liquidity,
             amount_0_min,
             amount_1_min,
+            deadline: None,
         })
         .instructions()?;
     Ok(instructions)
@@ -617,6 +626,7 @@ pub fn swap_instr(
     other_amount_threshold: u64,
     sqrt_price_limit_x64: Option<u128>,
     is_base_input: bool,
+    deadline: u64,
 ) -> Result<Vec<Instruction>> {
     let payer = read_keypair_file(&config.payer_path)?;
     let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
@@ -643,6 +653,7 @@ pub fn swap_instr(
             other_amount_threshold,
             sqrt_price_limit_x64: sqrt_price_limit_x64.unwrap_or(0u128),
             is_base_input,
+            deadline,
         })
         .instructions()?;
     Ok(instructions)
@@ -693,6 +704,7 @@ pub fn swap_v2_instr(
             other_amount_threshold,
             sqrt_price_limit_x64: sqrt_price_limit_x64.unwrap_or(0u128),
             is_base_input,
+            deadline: None,
         })
         .instructions()?;
     Ok(instructions)
@@ -142,6 +142,12 @@ pub fn handle_program_log(
             ConfigChangeEvent::DISCRIMINATOR => {
                 println!("{:#?}", decode_event::<ConfigChangeEvent>(&mut slice)?);
             }
+            ClosePersonalPositionEvent::DISCRIMINATOR => {
+                println!(
+                    "{:#?}",
+                    decode_event::<ClosePersonalPositionEvent>(&mut slice)?
+                );
+            }
             CollectPersonalFeeEvent::DISCRIMINATOR => {
                 println!(
                     "{:#?}",
@@ -1200,6 +1200,7 @@ fn main() -> Result<()> {
                 instructions.push(request_inits_instr);
                 let open_position_instr = open_position_with_token22_nft_instr(
                     &pool_config.clone(),
+                    pool.amm_config,
                     pool_config.pool_id_account.unwrap(),
                     pool.token_vault_0,
                     pool.token_vault_1,
@@ -1383,6 +1384,7 @@ fn main() -> Result<()> {
 
                 let increase_instr = increase_liquidity_instr(
                     &pool_config.clone(),
+                    pool.amm_config,
                     pool_config.pool_id_account.unwrap(),
                     pool.token_vault_0,
                     pool.token_vault_1,
@@ -1733,6 +1735,11 @@ fn main() -> Result<()> {
                 other_amount_threshold,
                 sqrt_price_limit_x64,
                 base_in,
+                // reject the swap if it lands more than a minute from now
+                std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)?
+                    .as_secs()
+                    + 60,
             )
             .unwrap();
             instructions.extend(swap_instr);
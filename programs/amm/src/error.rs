@@ -105,4 +105,110 @@ pub enum ErrorCode {
     MaxTokenOverflow,
     #[msg("calculate overflow")]
     CalculateOverflow,
+    #[msg("Invalid custom trade fee rate")]
+    InvalidCustomTradeFeeRate,
+    #[msg("Invalid quote orientation")]
+    InvalidQuoteOrientation,
+    #[msg("Unlock time must be later than the current unlock time")]
+    InvalidUnlockTime,
+    #[msg("Position is still locked")]
+    PositionStillLocked,
+    #[msg("Invalid fee distributor param")]
+    InvalidFeeDistributorParam,
+    #[msg("Position nft mint must have supply 1, decimals 0 and no authorities")]
+    InvalidPositionNftMint,
+    #[msg("Token program does not own the mint")]
+    InvalidTokenProgram,
+    #[msg("Pool vault is invalid")]
+    InvalidPoolVault,
+    #[msg("Hop amount out minimums must have one entry per hop")]
+    InvalidHopAmountOutMinimums,
+    #[msg("Invalid stop order param")]
+    InvalidStopOrderParam,
+    #[msg("Stop order is not triggered")]
+    StopOrderNotTriggered,
+    #[msg("Invalid position ladder param")]
+    InvalidLadderParam,
+    #[msg("Invalid protocol fee conversion param")]
+    InvalidConversionParam,
+    #[msg("Not enough observations to cover the twap window")]
+    ObservationNotEnough,
+    #[msg("Invalid buyback param")]
+    InvalidBuybackParam,
+    #[msg("Buyback is not due yet")]
+    BuybackNotDue,
+    #[msg("Protocol fee rate out of bounds")]
+    InvalidProtocolFeeRate,
+    #[msg("Protocol fee rate changed too much or too soon")]
+    ProtocolFeeRateStepExceeded,
+    #[msg("Bundle index out of range or in the wrong open state")]
+    InvalidBundleIndex,
+    #[msg("Close all bundled positions before deleting the bundle")]
+    PositionBundleNotEmpty,
+    #[msg("Invalid bundle rebalance template")]
+    InvalidRebalanceTemplate,
+    #[msg("The snapshot challenge window has passed")]
+    SnapshotFinalized,
+    #[msg("The same pool appears twice in the swap route")]
+    DuplicatePoolInRoute,
+    #[msg("The hop input mint is not the previous hop output mint")]
+    RouteMintMismatch,
+    #[msg("Route hops are unsupported or don't match the remaining accounts")]
+    InvalidRouteHop,
+    #[msg("Invalid fee exemption param")]
+    InvalidFeeExemptionParam,
+    #[msg("The calling program is not exempted from the protocol fee")]
+    NotFeeExempt,
+    #[msg("Admin instructions can't be invoked through CPI")]
+    CpiNotAllowed,
+    #[msg("Invalid partner fee param")]
+    InvalidPartnerFeeParam,
+    #[msg("The newest observation is too recent to poke")]
+    ObservationNotStale,
+    #[msg("Invalid admin heartbeat param")]
+    InvalidHeartbeatParam,
+    #[msg("Invalid incentive programs param")]
+    InvalidIncentiveProgramsParam,
+    #[msg("The position is enrolled in another incentive program")]
+    PositionAlreadyEnrolled,
+    #[msg("Hop sqrt price limits must have one entry per hop")]
+    InvalidHopSqrtPriceLimits,
+    #[msg("Invalid oracle consumer param")]
+    InvalidOracleConsumerParam,
+    #[msg("Invalid referral account")]
+    InvalidReferralAccount,
+    #[msg("Invalid launch schedule params")]
+    InvalidLaunchSchedule,
+    #[msg("The launch position is already at its scheduled range")]
+    LaunchShiftNotDue,
+    #[msg("Invalid split swap legs")]
+    InvalidSplitLegs,
+    #[msg("Invalid admin batch")]
+    InvalidAdminBatch,
+    #[msg("Invalid user stats")]
+    InvalidUserStats,
+    #[msg("Swap moves the price more than the pool allows")]
+    PriceImpactTooHigh,
+    #[msg("Invalid batch swaps")]
+    InvalidBatchSwaps,
+    #[msg("The position lease has not expired")]
+    PositionLeaseActive,
+    #[msg("The position lease has expired")]
+    PositionLeaseExpired,
+    #[msg("The swap hook program is not whitelisted by the amm config")]
+    SwapHookNotWhitelisted,
+    #[msg("The swap hook program is missing from the remaining accounts, or followed by too many accounts")]
+    InvalidSwapHookAccounts,
+    #[msg("Invalid batch positions")]
+    InvalidBatchPositions,
+    #[msg("Swap input is below the pool minimum")]
+    SwapAmountTooSmall,
+    #[msg("Position holds liquidity or owed amounts, or is within its grace period")]
+    PositionNotSweepable,
+    #[msg("Invalid position delegate scope")]
+    InvalidPositionDelegateScope,
+    #[msg("Donation exceeds one token per unit of in-range liquidity")]
+    DonationTooLarge,
+    #[msg("The fee growth snapshot must lie between the position's and the range's current one")]
+    InvalidPositionCheckpoint,
 }
@@ -0,0 +1,23 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::require_not_cpi;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct AppendAdminBatch<'info> {
+    /// Only admin can stage a batch
+    #[account(address = crate::admin::id() @ ErrorCode::NotApproved)]
+    pub owner: Signer<'info>,
+
+    /// The batch to extend, not executed yet
+    #[account(mut)]
+    pub admin_batch: Box<Account<'info, AdminBatchState>>,
+}
+
+pub fn append_admin_batch(
+    ctx: Context<AppendAdminBatch>,
+    entries: Vec<AdminBatchEntry>,
+) -> Result<()> {
+    require_not_cpi()?;
+    ctx.accounts.admin_batch.append(&entries)
+}
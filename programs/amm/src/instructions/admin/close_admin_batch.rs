@@ -0,0 +1,23 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::require_not_cpi;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CloseAdminBatch<'info> {
+    /// Only admin can close a batch
+    #[account(
+        mut,
+        address = crate::admin::id() @ ErrorCode::NotApproved
+    )]
+    pub owner: Signer<'info>,
+
+    /// The batch to close, executed or abandoned
+    #[account(mut, close = owner)]
+    pub admin_batch: Box<Account<'info, AdminBatchState>>,
+}
+
+/// Closes a batch, abandoning the entries not executed yet
+pub fn close_admin_batch(_ctx: Context<CloseAdminBatch>) -> Result<()> {
+    require_not_cpi()
+}
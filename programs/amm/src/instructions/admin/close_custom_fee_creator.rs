@@ -0,0 +1,31 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::require_not_cpi;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CloseCustomFeeCreator<'info> {
+    /// Only admin can remove a custom fee creator
+    #[account(
+        mut,
+        address = crate::admin::id() @ ErrorCode::NotApproved
+    )]
+    pub owner: Signer<'info>,
+
+    /// The whitelist account to be closed
+    #[account(
+        mut,
+        seeds = [
+            CUSTOM_FEE_CREATOR_SEED.as_bytes(),
+            custom_fee_creator.creator.as_ref(),
+        ],
+        bump = custom_fee_creator.bump,
+        close = owner
+    )]
+    pub custom_fee_creator: Account<'info, CustomFeeCreatorState>,
+}
+
+pub fn close_custom_fee_creator(_ctx: Context<CloseCustomFeeCreator>) -> Result<()> {
+    require_not_cpi()?;
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::{close_spl_account, require_not_cpi, transfer_position_nft};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct CloseLaunchSchedule<'info> {
+    /// Only admin can end a launch schedule
+    #[account(
+        mut,
+        address = crate::admin::id() @ ErrorCode::NotApproved
+    )]
+    pub owner: Signer<'info>,
+
+    /// The schedule to close
+    #[account(mut, close = owner)]
+    pub launch_schedule: Box<Account<'info, LaunchScheduleState>>,
+
+    /// Mint address of the escrowed position nft
+    #[account(
+        address = launch_schedule.position_nft_mint,
+        mint::token_program = token_program,
+    )]
+    pub position_nft_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The escrow token account holding the position nft
+    #[account(
+        mut,
+        address = launch_schedule.escrow_nft_account,
+    )]
+    pub escrow_nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The token account to receive the position nft
+    #[account(
+        mut,
+        token::mint = position_nft_mint,
+        token::token_program = token_program,
+    )]
+    pub position_nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token/Token2022 program of the position nft
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Ends the schedule and hands the position nft back, the position keeps its current range
+pub fn close_launch_schedule(ctx: Context<CloseLaunchSchedule>) -> Result<()> {
+    require_not_cpi()?;
+    let launch_schedule = ctx.accounts.launch_schedule.as_ref();
+    let launch_schedule_info = launch_schedule.to_account_info();
+    let token_program = ctx.accounts.token_program.to_account_info();
+    let escrow_nft_account = ctx.accounts.escrow_nft_account.to_account_info();
+    transfer_position_nft(
+        &launch_schedule_info,
+        &escrow_nft_account,
+        &ctx.accounts.position_nft_account.to_account_info(),
+        &ctx.accounts.position_nft_mint.to_account_info(),
+        &token_program,
+        &[&launch_schedule.seeds()],
+    )?;
+
+    close_spl_account(
+        &launch_schedule_info,
+        &ctx.accounts.owner.to_account_info(),
+        &escrow_nft_account,
+        &token_program,
+        &[&launch_schedule.seeds()],
+    )?;
+    Ok(())
+}
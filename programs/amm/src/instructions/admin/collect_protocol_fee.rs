@@ -38,13 +38,21 @@ pub struct CollectProtocolFee<'info> {
 
     /// The mint of token vault 0
     #[account(
-        address = token_vault_0.mint
+        address = token_vault_0.mint,
+        constraint = pool_state.load()?.is_valid_token_program(
+            &vault_0_mint.key(),
+            vault_0_mint.to_account_info().owner
+        ) @ ErrorCode::InvalidTokenProgram
     )]
     pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
 
     /// The mint of token vault 1
     #[account(
-        address = token_vault_1.mint
+        address = token_vault_1.mint,
+        constraint = pool_state.load()?.is_valid_token_program(
+            &vault_1_mint.key(),
+            vault_1_mint.to_account_info().owner
+        ) @ ErrorCode::InvalidTokenProgram
     )]
     pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
 
@@ -68,8 +76,11 @@ pub fn collect_protocol_fee(
     amount_0_requested: u64,
     amount_1_requested: u64,
 ) -> Result<()> {
+    require_not_cpi()?;
     let amount_0: u64;
     let amount_1: u64;
+    let remaining_fees_token_0: u64;
+    let remaining_fees_token_1: u64;
     {
         let mut pool_state = ctx.accounts.pool_state.load_mut()?;
 
@@ -84,6 +95,8 @@ pub fn collect_protocol_fee(
             .protocol_fees_token_1
             .checked_sub(amount_1)
             .unwrap();
+        remaining_fees_token_0 = pool_state.protocol_fees_token_0;
+        remaining_fees_token_1 = pool_state.protocol_fees_token_1;
     }
     transfer_from_pool_vault_to_user(
         &ctx.accounts.pool_state,
@@ -111,12 +124,20 @@ pub fn collect_protocol_fee(
         &ctx.accounts.token_vault_1.to_account_info(),
     )?;
 
+    ctx.accounts.token_vault_0.reload()?;
+    ctx.accounts.token_vault_1.reload()?;
     emit!(CollectProtocolFeeEvent {
         pool_state: ctx.accounts.pool_state.key(),
         recipient_token_account_0: ctx.accounts.recipient_token_account_0.key(),
         recipient_token_account_1: ctx.accounts.recipient_token_account_1.key(),
         amount_0,
         amount_1,
+        amount_0_requested,
+        amount_1_requested,
+        remaining_fees_token_0,
+        remaining_fees_token_1,
+        vault_0_balance: ctx.accounts.token_vault_0.amount,
+        vault_1_balance: ctx.accounts.token_vault_1.amount,
     });
 
     Ok(())
@@ -0,0 +1,177 @@
+use crate::error::ErrorCode;
+use crate::instructions::swap_v2::*;
+use crate::libraries::{big_num::U256, fixed_point_64, full_math::MulDiv, tick_math};
+use crate::states::*;
+use crate::util::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+/// The most a conversion can trail the swap pool's twap price, in basis points
+pub const MAX_CONVERSION_SLIPPAGE_BPS: u16 = 1_000;
+
+#[derive(Accounts)]
+pub struct ConvertProtocolFee<'info> {
+    /// Only admin can convert the protocol fee
+    #[account(address = crate::admin::id() @ ErrorCode::NotApproved)]
+    pub owner: Signer<'info>,
+
+    /// The pool the protocol fee accrued in
+    #[account(mut)]
+    pub fee_pool_state: AccountLoader<'info, PoolState>,
+
+    /// The fee pool vault holding the fee token
+    #[account(
+        mut,
+        constraint = fee_vault.key() == fee_pool_state.load()?.token_vault_0
+            || fee_vault.key() == fee_pool_state.load()?.token_vault_1
+    )]
+    pub fee_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of the fee token
+    #[account(
+        address = fee_vault.mint,
+        constraint = fee_pool_state.load()?.is_valid_token_program(
+            &fee_vault_mint.key(),
+            fee_vault_mint.to_account_info().owner
+        ) @ ErrorCode::InvalidTokenProgram
+    )]
+    pub fee_vault_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The swap from the fee token into the treasury token, the admin is the payer, the input
+    /// token account receives the collected fee and the output token account is the treasury
+    #[account(
+        constraint = swap.payer.key() == owner.key() @ ErrorCode::NotApproved,
+        constraint = swap.input_token_account.owner == owner.key() @ ErrorCode::NotApproved,
+        constraint = swap.input_vault.mint == fee_vault.mint,
+    )]
+    pub swap: SwapSingleV2<'info>,
+}
+
+/// Collect up to `amount_requested` of the protocol fee held in `fee_vault` and swap it into the
+/// treasury token, requiring the output to be within `max_slippage_bps` of the swap pool's twap
+/// price over the last `twap_window` seconds.
+pub fn convert_protocol_fee<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, ConvertProtocolFee<'info>>,
+    amount_requested: u64,
+    twap_window: u32,
+    max_slippage_bps: u16,
+) -> Result<()> {
+    require_not_cpi()?;
+    require!(
+        max_slippage_bps <= MAX_CONVERSION_SLIPPAGE_BPS,
+        ErrorCode::InvalidConversionParam
+    );
+
+    let amount;
+    {
+        let mut fee_pool_state = ctx.accounts.fee_pool_state.load_mut()?;
+        if ctx.accounts.fee_vault.key() == fee_pool_state.token_vault_0 {
+            amount = amount_requested.min(fee_pool_state.protocol_fees_token_0);
+            fee_pool_state.protocol_fees_token_0 -= amount;
+        } else {
+            amount = amount_requested.min(fee_pool_state.protocol_fees_token_1);
+            fee_pool_state.protocol_fees_token_1 -= amount;
+        }
+    }
+    require_gt!(amount, 0, ErrorCode::InvalidConversionParam);
+
+    let swap = &mut ctx.accounts.swap;
+    let input_balance_before = swap.input_token_account.amount;
+    transfer_from_pool_vault_to_user(
+        &ctx.accounts.fee_pool_state,
+        &ctx.accounts.fee_vault.to_account_info(),
+        &swap.input_token_account.to_account_info(),
+        Some(ctx.accounts.fee_vault_mint.clone()),
+        &swap.token_program,
+        Some(swap.token_program_2022.to_account_info()),
+        amount,
+    )?;
+    swap.input_token_account.reload()?;
+    let amount_in = swap
+        .input_token_account
+        .amount
+        .checked_sub(input_balance_before)
+        .unwrap();
+
+    let (zero_for_one, twap_tick) = {
+        let pool_state = swap.pool_state.load()?;
+        let twap_tick = swap
+            .observation_state
+            .load()?
+            .twap_tick(oracle::block_timestamp(), twap_window)
+            .ok_or(ErrorCode::ObservationNotEnough)?;
+        (swap.input_vault.mint == pool_state.token_mint_0, twap_tick)
+    };
+    let amount_out_at_twap = quote_at_sqrt_price(
+        amount_in,
+        tick_math::get_sqrt_price_at_tick(twap_tick)?,
+        zero_for_one,
+    );
+    let amount_out_minimum = amount_out_at_twap
+        .mul_div_floor(u64::from(10_000 - max_slippage_bps), 10_000)
+        .unwrap();
+
+    let amount_out = exact_internal_v2(swap, ctx.remaining_accounts, amount_in, 0, true)?;
+    require_gte!(
+        amount_out,
+        amount_out_minimum,
+        ErrorCode::TooLittleOutputReceived
+    );
+
+    emit!(ConvertProtocolFeeEvent {
+        fee_pool_state: ctx.accounts.fee_pool_state.key(),
+        swap_pool_state: swap.pool_state.key(),
+        treasury_token_account: swap.output_token_account.key(),
+        amount_in,
+        amount_out,
+        twap_tick,
+        amount_out_minimum,
+    });
+    Ok(())
+}
+
+/// The output of swapping `amount_in` at a constant `sqrt_price_x64`, ignoring fees
+pub fn quote_at_sqrt_price(amount_in: u64, sqrt_price_x64: u128, zero_for_one: bool) -> u64 {
+    let amount_in = U256::from(amount_in);
+    let sqrt_price_x64 = U256::from(sqrt_price_x64);
+    let q64 = U256::from(fixed_point_64::Q64);
+    let amount_out = if zero_for_one {
+        amount_in
+            .mul_div_floor(sqrt_price_x64, q64)
+            .and_then(|amount| amount.mul_div_floor(sqrt_price_x64, q64))
+    } else {
+        amount_in
+            .mul_div_floor(q64, sqrt_price_x64)
+            .and_then(|amount| amount.mul_div_floor(q64, sqrt_price_x64))
+    }
+    .unwrap();
+    if amount_out > U256::from(u64::MAX) {
+        u64::MAX
+    } else {
+        amount_out.as_u64()
+    }
+}
+
+#[cfg(test)]
+mod convert_protocol_fee_test {
+    use super::*;
+
+    #[test]
+    fn quote_at_sqrt_price_test() {
+        // price 1
+        assert_eq!(quote_at_sqrt_price(1_000, fixed_point_64::Q64, true), 1_000);
+        assert_eq!(
+            quote_at_sqrt_price(1_000, fixed_point_64::Q64, false),
+            1_000
+        );
+        // price 4, token_1 per token_0
+        let sqrt_price_x64 = fixed_point_64::Q64 * 2;
+        assert_eq!(quote_at_sqrt_price(1_000, sqrt_price_x64, true), 4_000);
+        assert_eq!(quote_at_sqrt_price(1_000, sqrt_price_x64, false), 250);
+        // saturates at the max amount
+        assert_eq!(
+            quote_at_sqrt_price(u64::MAX, tick_math::MAX_SQRT_PRICE_X64, true),
+            u64::MAX
+        );
+    }
+}
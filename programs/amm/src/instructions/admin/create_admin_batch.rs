@@ -0,0 +1,39 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::require_not_cpi;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(batch_id: u64)]
+pub struct CreateAdminBatch<'info> {
+    /// Only admin can stage a batch
+    #[account(
+        mut,
+        address = crate::admin::id() @ ErrorCode::NotApproved
+    )]
+    pub owner: Signer<'info>,
+
+    /// Initialize an account to store the staged entries
+    #[account(
+        init,
+        seeds = [ADMIN_BATCH_SEED.as_bytes(), &batch_id.to_be_bytes()],
+        bump,
+        payer = owner,
+        space = AdminBatchState::LEN
+    )]
+    pub admin_batch: Box<Account<'info, AdminBatchState>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_admin_batch(
+    ctx: Context<CreateAdminBatch>,
+    batch_id: u64,
+    entries: Vec<AdminBatchEntry>,
+) -> Result<()> {
+    require_not_cpi()?;
+    let admin_batch = ctx.accounts.admin_batch.as_mut();
+    admin_batch.bump = ctx.bumps.admin_batch;
+    admin_batch.batch_id = batch_id;
+    admin_batch.append(&entries)
+}
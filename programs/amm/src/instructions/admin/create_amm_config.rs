@@ -1,5 +1,6 @@
 use crate::error::ErrorCode;
 use crate::states::*;
+use crate::util::require_not_cpi;
 use anchor_lang::prelude::*;
 use std::ops::DerefMut;
 
@@ -37,6 +38,8 @@ pub fn create_amm_config(
     protocol_fee_rate: u32,
     fund_fee_rate: u32,
 ) -> Result<()> {
+    require_not_cpi()?;
+    AmmConfig::check_fee_rates(protocol_fee_rate, fund_fee_rate)?;
     let amm_config = ctx.accounts.amm_config.deref_mut();
     amm_config.owner = ctx.accounts.owner.key();
     amm_config.bump = ctx.bumps.amm_config;
@@ -46,6 +49,7 @@ pub fn create_amm_config(
     amm_config.tick_spacing = tick_spacing;
     amm_config.fund_fee_rate = fund_fee_rate;
     amm_config.fund_owner = ctx.accounts.owner.key();
+    amm_config.protocol_fee_rate_updated_at = u64::try_from(Clock::get()?.unix_timestamp).unwrap();
 
     emit!(ConfigChangeEvent {
         index: amm_config.index,
@@ -0,0 +1,86 @@
+use crate::error::ErrorCode;
+use crate::instructions::MAX_CONVERSION_SLIPPAGE_BPS;
+use crate::states::*;
+use crate::util::require_not_cpi;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct CreateBuyback<'info> {
+    /// Only admin can create a buyback
+    #[account(
+        mut,
+        address = crate::admin::id() @ ErrorCode::NotApproved
+    )]
+    pub owner: Signer<'info>,
+
+    /// The pool whose protocol fee funds the buyback
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The token bought back, one of the pool tokens
+    #[account(
+        constraint = buyback_mint.key() == pool_state.load()?.token_mint_0
+            || buyback_mint.key() == pool_state.load()?.token_mint_1
+            @ ErrorCode::InvalidBuybackParam,
+        mint::token_program = buyback_token_program
+    )]
+    pub buyback_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Initialize an account to store the buyback state
+    #[account(
+        init,
+        seeds = [BUYBACK_SEED.as_bytes(), pool_state.key().as_ref()],
+        bump,
+        payer = owner,
+        space = BuybackState::LEN
+    )]
+    pub buyback_state: Box<Account<'info, BuybackState>>,
+
+    /// The vault receiving the bought tokens
+    #[account(
+        init,
+        seeds = [BUYBACK_VAULT_SEED.as_bytes(), buyback_state.key().as_ref()],
+        bump,
+        payer = owner,
+        token::mint = buyback_mint,
+        token::authority = buyback_state,
+        token::token_program = buyback_token_program,
+    )]
+    pub buyback_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Spl token program or token program 2022 of the buyback mint
+    pub buyback_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn create_buyback(
+    ctx: Context<CreateBuyback>,
+    protocol_fee_share_rate: u32,
+    interval: u64,
+    twap_window: u32,
+    max_slippage_bps: u16,
+    burn: bool,
+) -> Result<()> {
+    require_not_cpi()?;
+    require!(
+        protocol_fee_share_rate > 0 && protocol_fee_share_rate <= FEE_RATE_DENOMINATOR_VALUE,
+        ErrorCode::InvalidBuybackParam
+    );
+    require!(
+        twap_window > 0 && max_slippage_bps <= MAX_CONVERSION_SLIPPAGE_BPS,
+        ErrorCode::InvalidBuybackParam
+    );
+
+    let buyback_state = ctx.accounts.buyback_state.as_mut();
+    buyback_state.bump = [ctx.bumps.buyback_state];
+    buyback_state.pool_id = ctx.accounts.pool_state.key();
+    buyback_state.buyback_mint = ctx.accounts.buyback_mint.key();
+    buyback_state.buyback_vault = ctx.accounts.buyback_vault.key();
+    buyback_state.protocol_fee_share_rate = protocol_fee_share_rate;
+    buyback_state.interval = interval;
+    buyback_state.twap_window = twap_window;
+    buyback_state.max_slippage_bps = max_slippage_bps;
+    buyback_state.burn = burn;
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::require_not_cpi;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(creator: Pubkey)]
+pub struct CreateCustomFeeCreator<'info> {
+    /// Only admin can whitelist a custom fee creator
+    #[account(
+        mut,
+        address = crate::admin::id() @ ErrorCode::NotApproved
+    )]
+    pub owner: Signer<'info>,
+
+    /// Initialize an account to mark the creator as whitelisted
+    #[account(
+        init,
+        seeds = [
+            CUSTOM_FEE_CREATOR_SEED.as_bytes(),
+            creator.as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = CustomFeeCreatorState::LEN
+    )]
+    pub custom_fee_creator: Account<'info, CustomFeeCreatorState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_custom_fee_creator(
+    ctx: Context<CreateCustomFeeCreator>,
+    creator: Pubkey,
+) -> Result<()> {
+    require_not_cpi()?;
+    let custom_fee_creator = &mut ctx.accounts.custom_fee_creator;
+    custom_fee_creator.bump = ctx.bumps.custom_fee_creator;
+    custom_fee_creator.creator = creator;
+    Ok(())
+}
@@ -0,0 +1,108 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::require_not_cpi;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct CreateFeeDistributor<'info> {
+    /// Only admin can create a fee distributor
+    #[account(
+        mut,
+        address = crate::admin::id() @ ErrorCode::NotApproved
+    )]
+    pub owner: Signer<'info>,
+
+    /// Protocol fees of pools belonging to this config can be distributed
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// The token to be staked
+    #[account(mint::token_program = stake_token_program)]
+    pub stake_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The protocol fee token to be distributed
+    #[account(mint::token_program = reward_token_program)]
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Initialize an account to store the distributor state
+    #[account(
+        init,
+        seeds = [
+            FEE_DISTRIBUTOR_SEED.as_bytes(),
+            amm_config.key().as_ref(),
+            stake_mint.key().as_ref(),
+            reward_mint.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = FeeDistributorState::LEN
+    )]
+    pub fee_distributor: Box<Account<'info, FeeDistributorState>>,
+
+    /// The vault holding staked tokens
+    #[account(
+        init,
+        seeds = [
+            FEE_DISTRIBUTOR_VAULT_SEED.as_bytes(),
+            fee_distributor.key().as_ref(),
+            stake_mint.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        token::mint = stake_mint,
+        token::authority = fee_distributor,
+        token::token_program = stake_token_program,
+    )]
+    pub stake_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The vault holding protocol fees to be claimed
+    #[account(
+        init,
+        seeds = [
+            FEE_DISTRIBUTOR_VAULT_SEED.as_bytes(),
+            fee_distributor.key().as_ref(),
+            reward_mint.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        token::mint = reward_mint,
+        token::authority = fee_distributor,
+        token::token_program = reward_token_program,
+    )]
+    pub reward_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Spl token program or token program 2022 of stake mint
+    pub stake_token_program: Interface<'info, TokenInterface>,
+    /// Spl token program or token program 2022 of reward mint
+    pub reward_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn create_fee_distributor(
+    ctx: Context<CreateFeeDistributor>,
+    protocol_fee_share_rate: u32,
+    epoch_duration: u64,
+) -> Result<()> {
+    require_not_cpi()?;
+    require_gte!(
+        FEE_RATE_DENOMINATOR_VALUE,
+        protocol_fee_share_rate,
+        ErrorCode::InvalidFeeDistributorParam
+    );
+    require_gt!(epoch_duration, 0, ErrorCode::InvalidFeeDistributorParam);
+    let block_timestamp = u64::try_from(Clock::get()?.unix_timestamp).unwrap();
+
+    let fee_distributor = ctx.accounts.fee_distributor.as_mut();
+    fee_distributor.bump = [ctx.bumps.fee_distributor];
+    fee_distributor.amm_config = ctx.accounts.amm_config.key();
+    fee_distributor.stake_mint = ctx.accounts.stake_mint.key();
+    fee_distributor.reward_mint = ctx.accounts.reward_mint.key();
+    fee_distributor.stake_vault = ctx.accounts.stake_vault.key();
+    fee_distributor.reward_vault = ctx.accounts.reward_vault.key();
+    fee_distributor.protocol_fee_share_rate = protocol_fee_share_rate;
+    fee_distributor.epoch_duration = epoch_duration;
+    fee_distributor.current_epoch = block_timestamp / epoch_duration;
+    fee_distributor.last_update_time = block_timestamp;
+    Ok(())
+}
@@ -0,0 +1,111 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::{require_not_cpi, transfer_position_nft};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct CreateLaunchSchedule<'info> {
+    /// Only admin can put a position on a launch schedule
+    #[account(
+        mut,
+        address = crate::admin::id() @ ErrorCode::NotApproved
+    )]
+    pub owner: Signer<'info>,
+
+    /// Mint address bound to the personal position.
+    #[account(
+        address = personal_position.nft_mint,
+        mint::token_program = token_program,
+    )]
+    pub position_nft_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The admin's token account holding the position nft
+    #[account(
+        mut,
+        token::mint = position_nft_mint,
+        token::authority = owner,
+        constraint = position_nft_account.amount == 1,
+        token::token_program = token_program,
+    )]
+    pub position_nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The position shifted by the schedule
+    #[account(
+        seeds = [POSITION_SEED.as_bytes(), position_nft_mint.key().as_ref()],
+        bump,
+        constraint = personal_position.pool_id == pool_state.key(),
+    )]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    /// The pool of the position
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Initialize an account to store the schedule
+    #[account(
+        init,
+        seeds = [LAUNCH_SCHEDULE_SEED.as_bytes(), position_nft_mint.key().as_ref()],
+        bump,
+        payer = owner,
+        space = LaunchScheduleState::LEN
+    )]
+    pub launch_schedule: Box<Account<'info, LaunchScheduleState>>,
+
+    /// The escrow token account to hold the position nft
+    #[account(
+        init,
+        seeds = [LAUNCH_SCHEDULE_VAULT_SEED.as_bytes(), position_nft_mint.key().as_ref()],
+        bump,
+        payer = owner,
+        token::mint = position_nft_mint,
+        token::authority = launch_schedule,
+        token::token_program = token_program,
+    )]
+    pub escrow_nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token/Token2022 program of the position nft
+    pub token_program: Interface<'info, TokenInterface>,
+    /// To create new program accounts
+    pub system_program: Program<'info, System>,
+    /// Sysvar for token account
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn create_launch_schedule(
+    ctx: Context<CreateLaunchSchedule>,
+    start_time: u64,
+    shift_interval: u64,
+    tick_shift: i32,
+    tick_lower_floor: i32,
+    beneficiary: Pubkey,
+) -> Result<()> {
+    require_not_cpi()?;
+    let tick_spacing = ctx.accounts.pool_state.load()?.tick_spacing;
+    let personal_position = ctx.accounts.personal_position.as_ref();
+
+    let launch_schedule = ctx.accounts.launch_schedule.as_mut();
+    launch_schedule.bump = [ctx.bumps.launch_schedule];
+    launch_schedule.pool_id = personal_position.pool_id;
+    launch_schedule.position_nft_mint = ctx.accounts.position_nft_mint.key();
+    launch_schedule.personal_position = personal_position.key();
+    launch_schedule.escrow_nft_account = ctx.accounts.escrow_nft_account.key();
+    launch_schedule.beneficiary = beneficiary;
+    launch_schedule.start_time = start_time;
+    launch_schedule.shift_interval = shift_interval;
+    launch_schedule.tick_shift = tick_shift;
+    launch_schedule.tick_lower_start = personal_position.tick_lower_index;
+    launch_schedule.tick_lower_floor = tick_lower_floor;
+    launch_schedule.tick_width =
+        personal_position.tick_upper_index - personal_position.tick_lower_index;
+    launch_schedule.check(tick_spacing)?;
+
+    transfer_position_nft(
+        &ctx.accounts.owner.to_account_info(),
+        &ctx.accounts.position_nft_account.to_account_info(),
+        &ctx.accounts.escrow_nft_account.to_account_info(),
+        &ctx.accounts.position_nft_mint.to_account_info(),
+        &ctx.accounts.token_program.to_account_info(),
+        &[],
+    )?;
+    Ok(())
+}
@@ -1,5 +1,6 @@
 use crate::error::ErrorCode;
 use crate::states::*;
+use crate::util::require_not_cpi;
 use anchor_lang::prelude::*;
 #[derive(Accounts)]
 pub struct CreateOperationAccount<'info> {
@@ -26,6 +27,7 @@ pub struct CreateOperationAccount<'info> {
 }
 
 pub fn create_operation_account(ctx: Context<CreateOperationAccount>) -> Result<()> {
+    require_not_cpi()?;
     let mut operation_state = ctx.accounts.operation_state.load_init()?;
     operation_state.initialize(ctx.bumps.operation_state);
     Ok(())
@@ -0,0 +1,34 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::require_not_cpi;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct DisputeLiquiditySnapshot<'info> {
+    /// Only admin can dispute a snapshot
+    #[account(
+        mut,
+        address = crate::admin::id() @ ErrorCode::NotApproved
+    )]
+    pub owner: Signer<'info>,
+
+    /// The disputed snapshot, closed so the epoch can be committed again
+    #[account(
+        mut,
+        constraint = !liquidity_snapshot.is_final(
+            u64::try_from(Clock::get()?.unix_timestamp).unwrap()
+        ) @ ErrorCode::SnapshotFinalized,
+        close = owner
+    )]
+    pub liquidity_snapshot: Box<Account<'info, LiquiditySnapshot>>,
+}
+
+pub fn dispute_liquidity_snapshot(ctx: Context<DisputeLiquiditySnapshot>) -> Result<()> {
+    require_not_cpi()?;
+    msg!(
+        "disputed snapshot of pool {} at epoch {}",
+        ctx.accounts.liquidity_snapshot.pool_id,
+        ctx.accounts.liquidity_snapshot.epoch
+    );
+    Ok(())
+}
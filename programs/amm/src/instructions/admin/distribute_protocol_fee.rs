@@ -0,0 +1,118 @@
+use crate::decrease_liquidity::check_unclaimed_fees_and_vault;
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct DistributeProtocolFee<'info> {
+    /// Only admin or config owner can distribute protocol fee
+    #[account(constraint = (owner.key() == amm_config.owner || owner.key() == crate::admin::id()) @ ErrorCode::NotApproved)]
+    pub owner: Signer<'info>,
+
+    /// Pool state stores accumulated protocol fee amount
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Amm config account stores owner
+    #[account(
+        address = pool_state.load()?.amm_config
+    )]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// The distributor receiving the protocol fee
+    #[account(
+        mut,
+        constraint = fee_distributor.amm_config == amm_config.key() @ ErrorCode::NotApproved
+    )]
+    pub fee_distributor: Box<Account<'info, FeeDistributorState>>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        mut,
+        constraint = token_vault_0.key() == pool_state.load()?.token_vault_0
+    )]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        mut,
+        constraint = token_vault_1.key() == pool_state.load()?.token_vault_1
+    )]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The distributor reward vault
+    #[account(
+        mut,
+        address = fee_distributor.reward_vault
+    )]
+    pub reward_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of the reward token
+    #[account(
+        address = fee_distributor.reward_mint
+    )]
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The SPL program to perform token transfers
+    pub token_program: Program<'info, Token>,
+
+    /// The SPL program 2022 to perform token transfers
+    pub token_program_2022: Program<'info, Token2022>,
+}
+
+pub fn distribute_protocol_fee(ctx: Context<DistributeProtocolFee>) -> Result<()> {
+    require_not_cpi()?;
+    let reward_mint = ctx.accounts.reward_mint.key();
+    let is_token_0 = reward_mint == ctx.accounts.token_vault_0.mint;
+    require!(
+        is_token_0 || reward_mint == ctx.accounts.token_vault_1.mint,
+        ErrorCode::InvalidFeeDistributorParam
+    );
+    let amount = ctx
+        .accounts
+        .pool_state
+        .load_mut()?
+        .take_distributed_protocol_fee(
+            is_token_0,
+            ctx.accounts.fee_distributor.protocol_fee_share_rate,
+        );
+
+    let token_vault = if is_token_0 {
+        ctx.accounts.token_vault_0.to_account_info()
+    } else {
+        ctx.accounts.token_vault_1.to_account_info()
+    };
+    transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &token_vault,
+        &ctx.accounts.reward_vault.to_account_info(),
+        Some(ctx.accounts.reward_mint.clone()),
+        &ctx.accounts.token_program,
+        Some(ctx.accounts.token_program_2022.to_account_info()),
+        amount,
+    )?;
+
+    check_unclaimed_fees_and_vault(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_0.to_account_info(),
+        &ctx.accounts.token_vault_1.to_account_info(),
+    )?;
+
+    // the distributor receives the amount net of transfer fee
+    let transfer_fee = get_transfer_fee(ctx.accounts.reward_mint.clone(), amount)?;
+    let block_timestamp = u64::try_from(Clock::get()?.unix_timestamp).unwrap();
+    let fee_distributor = ctx.accounts.fee_distributor.as_mut();
+    fee_distributor.update_epoch(block_timestamp);
+    fee_distributor.add_reward(amount.checked_sub(transfer_fee).unwrap());
+
+    emit!(DistributeProtocolFeeEvent {
+        fee_distributor: fee_distributor.key(),
+        pool_state: ctx.accounts.pool_state.key(),
+        amount,
+        epoch: fee_distributor.current_epoch,
+    });
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use super::update_amm_config::apply_amm_config_update;
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::require_not_cpi;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ExecuteAdminBatch<'info> {
+    /// Only admin can execute a batch
+    #[account(address = crate::admin::id() @ ErrorCode::NotApproved)]
+    pub owner: Signer<'info>,
+
+    /// The batch to execute the next chunk of
+    #[account(mut)]
+    pub admin_batch: Box<Account<'info, AdminBatchState>>,
+    // remaining accounts
+    // the amm config of each entry of the chunk, in entry order
+}
+
+/// Executes the next `max_entries` entries of the batch, which must hash to `entries_hash`
+pub fn execute_admin_batch<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, ExecuteAdminBatch<'info>>,
+    entries_hash: [u8; 32],
+    max_entries: u16,
+) -> Result<()> {
+    require_not_cpi()?;
+    let admin_batch = ctx.accounts.admin_batch.as_mut();
+    require!(
+        admin_batch.entries_hash() == entries_hash && !admin_batch.is_done(),
+        ErrorCode::InvalidAdminBatch
+    );
+    let chunk = admin_batch.next_chunk(max_entries);
+    require_eq!(
+        chunk.len(),
+        ctx.remaining_accounts.len(),
+        ErrorCode::AccountLack
+    );
+    for (entry, amm_config_info) in chunk.iter().zip(ctx.remaining_accounts) {
+        require_keys_eq!(amm_config_info.key(), entry.amm_config);
+        let mut amm_config = Account::<AmmConfig>::try_from(amm_config_info)?;
+        apply_amm_config_update(&mut amm_config, entry.param, entry.value, entry.key)?;
+        amm_config.exit(&crate::id())?;
+    }
+    Ok(())
+}
@@ -0,0 +1,97 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::require_not_cpi;
+use anchor_lang::{prelude::*, system_program, Discriminator};
+
+#[derive(Accounts)]
+pub struct MigrateAccountSpace<'info> {
+    /// Only admin can resize program accounts, pays the rent of the added space
+    #[account(
+        mut,
+        address = crate::admin::id() @ ErrorCode::NotApproved
+    )]
+    pub owner: Signer<'info>,
+
+    /// CHECK: An amm config, recognized by its discriminator
+    #[account(mut, owner = crate::id())]
+    pub program_account: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+    // remaining accounts
+    // more amm configs to resize in the same instruction
+}
+
+/// Grows amm configs created with an older layout to the current `AmmConfig::LEN`, the added
+/// bytes are zeroed. Accounts already at their current size are left as they are.
+///
+/// An amm config created before its layout grew fails to deserialize, and so every instruction
+/// using it fails, until it is resized. Upgrading the program must therefore be followed right
+/// away by resizing every existing config, all of them can go in one instruction
+pub fn migrate_account_space<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, MigrateAccountSpace<'info>>,
+) -> Result<()> {
+    require_not_cpi()?;
+    migrate_account(
+        &ctx.accounts.owner,
+        &ctx.accounts.program_account.to_account_info(),
+        &ctx.accounts.system_program,
+    )?;
+    for program_account in ctx.remaining_accounts.iter() {
+        require_keys_eq!(
+            *program_account.owner,
+            crate::id(),
+            error::ErrorCode::ConstraintOwner
+        );
+        require!(program_account.is_writable, error::ErrorCode::ConstraintMut);
+        migrate_account(
+            &ctx.accounts.owner,
+            program_account,
+            &ctx.accounts.system_program,
+        )?;
+    }
+    Ok(())
+}
+
+fn migrate_account<'info>(
+    owner: &Signer<'info>,
+    program_account: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    let space = {
+        let data = program_account.try_borrow_data()?;
+        let discriminator = data
+            .get(..8)
+            .ok_or(error::ErrorCode::AccountDiscriminatorNotFound)?;
+        current_space(discriminator).ok_or(error::ErrorCode::AccountDiscriminatorMismatch)?
+    };
+    if program_account.data_len() >= space {
+        return Ok(());
+    }
+
+    let required_lamports = Rent::get()?
+        .minimum_balance(space)
+        .saturating_sub(program_account.lamports());
+    if required_lamports > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                system_program::Transfer {
+                    from: owner.to_account_info(),
+                    to: program_account.clone(),
+                },
+            ),
+            required_lamports,
+        )?;
+    }
+    program_account.realloc(space, true)?;
+    Ok(())
+}
+
+/// The current size of the accounts whose layout grew since they were first deployed
+fn current_space(discriminator: &[u8]) -> Option<usize> {
+    if discriminator == AmmConfig::DISCRIMINATOR {
+        Some(AmmConfig::LEN)
+    } else {
+        None
+    }
+}
@@ -21,3 +21,66 @@ pub use transfer_reward_owner::*;
 
 pub mod update_pool_status;
 pub use update_pool_status::*;
+
+pub mod create_custom_fee_creator;
+pub use create_custom_fee_creator::*;
+
+pub mod close_custom_fee_creator;
+pub use close_custom_fee_creator::*;
+
+pub mod create_fee_distributor;
+pub use create_fee_distributor::*;
+
+pub mod distribute_protocol_fee;
+pub use distribute_protocol_fee::*;
+
+pub mod convert_protocol_fee;
+pub use convert_protocol_fee::*;
+
+pub mod create_buyback;
+pub use create_buyback::*;
+
+pub mod dispute_liquidity_snapshot;
+pub use dispute_liquidity_snapshot::*;
+
+pub mod set_fee_exempt_programs;
+pub use set_fee_exempt_programs::*;
+
+pub mod set_pool_partner;
+pub use set_pool_partner::*;
+
+pub mod set_admin_heartbeat;
+pub use set_admin_heartbeat::*;
+
+pub mod send_admin_heartbeat;
+pub use send_admin_heartbeat::*;
+
+pub mod set_incentive_programs;
+pub use set_incentive_programs::*;
+
+pub mod repair_position_checkpoint;
+pub use repair_position_checkpoint::*;
+
+pub mod migrate_account_space;
+pub use migrate_account_space::*;
+
+pub mod create_launch_schedule;
+pub use create_launch_schedule::*;
+
+pub mod close_launch_schedule;
+pub use close_launch_schedule::*;
+
+pub mod create_admin_batch;
+pub use create_admin_batch::*;
+
+pub mod append_admin_batch;
+pub use append_admin_batch::*;
+
+pub mod execute_admin_batch;
+pub use execute_admin_batch::*;
+
+pub mod close_admin_batch;
+pub use close_admin_batch::*;
+
+pub mod set_volume_rebate_threshold;
+pub use set_volume_rebate_threshold::*;
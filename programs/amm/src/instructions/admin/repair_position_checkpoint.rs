@@ -0,0 +1,123 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::require_not_cpi;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct RepairPositionCheckpoint<'info> {
+    /// Only admin can rewrite a position's fee checkpoint
+    #[account(
+        address = crate::admin::id() @ ErrorCode::NotApproved
+    )]
+    pub authority: Signer<'info>,
+
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The position whose fee growth snapshots are stale
+    #[account(mut, constraint = personal_position.pool_id == pool_state.key())]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    /// Stores init state for the lower tick
+    #[account(constraint = tick_array_lower.load()?.pool_id == pool_state.key())]
+    pub tick_array_lower: AccountLoader<'info, TickArrayState>,
+
+    /// Stores init state for the upper tick
+    #[account(constraint = tick_array_upper.load()?.pool_id == pool_state.key())]
+    pub tick_array_upper: AccountLoader<'info, TickArrayState>,
+}
+
+/// Overwrites the position's fee growth snapshots, the fees the position can collect from now
+/// on are measured from the new values. A snapshot can only be moved forward, up to the range's
+/// current fee growth, so a repair can never credit fees the position has not earned
+pub fn repair_position_checkpoint(
+    ctx: Context<RepairPositionCheckpoint>,
+    fee_growth_inside_0_last_x64: u128,
+    fee_growth_inside_1_last_x64: u128,
+) -> Result<()> {
+    require_not_cpi()?;
+    let personal_position = &mut ctx.accounts.personal_position;
+    let pool_state = ctx.accounts.pool_state.load()?;
+    let tick_lower_state = ctx
+        .accounts
+        .tick_array_lower
+        .load()?
+        .get_tick_state(personal_position.tick_lower_index, pool_state.tick_spacing)?;
+    let tick_upper_state = ctx
+        .accounts
+        .tick_array_upper
+        .load()?
+        .get_tick_state(personal_position.tick_upper_index, pool_state.tick_spacing)?;
+    let (fee_growth_inside_0_x64, fee_growth_inside_1_x64) = tick_array::get_fee_growth_inside(
+        &tick_lower_state,
+        &tick_upper_state,
+        pool_state.tick_current,
+        pool_state.fee_growth_global_0_x64,
+        pool_state.fee_growth_global_1_x64,
+    );
+    require!(
+        is_checkpoint_repair_bounded(
+            personal_position.fee_growth_inside_0_last_x64,
+            fee_growth_inside_0_last_x64,
+            fee_growth_inside_0_x64,
+        ) && is_checkpoint_repair_bounded(
+            personal_position.fee_growth_inside_1_last_x64,
+            fee_growth_inside_1_last_x64,
+            fee_growth_inside_1_x64,
+        ),
+        ErrorCode::InvalidPositionCheckpoint
+    );
+
+    emit!(RepairPositionCheckpointEvent {
+        position_nft_mint: personal_position.nft_mint,
+        authority: ctx.accounts.authority.key(),
+        old_fee_growth_inside_0_last_x64: personal_position.fee_growth_inside_0_last_x64,
+        old_fee_growth_inside_1_last_x64: personal_position.fee_growth_inside_1_last_x64,
+        fee_growth_inside_0_last_x64,
+        fee_growth_inside_1_last_x64,
+        fee_growth_inside_0_x64,
+        fee_growth_inside_1_x64,
+        token_fees_owed_0: personal_position.token_fees_owed_0,
+        token_fees_owed_1: personal_position.token_fees_owed_1,
+    });
+    personal_position.fee_growth_inside_0_last_x64 = fee_growth_inside_0_last_x64;
+    personal_position.fee_growth_inside_1_last_x64 = fee_growth_inside_1_last_x64;
+    Ok(())
+}
+
+/// Whether `new_last_x64` lies between the position's snapshot `old_last_x64` and the range's
+/// current fee growth `fee_growth_inside_x64`. Fee growth wraps around, so the distances from
+/// the old snapshot are compared
+fn is_checkpoint_repair_bounded(
+    old_last_x64: u128,
+    new_last_x64: u128,
+    fee_growth_inside_x64: u128,
+) -> bool {
+    new_last_x64.wrapping_sub(old_last_x64) <= fee_growth_inside_x64.wrapping_sub(old_last_x64)
+}
+
+#[cfg(test)]
+mod repair_position_checkpoint_test {
+    use super::is_checkpoint_repair_bounded;
+
+    #[test]
+    fn is_checkpoint_repair_bounded_test() {
+        assert!(is_checkpoint_repair_bounded(100, 100, 500));
+        assert!(is_checkpoint_repair_bounded(100, 300, 500));
+        assert!(is_checkpoint_repair_bounded(100, 500, 500));
+        // would credit fees the position never earned
+        assert!(!is_checkpoint_repair_bounded(100, 0, 500));
+        assert!(!is_checkpoint_repair_bounded(100, 99, 500));
+        // beyond the current fee growth
+        assert!(!is_checkpoint_repair_bounded(100, 501, 500));
+
+        // a snapshot corrupted past the current fee growth wraps around to it
+        assert!(is_checkpoint_repair_bounded(u128::MAX - 10, 200, 500));
+        assert!(is_checkpoint_repair_bounded(u128::MAX - 10, u128::MAX, 500));
+        assert!(!is_checkpoint_repair_bounded(u128::MAX - 10, 501, 500));
+        assert!(!is_checkpoint_repair_bounded(
+            u128::MAX - 10,
+            u128::MAX - 11,
+            500
+        ));
+    }
+}
@@ -0,0 +1,27 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::require_not_cpi;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SendAdminHeartbeat<'info> {
+    /// Only admin can send the heartbeat
+    #[account(
+        address = crate::admin::id() @ ErrorCode::NotApproved
+    )]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ADMIN_HEARTBEAT_SEED.as_bytes()],
+        bump = admin_heartbeat.bump,
+    )]
+    pub admin_heartbeat: Box<Account<'info, AdminHeartbeat>>,
+}
+
+pub fn send_admin_heartbeat(ctx: Context<SendAdminHeartbeat>) -> Result<()> {
+    require_not_cpi()?;
+    ctx.accounts.admin_heartbeat.last_heartbeat =
+        u64::try_from(Clock::get()?.unix_timestamp).unwrap();
+    Ok(())
+}
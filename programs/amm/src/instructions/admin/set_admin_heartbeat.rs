@@ -0,0 +1,41 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::require_not_cpi;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetAdminHeartbeat<'info> {
+    /// Only admin can configure the switch
+    #[account(
+        mut,
+        address = crate::admin::id() @ ErrorCode::NotApproved
+    )]
+    pub owner: Signer<'info>,
+
+    /// The dead man's switch, created on first use
+    #[account(
+        init_if_needed,
+        seeds = [ADMIN_HEARTBEAT_SEED.as_bytes()],
+        bump,
+        payer = owner,
+        space = AdminHeartbeat::LEN
+    )]
+    pub admin_heartbeat: Box<Account<'info, AdminHeartbeat>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn set_admin_heartbeat(
+    ctx: Context<SetAdminHeartbeat>,
+    inactivity_period: u64,
+    guardian: Pubkey,
+) -> Result<()> {
+    require_not_cpi()?;
+    let admin_heartbeat = ctx.accounts.admin_heartbeat.as_mut();
+    admin_heartbeat.bump = ctx.bumps.admin_heartbeat;
+    admin_heartbeat.configure(
+        inactivity_period,
+        guardian,
+        u64::try_from(Clock::get()?.unix_timestamp).unwrap(),
+    )
+}
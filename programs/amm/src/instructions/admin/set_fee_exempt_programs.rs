@@ -0,0 +1,40 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::require_not_cpi;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetFeeExemptPrograms<'info> {
+    /// Only admin can set the exempted programs
+    #[account(
+        mut,
+        address = crate::admin::id() @ ErrorCode::NotApproved
+    )]
+    pub owner: Signer<'info>,
+
+    /// The config the exemption applies to
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// The exemption list of the config, created on first use
+    #[account(
+        init_if_needed,
+        seeds = [FEE_EXEMPTION_SEED.as_bytes(), amm_config.key().as_ref()],
+        bump,
+        payer = owner,
+        space = FeeExemption::LEN
+    )]
+    pub fee_exemption: Box<Account<'info, FeeExemption>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn set_fee_exempt_programs(
+    ctx: Context<SetFeeExemptPrograms>,
+    programs: Vec<Pubkey>,
+) -> Result<()> {
+    require_not_cpi()?;
+    let fee_exemption = ctx.accounts.fee_exemption.as_mut();
+    fee_exemption.bump = ctx.bumps.fee_exemption;
+    fee_exemption.amm_config = ctx.accounts.amm_config.key();
+    fee_exemption.set_programs(&programs)
+}
@@ -0,0 +1,40 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::require_not_cpi;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetIncentivePrograms<'info> {
+    /// Only admin can approve incentive programs
+    #[account(
+        mut,
+        address = crate::admin::id() @ ErrorCode::NotApproved
+    )]
+    pub owner: Signer<'info>,
+
+    /// The config the approval applies to
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// The approved programs of the config, created on first use
+    #[account(
+        init_if_needed,
+        seeds = [INCENTIVE_PROGRAMS_SEED.as_bytes(), amm_config.key().as_ref()],
+        bump,
+        payer = owner,
+        space = IncentivePrograms::LEN
+    )]
+    pub incentive_programs: Box<Account<'info, IncentivePrograms>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn set_incentive_programs(
+    ctx: Context<SetIncentivePrograms>,
+    programs: Vec<Pubkey>,
+) -> Result<()> {
+    require_not_cpi()?;
+    let incentive_programs = ctx.accounts.incentive_programs.as_mut();
+    incentive_programs.bump = ctx.bumps.incentive_programs;
+    incentive_programs.amm_config = ctx.accounts.amm_config.key();
+    incentive_programs.set_programs(&programs)
+}
@@ -0,0 +1,30 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::require_not_cpi;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetPoolPartner<'info> {
+    /// Only admin can register a partner
+    #[account(
+        address = crate::admin::id() @ ErrorCode::NotApproved
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The config of the pool, its protocol and fund fee rates bound the partner fee rate
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Account<'info, AmmConfig>,
+}
+
+pub fn set_pool_partner(
+    ctx: Context<SetPoolPartner>,
+    partner: Pubkey,
+    partner_fee_rate: u32,
+) -> Result<()> {
+    require_not_cpi()?;
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    pool_state.set_partner(partner, partner_fee_rate, &ctx.accounts.amm_config)
+}
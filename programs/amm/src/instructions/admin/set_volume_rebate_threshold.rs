@@ -0,0 +1,26 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::require_not_cpi;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetVolumeRebateThreshold<'info> {
+    /// Only admin can set the volume a trader must reach for an epoch to qualify
+    #[account(
+        address = crate::admin::id() @ ErrorCode::NotApproved
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+pub fn set_volume_rebate_threshold(
+    ctx: Context<SetVolumeRebateThreshold>,
+    threshold: u64,
+) -> Result<()> {
+    require_not_cpi()?;
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    pool_state.volume_rebate_threshold = threshold;
+    Ok(())
+}
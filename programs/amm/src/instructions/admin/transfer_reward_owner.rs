@@ -1,5 +1,6 @@
 use crate::error::ErrorCode;
 use crate::states::*;
+use crate::util::require_not_cpi;
 use anchor_lang::prelude::*;
 #[derive(Accounts)]
 pub struct TransferRewardOwner<'info> {
@@ -17,6 +18,7 @@ pub fn transfer_reward_owner<'a, 'b, 'c, 'info>(
     ctx: Context<'a, 'b, 'c, 'info, TransferRewardOwner<'info>>,
     new_owner: Pubkey,
 ) -> Result<()> {
+    require_not_cpi()?;
     let mut pool_state = ctx.accounts.pool_state.load_mut()?;
     for reward_info in &mut pool_state.reward_infos {
         reward_info.authority = new_owner;
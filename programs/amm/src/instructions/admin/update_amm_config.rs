@@ -1,5 +1,6 @@
 use crate::error::ErrorCode;
 use crate::states::*;
+use crate::util::require_not_cpi;
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
@@ -14,20 +15,37 @@ pub struct UpdateAmmConfig<'info> {
 }
 
 pub fn update_amm_config(ctx: Context<UpdateAmmConfig>, param: u8, value: u32) -> Result<()> {
-    let amm_config = &mut ctx.accounts.amm_config;
+    require_not_cpi()?;
+    let key = match param {
+        3 | 4 | 12 => *ctx.remaining_accounts.iter().next().unwrap().key,
+        _ => Pubkey::default(),
+    };
+    apply_amm_config_update(&mut ctx.accounts.amm_config, param, value, key)
+}
+
+/// Applies one `update_amm_config` param, `key` is the new owner, fund owner or swap hook
+/// program for params 3, 4 and 12
+pub(crate) fn apply_amm_config_update(
+    amm_config: &mut Account<AmmConfig>,
+    param: u8,
+    value: u32,
+    key: Pubkey,
+) -> Result<()> {
     let match_param = Some(param);
     match match_param {
         Some(0) => update_trade_fee_rate(amm_config, value),
-        Some(1) => update_protocol_fee_rate(amm_config, value),
+        Some(1) => update_protocol_fee_rate(amm_config, value)?,
         Some(2) => update_fund_fee_rate(amm_config, value),
-        Some(3) => {
-            let new_owner = *ctx.remaining_accounts.iter().next().unwrap().key;
-            set_new_owner(amm_config, new_owner);
-        }
-        Some(4) => {
-            let new_fund_owner = *ctx.remaining_accounts.iter().next().unwrap().key;
-            set_new_fund_owner(amm_config, new_fund_owner);
-        }
+        Some(3) => set_new_owner(amm_config, key),
+        Some(4) => set_new_fund_owner(amm_config, key),
+        Some(5) => update_custom_fee_min_rate(amm_config, value),
+        Some(6) => update_custom_fee_max_rate(amm_config, value),
+        Some(8) => update_referral_fee_rate(amm_config, value),
+        Some(9) => update_volume_rebate_rate(amm_config, value),
+        Some(10) => amm_config.volume_rebate_min_epochs = value,
+        Some(11) => amm_config.paused = value != 0,
+        Some(12) => amm_config.swap_hook_program = key,
+        Some(13) => amm_config.empty_position_grace_epochs = u64::from(value),
         _ => return err!(ErrorCode::InvalidUpdateConfigFlag),
     }
 
@@ -44,10 +62,15 @@ pub fn update_amm_config(ctx: Context<UpdateAmmConfig>, param: u8, value: u32) -
     Ok(())
 }
 
-fn update_protocol_fee_rate(amm_config: &mut Account<AmmConfig>, protocol_fee_rate: u32) {
-    assert!(protocol_fee_rate <= FEE_RATE_DENOMINATOR_VALUE);
-    assert!(protocol_fee_rate + amm_config.fund_fee_rate <= FEE_RATE_DENOMINATOR_VALUE);
+fn update_protocol_fee_rate(
+    amm_config: &mut Account<AmmConfig>,
+    protocol_fee_rate: u32,
+) -> Result<()> {
+    let block_timestamp = u64::try_from(Clock::get()?.unix_timestamp).unwrap();
+    amm_config.check_protocol_fee_rate_update(protocol_fee_rate, block_timestamp)?;
     amm_config.protocol_fee_rate = protocol_fee_rate;
+    amm_config.protocol_fee_rate_updated_at = block_timestamp;
+    Ok(())
 }
 
 fn update_trade_fee_rate(amm_config: &mut Account<AmmConfig>, trade_fee_rate: u32) {
@@ -61,6 +84,35 @@ fn update_fund_fee_rate(amm_config: &mut Account<AmmConfig>, fund_fee_rate: u32)
     amm_config.fund_fee_rate = fund_fee_rate;
 }
 
+fn update_custom_fee_min_rate(amm_config: &mut Account<AmmConfig>, custom_fee_min_rate: u32) {
+    assert!(custom_fee_min_rate <= amm_config.custom_fee_max_rate);
+    amm_config.custom_fee_min_rate = custom_fee_min_rate;
+}
+
+fn update_custom_fee_max_rate(amm_config: &mut Account<AmmConfig>, custom_fee_max_rate: u32) {
+    assert!(custom_fee_max_rate < FEE_RATE_DENOMINATOR_VALUE);
+    if custom_fee_max_rate != 0 {
+        assert!(custom_fee_max_rate >= amm_config.custom_fee_min_rate);
+    } else {
+        amm_config.custom_fee_min_rate = 0;
+    }
+    amm_config.custom_fee_max_rate = custom_fee_max_rate;
+}
+
+fn update_referral_fee_rate(amm_config: &mut Account<AmmConfig>, referral_fee_rate: u32) {
+    assert!(referral_fee_rate <= FEE_RATE_DENOMINATOR_VALUE);
+    amm_config.referral_fee_rate = referral_fee_rate;
+}
+
+fn update_volume_rebate_rate(amm_config: &mut Account<AmmConfig>, volume_rebate_rate: u32) {
+    // the top tier rebate can't exceed the protocol fee
+    assert!(
+        u64::from(volume_rebate_rate) * u64::from(MAX_VOLUME_REBATE_TIER)
+            <= u64::from(FEE_RATE_DENOMINATOR_VALUE)
+    );
+    amm_config.volume_rebate_rate = volume_rebate_rate;
+}
+
 fn set_new_owner(amm_config: &mut Account<AmmConfig>, new_owner: Pubkey) {
     #[cfg(feature = "enable-log")]
     msg!(
@@ -1,5 +1,6 @@
 use crate::error::ErrorCode;
 use crate::states::*;
+use crate::util::require_not_cpi;
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
@@ -28,6 +29,7 @@ pub fn update_operation_account(
     param: u8,
     keys: Vec<Pubkey>,
 ) -> Result<()> {
+    require_not_cpi()?;
     let mut operation_state = ctx.accounts.operation_state.load_mut()?;
     let match_param = Some(param);
     match match_param {
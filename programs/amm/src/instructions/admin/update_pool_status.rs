@@ -1,4 +1,5 @@
 use crate::states::*;
+use crate::util::require_not_cpi;
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
@@ -13,6 +14,7 @@ pub struct UpdatePoolStatus<'info> {
 }
 
 pub fn update_pool_status(ctx: Context<UpdatePoolStatus>, status: u8) -> Result<()> {
+    require_not_cpi()?;
     require_gte!(255, status);
     let mut pool_state = ctx.accounts.pool_state.load_mut()?;
     pool_state.set_status(status);
@@ -0,0 +1,22 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CancelStopOrder<'info> {
+    /// The owner who placed the order
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The stop order to cancel, the rent and keeper reward are returned to the owner
+    #[account(
+        mut,
+        constraint = stop_order.owner == owner.key() @ ErrorCode::NotApproved,
+        close = owner
+    )]
+    pub stop_order: Box<Account<'info, StopOrderState>>,
+}
+
+pub fn cancel_stop_order(_ctx: Context<CancelStopOrder>) -> Result<()> {
+    Ok(())
+}
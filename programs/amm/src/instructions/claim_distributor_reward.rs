@@ -0,0 +1,77 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::transfer_from_distributor_vault_to_user;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct ClaimDistributorReward<'info> {
+    /// The staker
+    pub owner: Signer<'info>,
+
+    #[account(mut)]
+    pub fee_distributor: Box<Account<'info, FeeDistributorState>>,
+
+    #[account(
+        mut,
+        seeds = [
+            FEE_STAKER_SEED.as_bytes(),
+            fee_distributor.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump = fee_staker.bump,
+        constraint = fee_staker.owner == owner.key() @ ErrorCode::NotApproved
+    )]
+    pub fee_staker: Box<Account<'info, FeeStakerState>>,
+
+    /// The token account to receive the reward
+    #[account(
+        mut,
+        token::mint = reward_mint,
+    )]
+    pub recipient_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The distributor reward vault
+    #[account(
+        mut,
+        address = fee_distributor.reward_vault
+    )]
+    pub reward_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of the reward token
+    #[account(
+        address = fee_distributor.reward_mint,
+        mint::token_program = token_program,
+    )]
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Spl token program or token program 2022 of reward mint
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn claim_distributor_reward(ctx: Context<ClaimDistributorReward>) -> Result<()> {
+    let block_timestamp = u64::try_from(Clock::get()?.unix_timestamp).unwrap();
+    let fee_distributor = ctx.accounts.fee_distributor.as_mut();
+    fee_distributor.update_epoch(block_timestamp);
+
+    let fee_staker = ctx.accounts.fee_staker.as_mut();
+    fee_staker.settle(fee_distributor.reward_per_share_x64);
+    let amount = fee_staker.reward_owed;
+    fee_staker.reward_owed = 0;
+
+    transfer_from_distributor_vault_to_user(
+        &ctx.accounts.fee_distributor,
+        &ctx.accounts.reward_vault.to_account_info(),
+        &ctx.accounts.recipient_token_account.to_account_info(),
+        &ctx.accounts.reward_mint,
+        &ctx.accounts.token_program.to_account_info(),
+        amount,
+    )?;
+
+    emit!(ClaimDistributorRewardEvent {
+        fee_distributor: ctx.accounts.fee_distributor.key(),
+        owner: ctx.accounts.owner.key(),
+        amount,
+    });
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::is_authorized_for_token;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+#[derive(Accounts)]
+#[instruction(bundle_index: u16)]
+pub struct CloseBundledPosition<'info> {
+    /// The bundle NFT owner or delegate, receives the position rent
+    #[account(mut)]
+    pub bundle_authority: Signer<'info>,
+
+    /// The token account holding the bundle NFT
+    #[account(
+        constraint = is_authorized_for_token(
+            &bundle_authority.key(),
+            &position_bundle_token_account.to_account_info(),
+            &position_bundle.position_bundle_mint
+        ) @ ErrorCode::NotApproved
+    )]
+    pub position_bundle_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The bundle the position is open in
+    #[account(mut)]
+    pub position_bundle: Box<Account<'info, PositionBundle>>,
+
+    /// The bundled position to close
+    #[account(
+        mut,
+        seeds = [
+            POSITION_SEED.as_bytes(),
+            position_bundle.position_bundle_mint.as_ref(),
+            &bundle_index.to_be_bytes(),
+        ],
+        bump,
+        close = bundle_authority
+    )]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+}
+
+/// Close the empty position in slot `bundle_index`, freeing the slot for reuse.
+pub fn close_bundled_position(ctx: Context<CloseBundledPosition>, bundle_index: u16) -> Result<()> {
+    let personal_position = &ctx.accounts.personal_position;
+    require!(
+        personal_position.liquidity == 0
+            && personal_position.token_fees_owed_0 == 0
+            && personal_position.token_fees_owed_1 == 0
+            && personal_position
+                .reward_infos
+                .iter()
+                .all(|r| r.reward_amount_owed == 0),
+        ErrorCode::ClosePositionErr
+    );
+    emit!(ClosePersonalPositionEvent {
+        position_nft_mint: personal_position.nft_mint,
+        pool_state: personal_position.pool_id,
+        tick_lower_index: personal_position.tick_lower_index,
+        tick_upper_index: personal_position.tick_upper_index,
+        position_hash: personal_position.position_hash(),
+    });
+    ctx.accounts.position_bundle.close_slot(bundle_index)
+}
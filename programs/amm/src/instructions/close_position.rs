@@ -32,8 +32,7 @@ pub struct ClosePosition<'info> {
     #[account(
         mut, 
         seeds = [POSITION_SEED.as_bytes(), position_nft_mint.key().as_ref()],
-        bump,
-        close = nft_owner
+        bump
     )]
     pub personal_position: Box<Account<'info, PersonalPositionState>>,
 
@@ -42,6 +41,11 @@ pub struct ClosePosition<'info> {
 
     /// Token/Token2022 program to close token/mint account
     pub token_program: Interface<'info, TokenInterface>,
+
+    /// CHECK: Receives the rent of the closed accounts, such as the payer who sponsored the
+    /// position's creation. The nft owner gets it when not provided
+    #[account(mut)]
+    pub rent_recipient: Option<UncheckedAccount<'info>>,
 }
 
 pub fn close_position<'a, 'b, 'c, 'info>(
@@ -71,6 +75,10 @@ pub fn close_position<'a, 'b, 'c, 'info>(
         }
     }
 
+    let rent_recipient = match &ctx.accounts.rent_recipient {
+        Some(rent_recipient) => rent_recipient.to_account_info(),
+        None => ctx.accounts.nft_owner.to_account_info(),
+    };
     let token_program = ctx.accounts.token_program.to_account_info();
     let position_nft_mint = ctx.accounts.position_nft_mint.to_account_info();
     let personal_nft_account = ctx.accounts.position_nft_account.to_account_info();
@@ -86,7 +94,7 @@ pub fn close_position<'a, 'b, 'c, 'info>(
     // close use nft token account
     close_spl_account(
         &ctx.accounts.nft_owner,
-        &ctx.accounts.nft_owner,
+        &rent_recipient,
         &personal_nft_account,
         &token_program,
         &[],
@@ -96,11 +104,20 @@ pub fn close_position<'a, 'b, 'c, 'info>(
         // close nft mint account
         close_spl_account(
             &ctx.accounts.personal_position.to_account_info(),
-            &ctx.accounts.nft_owner,
+            &rent_recipient,
             &position_nft_mint,
             &token_program,
             &[&ctx.accounts.personal_position.seeds()],
         )?;
     }
-    Ok(())
+
+    let personal_position = &ctx.accounts.personal_position;
+    emit!(ClosePersonalPositionEvent {
+        position_nft_mint: personal_position.nft_mint,
+        pool_state: personal_position.pool_id,
+        tick_lower_index: personal_position.tick_lower_index,
+        tick_upper_index: personal_position.tick_upper_index,
+        position_hash: personal_position.position_hash(),
+    });
+    ctx.accounts.personal_position.close(rent_recipient)
 }
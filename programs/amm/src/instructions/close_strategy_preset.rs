@@ -0,0 +1,20 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CloseStrategyPreset<'info> {
+    /// The preset creator, receives the rent
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = authority,
+        close = authority
+    )]
+    pub strategy_preset: Box<Account<'info, StrategyPreset>>,
+}
+
+pub fn close_strategy_preset(_ctx: Context<CloseStrategyPreset>) -> Result<()> {
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use super::decrease_liquidity::{
+    check_unclaimed_fees_and_vault, collect_rewards, decrease_liquidity_and_update_position,
+    restore_fees_owed,
+};
+use super::decrease_liquidity_v2::DecreaseLiquidityV2;
+use crate::states::*;
+use crate::util::transfer_from_pool_vault_to_user;
+use anchor_lang::prelude::*;
+
+/// Settles the position's fees and rewards and transfers up to the requested amounts of each,
+/// what is left stays owed to the position
+pub fn collect_all<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, DecreaseLiquidityV2<'info>>,
+    amount_0_max: u64,
+    amount_1_max: u64,
+    reward_amounts_max: [u64; REWARD_NUM],
+) -> Result<()> {
+    ctx.accounts
+        .check_operator(ctx.remaining_accounts, POSITION_DELEGATE_SCOPE_COLLECT)?;
+    let accounts = ctx.accounts;
+    // no liquidity is removed, so the tick array bitmap extension is never needed
+    let (_, fees_0, _, fees_1) = decrease_liquidity_and_update_position(
+        &accounts.pool_state,
+        &mut accounts.protocol_position,
+        &mut accounts.personal_position,
+        &accounts.tick_array_lower,
+        &accounts.tick_array_upper,
+        None,
+        0,
+    )?;
+    let amount_0 = fees_0.min(amount_0_max);
+    let amount_1 = fees_1.min(amount_1_max);
+    {
+        let mut pool_state = accounts.pool_state.load_mut()?;
+        restore_fees_owed(
+            &mut pool_state,
+            &mut accounts.personal_position,
+            fees_0 - amount_0,
+            fees_1 - amount_1,
+        );
+    }
+
+    transfer_from_pool_vault_to_user(
+        &accounts.pool_state,
+        &accounts.token_vault_0.to_account_info(),
+        &accounts.recipient_token_account_0.to_account_info(),
+        Some(accounts.vault_0_mint.clone()),
+        &accounts.token_program,
+        Some(accounts.token_program_2022.to_account_info()),
+        amount_0,
+    )?;
+    transfer_from_pool_vault_to_user(
+        &accounts.pool_state,
+        &accounts.token_vault_1.to_account_info(),
+        &accounts.recipient_token_account_1.to_account_info(),
+        Some(accounts.vault_1_mint.clone()),
+        &accounts.token_program,
+        Some(accounts.token_program_2022.to_account_info()),
+        amount_1,
+    )?;
+    check_unclaimed_fees_and_vault(
+        &accounts.pool_state,
+        &accounts.token_vault_0.to_account_info(),
+        &accounts.token_vault_1.to_account_info(),
+    )?;
+
+    let reward_accounts: Vec<&'info AccountInfo<'info>> = ctx.remaining_accounts.iter().collect();
+    let reward_amounts = collect_rewards(
+        &accounts.pool_state,
+        &reward_accounts,
+        &accounts.token_program,
+        Some(accounts.token_program_2022.to_account_info()),
+        &mut accounts.personal_position,
+        true,
+        reward_amounts_max,
+    )?;
+
+    emit!(CollectAllEvent {
+        position_nft_mint: accounts.personal_position.nft_mint,
+        recipient_token_account_0: accounts.recipient_token_account_0.key(),
+        recipient_token_account_1: accounts.recipient_token_account_1.key(),
+        amount_0,
+        amount_1,
+        reward_amounts,
+    });
+    Ok(())
+}
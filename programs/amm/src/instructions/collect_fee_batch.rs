@@ -0,0 +1,224 @@
+use crate::decrease_liquidity::{
+    check_unclaimed_fees_and_vault, decrease_liquidity_and_update_position,
+};
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+/// The most positions a batch can collect from
+pub const MAX_BATCH_COLLECT_POSITIONS: usize = 10;
+/// The accounts every batched position takes: nft_account, personal_position, protocol_position,
+/// tick_array_lower and tick_array_upper
+pub const BATCH_COLLECT_POSITION_ACCOUNTS: usize = 5;
+
+/// Checks the remaining accounts hold between one and `MAX_BATCH_COLLECT_POSITIONS` whole
+/// position groups, returns the number of positions
+pub fn check_collect_fee_batch_accounts(remaining_accounts_len: usize) -> Result<usize> {
+    let positions = remaining_accounts_len / BATCH_COLLECT_POSITION_ACCOUNTS;
+    require!(
+        positions > 0 && positions <= MAX_BATCH_COLLECT_POSITIONS,
+        ErrorCode::InvalidBatchPositions
+    );
+    require_eq!(
+        positions * BATCH_COLLECT_POSITION_ACCOUNTS,
+        remaining_accounts_len,
+        ErrorCode::InvalidBatchPositions
+    );
+    Ok(positions)
+}
+
+#[derive(Accounts)]
+pub struct CollectFeeBatch<'info> {
+    /// The owner, delegated authority or operator of every position in the batch
+    pub nft_owner: Signer<'info>,
+
+    /// The pool all the positions belong to
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        mut,
+        constraint = token_vault_0.key() == pool_state.load()?.token_vault_0
+    )]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        mut,
+        constraint = token_vault_1.key() == pool_state.load()?.token_vault_1
+    )]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of token vault 0
+    #[account(address = token_vault_0.mint)]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token vault 1
+    #[account(address = token_vault_1.mint)]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The destination token account for the fees of all the positions in token_0
+    #[account(
+        mut,
+        token::mint = token_vault_0.mint
+    )]
+    pub recipient_token_account_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The destination token account for the fees of all the positions in token_1
+    #[account(
+        mut,
+        token::mint = token_vault_1.mint
+    )]
+    pub recipient_token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// SPL program to transfer out tokens
+    pub token_program: Program<'info, Token>,
+    /// Token program 2022
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// The delegation of the nft holder, when an operator signs
+    pub position_delegate: Option<Account<'info, PositionDelegate>>,
+    // remaining accounts
+    // per position: nft_account, personal_position, protocol_position, tick_array_lower and
+    // tick_array_upper
+}
+
+/// Collects the fees owed to every position of the batch, transferring their sum to the
+/// recipients at once
+pub fn collect_fee_batch<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, CollectFeeBatch<'info>>,
+) -> Result<()> {
+    check_collect_fee_batch_accounts(ctx.remaining_accounts.len())?;
+    let pool_id = ctx.accounts.pool_state.key();
+
+    let mut total_fees_0: u64 = 0;
+    let mut total_fees_1: u64 = 0;
+    for position_accounts in ctx
+        .remaining_accounts
+        .chunks_exact(BATCH_COLLECT_POSITION_ACCOUNTS)
+    {
+        // each position deserializes its accounts afresh, so a protocol position shared with an
+        // earlier position of the batch is read with its updated state
+        let mut personal_position = Box::new(Account::<PersonalPositionState>::try_from(
+            &position_accounts[1],
+        )?);
+        require_keys_eq!(personal_position.pool_id, pool_id);
+        if !is_authorized_for_token(
+            &ctx.accounts.nft_owner.key(),
+            &position_accounts[0],
+            &personal_position.nft_mint,
+        ) {
+            require!(
+                is_authorized_for_position(
+                    &ctx.accounts.nft_owner.key(),
+                    &position_accounts[0],
+                    &personal_position.nft_mint,
+                    ctx.accounts.position_delegate.as_deref(),
+                    POSITION_DELEGATE_SCOPE_COLLECT,
+                ),
+                ErrorCode::NotApproved
+            );
+            // an operator collects into the holder's accounts
+            let holder = ctx.accounts.position_delegate.as_ref().unwrap().owner;
+            require!(
+                ctx.accounts.recipient_token_account_0.owner == holder
+                    && ctx.accounts.recipient_token_account_1.owner == holder,
+                ErrorCode::NotApproved
+            );
+        }
+        let mut protocol_position = Box::new(Account::<ProtocolPositionState>::try_from(
+            &position_accounts[2],
+        )?);
+        require_keys_eq!(protocol_position.pool_id, pool_id);
+        require_eq!(
+            protocol_position.tick_lower_index,
+            personal_position.tick_lower_index
+        );
+        require_eq!(
+            protocol_position.tick_upper_index,
+            personal_position.tick_upper_index
+        );
+        let tick_array_lower = AccountLoader::<TickArrayState>::try_from(&position_accounts[3])?;
+        let tick_array_upper = AccountLoader::<TickArrayState>::try_from(&position_accounts[4])?;
+
+        // no liquidity is removed, so no tick flips and the bitmap extension is never touched
+        let (_, fees_0, _, fees_1) = decrease_liquidity_and_update_position(
+            &ctx.accounts.pool_state,
+            &mut protocol_position,
+            &mut personal_position,
+            &tick_array_lower,
+            &tick_array_upper,
+            None,
+            0,
+        )?;
+        protocol_position.exit(&crate::id())?;
+        personal_position.exit(&crate::id())?;
+
+        total_fees_0 = total_fees_0.checked_add(fees_0).unwrap();
+        total_fees_1 = total_fees_1.checked_add(fees_1).unwrap();
+        emit!(CollectPersonalFeeEvent {
+            position_nft_mint: personal_position.nft_mint,
+            recipient_token_account_0: ctx.accounts.recipient_token_account_0.key(),
+            recipient_token_account_1: ctx.accounts.recipient_token_account_1.key(),
+            amount_0: fees_0,
+            amount_1: fees_1,
+        });
+    }
+
+    transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_0.to_account_info(),
+        &ctx.accounts.recipient_token_account_0.to_account_info(),
+        Some(ctx.accounts.vault_0_mint.clone()),
+        &ctx.accounts.token_program,
+        Some(ctx.accounts.token_program_2022.to_account_info()),
+        total_fees_0,
+    )?;
+
+    transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_1.to_account_info(),
+        &ctx.accounts.recipient_token_account_1.to_account_info(),
+        Some(ctx.accounts.vault_1_mint.clone()),
+        &ctx.accounts.token_program,
+        Some(ctx.accounts.token_program_2022.to_account_info()),
+        total_fees_1,
+    )?;
+
+    check_unclaimed_fees_and_vault(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_0.to_account_info(),
+        &ctx.accounts.token_vault_1.to_account_info(),
+    )
+}
+
+#[cfg(test)]
+mod collect_fee_batch_test {
+    use super::*;
+
+    #[test]
+    fn check_collect_fee_batch_accounts_test() {
+        assert_eq!(
+            check_collect_fee_batch_accounts(BATCH_COLLECT_POSITION_ACCOUNTS).unwrap(),
+            1
+        );
+        assert_eq!(
+            check_collect_fee_batch_accounts(
+                MAX_BATCH_COLLECT_POSITIONS * BATCH_COLLECT_POSITION_ACCOUNTS
+            )
+            .unwrap(),
+            MAX_BATCH_COLLECT_POSITIONS
+        );
+        // a partial position group
+        assert!(check_collect_fee_batch_accounts(BATCH_COLLECT_POSITION_ACCOUNTS + 2).is_err());
+        assert!(check_collect_fee_batch_accounts(0).is_err());
+        assert!(check_collect_fee_batch_accounts(
+            (MAX_BATCH_COLLECT_POSITIONS + 1) * BATCH_COLLECT_POSITION_ACCOUNTS
+        )
+        .is_err());
+    }
+}
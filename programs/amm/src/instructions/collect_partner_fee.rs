@@ -0,0 +1,136 @@
+use crate::decrease_liquidity::check_unclaimed_fees_and_vault;
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct CollectPartnerFee<'info> {
+    /// Only the partner registered on the pool can collect its fees
+    #[account(address = pool_state.load()?.partner @ ErrorCode::NotApproved)]
+    pub partner: Signer<'info>,
+
+    /// Pool state stores accumulated partner fee amount
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        mut,
+        constraint = token_vault_0.key() == pool_state.load()?.token_vault_0
+    )]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        mut,
+        constraint = token_vault_1.key() == pool_state.load()?.token_vault_1
+    )]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of token vault 0
+    #[account(
+        address = token_vault_0.mint,
+        constraint = pool_state.load()?.is_valid_token_program(
+            &vault_0_mint.key(),
+            vault_0_mint.to_account_info().owner
+        ) @ ErrorCode::InvalidTokenProgram
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token vault 1
+    #[account(
+        address = token_vault_1.mint,
+        constraint = pool_state.load()?.is_valid_token_program(
+            &vault_1_mint.key(),
+            vault_1_mint.to_account_info().owner
+        ) @ ErrorCode::InvalidTokenProgram
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The partner's token account that receives the collected token_0 fees
+    #[account(mut, token::authority = partner)]
+    pub recipient_token_account_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The partner's token account that receives the collected token_1 fees
+    #[account(mut, token::authority = partner)]
+    pub recipient_token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The SPL program to perform token transfers
+    pub token_program: Program<'info, Token>,
+
+    /// The SPL program 2022 to perform token transfers
+    pub token_program_2022: Program<'info, Token2022>,
+}
+
+pub fn collect_partner_fee(
+    ctx: Context<CollectPartnerFee>,
+    amount_0_requested: u64,
+    amount_1_requested: u64,
+) -> Result<()> {
+    let amount_0: u64;
+    let amount_1: u64;
+    let remaining_fees_token_0: u64;
+    let remaining_fees_token_1: u64;
+    {
+        let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+        amount_0 = amount_0_requested.min(pool_state.partner_fees_token_0);
+        amount_1 = amount_1_requested.min(pool_state.partner_fees_token_1);
+
+        pool_state.partner_fees_token_0 = pool_state
+            .partner_fees_token_0
+            .checked_sub(amount_0)
+            .unwrap();
+        pool_state.partner_fees_token_1 = pool_state
+            .partner_fees_token_1
+            .checked_sub(amount_1)
+            .unwrap();
+        remaining_fees_token_0 = pool_state.partner_fees_token_0;
+        remaining_fees_token_1 = pool_state.partner_fees_token_1;
+    }
+    transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_0.to_account_info(),
+        &ctx.accounts.recipient_token_account_0.to_account_info(),
+        Some(ctx.accounts.vault_0_mint.clone()),
+        &ctx.accounts.token_program,
+        Some(ctx.accounts.token_program_2022.to_account_info()),
+        amount_0,
+    )?;
+
+    transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_1.to_account_info(),
+        &ctx.accounts.recipient_token_account_1.to_account_info(),
+        Some(ctx.accounts.vault_1_mint.clone()),
+        &ctx.accounts.token_program,
+        Some(ctx.accounts.token_program_2022.to_account_info()),
+        amount_1,
+    )?;
+
+    check_unclaimed_fees_and_vault(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_0.to_account_info(),
+        &ctx.accounts.token_vault_1.to_account_info(),
+    )?;
+
+    ctx.accounts.token_vault_0.reload()?;
+    ctx.accounts.token_vault_1.reload()?;
+    emit!(CollectProtocolFeeEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        recipient_token_account_0: ctx.accounts.recipient_token_account_0.key(),
+        recipient_token_account_1: ctx.accounts.recipient_token_account_1.key(),
+        amount_0,
+        amount_1,
+        amount_0_requested,
+        amount_1_requested,
+        remaining_fees_token_0,
+        remaining_fees_token_1,
+        vault_0_balance: ctx.accounts.token_vault_0.amount,
+        vault_1_balance: ctx.accounts.token_vault_1.amount,
+    });
+
+    Ok(())
+}
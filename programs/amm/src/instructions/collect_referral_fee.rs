@@ -0,0 +1,139 @@
+use crate::decrease_liquidity::check_unclaimed_fees_and_vault;
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct CollectReferralFee<'info> {
+    /// Only the referrer of the referral account can collect its fees
+    pub referrer: Signer<'info>,
+
+    /// Pool state stores the referral fees owed to all referrers
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Referral account stores the fees owed to the referrer
+    #[account(
+        mut,
+        has_one = referrer @ ErrorCode::NotApproved,
+        constraint = referral_account.pool_id == pool_state.key() @ ErrorCode::InvalidReferralAccount
+    )]
+    pub referral_account: Box<Account<'info, ReferralAccount>>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        mut,
+        constraint = token_vault_0.key() == pool_state.load()?.token_vault_0
+    )]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        mut,
+        constraint = token_vault_1.key() == pool_state.load()?.token_vault_1
+    )]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of token vault 0
+    #[account(
+        address = token_vault_0.mint,
+        constraint = pool_state.load()?.is_valid_token_program(
+            &vault_0_mint.key(),
+            vault_0_mint.to_account_info().owner
+        ) @ ErrorCode::InvalidTokenProgram
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token vault 1
+    #[account(
+        address = token_vault_1.mint,
+        constraint = pool_state.load()?.is_valid_token_program(
+            &vault_1_mint.key(),
+            vault_1_mint.to_account_info().owner
+        ) @ ErrorCode::InvalidTokenProgram
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The referrer's token account that receives the collected token_0 fees
+    #[account(mut, token::authority = referrer)]
+    pub recipient_token_account_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The referrer's token account that receives the collected token_1 fees
+    #[account(mut, token::authority = referrer)]
+    pub recipient_token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The SPL program to perform token transfers
+    pub token_program: Program<'info, Token>,
+
+    /// The SPL program 2022 to perform token transfers
+    pub token_program_2022: Program<'info, Token2022>,
+}
+
+pub fn collect_referral_fee(
+    ctx: Context<CollectReferralFee>,
+    amount_0_requested: u64,
+    amount_1_requested: u64,
+) -> Result<()> {
+    let referral_account = &mut ctx.accounts.referral_account;
+    let amount_0 = amount_0_requested.min(referral_account.fees_token_0);
+    let amount_1 = amount_1_requested.min(referral_account.fees_token_1);
+    referral_account.fees_token_0 = referral_account.fees_token_0.checked_sub(amount_0).unwrap();
+    referral_account.fees_token_1 = referral_account.fees_token_1.checked_sub(amount_1).unwrap();
+    {
+        let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+        pool_state.referral_fees_token_0 = pool_state
+            .referral_fees_token_0
+            .checked_sub(amount_0)
+            .unwrap();
+        pool_state.referral_fees_token_1 = pool_state
+            .referral_fees_token_1
+            .checked_sub(amount_1)
+            .unwrap();
+    }
+    transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_0.to_account_info(),
+        &ctx.accounts.recipient_token_account_0.to_account_info(),
+        Some(ctx.accounts.vault_0_mint.clone()),
+        &ctx.accounts.token_program,
+        Some(ctx.accounts.token_program_2022.to_account_info()),
+        amount_0,
+    )?;
+
+    transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_1.to_account_info(),
+        &ctx.accounts.recipient_token_account_1.to_account_info(),
+        Some(ctx.accounts.vault_1_mint.clone()),
+        &ctx.accounts.token_program,
+        Some(ctx.accounts.token_program_2022.to_account_info()),
+        amount_1,
+    )?;
+
+    check_unclaimed_fees_and_vault(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_0.to_account_info(),
+        &ctx.accounts.token_vault_1.to_account_info(),
+    )?;
+
+    ctx.accounts.token_vault_0.reload()?;
+    ctx.accounts.token_vault_1.reload()?;
+    emit!(CollectProtocolFeeEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        recipient_token_account_0: ctx.accounts.recipient_token_account_0.key(),
+        recipient_token_account_1: ctx.accounts.recipient_token_account_1.key(),
+        amount_0,
+        amount_1,
+        amount_0_requested,
+        amount_1_requested,
+        remaining_fees_token_0: ctx.accounts.referral_account.fees_token_0,
+        remaining_fees_token_1: ctx.accounts.referral_account.fees_token_1,
+        vault_0_balance: ctx.accounts.token_vault_0.amount,
+        vault_1_balance: ctx.accounts.token_vault_1.amount,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CommitLiquiditySnapshot<'info> {
+    /// Anyone can commit the snapshot of the current epoch
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The pool the snapshot is taken of
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Initialize an account to store the snapshot of the current epoch
+    #[account(
+        init,
+        seeds = [
+            LIQUIDITY_SNAPSHOT_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &Clock::get()?.epoch.to_be_bytes(),
+        ],
+        bump,
+        payer = payer,
+        space = LiquiditySnapshot::LEN
+    )]
+    pub liquidity_snapshot: Box<Account<'info, LiquiditySnapshot>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn commit_liquidity_snapshot(
+    ctx: Context<CommitLiquiditySnapshot>,
+    merkle_root: [u8; 32],
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let block_timestamp = u64::try_from(clock.unix_timestamp).unwrap();
+    let pool_state = ctx.accounts.pool_state.load()?;
+
+    let liquidity_snapshot = ctx.accounts.liquidity_snapshot.as_mut();
+    liquidity_snapshot.bump = ctx.bumps.liquidity_snapshot;
+    liquidity_snapshot.pool_id = ctx.accounts.pool_state.key();
+    liquidity_snapshot.epoch = clock.epoch;
+    liquidity_snapshot.submitter = ctx.accounts.payer.key();
+    liquidity_snapshot.merkle_root = merkle_root;
+    liquidity_snapshot.pool_liquidity = pool_state.liquidity;
+    liquidity_snapshot.tick_current = pool_state.tick_current;
+    liquidity_snapshot.challenge_end_time = block_timestamp + SNAPSHOT_CHALLENGE_WINDOW;
+
+    emit!(LiquiditySnapshotEvent {
+        liquidity_snapshot: liquidity_snapshot.key(),
+        pool_state: liquidity_snapshot.pool_id,
+        epoch: liquidity_snapshot.epoch,
+        merkle_root,
+        pool_liquidity: liquidity_snapshot.pool_liquidity,
+        challenge_end_time: liquidity_snapshot.challenge_end_time,
+    });
+    Ok(())
+}
@@ -0,0 +1,151 @@
+use super::decrease_liquidity::{decrease_liquidity_and_update_position, restore_fees_owed};
+use super::increase_liquidity::credit_position_liquidity;
+use super::open_position::add_liquidity_to_range;
+use crate::error::ErrorCode;
+use crate::libraries::{liquidity_math, tick_math};
+use crate::states::*;
+use crate::util::{is_authorized_for_position, AccountLoad};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+#[derive(Accounts)]
+pub struct CompoundFees<'info> {
+    /// The position owner, delegated authority or operator
+    pub nft_owner: Signer<'info>,
+
+    /// The token account for nft
+    #[account(
+        constraint = is_authorized_for_position(
+            &nft_owner.key(),
+            &nft_account.to_account_info(),
+            &personal_position.nft_mint,
+            position_delegate.as_deref(),
+            POSITION_DELEGATE_SCOPE_COMPOUND,
+        ) @ ErrorCode::NotApproved,
+    )]
+    pub nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The pool's config, liquidity can't be added while it is paused
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    #[account(
+        mut,
+        seeds = [
+            POSITION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &personal_position.tick_lower_index.to_be_bytes(),
+            &personal_position.tick_upper_index.to_be_bytes(),
+        ],
+        bump,
+        constraint = protocol_position.pool_id == pool_state.key(),
+    )]
+    pub protocol_position: Box<Account<'info, ProtocolPositionState>>,
+
+    /// The position whose fees are compounded
+    #[account(mut, constraint = personal_position.pool_id == pool_state.key())]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    /// Stores init state for the lower tick
+    #[account(mut, constraint = tick_array_lower.load()?.pool_id == pool_state.key())]
+    pub tick_array_lower: AccountLoader<'info, TickArrayState>,
+
+    /// Stores init state for the upper tick
+    #[account(mut, constraint = tick_array_upper.load()?.pool_id == pool_state.key())]
+    pub tick_array_upper: AccountLoader<'info, TickArrayState>,
+
+    /// The delegation of the nft holder, when an operator signs
+    pub position_delegate: Option<Account<'info, PositionDelegate>>,
+    // remaining account
+    // the tick array bitmap extension if the position needs it
+}
+
+/// Settles the position's fees and adds as much of them as the range takes at the current
+/// price back to its liquidity. The tokens never leave the vaults, the rest stays owed
+pub fn compound_fees<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, CompoundFees<'info>>,
+) -> Result<()> {
+    let accounts = ctx.accounts;
+    let tick_lower_index = accounts.personal_position.tick_lower_index;
+    let tick_upper_index = accounts.personal_position.tick_upper_index;
+    let tick_array_bitmap_extension = if accounts
+        .pool_state
+        .load()?
+        .is_overflow_default_tickarray_bitmap(vec![tick_lower_index, tick_upper_index])
+    {
+        require_keys_eq!(
+            ctx.remaining_accounts[0].key(),
+            TickArrayBitmapExtension::key(accounts.pool_state.key())
+        );
+        Some(&ctx.remaining_accounts[0])
+    } else {
+        None
+    };
+
+    let (_, fees_0, _, fees_1) = decrease_liquidity_and_update_position(
+        &accounts.pool_state,
+        &mut accounts.protocol_position,
+        &mut accounts.personal_position,
+        &accounts.tick_array_lower,
+        &accounts.tick_array_upper,
+        tick_array_bitmap_extension,
+        0,
+    )?;
+
+    let mut pool_state = accounts.pool_state.load_mut()?;
+    if !pool_state.get_status_by_bit(PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity)
+        || !accounts
+            .amm_config
+            .get_status_by_bit(PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity)
+    {
+        return err!(ErrorCode::NotApproved);
+    }
+    // the deposit rounds up, leave a unit of slack so it never exceeds the fees
+    let liquidity = liquidity_math::get_liquidity_from_amounts(
+        pool_state.sqrt_price_x64,
+        tick_math::get_sqrt_price_at_tick(tick_lower_index)?,
+        tick_math::get_sqrt_price_at_tick(tick_upper_index)?,
+        fees_0.saturating_sub(1),
+        fees_1.saturating_sub(1),
+    );
+    let (mut amount_0, mut amount_1) = (0, 0);
+    if liquidity > 0 {
+        (amount_0, amount_1) = add_liquidity_to_range(
+            &AccountLoad::<TickArrayState>::try_from(&accounts.tick_array_lower.to_account_info())?,
+            &AccountLoad::<TickArrayState>::try_from(&accounts.tick_array_upper.to_account_info())?,
+            &mut accounts.protocol_position,
+            tick_array_bitmap_extension,
+            &mut pool_state,
+            liquidity,
+            tick_lower_index,
+            tick_upper_index,
+        )?;
+    }
+    credit_position_liquidity(
+        &mut accounts.personal_position,
+        &accounts.protocol_position,
+        liquidity,
+    )?;
+
+    // the fees the range did not take stay owed and unclaimed
+    let personal_position = &mut accounts.personal_position;
+    restore_fees_owed(
+        &mut pool_state,
+        personal_position,
+        fees_0.checked_sub(amount_0).unwrap(),
+        fees_1.checked_sub(amount_1).unwrap(),
+    );
+
+    emit!(CompoundFeesEvent {
+        position_nft_mint: personal_position.nft_mint,
+        liquidity,
+        amount_0,
+        amount_1,
+        token_fees_owed_0: personal_position.token_fees_owed_0,
+        token_fees_owed_1: personal_position.token_fees_owed_1,
+    });
+    Ok(())
+}
@@ -0,0 +1,277 @@
+use crate::error::ErrorCode;
+use crate::instructions::quote_at_sqrt_price;
+use crate::libraries::{full_math::MulDiv, tick_math};
+use crate::states::*;
+use crate::swap::swap_internal;
+use crate::util::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+use std::collections::VecDeque;
+use std::ops::Deref;
+
+#[derive(Accounts)]
+pub struct CrankBuyback<'info> {
+    /// Anyone can crank once the interval has passed
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [BUYBACK_SEED.as_bytes(), pool_state.key().as_ref()],
+        bump = buyback_state.bump[0],
+    )]
+    pub buyback_state: Box<Account<'info, BuybackState>>,
+
+    /// The factory state to read protocol fees
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// The pool whose protocol fee is swapped
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The program account for the most recent oracle observation
+    #[account(mut, address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+
+    /// The pool vault of the buyback token
+    #[account(
+        mut,
+        constraint = (output_vault.key() == pool_state.load()?.token_vault_0
+            || output_vault.key() == pool_state.load()?.token_vault_1)
+            && output_vault.mint == buyback_state.buyback_mint
+    )]
+    pub output_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The vault receiving the bought tokens
+    #[account(mut, address = buyback_state.buyback_vault)]
+    pub buyback_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The buyback token mint
+    #[account(mut, address = buyback_state.buyback_mint)]
+    pub buyback_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// SPL program for token transfers
+    pub token_program: Program<'info, Token>,
+
+    /// SPL program 2022 for token transfers
+    pub token_program_2022: Program<'info, Token2022>,
+    // remaining accounts
+    // tickarray_bitmap_extension: must add account if need regardless the sequence
+    // tick_array_account_1
+    // tick_array_account_2
+    // tick_array_account_...
+    // swap_hook_program: must add if the pool has a swap hook, followed by its accounts
+}
+
+pub fn crank_buyback<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, CrankBuyback<'info>>,
+) -> Result<()> {
+    let block_timestamp = u64::try_from(Clock::get()?.unix_timestamp).unwrap();
+    let buyback_state = ctx.accounts.buyback_state.as_mut();
+    require!(
+        buyback_state.is_due(block_timestamp),
+        ErrorCode::BuybackNotDue
+    );
+    buyback_state.last_crank_time = block_timestamp;
+
+    let amount_in;
+    let amount_out;
+    let amount_out_minimum;
+    let twap_tick;
+    let tick;
+    let zero_for_one;
+    {
+        let pool_state = ctx.accounts.pool_state.load()?;
+        require_gt!(block_timestamp, pool_state.open_time);
+        // the fee of the other token is swapped into the buyback token
+        zero_for_one = buyback_state.buyback_mint == pool_state.token_mint_1;
+        let protocol_fees = if zero_for_one {
+            pool_state.protocol_fees_token_0
+        } else {
+            pool_state.protocol_fees_token_1
+        };
+        amount_in = protocol_fees
+            .mul_div_floor(
+                u64::from(buyback_state.protocol_fee_share_rate),
+                u64::from(FEE_RATE_DENOMINATOR_VALUE),
+            )
+            .unwrap();
+        require_gt!(amount_in, 0, ErrorCode::BuybackNotDue);
+    }
+
+    // the buyback is a swap of the pool like any other, so the pool's hook sees it too
+    let (remaining_accounts, hook_accounts) = split_swap_hook_accounts(
+        ctx.remaining_accounts,
+        ctx.accounts
+            .pool_state
+            .load()?
+            .swap_hook(&ctx.accounts.amm_config),
+    )?;
+    let sqrt_price_limit_x64 = if zero_for_one {
+        tick_math::MIN_SQRT_PRICE_X64 + 1
+    } else {
+        tick_math::MAX_SQRT_PRICE_X64 - 1
+    };
+    invoke_swap_hook(
+        hook_accounts,
+        &ctx.accounts.pool_state.to_account_info(),
+        &ctx.accounts.payer.to_account_info(),
+        BEFORE_SWAP_HOOK,
+        BeforeSwapArgs {
+            zero_for_one,
+            is_base_input: true,
+            amount_specified: amount_in,
+            sqrt_price_limit_x64,
+        },
+    )?;
+
+    let amount_0;
+    let amount_1;
+    let sqrt_price_x64;
+    {
+        let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+        let observation_state = &mut ctx.accounts.observation_state.load_mut()?;
+        twap_tick = observation_state
+            .twap_tick(oracle::block_timestamp(), buyback_state.twap_window)
+            .ok_or(ErrorCode::ObservationNotEnough)?;
+        amount_out_minimum = quote_at_sqrt_price(
+            amount_in,
+            tick_math::get_sqrt_price_at_tick(twap_tick)?,
+            zero_for_one,
+        )
+        .mul_div_floor(u64::from(10_000 - buyback_state.max_slippage_bps), 10_000)
+        .unwrap();
+
+        let mut tickarray_bitmap_extension = None;
+        let tick_array_states = &mut VecDeque::new();
+        let tick_array_bitmap_extension_key = TickArrayBitmapExtension::key(pool_state.key());
+        for account_info in remaining_accounts.iter() {
+            if account_info.key().eq(&tick_array_bitmap_extension_key) {
+                tickarray_bitmap_extension = Some(
+                    *(AccountLoader::<TickArrayBitmapExtension>::try_from(account_info)?
+                        .load()?
+                        .deref()),
+                );
+                continue;
+            }
+            tick_array_states.push_back(AccountLoad::load_data_mut(account_info)?);
+        }
+
+        // the input is already in the pool vault, the swap only moves it from the
+        // protocol fee to the pool's reserves
+        (amount_0, amount_1) = swap_internal(
+            &ctx.accounts.amm_config,
+            pool_state,
+            tick_array_states,
+            observation_state,
+            &tickarray_bitmap_extension,
+            amount_in,
+            sqrt_price_limit_x64,
+            zero_for_one,
+            true,
+            oracle::block_timestamp(),
+        )?;
+        let amount_swapped;
+        if zero_for_one {
+            (amount_swapped, amount_out) = (amount_0, amount_1);
+            pool_state.protocol_fees_token_0 = pool_state
+                .protocol_fees_token_0
+                .checked_sub(amount_0)
+                .unwrap();
+        } else {
+            (amount_swapped, amount_out) = (amount_1, amount_0);
+            pool_state.protocol_fees_token_1 = pool_state
+                .protocol_fees_token_1
+                .checked_sub(amount_1)
+                .unwrap();
+        }
+        require_eq!(amount_swapped, amount_in, ErrorCode::LiquidityInsufficient);
+        require_gte!(
+            amount_out,
+            amount_out_minimum,
+            ErrorCode::TooLittleOutputReceived
+        );
+        tick = pool_state.tick_current;
+        sqrt_price_x64 = pool_state.sqrt_price_x64;
+    }
+    invoke_swap_hook(
+        hook_accounts,
+        &ctx.accounts.pool_state.to_account_info(),
+        &ctx.accounts.payer.to_account_info(),
+        AFTER_SWAP_HOOK,
+        AfterSwapArgs {
+            zero_for_one,
+            is_base_input: true,
+            amount_0,
+            amount_1,
+            sqrt_price_x64,
+            tick,
+        },
+    )?;
+
+    let buyback_balance_before = ctx.accounts.buyback_vault.amount;
+    transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.output_vault.to_account_info(),
+        &ctx.accounts.buyback_vault.to_account_info(),
+        Some(ctx.accounts.buyback_mint.clone()),
+        &ctx.accounts.token_program,
+        Some(ctx.accounts.token_program_2022.to_account_info()),
+        amount_out,
+    )?;
+    ctx.accounts.buyback_vault.reload()?;
+    let amount_received = ctx
+        .accounts
+        .buyback_vault
+        .amount
+        .checked_sub(buyback_balance_before)
+        .unwrap();
+
+    let buyback_state = ctx.accounts.buyback_state.as_ref();
+    let amount_burned = if buyback_state.burn {
+        let token_program = if *ctx.accounts.buyback_mint.to_account_info().owner == Token2022::id()
+        {
+            ctx.accounts.token_program_2022.to_account_info()
+        } else {
+            ctx.accounts.token_program.to_account_info()
+        };
+        burn_from_vault(
+            &buyback_state.to_account_info(),
+            &ctx.accounts.buyback_vault.to_account_info(),
+            &ctx.accounts.buyback_mint.to_account_info(),
+            &token_program,
+            &[&buyback_state.seeds()],
+            amount_received,
+        )?;
+        amount_received
+    } else {
+        0
+    };
+
+    let buyback_state = ctx.accounts.buyback_state.as_mut();
+    buyback_state.total_amount_in = buyback_state
+        .total_amount_in
+        .checked_add(amount_in)
+        .unwrap();
+    buyback_state.total_amount_out = buyback_state
+        .total_amount_out
+        .checked_add(amount_received)
+        .unwrap();
+    buyback_state.total_burned = buyback_state
+        .total_burned
+        .checked_add(amount_burned)
+        .unwrap();
+
+    emit!(BuybackEvent {
+        buyback_state: buyback_state.key(),
+        pool_state: ctx.accounts.pool_state.key(),
+        amount_in,
+        amount_out: amount_received,
+        amount_out_minimum,
+        twap_tick,
+        amount_burned,
+        tick,
+    });
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use crate::states::*;
+use crate::util::get_recent_epoch;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CrankEpochFeeReport<'info> {
+    /// Anyone can crank, pays for the report account on the first call
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Stores the pool's counters at the start of the epoch being reported
+    #[account(
+        init_if_needed,
+        seeds = [
+            EPOCH_FEE_REPORT_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = EpochFeeReportState::LEN
+    )]
+    pub epoch_fee_report: Box<Account<'info, EpochFeeReportState>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn crank_epoch_fee_report(ctx: Context<CrankEpochFeeReport>) -> Result<()> {
+    let epoch = get_recent_epoch()?;
+    let pool_state = ctx.accounts.pool_state.load()?;
+    let epoch_fee_report = ctx.accounts.epoch_fee_report.as_mut();
+    // the first crank only starts the epoch, there is nothing to report yet
+    if epoch_fee_report.pool_id == Pubkey::default() {
+        epoch_fee_report.bump = ctx.bumps.epoch_fee_report;
+        epoch_fee_report.pool_id = ctx.accounts.pool_state.key();
+        epoch_fee_report.snapshot(epoch, &pool_state);
+        return Ok(());
+    }
+    if let Some(report) = epoch_fee_report.roll(epoch, &pool_state) {
+        emit!(report);
+    }
+    Ok(())
+}
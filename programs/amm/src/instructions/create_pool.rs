@@ -109,14 +109,189 @@ pub struct CreatePool<'info> {
     pub rent: Sysvar<'info, Rent>,
 }
 
+#[derive(Accounts)]
+pub struct CreateCustomFeePool<'info> {
+    /// Address paying to create the pool. Can be anyone
+    #[account(mut)]
+    pub pool_creator: Signer<'info>,
+
+    /// Which config the pool belongs to.
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// The pool creator's custom fee whitelist entry
+    #[account(
+        seeds = [
+            CUSTOM_FEE_CREATOR_SEED.as_bytes(),
+            pool_creator.key().as_ref(),
+        ],
+        bump = custom_fee_creator.bump,
+    )]
+    pub custom_fee_creator: Box<Account<'info, CustomFeeCreatorState>>,
+
+    /// Initialize an account to store the pool state
+    #[account(
+        init,
+        seeds = [
+            CUSTOM_FEE_POOL_SEED.as_bytes(),
+            amm_config.key().as_ref(),
+            token_mint_0.key().as_ref(),
+            token_mint_1.key().as_ref(),
+        ],
+        bump,
+        payer = pool_creator,
+        space = PoolState::LEN
+    )]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Token_0 mint, the key must be smaller then token_1 mint.
+    #[account(
+        constraint = token_mint_0.key() < token_mint_1.key(),
+        mint::token_program = token_program_0
+    )]
+    pub token_mint_0: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Token_1 mint
+    #[account(
+        mint::token_program = token_program_1
+    )]
+    pub token_mint_1: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Token_0 vault for the pool
+    #[account(
+        init,
+        seeds =[
+            POOL_VAULT_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            token_mint_0.key().as_ref(),
+        ],
+        bump,
+        payer = pool_creator,
+        token::mint = token_mint_0,
+        token::authority = pool_state,
+        token::token_program = token_program_0,
+    )]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token_1 vault for the pool
+    #[account(
+        init,
+        seeds =[
+            POOL_VAULT_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            token_mint_1.key().as_ref(),
+        ],
+        bump,
+        payer = pool_creator,
+        token::mint = token_mint_1,
+        token::authority = pool_state,
+        token::token_program = token_program_1,
+    )]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Initialize an account to store oracle observations
+    #[account(
+        init,
+        seeds = [
+            OBSERVATION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+        payer = pool_creator,
+        space = ObservationState::LEN
+    )]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+
+    /// Initialize an account to store if a tick array is initialized.
+    #[account(
+        init,
+        seeds = [
+            POOL_TICK_ARRAY_BITMAP_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+        payer = pool_creator,
+        space = TickArrayBitmapExtension::LEN
+    )]
+    pub tick_array_bitmap: AccountLoader<'info, TickArrayBitmapExtension>,
+
+    /// Spl token program or token program 2022
+    pub token_program_0: Interface<'info, TokenInterface>,
+    /// Spl token program or token program 2022
+    pub token_program_1: Interface<'info, TokenInterface>,
+    /// To create a new program account
+    pub system_program: Program<'info, System>,
+    /// Sysvar for program account
+    pub rent: Sysvar<'info, Rent>,
+}
+
 pub fn create_pool(ctx: Context<CreatePool>, sqrt_price_x64: u128, open_time: u64) -> Result<()> {
-    if !(util::is_supported_mint(&ctx.accounts.token_mint_0).unwrap()
-        && util::is_supported_mint(&ctx.accounts.token_mint_1).unwrap())
+    create_pool_internal(
+        &ctx.accounts.pool_creator,
+        &ctx.accounts.amm_config,
+        &ctx.accounts.pool_state,
+        ctx.bumps.pool_state,
+        &ctx.accounts.token_mint_0,
+        &ctx.accounts.token_mint_1,
+        &ctx.accounts.token_vault_0,
+        &ctx.accounts.token_vault_1,
+        &ctx.accounts.observation_state,
+        &ctx.accounts.tick_array_bitmap,
+        sqrt_price_x64,
+        open_time,
+        0,
+    )
+}
+
+/// Create a pool with a bespoke trade fee at its own address, apart from the config's pool of
+/// the same pair
+pub fn create_custom_fee_pool(
+    ctx: Context<CreateCustomFeePool>,
+    sqrt_price_x64: u128,
+    open_time: u64,
+    trade_fee_rate: u32,
+) -> Result<()> {
+    ctx.accounts
+        .amm_config
+        .check_custom_trade_fee_rate(trade_fee_rate)?;
+    create_pool_internal(
+        &ctx.accounts.pool_creator,
+        &ctx.accounts.amm_config,
+        &ctx.accounts.pool_state,
+        ctx.bumps.pool_state,
+        &ctx.accounts.token_mint_0,
+        &ctx.accounts.token_mint_1,
+        &ctx.accounts.token_vault_0,
+        &ctx.accounts.token_vault_1,
+        &ctx.accounts.observation_state,
+        &ctx.accounts.tick_array_bitmap,
+        sqrt_price_x64,
+        open_time,
+        trade_fee_rate,
+    )
+}
+
+fn create_pool_internal<'info>(
+    pool_creator: &Signer<'info>,
+    amm_config: &Account<'info, AmmConfig>,
+    pool_state_loader: &AccountLoader<'info, PoolState>,
+    bump: u8,
+    token_mint_0: &InterfaceAccount<'info, Mint>,
+    token_mint_1: &InterfaceAccount<'info, Mint>,
+    token_vault_0: &InterfaceAccount<'info, TokenAccount>,
+    token_vault_1: &InterfaceAccount<'info, TokenAccount>,
+    observation_state: &AccountLoader<'info, ObservationState>,
+    tick_array_bitmap: &AccountLoader<'info, TickArrayBitmapExtension>,
+    sqrt_price_x64: u128,
+    open_time: u64,
+    custom_trade_fee_rate: u32,
+) -> Result<()> {
+    if !(util::is_supported_mint(token_mint_0).unwrap()
+        && util::is_supported_mint(token_mint_1).unwrap())
     {
         return err!(ErrorCode::NotSupportMint);
     }
-    let pool_id = ctx.accounts.pool_state.key();
-    let mut pool_state = ctx.accounts.pool_state.load_init()?;
+    let pool_id = pool_state_loader.key();
+    let mut pool_state = pool_state_loader.load_init()?;
 
     let tick = tick_math::get_tick_at_sqrt_price(sqrt_price_x64)?;
     #[cfg(feature = "enable-log")]
@@ -126,40 +301,40 @@ pub fn create_pool(ctx: Context<CreatePool>, sqrt_price_x64: u128, open_time: u6
         tick
     );
     // init observation
-    ctx.accounts
-        .observation_state
-        .load_init()?
-        .initialize(pool_id)?;
+    observation_state.load_init()?.initialize(pool_id)?;
 
-    let bump = ctx.bumps.pool_state;
     pool_state.initialize(
         bump,
         sqrt_price_x64,
         open_time,
         tick,
-        ctx.accounts.pool_creator.key(),
-        ctx.accounts.token_vault_0.key(),
-        ctx.accounts.token_vault_1.key(),
-        ctx.accounts.amm_config.as_ref(),
-        ctx.accounts.token_mint_0.as_ref(),
-        ctx.accounts.token_mint_1.as_ref(),
-        ctx.accounts.observation_state.key(),
+        pool_creator.key(),
+        token_vault_0.key(),
+        token_vault_1.key(),
+        amm_config,
+        token_mint_0,
+        token_mint_1,
+        observation_state.key(),
+    )?;
+    pool_state.custom_trade_fee_rate = custom_trade_fee_rate;
+    util::verify_vaults(
+        &pool_id,
+        &pool_state,
+        &token_vault_0.to_account_info(),
+        &token_vault_1.to_account_info(),
     )?;
 
-    ctx.accounts
-        .tick_array_bitmap
-        .load_init()?
-        .initialize(pool_id);
+    tick_array_bitmap.load_init()?.initialize(pool_id);
 
     emit!(PoolCreatedEvent {
-        token_mint_0: ctx.accounts.token_mint_0.key(),
-        token_mint_1: ctx.accounts.token_mint_1.key(),
-        tick_spacing: ctx.accounts.amm_config.tick_spacing,
-        pool_state: ctx.accounts.pool_state.key(),
+        token_mint_0: token_mint_0.key(),
+        token_mint_1: token_mint_1.key(),
+        tick_spacing: amm_config.tick_spacing,
+        pool_state: pool_id,
         sqrt_price_x64,
         tick,
-        token_vault_0: ctx.accounts.token_vault_0.key(),
-        token_vault_1: ctx.accounts.token_vault_1.key(),
+        token_vault_0: token_vault_0.key(),
+        token_vault_1: token_vault_1.key(),
     });
     Ok(())
 }
@@ -0,0 +1,353 @@
+use super::open_position::open_position;
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::{
+    create_or_allocate_account, create_position_nft_mint_with_extensions, get_token_account_amount,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::{create, AssociatedToken, Create};
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+/// Accounts passed in remaining accounts for each rung
+const ACCOUNTS_PER_RUNG: usize = 6;
+
+#[derive(Accounts)]
+pub struct CreatePositionLadder<'info> {
+    /// Pays to mint the positions
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Receives the position NFTs
+    pub position_nft_owner: UncheckedAccount<'info>,
+
+    /// Add liquidity for this pool
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The pool's config, liquidity can't be added while it is paused
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// The token_0 account deposit token to the pool
+    #[account(
+        mut,
+        token::mint = token_vault_0.mint
+    )]
+    pub token_account_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The token_1 account deposit token to the pool
+    #[account(
+        mut,
+        token::mint = token_vault_1.mint
+    )]
+    pub token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        mut,
+        constraint = token_vault_0.key() == pool_state.load()?.token_vault_0
+    )]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        mut,
+        constraint = token_vault_1.key() == pool_state.load()?.token_vault_1
+    )]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Sysvar for token mint and ATA creation
+    pub rent: Sysvar<'info, Rent>,
+
+    /// Program to create the position manager state account
+    pub system_program: Program<'info, System>,
+
+    /// Program to transfer for token account
+    pub token_program: Program<'info, Token>,
+
+    /// Program to create an ATA for receiving position NFT
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    /// Program to create NFT mint/token account and transfer for token22 account
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// The mint of token vault 0
+    #[account(
+        address = token_vault_0.mint,
+        constraint = pool_state.load()?.is_valid_token_program(
+            &vault_0_mint.key(),
+            vault_0_mint.to_account_info().owner
+        ) @ ErrorCode::InvalidTokenProgram
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token vault 1
+    #[account(
+        address = token_vault_1.mint,
+        constraint = pool_state.load()?.is_valid_token_program(
+            &vault_1_mint.key(),
+            vault_1_mint.to_account_info().owner
+        ) @ ErrorCode::InvalidTokenProgram
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+    // remaining accounts
+    // optional tick_array_bitmap extension, followed by one group per rung:
+    // position_nft_mint(signer), position_nft_account, personal_position,
+    // protocol_position, tick_array_lower, tick_array_upper
+}
+
+/// Open `weights.len()` adjacent positions of `rung_tick_width` ticks starting at `tick_lower_index`,
+/// the total `liquidity` is split between the rungs in proportion to `weights`.
+pub fn create_position_ladder<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, CreatePositionLadder<'info>>,
+    tick_lower_index: i32,
+    rung_tick_width: i32,
+    liquidity: u128,
+    weights: Vec<u32>,
+    amount_0_max: u64,
+    amount_1_max: u64,
+    with_metadata: bool,
+) -> Result<()> {
+    create_position_ladder_internal(
+        ctx.accounts,
+        ctx.remaining_accounts,
+        tick_lower_index,
+        rung_tick_width,
+        liquidity,
+        &weights,
+        amount_0_max,
+        amount_1_max,
+        with_metadata,
+    )
+}
+
+pub fn create_position_ladder_internal<'b, 'c: 'info, 'info>(
+    accounts: &'b CreatePositionLadder<'info>,
+    remaining_accounts: &'c [AccountInfo<'info>],
+    tick_lower_index: i32,
+    rung_tick_width: i32,
+    liquidity: u128,
+    weights: &[u32],
+    amount_0_max: u64,
+    amount_1_max: u64,
+    with_metadata: bool,
+) -> Result<()> {
+    let rungs = weights.len();
+    require!(
+        rungs > 0 && rungs <= LADDER_MAX_RUNGS,
+        ErrorCode::InvalidLadderParam
+    );
+    require!(rung_tick_width > 0, ErrorCode::InvalidLadderParam);
+    let weight_sum: u64 = weights.iter().map(|w| u64::from(*w)).sum();
+    require!(
+        weights.iter().all(|w| *w > 0),
+        ErrorCode::InvalidLadderParam
+    );
+
+    let pool_id = accounts.pool_state.key();
+    let tick_spacing = accounts.pool_state.load()?.tick_spacing;
+
+    let mut remaining_accounts = remaining_accounts;
+    let bitmap_extension_accounts = match remaining_accounts.first() {
+        Some(account) if account.key() == TickArrayBitmapExtension::key(pool_id) => {
+            let (bitmap, rest) = remaining_accounts.split_at(1);
+            remaining_accounts = rest;
+            bitmap
+        }
+        _ => &remaining_accounts[..0],
+    };
+    require!(
+        remaining_accounts.len() == rungs * ACCOUNTS_PER_RUNG,
+        ErrorCode::AccountLack
+    );
+
+    let token_account_0 = accounts.token_account_0.to_account_info();
+    let token_account_1 = accounts.token_account_1.to_account_info();
+    let balance_0_before = get_token_account_amount(&token_account_0)?;
+    let balance_1_before = get_token_account_amount(&token_account_1)?;
+
+    for (i, (weight, rung_accounts)) in weights
+        .iter()
+        .zip(remaining_accounts.chunks(ACCOUNTS_PER_RUNG))
+        .enumerate()
+    {
+        let rung_tick_lower = i32::try_from(i)
+            .ok()
+            .and_then(|i| i.checked_mul(rung_tick_width))
+            .and_then(|offset| tick_lower_index.checked_add(offset))
+            .ok_or(ErrorCode::InvalidLadderParam)?;
+        let rung_tick_upper = rung_tick_lower
+            .checked_add(rung_tick_width)
+            .ok_or(ErrorCode::InvalidLadderParam)?;
+        let tick_array_lower_start_index =
+            TickArrayState::get_array_start_index(rung_tick_lower, tick_spacing);
+        let tick_array_upper_start_index =
+            TickArrayState::get_array_start_index(rung_tick_upper, tick_spacing);
+
+        let rung_liquidity = liquidity
+            .checked_mul(u128::from(*weight))
+            .ok_or(ErrorCode::CalculateOverflow)?
+            / u128::from(weight_sum);
+        require_gt!(rung_liquidity, 0, ErrorCode::InvalidLadderParam);
+
+        // whatever previous rungs did not spend is available to this one
+        let spent_0 = balance_0_before - get_token_account_amount(&token_account_0)?;
+        let spent_1 = balance_1_before - get_token_account_amount(&token_account_1)?;
+        let rung_amount_0_max = amount_0_max
+            .checked_sub(spent_0)
+            .ok_or(ErrorCode::PriceSlippageCheck)?;
+        let rung_amount_1_max = amount_1_max
+            .checked_sub(spent_1)
+            .ok_or(ErrorCode::PriceSlippageCheck)?;
+
+        let position_nft_mint = &rung_accounts[0];
+        let position_nft_account = &rung_accounts[1];
+        let personal_position_info = &rung_accounts[2];
+        let protocol_position_info = &rung_accounts[3];
+        let tick_array_lower = UncheckedAccount::try_from(&rung_accounts[4]);
+        let tick_array_upper = UncheckedAccount::try_from(&rung_accounts[5]);
+
+        for (tick_array, start_index) in [
+            (&tick_array_lower, tick_array_lower_start_index),
+            (&tick_array_upper, tick_array_upper_start_index),
+        ] {
+            let (expect_pda_address, _) = Pubkey::find_program_address(
+                &[
+                    TICK_ARRAY_SEED.as_bytes(),
+                    pool_id.as_ref(),
+                    &start_index.to_be_bytes(),
+                ],
+                &crate::id(),
+            );
+            require_keys_eq!(
+                tick_array.key(),
+                expect_pda_address,
+                ErrorCode::InvalidTickArray
+            );
+        }
+
+        let (personal_position_key, personal_position_bump) = Pubkey::find_program_address(
+            &[POSITION_SEED.as_bytes(), position_nft_mint.key().as_ref()],
+            &crate::id(),
+        );
+        require_keys_eq!(personal_position_info.key(), personal_position_key);
+        let (protocol_position_key, protocol_position_bump) = Pubkey::find_program_address(
+            &[
+                POSITION_SEED.as_bytes(),
+                pool_id.as_ref(),
+                &rung_tick_lower.to_be_bytes(),
+                &rung_tick_upper.to_be_bytes(),
+            ],
+            &crate::id(),
+        );
+        require_keys_eq!(protocol_position_info.key(), protocol_position_key);
+
+        create_or_allocate_account(
+            &crate::id(),
+            accounts.payer.to_account_info(),
+            accounts.system_program.to_account_info(),
+            personal_position_info.clone(),
+            &[
+                POSITION_SEED.as_bytes(),
+                position_nft_mint.key().as_ref(),
+                &[personal_position_bump],
+            ],
+            PersonalPositionState::LEN,
+        )?;
+        let mut personal_position = Box::new(Account::<PersonalPositionState>::try_from_unchecked(
+            personal_position_info,
+        )?);
+
+        let mut protocol_position = if protocol_position_info.owner == &crate::id() {
+            Box::new(Account::<ProtocolPositionState>::try_from(
+                protocol_position_info,
+            )?)
+        } else {
+            create_or_allocate_account(
+                &crate::id(),
+                accounts.payer.to_account_info(),
+                accounts.system_program.to_account_info(),
+                protocol_position_info.clone(),
+                &[
+                    POSITION_SEED.as_bytes(),
+                    pool_id.as_ref(),
+                    &rung_tick_lower.to_be_bytes(),
+                    &rung_tick_upper.to_be_bytes(),
+                    &[protocol_position_bump],
+                ],
+                ProtocolPositionState::LEN,
+            )?;
+            Box::new(Account::<ProtocolPositionState>::try_from_unchecked(
+                protocol_position_info,
+            )?)
+        };
+
+        create_position_nft_mint_with_extensions(
+            &accounts.payer,
+            position_nft_mint,
+            &accounts.pool_state.to_account_info(),
+            personal_position_info,
+            &accounts.system_program,
+            &accounts.token_program_2022,
+            with_metadata,
+        )?;
+
+        // create user position nft account
+        create(CpiContext::new(
+            accounts.associated_token_program.to_account_info(),
+            Create {
+                payer: accounts.payer.to_account_info(),
+                associated_token: position_nft_account.clone(),
+                authority: accounts.position_nft_owner.to_account_info(),
+                mint: position_nft_mint.clone(),
+                system_program: accounts.system_program.to_account_info(),
+                token_program: accounts.token_program_2022.to_account_info(),
+            },
+        ))?;
+
+        open_position(
+            &accounts.payer,
+            &accounts.position_nft_owner,
+            position_nft_mint,
+            position_nft_account,
+            None,
+            &accounts.pool_state,
+            &accounts.amm_config,
+            &tick_array_lower,
+            &tick_array_upper,
+            &mut protocol_position,
+            &mut personal_position,
+            &token_account_0,
+            &token_account_1,
+            &accounts.token_vault_0.to_account_info(),
+            &accounts.token_vault_1.to_account_info(),
+            &accounts.rent,
+            &accounts.system_program,
+            &accounts.token_program,
+            &accounts.associated_token_program,
+            None,
+            Some(&accounts.token_program_2022),
+            Some(accounts.vault_0_mint.clone()),
+            Some(accounts.vault_1_mint.clone()),
+            bitmap_extension_accounts,
+            protocol_position_bump,
+            personal_position_bump,
+            rung_liquidity,
+            rung_amount_0_max,
+            rung_amount_1_max,
+            rung_tick_lower,
+            rung_tick_upper,
+            tick_array_lower_start_index,
+            tick_array_upper_start_index,
+            with_metadata,
+            None,
+            true,
+        )?;
+
+        // the positions are not part of the accounts struct, persist them here
+        protocol_position.exit(&crate::id())?;
+        personal_position.exit(&crate::id())?;
+    }
+    Ok(())
+}
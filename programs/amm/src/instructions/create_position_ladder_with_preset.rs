@@ -0,0 +1,37 @@
+use super::create_position_ladder::*;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CreatePositionLadderWithPreset<'info> {
+    pub ladder: CreatePositionLadder<'info>,
+
+    /// The range recipe to open
+    pub strategy_preset: Box<Account<'info, StrategyPreset>>,
+}
+
+pub fn create_position_ladder_with_preset<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, CreatePositionLadderWithPreset<'info>>,
+    liquidity: u128,
+    amount_0_max: u64,
+    amount_1_max: u64,
+    with_metadata: bool,
+) -> Result<()> {
+    let (tick_lower_index, rung_tick_width) = {
+        let pool_state = ctx.accounts.ladder.pool_state.load()?;
+        ctx.accounts
+            .strategy_preset
+            .ladder_range(pool_state.tick_current, pool_state.tick_spacing)?
+    };
+    create_position_ladder_internal(
+        &ctx.accounts.ladder,
+        ctx.remaining_accounts,
+        tick_lower_index,
+        rung_tick_width,
+        liquidity,
+        &ctx.accounts.strategy_preset.weights(),
+        amount_0_max,
+        amount_1_max,
+        with_metadata,
+    )
+}
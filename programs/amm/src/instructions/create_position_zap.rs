@@ -0,0 +1,319 @@
+use super::open_position::open_position;
+use super::swap_and_increase_liquidity::get_swap_amount_for_range;
+use crate::error::ErrorCode;
+use crate::libraries::{liquidity_math, tick_math};
+use crate::states::*;
+use crate::swap_v2::{exact_internal_v2, SwapSingleV2};
+use crate::util::{create_position_nft_mint_with_extensions, get_transfer_fee};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::{create, AssociatedToken, Create};
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+#[instruction(tick_lower_index: i32, tick_upper_index: i32,tick_array_lower_start_index:i32,tick_array_upper_start_index:i32)]
+pub struct CreatePositionZap<'info> {
+    /// Pays to mint the position and the input token of the zap
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Receives the position NFT
+    pub position_nft_owner: UncheckedAccount<'info>,
+
+    /// Unique token mint address, initialize in constract
+    #[account(mut)]
+    pub position_nft_mint: Signer<'info>,
+
+    /// CHECK: ATA address where position NFT will be minted, initialize in constract
+    #[account(mut)]
+    pub position_nft_account: UncheckedAccount<'info>,
+
+    /// The factory state to read protocol fees
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// Add liquidity for this pool
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Store the information of market marking in range
+    #[account(
+        init_if_needed,
+        seeds = [
+            POSITION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &tick_lower_index.to_be_bytes(),
+            &tick_upper_index.to_be_bytes(),
+        ],
+        bump,
+        payer = payer,
+        space = ProtocolPositionState::LEN
+    )]
+    pub protocol_position: Box<Account<'info, ProtocolPositionState>>,
+
+    /// CHECK:  Account to store data for the position's lower tick
+    #[account(
+        mut,
+        seeds = [
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &tick_array_lower_start_index.to_be_bytes(),
+        ],
+        bump,
+    )]
+    pub tick_array_lower: UncheckedAccount<'info>,
+
+    /// CHECK: Account to store data for the position's upper tick
+    #[account(
+        mut,
+        seeds = [
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &tick_array_upper_start_index.to_be_bytes(),
+        ],
+        bump,
+    )]
+    pub tick_array_upper: UncheckedAccount<'info>,
+
+    /// personal position state
+    #[account(
+        init,
+        seeds = [POSITION_SEED.as_bytes(), position_nft_mint.key().as_ref()],
+        bump,
+        payer = payer,
+        space = PersonalPositionState::LEN
+    )]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    /// The token_0 account deposit token to the pool
+    #[account(
+        mut,
+        token::mint = token_vault_0.mint
+    )]
+    pub token_account_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The token_1 account deposit token to the pool
+    #[account(
+        mut,
+        token::mint = token_vault_1.mint
+    )]
+    pub token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        mut,
+        constraint = token_vault_0.key() == pool_state.load()?.token_vault_0
+    )]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        mut,
+        constraint = token_vault_1.key() == pool_state.load()?.token_vault_1
+    )]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The program account for the most recent oracle observation
+    #[account(mut, address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+
+    /// Sysvar for token mint and ATA creation
+    pub rent: Sysvar<'info, Rent>,
+
+    /// Program to create the position manager state account
+    pub system_program: Program<'info, System>,
+
+    /// Program to transfer for token account
+    pub token_program: Program<'info, Token>,
+
+    /// Program to create an ATA for receiving position NFT
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    /// Program to create NFT mint/token account and transfer for token22 account
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// CHECK:
+    #[account(
+        address = spl_memo::id()
+    )]
+    pub memo_program: UncheckedAccount<'info>,
+
+    /// The mint of token vault 0
+    #[account(
+        address = token_vault_0.mint,
+        constraint = pool_state.load()?.is_valid_token_program(
+            &vault_0_mint.key(),
+            vault_0_mint.to_account_info().owner
+        ) @ ErrorCode::InvalidTokenProgram
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token vault 1
+    #[account(
+        address = token_vault_1.mint,
+        constraint = pool_state.load()?.is_valid_token_program(
+            &vault_1_mint.key(),
+            vault_1_mint.to_account_info().owner
+        ) @ ErrorCode::InvalidTokenProgram
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+    // remaining accounts
+    // the tick array bitmap extension first if the swap or the position needs it, then the tick
+    // arrays of the swap
+}
+
+/// Opens a position wrapped in a Token2022 NFT from `amount` of a single token: part of it is
+/// swapped in the pool so the rest and the swap output match the range ratio, what the
+/// position can't use stays in the token accounts
+pub fn create_position_zap<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, CreatePositionZap<'info>>,
+    tick_lower_index: i32,
+    tick_upper_index: i32,
+    tick_array_lower_start_index: i32,
+    tick_array_upper_start_index: i32,
+    amount: u64,
+    is_token_0: bool,
+    liquidity_min: u128,
+    with_metadata: bool,
+) -> Result<()> {
+    let sqrt_price_lower_x64 = tick_math::get_sqrt_price_at_tick(tick_lower_index)?;
+    let sqrt_price_upper_x64 = tick_math::get_sqrt_price_at_tick(tick_upper_index)?;
+    let swap_amount = {
+        let pool_state = ctx.accounts.pool_state.load()?;
+        get_swap_amount_for_range(
+            amount,
+            is_token_0,
+            pool_state.sqrt_price_x64,
+            sqrt_price_lower_x64,
+            sqrt_price_upper_x64,
+            pool_state.trade_fee_rate(&ctx.accounts.amm_config),
+        )
+    };
+
+    let accounts = ctx.accounts;
+    let output_balance_before = if is_token_0 {
+        accounts.token_account_1.amount
+    } else {
+        accounts.token_account_0.amount
+    };
+    if swap_amount != 0 {
+        let mut swap = if is_token_0 {
+            SwapSingleV2 {
+                payer: accounts.payer.clone(),
+                amm_config: accounts.amm_config.clone(),
+                pool_state: accounts.pool_state.clone(),
+                input_token_account: accounts.token_account_0.clone(),
+                output_token_account: accounts.token_account_1.clone(),
+                input_vault: accounts.token_vault_0.clone(),
+                output_vault: accounts.token_vault_1.clone(),
+                observation_state: accounts.observation_state.clone(),
+                token_program: accounts.token_program.clone(),
+                token_program_2022: accounts.token_program_2022.clone(),
+                memo_program: accounts.memo_program.clone(),
+                input_vault_mint: accounts.vault_0_mint.clone(),
+                output_vault_mint: accounts.vault_1_mint.clone(),
+            }
+        } else {
+            SwapSingleV2 {
+                payer: accounts.payer.clone(),
+                amm_config: accounts.amm_config.clone(),
+                pool_state: accounts.pool_state.clone(),
+                input_token_account: accounts.token_account_1.clone(),
+                output_token_account: accounts.token_account_0.clone(),
+                input_vault: accounts.token_vault_1.clone(),
+                output_vault: accounts.token_vault_0.clone(),
+                observation_state: accounts.observation_state.clone(),
+                token_program: accounts.token_program.clone(),
+                token_program_2022: accounts.token_program_2022.clone(),
+                memo_program: accounts.memo_program.clone(),
+                input_vault_mint: accounts.vault_1_mint.clone(),
+                output_vault_mint: accounts.vault_0_mint.clone(),
+            }
+        };
+        exact_internal_v2(&mut swap, ctx.remaining_accounts, swap_amount, 0, true)?;
+        accounts.token_account_0.reload()?;
+        accounts.token_account_1.reload()?;
+    }
+
+    // the input left after the swap and the swap output, as received
+    let (amount_0_max, amount_1_max) = if is_token_0 {
+        (
+            amount - swap_amount,
+            accounts.token_account_1.amount - output_balance_before,
+        )
+    } else {
+        (
+            accounts.token_account_0.amount - output_balance_before,
+            amount - swap_amount,
+        )
+    };
+    let liquidity = liquidity_math::get_liquidity_from_amounts(
+        accounts.pool_state.load()?.sqrt_price_x64,
+        sqrt_price_lower_x64,
+        sqrt_price_upper_x64,
+        amount_0_max - get_transfer_fee(accounts.vault_0_mint.clone(), amount_0_max)?,
+        amount_1_max - get_transfer_fee(accounts.vault_1_mint.clone(), amount_1_max)?,
+    );
+    require_gte!(liquidity, liquidity_min, ErrorCode::PriceSlippageCheck);
+
+    create_position_nft_mint_with_extensions(
+        &accounts.payer,
+        &accounts.position_nft_mint,
+        &accounts.pool_state.to_account_info(),
+        &accounts.personal_position.to_account_info(),
+        &accounts.system_program,
+        &accounts.token_program_2022,
+        with_metadata,
+    )?;
+
+    create(CpiContext::new(
+        accounts.associated_token_program.to_account_info(),
+        Create {
+            payer: accounts.payer.to_account_info(),
+            associated_token: accounts.position_nft_account.to_account_info(),
+            authority: accounts.position_nft_owner.to_account_info(),
+            mint: accounts.position_nft_mint.to_account_info(),
+            system_program: accounts.system_program.to_account_info(),
+            token_program: accounts.token_program_2022.to_account_info(),
+        },
+    ))?;
+
+    open_position(
+        &accounts.payer,
+        &accounts.position_nft_owner,
+        &accounts.position_nft_mint,
+        &accounts.position_nft_account,
+        None,
+        &accounts.pool_state,
+        &accounts.amm_config,
+        &accounts.tick_array_lower,
+        &accounts.tick_array_upper,
+        &mut accounts.protocol_position,
+        &mut accounts.personal_position,
+        &accounts.token_account_0.to_account_info(),
+        &accounts.token_account_1.to_account_info(),
+        &accounts.token_vault_0.to_account_info(),
+        &accounts.token_vault_1.to_account_info(),
+        &accounts.rent,
+        &accounts.system_program,
+        &accounts.token_program,
+        &accounts.associated_token_program,
+        None,
+        Some(&accounts.token_program_2022),
+        Some(accounts.vault_0_mint.clone()),
+        Some(accounts.vault_1_mint.clone()),
+        ctx.remaining_accounts,
+        ctx.bumps.protocol_position,
+        ctx.bumps.personal_position,
+        liquidity,
+        amount_0_max,
+        amount_1_max,
+        tick_lower_index,
+        tick_upper_index,
+        tick_array_lower_start_index,
+        tick_array_upper_start_index,
+        with_metadata,
+        None,
+        true,
+    )
+}
@@ -0,0 +1,36 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CreateReferralAccount<'info> {
+    /// The referrer, pays the rent of the referral account
+    #[account(mut)]
+    pub referrer: Signer<'info>,
+
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Initialize an account to store the referrer's fees in the pool
+    #[account(
+        init,
+        seeds = [
+            REFERRAL_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            referrer.key().as_ref(),
+        ],
+        bump,
+        payer = referrer,
+        space = ReferralAccount::LEN
+    )]
+    pub referral_account: Box<Account<'info, ReferralAccount>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_referral_account(ctx: Context<CreateReferralAccount>) -> Result<()> {
+    ctx.accounts.referral_account.initialize(
+        ctx.bumps.referral_account,
+        ctx.accounts.pool_state.key(),
+        ctx.accounts.referrer.key(),
+    );
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(name: [u8; 32])]
+pub struct CreateStrategyPreset<'info> {
+    /// The preset creator
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Initialize an account to store the preset
+    #[account(
+        init,
+        seeds = [STRATEGY_PRESET_SEED.as_bytes(), authority.key().as_ref(), name.as_ref()],
+        bump,
+        payer = authority,
+        space = StrategyPreset::LEN
+    )]
+    pub strategy_preset: Box<Account<'info, StrategyPreset>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_strategy_preset(
+    ctx: Context<CreateStrategyPreset>,
+    name: [u8; 32],
+    tick_width: i32,
+    weights: Vec<u32>,
+    flags: u8,
+) -> Result<()> {
+    let bump = ctx.bumps.strategy_preset;
+    ctx.accounts.strategy_preset.initialize(
+        bump,
+        ctx.accounts.authority.key(),
+        name,
+        tick_width,
+        &weights,
+        flags,
+    )
+}
@@ -0,0 +1,38 @@
+use crate::states::*;
+use crate::util::get_recent_epoch;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CreateUserStats<'info> {
+    /// The trader, pays the rent of the user stats
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Initialize an account to track the trader's volume in the pool
+    #[account(
+        init,
+        seeds = [
+            USER_STATS_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = UserStats::LEN
+    )]
+    pub user_stats: Box<Account<'info, UserStats>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_user_stats(ctx: Context<CreateUserStats>) -> Result<()> {
+    ctx.accounts.user_stats.initialize(
+        ctx.bumps.user_stats,
+        ctx.accounts.pool_state.key(),
+        ctx.accounts.owner.key(),
+        get_recent_epoch()?,
+    );
+    Ok(())
+}
@@ -2,7 +2,9 @@ use super::calculate_latest_token_fees;
 use super::modify_position;
 use crate::error::ErrorCode;
 use crate::states::*;
-use crate::util::{self, transfer_from_pool_vault_to_user};
+use crate::util::{
+    self, get_token_account_amount, is_authorized_for_token, transfer_from_pool_vault_to_user,
+};
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Token, TokenAccount};
 use anchor_spl::token_2022::spl_token_2022;
@@ -19,9 +21,11 @@ pub struct DecreaseLiquidity<'info> {
 
     /// The token account for the tokenized position
     #[account(
-        constraint = nft_account.mint == personal_position.nft_mint,
-        constraint = nft_account.amount == 1,
-        token::authority = nft_owner
+        constraint = is_authorized_for_token(
+            &nft_owner.key(),
+            &nft_account.to_account_info(),
+            &personal_position.nft_mint
+        ) @ ErrorCode::NotApproved
     )]
     pub nft_account: Box<Account<'info, TokenAccount>>,
 
@@ -230,6 +234,17 @@ pub fn decrease_liquidity<'a, 'b, 'c: 'info, 'info>(
         transfer_fee_1,
         latest_fees_owed_1
     );
+    crate::log_kv!(
+        "decrease_liquidity",
+        liquidity = liquidity,
+        pool_tick = pool_tick_current,
+        decrease_amount_0 = decrease_amount_0,
+        decrease_amount_1 = decrease_amount_1,
+        fees_owed_0 = latest_fees_owed_0,
+        fees_owed_1 = latest_fees_owed_1,
+        transfer_fee_0 = transfer_fee_0,
+        transfer_fee_1 = transfer_fee_1,
+    );
     if liquidity > 0 {
         require_gte!(
             decrease_amount_0 - transfer_fee_0,
@@ -242,6 +257,8 @@ pub fn decrease_liquidity<'a, 'b, 'c: 'info, 'info>(
             ErrorCode::PriceSlippageCheck
         );
     }
+    let vault_0_balance_before = get_token_account_amount(token_vault_0)?;
+    let vault_1_balance_before = get_token_account_amount(token_vault_1)?;
     let transfer_amount_0 = decrease_amount_0 + latest_fees_owed_0;
     let transfer_amount_1 = decrease_amount_1 + latest_fees_owed_1;
 
@@ -283,7 +300,20 @@ pub fn decrease_liquidity<'a, 'b, 'c: 'info, 'info>(
         } else {
             true
         },
+        [u64::MAX; REWARD_NUM],
     )?;
+    let tick_lower = personal_position.tick_lower_index;
+    let tick_upper = personal_position.tick_upper_index;
+    let (pool_liquidity, tick_spacing) = {
+        let pool_state = pool_state_loader.load()?;
+        (pool_state.liquidity, pool_state.tick_spacing)
+    };
+    let tick_lower_state = tick_array_lower_loader
+        .load()?
+        .get_tick_state(tick_lower, tick_spacing)?;
+    let tick_upper_state = tick_array_upper_loader
+        .load()?
+        .get_tick_state(tick_upper, tick_spacing)?;
     emit!(DecreaseLiquidityEvent {
         position_nft_mint: personal_position.nft_mint,
         liquidity,
@@ -294,6 +324,20 @@ pub fn decrease_liquidity<'a, 'b, 'c: 'info, 'info>(
         reward_amounts,
         transfer_fee_0: transfer_fee_0,
         transfer_fee_1: transfer_fee_1,
+        vault_0_balance_before,
+        vault_1_balance_before,
+        vault_0_balance_after: get_token_account_amount(token_vault_0)?,
+        vault_1_balance_after: get_token_account_amount(token_vault_1)?,
+        position_hash: personal_position.position_hash(),
+        incentive_program: personal_position.incentive_program,
+        tick_lower,
+        tick_upper,
+        position_liquidity: personal_position.liquidity,
+        pool_liquidity,
+        tick_lower_liquidity_net: tick_lower_state.liquidity_net,
+        tick_lower_liquidity_gross: tick_lower_state.liquidity_gross,
+        tick_upper_liquidity_net: tick_upper_state.liquidity_net,
+        tick_upper_liquidity_gross: tick_upper_state.liquidity_gross,
     });
 
     Ok(())
@@ -362,6 +406,13 @@ pub fn decrease_liquidity_and_update_position<'a, 'b, 'c: 'info, 'info>(
 
         personal_position.token_fees_owed_0 = 0;
         personal_position.token_fees_owed_1 = 0;
+        // a lifetime statistic, it must never block a collect
+        personal_position.fees_collected_0 = personal_position
+            .fees_collected_0
+            .saturating_add(latest_fees_owed_0);
+        personal_position.fees_collected_1 = personal_position
+            .fees_collected_1
+            .saturating_add(latest_fees_owed_1);
 
         pool_state.total_fees_claimed_token_0 = pool_state
             .total_fees_claimed_token_0
@@ -381,6 +432,36 @@ pub fn decrease_liquidity_and_update_position<'a, 'b, 'c: 'info, 'info>(
     ))
 }
 
+/// Hands fees settled by `decrease_liquidity_and_update_position` but not transferred back to
+/// the position, they stay owed to it and unclaimed in the pool
+pub fn restore_fees_owed(
+    pool_state: &mut PoolState,
+    personal_position: &mut PersonalPositionState,
+    amount_0: u64,
+    amount_1: u64,
+) {
+    personal_position.token_fees_owed_0 = personal_position
+        .token_fees_owed_0
+        .checked_add(amount_0)
+        .unwrap();
+    personal_position.token_fees_owed_1 = personal_position
+        .token_fees_owed_1
+        .checked_add(amount_1)
+        .unwrap();
+    personal_position.fees_collected_0 =
+        personal_position.fees_collected_0.saturating_sub(amount_0);
+    personal_position.fees_collected_1 =
+        personal_position.fees_collected_1.saturating_sub(amount_1);
+    pool_state.total_fees_claimed_token_0 = pool_state
+        .total_fees_claimed_token_0
+        .checked_sub(amount_0)
+        .unwrap();
+    pool_state.total_fees_claimed_token_1 = pool_state
+        .total_fees_claimed_token_1
+        .checked_sub(amount_1)
+        .unwrap();
+}
+
 pub fn burn_liquidity<'c: 'info, 'info>(
     pool_state: &mut RefMut<PoolState>,
     tick_array_lower_loader: &AccountLoader<'info, TickArrayState>,
@@ -461,6 +542,7 @@ pub fn collect_rewards<'a, 'b, 'c, 'info>(
     token_program_2022: Option<AccountInfo<'info>>,
     personal_position_state: &mut PersonalPositionState,
     need_reward_mint: bool,
+    reward_amounts_max: [u64; REWARD_NUM],
 ) -> Result<[u64; REWARD_NUM]> {
     let mut reward_amounts: [u64; REWARD_NUM] = [0, 0, 0];
     if !pool_state_loader
@@ -513,7 +595,8 @@ pub fn collect_rewards<'a, 'b, 'c, 'info>(
             reward_token_vault.amount
         } else {
             reward_amount_owed
-        };
+        }
+        .min(reward_amounts_max[i]);
 
         if transfer_amount > 0 {
             msg!(
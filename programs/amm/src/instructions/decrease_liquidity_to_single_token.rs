@@ -0,0 +1,256 @@
+use super::decrease_liquidity::decrease_liquidity;
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::swap_v2::{exact_internal_v2, SwapSingleV2};
+use crate::util::is_authorized_for_token;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::Mint;
+use anchor_spl::token_interface::{Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct DecreaseLiquidityToSingleToken<'info> {
+    /// The position owner or delegated authority
+    pub nft_owner: Signer<'info>,
+
+    /// The token account for the tokenized position
+    #[account(
+        constraint = is_authorized_for_token(
+            &nft_owner.key(),
+            &nft_account.to_account_info(),
+            &personal_position.nft_mint
+        ) @ ErrorCode::NotApproved,
+    )]
+    pub nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Decrease liquidity for this position
+    #[account(mut, constraint = personal_position.pool_id == pool_state.key())]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The factory state to read protocol fees
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// The program account for the most recent oracle observation
+    #[account(mut, address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+
+    #[account(
+        mut,
+        seeds = [
+            POSITION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &personal_position.tick_lower_index.to_be_bytes(),
+            &personal_position.tick_upper_index.to_be_bytes(),
+        ],
+        bump,
+        constraint = protocol_position.pool_id == pool_state.key(),
+    )]
+    pub protocol_position: Box<Account<'info, ProtocolPositionState>>,
+
+    /// Token_0 vault
+    #[account(
+        mut,
+        constraint = token_vault_0.key() == pool_state.load()?.token_vault_0
+    )]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token_1 vault
+    #[account(
+        mut,
+        constraint = token_vault_1.key() == pool_state.load()?.token_vault_1
+    )]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Stores init state for the lower tick
+    #[account(mut, constraint = tick_array_lower.load()?.pool_id == pool_state.key())]
+    pub tick_array_lower: AccountLoader<'info, TickArrayState>,
+
+    /// Stores init state for the upper tick
+    #[account(mut, constraint = tick_array_upper.load()?.pool_id == pool_state.key())]
+    pub tick_array_upper: AccountLoader<'info, TickArrayState>,
+
+    /// The destination token account for receive amount_0
+    #[account(
+        mut,
+        token::mint = token_vault_0.mint
+    )]
+    pub recipient_token_account_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The destination token account for receive amount_1
+    #[account(
+        mut,
+        token::mint = token_vault_1.mint
+    )]
+    pub recipient_token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// SPL program to transfer out tokens
+    pub token_program: Program<'info, Token>,
+    /// Token program 2022
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// memo program
+    /// CHECK:
+    #[account(
+        address = spl_memo::id()
+    )]
+    pub memo_program: UncheckedAccount<'info>,
+
+    /// The mint of token vault 0
+    #[account(
+        address = token_vault_0.mint,
+        constraint = pool_state.load()?.is_valid_token_program(
+            &vault_0_mint.key(),
+            vault_0_mint.to_account_info().owner
+        ) @ ErrorCode::InvalidTokenProgram
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token vault 1
+    #[account(
+        address = token_vault_1.mint,
+        constraint = pool_state.load()?.is_valid_token_program(
+            &vault_1_mint.key(),
+            vault_1_mint.to_account_info().owner
+        ) @ ErrorCode::InvalidTokenProgram
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+    // remaining accounts
+    // the tick array bitmap extension if the position or the swap needs it, then the reward
+    // accounts as for `decrease_liquidity_v2`, then the tick arrays of the swap
+}
+
+/// Decreases the position's liquidity and swaps everything received of the other token, principal
+/// and fees, into the token chosen by `to_token_0` within the same pool
+pub fn decrease_liquidity_to_single_token<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, DecreaseLiquidityToSingleToken<'info>>,
+    liquidity: u128,
+    to_token_0: bool,
+    amount_out_min: u64,
+) -> Result<()> {
+    let remaining_accounts: &'info [AccountInfo<'info>] = ctx.remaining_accounts;
+    // the accounts of the decrease come first, the rest are the tick arrays of the swap
+    let (bitmap_extension_len, decrease_accounts_len) = {
+        let pool_state = ctx.accounts.pool_state.load()?;
+        let bitmap_extension_len = match remaining_accounts.first() {
+            Some(account_info)
+                if account_info.key() == TickArrayBitmapExtension::key(pool_state.key()) =>
+            {
+                1
+            }
+            _ => 0,
+        };
+        let reward_count = pool_state
+            .reward_infos
+            .iter()
+            .filter(|reward_info| reward_info.initialized())
+            .count();
+        (
+            bitmap_extension_len,
+            bitmap_extension_len + reward_count * 3,
+        )
+    };
+    require_gte!(
+        remaining_accounts.len(),
+        decrease_accounts_len,
+        ErrorCode::InvalidRewardInputAccountNumber
+    );
+    let (decrease_accounts, swap_tick_arrays) = remaining_accounts.split_at(decrease_accounts_len);
+
+    let balance_0_before = ctx.accounts.recipient_token_account_0.amount;
+    let balance_1_before = ctx.accounts.recipient_token_account_1.amount;
+    decrease_liquidity(
+        &ctx.accounts.pool_state,
+        &mut ctx.accounts.protocol_position,
+        &mut ctx.accounts.personal_position,
+        &ctx.accounts.token_vault_0.to_account_info(),
+        &ctx.accounts.token_vault_1.to_account_info(),
+        &ctx.accounts.tick_array_lower,
+        &ctx.accounts.tick_array_upper,
+        &ctx.accounts.recipient_token_account_0.to_account_info(),
+        &ctx.accounts.recipient_token_account_1.to_account_info(),
+        &ctx.accounts.token_program,
+        Some(ctx.accounts.token_program_2022.clone()),
+        Some(ctx.accounts.memo_program.clone()),
+        Some(ctx.accounts.vault_0_mint.clone()),
+        Some(ctx.accounts.vault_1_mint.clone()),
+        decrease_accounts,
+        liquidity,
+        0,
+        0,
+    )?;
+    ctx.accounts.recipient_token_account_0.reload()?;
+    ctx.accounts.recipient_token_account_1.reload()?;
+
+    let accounts = &ctx.accounts;
+    let (
+        input_token_account,
+        output_token_account,
+        input_vault,
+        output_vault,
+        input_vault_mint,
+        output_vault_mint,
+        input_amount,
+        output_balance_before,
+    ) = if to_token_0 {
+        (
+            &accounts.recipient_token_account_1,
+            &accounts.recipient_token_account_0,
+            &accounts.token_vault_1,
+            &accounts.token_vault_0,
+            &accounts.vault_1_mint,
+            &accounts.vault_0_mint,
+            accounts.recipient_token_account_1.amount - balance_1_before,
+            balance_0_before,
+        )
+    } else {
+        (
+            &accounts.recipient_token_account_0,
+            &accounts.recipient_token_account_1,
+            &accounts.token_vault_0,
+            &accounts.token_vault_1,
+            &accounts.vault_0_mint,
+            &accounts.vault_1_mint,
+            accounts.recipient_token_account_0.amount - balance_0_before,
+            balance_1_before,
+        )
+    };
+    let mut output_token_account = output_token_account.clone();
+    if input_amount != 0 {
+        exact_internal_v2(
+            &mut SwapSingleV2 {
+                payer: accounts.nft_owner.clone(),
+                amm_config: accounts.amm_config.clone(),
+                pool_state: accounts.pool_state.clone(),
+                input_token_account: input_token_account.clone(),
+                output_token_account: output_token_account.clone(),
+                input_vault: input_vault.clone(),
+                output_vault: output_vault.clone(),
+                observation_state: accounts.observation_state.clone(),
+                token_program: accounts.token_program.clone(),
+                token_program_2022: accounts.token_program_2022.clone(),
+                memo_program: accounts.memo_program.clone(),
+                input_vault_mint: input_vault_mint.clone(),
+                output_vault_mint: output_vault_mint.clone(),
+            },
+            decrease_accounts[..bitmap_extension_len]
+                .iter()
+                .chain(swap_tick_arrays),
+            input_amount,
+            0,
+            true,
+        )?;
+        output_token_account.reload()?;
+    }
+
+    // the slippage bound covers the withdrawal and the swap together
+    require_gte!(
+        output_token_account.amount - output_balance_before,
+        amount_out_min,
+        ErrorCode::TooLittleOutputReceived
+    );
+    Ok(())
+}
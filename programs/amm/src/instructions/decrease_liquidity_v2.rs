@@ -1,5 +1,7 @@
 use super::decrease_liquidity::decrease_liquidity;
+use crate::error::ErrorCode;
 use crate::states::*;
+use crate::util::{check_payouts_to_holder, is_authorized_for_position, is_authorized_for_token};
 use anchor_lang::prelude::*;
 use anchor_spl::token::Token;
 use anchor_spl::token_interface::Mint;
@@ -7,14 +9,18 @@ use anchor_spl::token_interface::{Token2022, TokenAccount};
 
 #[derive(Accounts)]
 pub struct DecreaseLiquidityV2<'info> {
-    /// The position owner or delegated authority
+    /// The position owner, delegated authority or operator
     pub nft_owner: Signer<'info>,
 
     /// The token account for the tokenized position
     #[account(
-        constraint = nft_account.mint == personal_position.nft_mint,
-        constraint = nft_account.amount == 1,
-        token::authority = nft_owner,
+        constraint = is_authorized_for_position(
+            &nft_owner.key(),
+            &nft_account.to_account_info(),
+            &personal_position.nft_mint,
+            position_delegate.as_deref(),
+            POSITION_DELEGATE_SCOPE_COLLECT,
+        ) @ ErrorCode::NotApproved,
     )]
     pub nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
@@ -88,15 +94,26 @@ pub struct DecreaseLiquidityV2<'info> {
 
     /// The mint of token vault 0
     #[account(
-        address = token_vault_0.mint
+        address = token_vault_0.mint,
+        constraint = pool_state.load()?.is_valid_token_program(
+            &vault_0_mint.key(),
+            vault_0_mint.to_account_info().owner
+        ) @ ErrorCode::InvalidTokenProgram
     )]
     pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
 
     /// The mint of token vault 1
     #[account(
-        address = token_vault_1.mint
+        address = token_vault_1.mint,
+        constraint = pool_state.load()?.is_valid_token_program(
+            &vault_1_mint.key(),
+            vault_1_mint.to_account_info().owner
+        ) @ ErrorCode::InvalidTokenProgram
     )]
     pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The delegation of the nft holder, when an operator signs
+    pub position_delegate: Option<Account<'info, PositionDelegate>>,
     // remaining account
     // #[account(
     //     seeds = [
@@ -108,12 +125,59 @@ pub struct DecreaseLiquidityV2<'info> {
     // pub tick_array_bitmap: AccountLoader<'info, TickArrayBitmapExtension>,
 }
 
+impl<'info> DecreaseLiquidityV2<'info> {
+    /// Applies the operator rules when the signer acts through `position_delegate`: the
+    /// delegation must grant `scope` and the payouts must all go to the nft holder
+    pub fn check_operator(
+        &self,
+        remaining_accounts: &[AccountInfo<'info>],
+        scope: u8,
+    ) -> Result<()> {
+        let nft_account = self.nft_account.to_account_info();
+        if is_authorized_for_token(
+            &self.nft_owner.key(),
+            &nft_account,
+            &self.personal_position.nft_mint,
+        ) {
+            return Ok(());
+        }
+        require!(
+            is_authorized_for_position(
+                &self.nft_owner.key(),
+                &nft_account,
+                &self.personal_position.nft_mint,
+                self.position_delegate.as_deref(),
+                scope,
+            ),
+            ErrorCode::NotApproved
+        );
+        check_payouts_to_holder(
+            &self.nft_account.owner,
+            &self.pool_state.key(),
+            [
+                &self.recipient_token_account_0.to_account_info(),
+                &self.recipient_token_account_1.to_account_info(),
+            ]
+            .into_iter()
+            .chain(remaining_accounts),
+        )
+    }
+}
+
 pub fn decrease_liquidity_v2<'a, 'b, 'c: 'info, 'info>(
     ctx: Context<'a, 'b, 'c, 'info, DecreaseLiquidityV2<'info>>,
     liquidity: u128,
     amount_0_min: u64,
     amount_1_min: u64,
 ) -> Result<()> {
+    ctx.accounts.check_operator(
+        ctx.remaining_accounts,
+        if liquidity == 0 {
+            POSITION_DELEGATE_SCOPE_COLLECT
+        } else {
+            POSITION_DELEGATE_SCOPE_DECREASE
+        },
+    )?;
     decrease_liquidity(
         &ctx.accounts.pool_state,
         &mut ctx.accounts.protocol_position,
@@ -0,0 +1,70 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::{burn, close_spl_account};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct DeletePositionBundle<'info> {
+    /// The bundle NFT owner, receives the rent of the closed accounts
+    #[account(mut)]
+    pub bundle_owner: Signer<'info>,
+
+    /// The bundle NFT mint
+    #[account(
+        mut,
+        address = position_bundle.position_bundle_mint,
+        mint::token_program = token_program_2022,
+    )]
+    pub position_bundle_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The token account holding the bundle NFT
+    #[account(
+        mut,
+        token::mint = position_bundle_mint,
+        token::authority = bundle_owner,
+        constraint = position_bundle_token_account.amount == 1,
+        token::token_program = token_program_2022,
+    )]
+    pub position_bundle_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The bundle to delete, must have no open position
+    #[account(
+        mut,
+        constraint = position_bundle.is_empty() @ ErrorCode::PositionBundleNotEmpty,
+        close = bundle_owner
+    )]
+    pub position_bundle: Box<Account<'info, PositionBundle>>,
+
+    /// Token2022 program to burn and close the NFT accounts
+    pub token_program_2022: Program<'info, Token2022>,
+}
+
+pub fn delete_position_bundle(ctx: Context<DeletePositionBundle>) -> Result<()> {
+    let token_program = ctx.accounts.token_program_2022.to_account_info();
+    let position_bundle_mint = ctx.accounts.position_bundle_mint.to_account_info();
+    let position_bundle_token_account =
+        ctx.accounts.position_bundle_token_account.to_account_info();
+    burn(
+        &ctx.accounts.bundle_owner,
+        &position_bundle_mint,
+        &position_bundle_token_account,
+        &token_program,
+        &[],
+        1,
+    )?;
+    close_spl_account(
+        &ctx.accounts.bundle_owner,
+        &ctx.accounts.bundle_owner,
+        &position_bundle_token_account,
+        &token_program,
+        &[],
+    )?;
+    close_spl_account(
+        &ctx.accounts.position_bundle.to_account_info(),
+        &ctx.accounts.bundle_owner,
+        &position_bundle_mint,
+        &token_program,
+        &[&ctx.accounts.position_bundle.seeds()],
+    )
+}
@@ -0,0 +1,34 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct DeregisterOracleConsumer<'info> {
+    /// The consumer's authority, gets the rent back
+    #[account(mut)]
+    pub consumer: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        mut,
+        constraint = oracle_consumer.consumer == consumer.key() @ ErrorCode::NotApproved,
+        constraint = oracle_consumer.pool_id == pool_state.key(),
+        close = consumer
+    )]
+    pub oracle_consumer: Box<Account<'info, OracleConsumer>>,
+}
+
+pub fn deregister_oracle_consumer(ctx: Context<DeregisterOracleConsumer>) -> Result<()> {
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    pool_state.oracle_consumer_count = pool_state.oracle_consumer_count.checked_sub(1).unwrap();
+    emit!(OracleConsumerEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        consumer: ctx.accounts.consumer.key(),
+        max_staleness: ctx.accounts.oracle_consumer.max_staleness,
+        registered: false,
+        consumer_count: pool_state.oracle_consumer_count,
+    });
+    Ok(())
+}
@@ -0,0 +1,112 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct Donate<'info> {
+    /// The donor
+    pub payer: Signer<'info>,
+
+    /// The pool whose in-range liquidity receives the donation
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The donor's token account for token_0
+    #[account(mut, token::mint = token_vault_0.mint)]
+    pub token_account_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The donor's token account for token_1
+    #[account(mut, token::mint = token_vault_1.mint)]
+    pub token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        mut,
+        constraint = token_vault_0.key() == pool_state.load()?.token_vault_0
+    )]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        mut,
+        constraint = token_vault_1.key() == pool_state.load()?.token_vault_1
+    )]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of token vault 0
+    #[account(
+        address = token_vault_0.mint,
+        constraint = pool_state.load()?.is_valid_token_program(
+            &vault_0_mint.key(),
+            vault_0_mint.to_account_info().owner
+        ) @ ErrorCode::InvalidTokenProgram
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token vault 1
+    #[account(
+        address = token_vault_1.mint,
+        constraint = pool_state.load()?.is_valid_token_program(
+            &vault_1_mint.key(),
+            vault_1_mint.to_account_info().owner
+        ) @ ErrorCode::InvalidTokenProgram
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The SPL program to perform token transfers
+    pub token_program: Program<'info, Token>,
+
+    /// The SPL program 2022 to perform token transfers
+    pub token_program_2022: Program<'info, Token2022>,
+}
+
+/// Transfers the amounts into the vaults and shares what arrives, net of any transfer fee,
+/// across the current in-range liquidity as trading fees. Tokens sent to the vaults directly
+/// are never accounted to anyone.
+pub fn donate(ctx: Context<Donate>, amount_0: u64, amount_1: u64) -> Result<()> {
+    require!(
+        amount_0 != 0 || amount_1 != 0,
+        ErrorCode::ForbidBothZeroForSupplyLiquidity
+    );
+    let amount_0_credited =
+        amount_0 - get_transfer_fee(ctx.accounts.vault_0_mint.clone(), amount_0)?;
+    let amount_1_credited =
+        amount_1 - get_transfer_fee(ctx.accounts.vault_1_mint.clone(), amount_1)?;
+
+    let liquidity = {
+        let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+        pool_state.donate(amount_0_credited, amount_1_credited)?;
+        pool_state.liquidity
+    };
+
+    transfer_from_user_to_pool_vault(
+        &ctx.accounts.payer,
+        &ctx.accounts.token_account_0.to_account_info(),
+        &ctx.accounts.token_vault_0.to_account_info(),
+        Some(ctx.accounts.vault_0_mint.clone()),
+        &ctx.accounts.token_program,
+        Some(ctx.accounts.token_program_2022.to_account_info()),
+        amount_0,
+    )?;
+    transfer_from_user_to_pool_vault(
+        &ctx.accounts.payer,
+        &ctx.accounts.token_account_1.to_account_info(),
+        &ctx.accounts.token_vault_1.to_account_info(),
+        Some(ctx.accounts.vault_1_mint.clone()),
+        &ctx.accounts.token_program,
+        Some(ctx.accounts.token_program_2022.to_account_info()),
+        amount_1,
+    )?;
+
+    emit!(DonateEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        sender: ctx.accounts.payer.key(),
+        amount_0: amount_0_credited,
+        amount_1: amount_1_credited,
+        liquidity,
+    });
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::{close_spl_account, transfer_position_nft};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct EndPositionLease<'info> {
+    /// The owner of the leased position
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = position_lease.owner == owner.key() @ ErrorCode::NotApproved,
+        close = owner
+    )]
+    pub position_lease: Box<Account<'info, PositionLeaseState>>,
+
+    /// Mint address of the leased position nft
+    #[account(
+        address = position_lease.position_nft_mint,
+        mint::token_program = token_program,
+    )]
+    pub position_nft_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The escrow token account holding the position nft
+    #[account(
+        mut,
+        address = position_lease.leased_nft_account,
+    )]
+    pub leased_nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The token account to receive the position nft
+    #[account(
+        mut,
+        token::mint = position_nft_mint,
+        token::token_program = token_program,
+    )]
+    pub position_nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token/Token2022 program of the position nft
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn end_position_lease(ctx: Context<EndPositionLease>) -> Result<()> {
+    let block_timestamp = u64::try_from(Clock::get()?.unix_timestamp).unwrap();
+    let position_lease = ctx.accounts.position_lease.as_ref();
+    require!(
+        !position_lease.is_active(block_timestamp),
+        ErrorCode::PositionLeaseActive
+    );
+
+    let position_lease_info = position_lease.to_account_info();
+    let token_program = ctx.accounts.token_program.to_account_info();
+    let leased_nft_account = ctx.accounts.leased_nft_account.to_account_info();
+    transfer_position_nft(
+        &position_lease_info,
+        &leased_nft_account,
+        &ctx.accounts.position_nft_account.to_account_info(),
+        &ctx.accounts.position_nft_mint.to_account_info(),
+        &token_program,
+        &[&position_lease.seeds()],
+    )?;
+
+    // close the escrow token account
+    close_spl_account(
+        &position_lease_info,
+        &ctx.accounts.owner.to_account_info(),
+        &leased_nft_account,
+        &token_program,
+        &[&position_lease.seeds()],
+    )?;
+
+    emit!(EndPositionLeaseEvent {
+        position_nft_mint: position_lease.position_nft_mint,
+        owner: position_lease.owner,
+        lessee: position_lease.lessee,
+    });
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ExtendPositionLock<'info> {
+    /// The owner of the locked position
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = locked_position.owner == owner.key() @ ErrorCode::NotApproved
+    )]
+    pub locked_position: Box<Account<'info, LockedPositionState>>,
+}
+
+pub fn extend_position_lock(ctx: Context<ExtendPositionLock>, unlock_time: u64) -> Result<()> {
+    let locked_position = ctx.accounts.locked_position.as_mut();
+    require_gt!(
+        unlock_time,
+        locked_position.unlock_time,
+        ErrorCode::InvalidUnlockTime
+    );
+    locked_position.unlock_time = unlock_time;
+
+    emit!(LockPositionEvent {
+        position_nft_mint: locked_position.position_nft_mint,
+        owner: locked_position.owner,
+        unlock_time,
+    });
+    Ok(())
+}
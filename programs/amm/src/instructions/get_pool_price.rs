@@ -0,0 +1,26 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct GetPoolPrice<'info> {
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PoolPrice {
+    /// Decimal adjusted price of token_0 denominated in token_1, as a Q64.64
+    pub price_x64: u128,
+    /// Decimal adjusted price of token_1 denominated in token_0, as a Q64.64
+    pub inverse_price_x64: u128,
+    /// The creator's preferred quote orientation
+    pub quote_orientation: u8,
+}
+
+pub fn get_pool_price(ctx: Context<GetPoolPrice>) -> Result<PoolPrice> {
+    let pool_state = ctx.accounts.pool_state.load()?;
+    Ok(PoolPrice {
+        price_x64: pool_state.get_price_x64(QuoteOrientation::Token1PerToken0)?,
+        inverse_price_x64: pool_state.get_price_x64(QuoteOrientation::Token0PerToken1)?,
+        quote_orientation: pool_state.get_quote_orientation() as u8,
+    })
+}
@@ -0,0 +1,186 @@
+use super::increase_liquidity::calculate_latest_token_fees;
+use crate::libraries::liquidity_math;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct GetPositionState<'info> {
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The position to report on
+    #[account(constraint = personal_position.pool_id == pool_state.key())]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    /// Stores init state for the lower tick
+    #[account(constraint = tick_array_lower.load()?.pool_id == pool_state.key())]
+    pub tick_array_lower: AccountLoader<'info, TickArrayState>,
+
+    /// Stores init state for the upper tick
+    #[account(constraint = tick_array_upper.load()?.pool_id == pool_state.key())]
+    pub tick_array_upper: AccountLoader<'info, TickArrayState>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PositionSnapshot {
+    /// The liquidity of the position
+    pub liquidity: u128,
+    /// The lower bound tick of the position
+    pub tick_lower_index: i32,
+    /// The upper bound tick of the position
+    pub tick_upper_index: i32,
+    /// The token_0 fees a collect would pay out now
+    pub fees_owed_0: u64,
+    /// The token_1 fees a collect would pay out now
+    pub fees_owed_1: u64,
+    /// The rewards a collect would pay out now, by reward index
+    pub reward_amounts_owed: [u64; REWARD_NUM],
+    /// The token_0 removing all the liquidity would withdraw at the current price
+    pub amount_0: u64,
+    /// The token_1 removing all the liquidity would withdraw at the current price
+    pub amount_1: u64,
+}
+
+/// Settles `personal_position` against the pool with the same math a collect uses, the pool's
+/// reward infos must already be brought up to the current time
+pub fn position_snapshot(
+    pool_state: &PoolState,
+    personal_position: &PersonalPositionState,
+    tick_lower_state: &TickState,
+    tick_upper_state: &TickState,
+) -> Result<PositionSnapshot> {
+    let (fee_growth_inside_0_x64, fee_growth_inside_1_x64) = tick_array::get_fee_growth_inside(
+        tick_lower_state,
+        tick_upper_state,
+        pool_state.tick_current,
+        pool_state.fee_growth_global_0_x64,
+        pool_state.fee_growth_global_1_x64,
+    );
+    let reward_growths_inside = tick_array::get_reward_growths_inside(
+        tick_lower_state,
+        tick_upper_state,
+        pool_state.tick_current,
+        &pool_state.reward_infos,
+    );
+    let mut settled_position = personal_position.clone();
+    settled_position.update_rewards(reward_growths_inside, true)?;
+
+    let (amount_0, amount_1) = liquidity_math::get_delta_amounts_signed(
+        pool_state.tick_current,
+        pool_state.sqrt_price_x64,
+        personal_position.tick_lower_index,
+        personal_position.tick_upper_index,
+        -i128::try_from(personal_position.liquidity).unwrap(),
+    )?;
+
+    Ok(PositionSnapshot {
+        liquidity: personal_position.liquidity,
+        tick_lower_index: personal_position.tick_lower_index,
+        tick_upper_index: personal_position.tick_upper_index,
+        fees_owed_0: calculate_latest_token_fees(
+            personal_position.token_fees_owed_0,
+            personal_position.fee_growth_inside_0_last_x64,
+            fee_growth_inside_0_x64,
+            personal_position.liquidity,
+        ),
+        fees_owed_1: calculate_latest_token_fees(
+            personal_position.token_fees_owed_1,
+            personal_position.fee_growth_inside_1_last_x64,
+            fee_growth_inside_1_x64,
+            personal_position.liquidity,
+        ),
+        reward_amounts_owed: settled_position
+            .reward_infos
+            .map(|reward_info| reward_info.reward_amount_owed),
+        amount_0,
+        amount_1,
+    })
+}
+
+/// Reports what the position would collect and withdraw if it were closed out now, so clients
+/// don't have to replicate the fee and reward growth math
+pub fn get_position_state(ctx: Context<GetPositionState>) -> Result<PositionSnapshot> {
+    let personal_position = &ctx.accounts.personal_position;
+    // the rewards are settled on a copy, the pool is not written
+    let mut pool_state = *ctx.accounts.pool_state.load()?;
+    pool_state.update_reward_infos(u64::try_from(Clock::get()?.unix_timestamp).unwrap())?;
+
+    let tick_lower_state = ctx
+        .accounts
+        .tick_array_lower
+        .load()?
+        .get_tick_state(personal_position.tick_lower_index, pool_state.tick_spacing)?;
+    let tick_upper_state = ctx
+        .accounts
+        .tick_array_upper
+        .load()?
+        .get_tick_state(personal_position.tick_upper_index, pool_state.tick_spacing)?;
+    position_snapshot(
+        &pool_state,
+        personal_position,
+        &tick_lower_state,
+        &tick_upper_state,
+    )
+}
+
+#[cfg(test)]
+mod get_position_state_test {
+    use super::*;
+    use crate::libraries::{fixed_point_64, tick_math};
+    use crate::states::pool_test::build_pool;
+    use crate::states::tick_array_test::build_tick;
+
+    #[test]
+    fn position_snapshot_test() {
+        let pool_state = build_pool(0, 10, tick_math::get_sqrt_price_at_tick(0).unwrap(), 1000);
+        let mut pool_state = pool_state.take();
+        pool_state.fee_growth_global_0_x64 = 3 * fixed_point_64::Q64;
+        pool_state.fee_growth_global_1_x64 = 5 * fixed_point_64::Q64;
+        pool_state.reward_infos[0].token_mint = Pubkey::new_unique();
+        pool_state.reward_infos[0].reward_growth_global_x64 = 7 * fixed_point_64::Q64;
+        let tick_lower_state = build_tick(-100, 1000, 1000).take();
+        let tick_upper_state = build_tick(100, 1000, -1000).take();
+
+        let mut personal_position = PersonalPositionState {
+            pool_id: Pubkey::new_unique(),
+            tick_lower_index: -100,
+            tick_upper_index: 100,
+            liquidity: 1000,
+            fee_growth_inside_0_last_x64: fixed_point_64::Q64,
+            token_fees_owed_0: 5,
+            ..Default::default()
+        };
+        personal_position.reward_infos[0].reward_amount_owed = 11;
+        let snapshot = position_snapshot(
+            &pool_state,
+            &personal_position,
+            &tick_lower_state,
+            &tick_upper_state,
+        )
+        .unwrap();
+        assert_eq!(snapshot.liquidity, 1000);
+        assert_eq!(snapshot.fees_owed_0, 5 + 2 * 1000);
+        assert_eq!(snapshot.fees_owed_1, 5 * 1000);
+        assert_eq!(snapshot.reward_amounts_owed, [11 + 7 * 1000, 0, 0]);
+        // in range the position holds both tokens, the withdrawal rounds down
+        let (amount_0, amount_1) =
+            liquidity_math::get_delta_amounts_signed(0, pool_state.sqrt_price_x64, -100, 100, 1000)
+                .unwrap();
+        assert!(snapshot.amount_0 > 0 && snapshot.amount_0 <= amount_0);
+        assert!(snapshot.amount_1 > 0 && snapshot.amount_1 <= amount_1);
+
+        // below the range the position is all token_0
+        pool_state.tick_current = -200;
+        pool_state.sqrt_price_x64 = tick_math::get_sqrt_price_at_tick(-200).unwrap();
+        let snapshot = position_snapshot(
+            &pool_state,
+            &personal_position,
+            &tick_lower_state,
+            &tick_upper_state,
+        )
+        .unwrap();
+        assert!(snapshot.amount_0 > 0);
+        assert_eq!(snapshot.amount_1, 0);
+        // the position is not settled
+        assert_eq!(personal_position.token_fees_owed_0, 5);
+    }
+}
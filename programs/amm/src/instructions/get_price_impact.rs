@@ -0,0 +1,105 @@
+use super::swap::{quote_swap, QuoteSwap};
+use crate::error::ErrorCode;
+use crate::libraries::{big_num::U256, fixed_point_64, full_math::MulDiv, price_math};
+use anchor_lang::prelude::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PriceImpact {
+    /// The amount the pool would take in, trade fee included
+    pub amount_in: u64,
+    /// The amount the pool would pay out
+    pub amount_out: u64,
+    /// Decimal adjusted price of token_0 denominated in token_1 before the trade, as a Q64.64
+    pub spot_price_x64: u128,
+    /// Decimal adjusted average price the trade fills at, token_1 per token_0, as a Q64.64
+    pub execution_price_x64: u128,
+    /// Decimal adjusted price the pool is left at, token_1 per token_0, as a Q64.64
+    pub end_price_x64: u128,
+    /// How far the execution price is from the spot price, in bips of the spot price
+    pub price_impact_bps: u64,
+}
+
+/// The raw average price of a fill of `amount_0` against `amount_1`, token_1 per token_0 as a
+/// Q64.64
+pub fn execution_price_x64(amount_0: u64, amount_1: u64) -> Result<u128> {
+    require!(amount_0 != 0, ErrorCode::CalculateOverflow);
+    let price_x64 = (U256::from(amount_1) << fixed_point_64::RESOLUTION) / U256::from(amount_0);
+    require!(price_x64.bits() <= 128, ErrorCode::CalculateOverflow);
+    Ok(price_x64.as_u128())
+}
+
+/// The distance between `execution_price_x64` and `spot_price_x64` in bips of the spot price,
+/// saturating at `u64::MAX`
+pub fn price_impact_bps(spot_price_x64: u128, execution_price_x64: u128) -> u64 {
+    if spot_price_x64 == 0 {
+        return u64::MAX;
+    }
+    let price_delta = U256::from(spot_price_x64.abs_diff(execution_price_x64));
+    match price_delta.mul_div_floor(U256::from(10_000), U256::from(spot_price_x64)) {
+        Some(impact) if impact <= U256::from(u64::MAX) => impact.as_u64(),
+        _ => u64::MAX,
+    }
+}
+
+/// Quotes the trade with the same tick traversal as the swap, and compares the price it fills at
+/// and the price it leaves the pool at with the current price
+pub fn get_price_impact<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, QuoteSwap<'info>>,
+    amount: u64,
+    zero_for_one: bool,
+    is_base_input: bool,
+) -> Result<PriceImpact> {
+    let (sqrt_price_x64, decimals_0, decimals_1) = {
+        let pool_state = ctx.accounts.pool_state.load()?;
+        (
+            pool_state.sqrt_price_x64,
+            pool_state.mint_decimals_0,
+            pool_state.mint_decimals_1,
+        )
+    };
+    let quote = quote_swap(ctx, amount, 0, zero_for_one, is_base_input)?;
+    let (amount_0, amount_1) = if zero_for_one {
+        (quote.amount_in, quote.amount_out)
+    } else {
+        (quote.amount_out, quote.amount_in)
+    };
+    let spot_price_x64 =
+        price_math::get_price_x64_with_decimals(sqrt_price_x64, decimals_0, decimals_1, false)?;
+    let execution_price_x64 = price_math::adjust_price_x64_by_decimals(
+        execution_price_x64(amount_0, amount_1)?,
+        decimals_0,
+        decimals_1,
+    )?;
+    Ok(PriceImpact {
+        amount_in: quote.amount_in,
+        amount_out: quote.amount_out,
+        spot_price_x64,
+        execution_price_x64,
+        end_price_x64: price_math::get_price_x64_with_decimals(
+            quote.sqrt_price_x64_after,
+            decimals_0,
+            decimals_1,
+            false,
+        )?,
+        price_impact_bps: price_impact_bps(spot_price_x64, execution_price_x64),
+    })
+}
+
+#[cfg(test)]
+mod get_price_impact_test {
+    use super::*;
+    use crate::libraries::fixed_point_64::Q64;
+
+    #[test]
+    fn execution_price_test() {
+        assert_eq!(execution_price_x64(100, 100).unwrap(), Q64);
+        assert_eq!(execution_price_x64(200, 100).unwrap(), Q64 / 2);
+        assert!(execution_price_x64(0, 100).is_err());
+
+        assert_eq!(price_impact_bps(Q64, Q64), 0);
+        // the fill is 1% worse than spot either way
+        assert_eq!(price_impact_bps(100 * Q64, 99 * Q64), 100);
+        assert_eq!(price_impact_bps(100 * Q64, 101 * Q64), 100);
+        assert_eq!(price_impact_bps(0, Q64), u64::MAX);
+    }
+}
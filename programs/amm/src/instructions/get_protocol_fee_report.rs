@@ -0,0 +1,89 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+/// Reads the pools to report from remaining accounts, any number of configs can be mixed
+#[derive(Accounts)]
+pub struct GetProtocolFeeReport {}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MintProtocolFees {
+    pub mint: Pubkey,
+    /// The protocol fees accrued and not yet collected, summed over the reported pools
+    pub amount: u128,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProtocolFeeReport {
+    /// The number of distinct pools reported, a pool passed twice is counted once
+    pub pool_count: u16,
+    /// The totals per mint, in the order the mints are first met
+    pub totals: Vec<MintProtocolFees>,
+}
+
+impl ProtocolFeeReport {
+    pub fn add(&mut self, mint: Pubkey, amount: u64) {
+        match self.totals.iter_mut().find(|total| total.mint == mint) {
+            Some(total) => total.amount += u128::from(amount),
+            None => self.totals.push(MintProtocolFees {
+                mint,
+                amount: u128::from(amount),
+            }),
+        }
+    }
+}
+
+pub fn get_protocol_fee_report<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, GetProtocolFeeReport>,
+) -> Result<ProtocolFeeReport> {
+    let mut report = ProtocolFeeReport::default();
+    let mut pool_ids = Vec::with_capacity(ctx.remaining_accounts.len());
+    for account_info in ctx.remaining_accounts {
+        if pool_ids.contains(account_info.key) {
+            continue;
+        }
+        pool_ids.push(*account_info.key);
+        let pool_state_loader = AccountLoader::<PoolState>::try_from(account_info)?;
+        let pool_state = pool_state_loader.load()?;
+        report.add(pool_state.token_mint_0, pool_state.protocol_fees_token_0);
+        report.add(pool_state.token_mint_1, pool_state.protocol_fees_token_1);
+    }
+    report.pool_count = u16::try_from(pool_ids.len()).unwrap();
+    Ok(report)
+}
+
+#[cfg(test)]
+mod get_protocol_fee_report_test {
+    use super::*;
+
+    #[test]
+    fn add_test() {
+        let (mint_a, mint_b, mint_c) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let mut report = ProtocolFeeReport::default();
+        // pools a/b and b/c
+        report.add(mint_a, 10);
+        report.add(mint_b, u64::MAX);
+        report.add(mint_b, u64::MAX);
+        report.add(mint_c, 0);
+        assert_eq!(
+            report.totals,
+            vec![
+                MintProtocolFees {
+                    mint: mint_a,
+                    amount: 10
+                },
+                MintProtocolFees {
+                    mint: mint_b,
+                    amount: 2 * u128::from(u64::MAX)
+                },
+                MintProtocolFees {
+                    mint: mint_c,
+                    amount: 0
+                },
+            ]
+        );
+    }
+}
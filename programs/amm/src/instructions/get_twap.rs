@@ -0,0 +1,46 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+/// The fewest observations a twap must span to be reported as confident
+pub const TWAP_MIN_OBSERVATIONS: u16 = 3;
+/// The oldest the newest observation can be, in seconds, for a twap to be reported as confident
+pub const TWAP_MAX_STALENESS: u32 = 5 * 60;
+
+#[derive(Accounts)]
+pub struct GetTwap<'info> {
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The oracle of the pool
+    #[account(address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TwapResult {
+    /// The time weighted average tick over the window, rounded down
+    pub twap_tick: i32,
+    /// The number of observations the average spans
+    pub observation_count: u16,
+    /// Seconds since the newest observation
+    pub staleness: u32,
+    /// Whether the average spans at least `TWAP_MIN_OBSERVATIONS` observations and the newest
+    /// is at most `TWAP_MAX_STALENESS` seconds old
+    pub confident: bool,
+}
+
+pub fn get_twap(ctx: Context<GetTwap>, window_seconds: u32) -> Result<TwapResult> {
+    let twap = ctx
+        .accounts
+        .observation_state
+        .load()?
+        .twap(oracle::block_timestamp(), window_seconds)
+        .ok_or(ErrorCode::ObservationNotEnough)?;
+    Ok(TwapResult {
+        twap_tick: twap.tick,
+        observation_count: twap.observation_count,
+        staleness: twap.staleness,
+        confident: twap.observation_count >= TWAP_MIN_OBSERVATIONS
+            && twap.staleness <= TWAP_MAX_STALENESS,
+    })
+}
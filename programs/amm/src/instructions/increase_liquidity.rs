@@ -14,15 +14,21 @@ pub struct IncreaseLiquidity<'info> {
 
     /// The token account for nft
     #[account(
-        constraint = nft_account.mint == personal_position.nft_mint,
-        constraint = nft_account.amount == 1,
-        token::authority = nft_owner
+        constraint = is_authorized_for_token(
+            &nft_owner.key(),
+            &nft_account.to_account_info(),
+            &personal_position.nft_mint
+        ) @ ErrorCode::NotApproved
     )]
     pub nft_account: Box<Account<'info, TokenAccount>>,
 
     #[account(mut)]
     pub pool_state: AccountLoader<'info, PoolState>,
 
+    /// The pool's config, liquidity can't be added while it is paused
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
     #[account(
         mut,
         seeds = [
@@ -99,6 +105,7 @@ pub fn increase_liquidity_v1<'a, 'b, 'c: 'info, 'info>(
     increase_liquidity(
         &ctx.accounts.nft_owner,
         &ctx.accounts.pool_state,
+        &ctx.accounts.amm_config,
         &mut ctx.accounts.protocol_position,
         &mut ctx.accounts.personal_position,
         &ctx.accounts.tick_array_lower,
@@ -122,6 +129,7 @@ pub fn increase_liquidity_v1<'a, 'b, 'c: 'info, 'info>(
 pub fn increase_liquidity<'a, 'b, 'c: 'info, 'info>(
     nft_owner: &'b Signer<'info>,
     pool_state_loader: &'b AccountLoader<'info, PoolState>,
+    amm_config: &AmmConfig,
     protocol_position: &'b mut Box<Account<'info, ProtocolPositionState>>,
     personal_position: &'b mut Box<Account<'info, PersonalPositionState>>,
     tick_array_lower_loader: &'b AccountLoader<'info, TickArrayState>,
@@ -143,7 +151,9 @@ pub fn increase_liquidity<'a, 'b, 'c: 'info, 'info>(
 ) -> Result<()> {
     let mut liquidity = liquidity;
     let pool_state = &mut pool_state_loader.load_mut()?;
-    if !pool_state.get_status_by_bit(PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity) {
+    if !pool_state.get_status_by_bit(PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity)
+        || !amm_config.get_status_by_bit(PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity)
+    {
         return err!(ErrorCode::NotApproved);
     }
     let tick_lower = personal_position.tick_lower_index;
@@ -152,6 +162,9 @@ pub fn increase_liquidity<'a, 'b, 'c: 'info, 'info>(
     let use_tickarray_bitmap_extension =
         pool_state.is_overflow_default_tickarray_bitmap(vec![tick_lower, tick_upper]);
 
+    let vault_0_balance_before = get_token_account_amount(token_vault_0)?;
+    let vault_1_balance_before = get_token_account_amount(token_vault_1)?;
+
     let (amount_0, amount_1, amount_0_transfer_fee, amount_1_transfer_fee) = add_liquidity(
         &nft_owner,
         token_account_0,
@@ -183,33 +196,44 @@ pub fn increase_liquidity<'a, 'b, 'c: 'info, 'info>(
         base_flag,
     )?;
 
-    personal_position.token_fees_owed_0 = calculate_latest_token_fees(
-        personal_position.token_fees_owed_0,
-        personal_position.fee_growth_inside_0_last_x64,
-        protocol_position.fee_growth_inside_0_last_x64,
-        personal_position.liquidity,
-    );
-    personal_position.token_fees_owed_1 = calculate_latest_token_fees(
-        personal_position.token_fees_owed_1,
-        personal_position.fee_growth_inside_1_last_x64,
-        protocol_position.fee_growth_inside_1_last_x64,
-        personal_position.liquidity,
-    );
-
-    personal_position.fee_growth_inside_0_last_x64 = protocol_position.fee_growth_inside_0_last_x64;
-    personal_position.fee_growth_inside_1_last_x64 = protocol_position.fee_growth_inside_1_last_x64;
-
-    // update rewards, must update before increase liquidity
-    personal_position.update_rewards(protocol_position.reward_growth_inside, true)?;
-    personal_position.liquidity = personal_position.liquidity.checked_add(liquidity).unwrap();
+    credit_position_liquidity(personal_position, protocol_position, liquidity)?;
 
+    crate::log_kv!(
+        "increase_liquidity",
+        liquidity = liquidity,
+        amount_0 = amount_0,
+        amount_1 = amount_1,
+        amount_0_transfer_fee = amount_0_transfer_fee,
+        amount_1_transfer_fee = amount_1_transfer_fee,
+    );
+    let tick_spacing = pool_state.tick_spacing;
+    let tick_lower_state = tick_array_lower_loader
+        .load()?
+        .get_tick_state(tick_lower, tick_spacing)?;
+    let tick_upper_state = tick_array_upper_loader
+        .load()?
+        .get_tick_state(tick_upper, tick_spacing)?;
     emit!(IncreaseLiquidityEvent {
         position_nft_mint: personal_position.nft_mint,
         liquidity,
         amount_0,
         amount_1,
         amount_0_transfer_fee,
-        amount_1_transfer_fee
+        amount_1_transfer_fee,
+        vault_0_balance_before,
+        vault_1_balance_before,
+        vault_0_balance_after: get_token_account_amount(token_vault_0)?,
+        vault_1_balance_after: get_token_account_amount(token_vault_1)?,
+        position_hash: personal_position.position_hash(),
+        incentive_program: personal_position.incentive_program,
+        tick_lower,
+        tick_upper,
+        position_liquidity: personal_position.liquidity,
+        pool_liquidity: pool_state.liquidity,
+        tick_lower_liquidity_net: tick_lower_state.liquidity_net,
+        tick_lower_liquidity_gross: tick_lower_state.liquidity_gross,
+        tick_upper_liquidity_net: tick_upper_state.liquidity_net,
+        tick_upper_liquidity_gross: tick_upper_state.liquidity_gross,
     });
 
     Ok(())
@@ -230,3 +254,32 @@ pub fn calculate_latest_token_fees(
     msg!("calculate_latest_token_fees fee_growth_delta:{}, fee_growth_inside_latest_x64:{}, fee_growth_inside_last_x64:{}, liquidity:{}", fee_growth_delta, fee_growth_inside_latest_x64, fee_growth_inside_last_x64, liquidity);
     last_total_fees.checked_add(fee_growth_delta).unwrap()
 }
+
+/// Settles the position's fees and rewards up to the protocol position's growth, then adds
+/// `liquidity` already added to the range
+pub fn credit_position_liquidity(
+    personal_position: &mut PersonalPositionState,
+    protocol_position: &ProtocolPositionState,
+    liquidity: u128,
+) -> Result<()> {
+    personal_position.token_fees_owed_0 = calculate_latest_token_fees(
+        personal_position.token_fees_owed_0,
+        personal_position.fee_growth_inside_0_last_x64,
+        protocol_position.fee_growth_inside_0_last_x64,
+        personal_position.liquidity,
+    );
+    personal_position.token_fees_owed_1 = calculate_latest_token_fees(
+        personal_position.token_fees_owed_1,
+        personal_position.fee_growth_inside_1_last_x64,
+        protocol_position.fee_growth_inside_1_last_x64,
+        personal_position.liquidity,
+    );
+
+    personal_position.fee_growth_inside_0_last_x64 = protocol_position.fee_growth_inside_0_last_x64;
+    personal_position.fee_growth_inside_1_last_x64 = protocol_position.fee_growth_inside_1_last_x64;
+
+    // update rewards, must update before increase liquidity
+    personal_position.update_rewards(protocol_position.reward_growth_inside, true)?;
+    personal_position.liquidity = personal_position.liquidity.checked_add(liquidity).unwrap();
+    Ok(())
+}
@@ -1,5 +1,7 @@
 use super::increase_liquidity::increase_liquidity;
+use crate::error::ErrorCode;
 use crate::states::*;
+use crate::util::is_authorized_for_token;
 use anchor_lang::prelude::*;
 use anchor_spl::token::Token;
 use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
@@ -11,15 +13,21 @@ pub struct IncreaseLiquidityV2<'info> {
 
     /// The token account for nft
     #[account(
-        constraint = nft_account.mint == personal_position.nft_mint,
-        constraint = nft_account.amount == 1,
-        token::authority = nft_owner,
+        constraint = is_authorized_for_token(
+            &nft_owner.key(),
+            &nft_account.to_account_info(),
+            &personal_position.nft_mint
+        ) @ ErrorCode::NotApproved,
     )]
     pub nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(mut)]
     pub pool_state: AccountLoader<'info, PoolState>,
 
+    /// The pool's config, liquidity can't be added while it is paused
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
     #[account(
         mut,
         seeds = [
@@ -81,13 +89,21 @@ pub struct IncreaseLiquidityV2<'info> {
 
     /// The mint of token vault 0
     #[account(
-            address = token_vault_0.mint
+        address = token_vault_0.mint,
+        constraint = pool_state.load()?.is_valid_token_program(
+            &vault_0_mint.key(),
+            vault_0_mint.to_account_info().owner
+        ) @ ErrorCode::InvalidTokenProgram
     )]
     pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
 
     /// The mint of token vault 1
     #[account(
-            address = token_vault_1.mint
+        address = token_vault_1.mint,
+        constraint = pool_state.load()?.is_valid_token_program(
+            &vault_1_mint.key(),
+            vault_1_mint.to_account_info().owner
+        ) @ ErrorCode::InvalidTokenProgram
     )]
     pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
     // remaining account
@@ -111,6 +127,7 @@ pub fn increase_liquidity_v2<'a, 'b, 'c: 'info, 'info>(
     increase_liquidity(
         &ctx.accounts.nft_owner,
         &ctx.accounts.pool_state,
+        &ctx.accounts.amm_config,
         &mut ctx.accounts.protocol_position,
         &mut ctx.accounts.personal_position,
         &ctx.accounts.tick_array_lower,
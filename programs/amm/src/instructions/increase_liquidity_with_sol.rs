@@ -0,0 +1,148 @@
+use super::increase_liquidity::increase_liquidity;
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+#[derive(Accounts)]
+pub struct IncreaseLiquidityWithSol<'info> {
+    /// Pays to mint the position, funds its native mint token accounts and receives what is
+    /// left of them
+    #[account(mut)]
+    pub nft_owner: Signer<'info>,
+
+    /// The token account for nft
+    #[account(
+        constraint = is_authorized_for_token(
+            &nft_owner.key(),
+            &nft_account.to_account_info(),
+            &personal_position.nft_mint
+        ) @ ErrorCode::NotApproved
+    )]
+    pub nft_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The pool's config, liquidity can't be added while it is paused
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    #[account(
+        mut,
+        seeds = [
+            POSITION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &personal_position.tick_lower_index.to_be_bytes(),
+            &personal_position.tick_upper_index.to_be_bytes(),
+        ],
+        bump,
+        constraint = protocol_position.pool_id == pool_state.key(),
+    )]
+    pub protocol_position: Box<Account<'info, ProtocolPositionState>>,
+
+    /// Increase liquidity for this position
+    #[account(mut, constraint = personal_position.pool_id == pool_state.key())]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    /// Stores init state for the lower tick
+    #[account(mut, constraint = tick_array_lower.load()?.pool_id == pool_state.key())]
+    pub tick_array_lower: AccountLoader<'info, TickArrayState>,
+
+    /// Stores init state for the upper tick
+    #[account(mut, constraint = tick_array_upper.load()?.pool_id == pool_state.key())]
+    pub tick_array_upper: AccountLoader<'info, TickArrayState>,
+
+    /// The payer's token account for token_0, wrapped from SOL if of the native mint
+    #[account(
+        mut,
+        token::mint = token_vault_0.mint
+    )]
+    pub token_account_0: Box<Account<'info, TokenAccount>>,
+
+    /// The payer's token account for token_1, wrapped from SOL if of the native mint
+    #[account(
+        mut,
+        token::mint = token_vault_1.mint
+    )]
+    pub token_account_1: Box<Account<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        mut,
+        constraint = token_vault_0.key() == pool_state.load()?.token_vault_0
+    )]
+    pub token_vault_0: Box<Account<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        mut,
+        constraint = token_vault_1.key() == pool_state.load()?.token_vault_1
+    )]
+    pub token_vault_1: Box<Account<'info, TokenAccount>>,
+
+    /// Program to create mint account and mint tokens
+    pub token_program: Program<'info, Token>,
+
+    /// System program to wrap native SOL
+    pub system_program: Program<'info, System>,
+    // remaining account
+    // #[account(
+    //     seeds = [
+    //         POOL_TICK_ARRAY_BITMAP_SEED.as_bytes(),
+    //         pool_state.key().as_ref(),
+    //     ],
+    //     bump
+    // )]
+    // pub tick_array_bitmap: AccountLoader<'info, TickArrayBitmapExtension>,
+}
+
+/// Increases liquidity as `increase_liquidity` does, funding the nft owner's native mint token
+/// accounts from its lamports up to the maximum amounts and closing them back to it at the end
+pub fn increase_liquidity_with_sol<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, IncreaseLiquidityWithSol<'info>>,
+    liquidity: u128,
+    amount_0_max: u64,
+    amount_1_max: u64,
+) -> Result<()> {
+    let nft_owner = ctx.accounts.nft_owner.to_account_info();
+    for (token_account, amount_max) in [
+        (&ctx.accounts.token_account_0, amount_0_max),
+        (&ctx.accounts.token_account_1, amount_1_max),
+    ] {
+        wrap_sol(
+            &nft_owner,
+            token_account,
+            &ctx.accounts.token_program,
+            &ctx.accounts.system_program,
+            amount_max,
+        )?;
+    }
+    increase_liquidity(
+        &ctx.accounts.nft_owner,
+        &ctx.accounts.pool_state,
+        &ctx.accounts.amm_config,
+        &mut ctx.accounts.protocol_position,
+        &mut ctx.accounts.personal_position,
+        &ctx.accounts.tick_array_lower,
+        &ctx.accounts.tick_array_upper,
+        &ctx.accounts.token_account_0.to_account_info(),
+        &ctx.accounts.token_account_1.to_account_info(),
+        &ctx.accounts.token_vault_0.to_account_info(),
+        &ctx.accounts.token_vault_1.to_account_info(),
+        &ctx.accounts.token_program,
+        None,
+        None,
+        None,
+        &ctx.remaining_accounts,
+        liquidity,
+        amount_0_max,
+        amount_1_max,
+        None,
+    )?;
+    for token_account in [&ctx.accounts.token_account_0, &ctx.accounts.token_account_1] {
+        unwrap_sol(&nft_owner, token_account, &ctx.accounts.token_program)?;
+    }
+    Ok(())
+}
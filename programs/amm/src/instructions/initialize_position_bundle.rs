@@ -0,0 +1,102 @@
+use crate::states::*;
+use crate::util::{check_position_nft_mint, create_position_nft_mint_with_extensions};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::{create, AssociatedToken, Create};
+use anchor_spl::token_2022::{self, spl_token_2022::instruction::AuthorityType, Token2022};
+
+#[derive(Accounts)]
+pub struct InitializePositionBundle<'info> {
+    /// Pays to create the bundle
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Receives the bundle NFT
+    pub position_bundle_owner: UncheckedAccount<'info>,
+
+    /// Unique token mint address, initialize in contract
+    #[account(mut)]
+    pub position_bundle_mint: Signer<'info>,
+
+    /// CHECK: ATA address where the bundle NFT will be minted, initialize in contract
+    #[account(mut)]
+    pub position_bundle_token_account: UncheckedAccount<'info>,
+
+    /// The bundle state, also the authority of the bundle mint
+    #[account(
+        init,
+        seeds = [POSITION_BUNDLE_SEED.as_bytes(), position_bundle_mint.key().as_ref()],
+        bump,
+        payer = payer,
+        space = PositionBundle::LEN
+    )]
+    pub position_bundle: Box<Account<'info, PositionBundle>>,
+
+    /// Program to create the bundle state account
+    pub system_program: Program<'info, System>,
+
+    /// Program to create an ATA for receiving the bundle NFT
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    /// Program to create the NFT mint and token account
+    pub token_program_2022: Program<'info, Token2022>,
+}
+
+pub fn initialize_position_bundle(ctx: Context<InitializePositionBundle>) -> Result<()> {
+    let position_bundle = ctx.accounts.position_bundle.as_mut();
+    position_bundle.bump = [ctx.bumps.position_bundle];
+    position_bundle.position_bundle_mint = ctx.accounts.position_bundle_mint.key();
+
+    let position_bundle_info = position_bundle.to_account_info();
+    let position_bundle_mint = ctx.accounts.position_bundle_mint.to_account_info();
+    create_position_nft_mint_with_extensions(
+        &ctx.accounts.payer,
+        &position_bundle_mint,
+        &position_bundle_info,
+        &position_bundle_info,
+        &ctx.accounts.system_program,
+        &ctx.accounts.token_program_2022,
+        false,
+    )?;
+
+    create(CpiContext::new(
+        ctx.accounts.associated_token_program.to_account_info(),
+        Create {
+            payer: ctx.accounts.payer.to_account_info(),
+            associated_token: ctx.accounts.position_bundle_token_account.to_account_info(),
+            authority: ctx.accounts.position_bundle_owner.to_account_info(),
+            mint: position_bundle_mint.clone(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            token_program: ctx.accounts.token_program_2022.to_account_info(),
+        },
+    ))?;
+
+    let seeds = position_bundle.seeds();
+    token_2022::mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program_2022.to_account_info(),
+            token_2022::MintTo {
+                mint: position_bundle_mint.clone(),
+                to: ctx.accounts.position_bundle_token_account.to_account_info(),
+                authority: position_bundle_info.clone(),
+            },
+            &[&seeds],
+        ),
+        1,
+    )?;
+
+    // Disable minting
+    token_2022::set_authority(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program_2022.to_account_info(),
+            token_2022::SetAuthority {
+                current_authority: position_bundle_info,
+                account_or_mint: position_bundle_mint.clone(),
+            },
+            &[&seeds],
+        ),
+        AuthorityType::MintTokens,
+        None,
+    )?;
+
+    check_position_nft_mint(&position_bundle_mint)
+}
@@ -84,6 +84,16 @@ impl InitializeRewardParam {
         {
             return Err(ErrorCode::InvalidRewardPeriod.into());
         }
+        // the whole campaign must be fundable in one transfer
+        let reward_amount = U256::from(time_delta)
+            .mul_div_ceil(
+                U256::from(self.emissions_per_second_x64),
+                U256::from(fixed_point_64::Q64),
+            )
+            .unwrap();
+        if reward_amount > U256::from(u64::MAX) {
+            return Err(ErrorCode::InvalidRewardInitParam.into());
+        }
         Ok(())
     }
 }
@@ -96,12 +106,17 @@ pub fn initialize_reward(
         return err!(ErrorCode::NotSupportMint);
     }
     let operation_state = ctx.accounts.operation_state.load()?;
+    let reward_funder = ctx.accounts.reward_funder.key();
+    let admin_operator = reward_funder == crate::admin::id()
+        || operation_state.validate_operation_owner(reward_funder);
     require!(
-        ctx.accounts.reward_funder.key() == crate::admin::id()
-            || ctx.accounts.reward_funder.key() == ctx.accounts.pool_state.load()?.owner
-            || operation_state.validate_operation_owner(ctx.accounts.reward_funder.key()),
+        admin_operator || reward_funder == ctx.accounts.pool_state.load()?.owner,
         ErrorCode::NotApproved
     );
+    // the pool creator may be a program, the admin must sign a top level instruction
+    if admin_operator {
+        util::require_not_cpi()?;
+    }
 
     // Clock
     let clock = Clock::get()?;
@@ -0,0 +1,221 @@
+use super::decrease_liquidity::decrease_liquidity;
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::transfer_position_nft;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct LeasePosition<'info> {
+    /// The position nft owner
+    #[account(mut)]
+    pub nft_owner: Signer<'info>,
+
+    /// CHECK: The lessee granted the right to collect the position's fees and rewards
+    pub lessee: UncheckedAccount<'info>,
+
+    /// Mint address bound to the personal position.
+    #[account(
+        address = personal_position.nft_mint,
+        mint::token_program = token_program,
+    )]
+    pub position_nft_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The token account holding the position nft
+    #[account(
+        mut,
+        token::mint = position_nft_mint,
+        token::authority = nft_owner,
+        constraint = position_nft_account.amount == 1,
+        token::token_program = token_program,
+    )]
+    pub position_nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The position to be leased
+    #[account(
+        mut,
+        seeds = [POSITION_SEED.as_bytes(), position_nft_mint.key().as_ref()],
+        bump,
+        constraint = personal_position.pool_id == pool_state.key()
+    )]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    /// Initialize an account to store the lease
+    #[account(
+        init,
+        seeds = [POSITION_LEASE_SEED.as_bytes(), position_nft_mint.key().as_ref()],
+        bump,
+        payer = nft_owner,
+        space = PositionLeaseState::LEN
+    )]
+    pub position_lease: Box<Account<'info, PositionLeaseState>>,
+
+    /// The escrow token account to hold the position nft during the lease
+    #[account(
+        init,
+        seeds = [POSITION_LEASE_VAULT_SEED.as_bytes(), position_nft_mint.key().as_ref()],
+        bump,
+        payer = nft_owner,
+        token::mint = position_nft_mint,
+        token::authority = position_lease,
+        token::token_program = token_program,
+    )]
+    pub leased_nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token/Token2022 program of the position nft
+    pub token_program: Interface<'info, TokenInterface>,
+    /// To create new program accounts
+    pub system_program: Program<'info, System>,
+    /// Sysvar for token account
+    pub rent: Sysvar<'info, Rent>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        mut,
+        seeds = [
+            POSITION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &personal_position.tick_lower_index.to_be_bytes(),
+            &personal_position.tick_upper_index.to_be_bytes(),
+        ],
+        bump,
+        constraint = protocol_position.pool_id == pool_state.key(),
+    )]
+    pub protocol_position: Box<Account<'info, ProtocolPositionState>>,
+
+    /// Token_0 vault
+    #[account(
+        mut,
+        constraint = token_vault_0.key() == pool_state.load()?.token_vault_0
+    )]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token_1 vault
+    #[account(
+        mut,
+        constraint = token_vault_1.key() == pool_state.load()?.token_vault_1
+    )]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Stores init state for the lower tick
+    #[account(mut, constraint = tick_array_lower.load()?.pool_id == pool_state.key())]
+    pub tick_array_lower: AccountLoader<'info, TickArrayState>,
+
+    /// Stores init state for the upper tick
+    #[account(mut, constraint = tick_array_upper.load()?.pool_id == pool_state.key())]
+    pub tick_array_upper: AccountLoader<'info, TickArrayState>,
+
+    /// The owner's token account receiving the fees of token_0 accrued before the lease
+    #[account(
+        mut,
+        token::mint = token_vault_0.mint
+    )]
+    pub recipient_token_account_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The owner's token account receiving the fees of token_1 accrued before the lease
+    #[account(
+        mut,
+        token::mint = token_vault_1.mint
+    )]
+    pub recipient_token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// SPL program to transfer out the pool's tokens
+    pub vault_token_program: Program<'info, Token>,
+    /// Token program 2022 to transfer out the pool's tokens
+    pub vault_token_program_2022: Program<'info, Token2022>,
+
+    /// memo program
+    /// CHECK:
+    #[account(
+        address = spl_memo::id()
+    )]
+    pub memo_program: UncheckedAccount<'info>,
+
+    /// The mint of token vault 0
+    #[account(
+        address = token_vault_0.mint,
+        constraint = pool_state.load()?.is_valid_token_program(
+            &vault_0_mint.key(),
+            vault_0_mint.to_account_info().owner
+        ) @ ErrorCode::InvalidTokenProgram
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token vault 1
+    #[account(
+        address = token_vault_1.mint,
+        constraint = pool_state.load()?.is_valid_token_program(
+            &vault_1_mint.key(),
+            vault_1_mint.to_account_info().owner
+        ) @ ErrorCode::InvalidTokenProgram
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+    // remaining accounts
+    // the reward vault, owner's reward token account and reward mint of each reward,
+    // tickarray_bitmap_extension if needed
+}
+
+/// Escrows the position nft for the lessee. The fees and rewards accrued before the lease are
+/// collected to the owner first, the lessee only gets what the position earns during the lease
+pub fn lease_position<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, LeasePosition<'info>>,
+    expires_at: u64,
+) -> Result<()> {
+    let block_timestamp = u64::try_from(Clock::get()?.unix_timestamp).unwrap();
+    require_gt!(expires_at, block_timestamp, ErrorCode::InvalidUnlockTime);
+    require_keys_neq!(
+        ctx.accounts.lessee.key(),
+        ctx.accounts.nft_owner.key(),
+        ErrorCode::NotApproved
+    );
+
+    decrease_liquidity(
+        &ctx.accounts.pool_state,
+        &mut ctx.accounts.protocol_position,
+        &mut ctx.accounts.personal_position,
+        &ctx.accounts.token_vault_0.to_account_info(),
+        &ctx.accounts.token_vault_1.to_account_info(),
+        &ctx.accounts.tick_array_lower,
+        &ctx.accounts.tick_array_upper,
+        &ctx.accounts.recipient_token_account_0.to_account_info(),
+        &ctx.accounts.recipient_token_account_1.to_account_info(),
+        &ctx.accounts.vault_token_program,
+        Some(ctx.accounts.vault_token_program_2022.clone()),
+        Some(ctx.accounts.memo_program.clone()),
+        Some(ctx.accounts.vault_0_mint.clone()),
+        Some(ctx.accounts.vault_1_mint.clone()),
+        ctx.remaining_accounts,
+        0,
+        0,
+        0,
+    )?;
+
+    let position_lease = ctx.accounts.position_lease.as_mut();
+    position_lease.bump = [ctx.bumps.position_lease];
+    position_lease.owner = ctx.accounts.nft_owner.key();
+    position_lease.lessee = ctx.accounts.lessee.key();
+    position_lease.position_nft_mint = ctx.accounts.position_nft_mint.key();
+    position_lease.personal_position = ctx.accounts.personal_position.key();
+    position_lease.leased_nft_account = ctx.accounts.leased_nft_account.key();
+    position_lease.expires_at = expires_at;
+
+    transfer_position_nft(
+        &ctx.accounts.nft_owner.to_account_info(),
+        &ctx.accounts.position_nft_account.to_account_info(),
+        &ctx.accounts.leased_nft_account.to_account_info(),
+        &ctx.accounts.position_nft_mint.to_account_info(),
+        &ctx.accounts.token_program.to_account_info(),
+        &[],
+    )?;
+
+    emit!(LeasePositionEvent {
+        position_nft_mint: position_lease.position_nft_mint,
+        owner: position_lease.owner,
+        lessee: position_lease.lessee,
+        expires_at,
+    });
+    Ok(())
+}
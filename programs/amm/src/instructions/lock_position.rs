@@ -0,0 +1,206 @@
+use super::open_position::{get_metadata_data, initialize_token_metadata_extension};
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::{create_mint_with_extensions, transfer_position_nft};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::{create, AssociatedToken, Create};
+use anchor_spl::token_2022::{
+    self,
+    spl_token_2022::{extension::ExtensionType, instruction::AuthorityType},
+    Token2022,
+};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct LockPosition<'info> {
+    /// The position nft owner
+    #[account(mut)]
+    pub nft_owner: Signer<'info>,
+
+    /// Mint address bound to the personal position.
+    #[account(
+        address = personal_position.nft_mint,
+        mint::token_program = token_program,
+    )]
+    pub position_nft_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The token account holding the position nft
+    #[account(
+        mut,
+        token::mint = position_nft_mint,
+        token::authority = nft_owner,
+        constraint = position_nft_account.amount == 1,
+        token::token_program = token_program,
+    )]
+    pub position_nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The position to be locked
+    #[account(
+        seeds = [POSITION_SEED.as_bytes(), position_nft_mint.key().as_ref()],
+        bump,
+    )]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    /// Initialize an account to store the lock
+    #[account(
+        init,
+        seeds = [LOCKED_POSITION_SEED.as_bytes(), position_nft_mint.key().as_ref()],
+        bump,
+        payer = nft_owner,
+        space = LockedPositionState::LEN
+    )]
+    pub locked_position: Box<Account<'info, LockedPositionState>>,
+
+    /// The escrow token account to hold the position nft
+    #[account(
+        init,
+        seeds = [LOCKED_POSITION_VAULT_SEED.as_bytes(), position_nft_mint.key().as_ref()],
+        bump,
+        payer = nft_owner,
+        token::mint = position_nft_mint,
+        token::authority = locked_position,
+        token::token_program = token_program,
+    )]
+    pub locked_nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token/Token2022 program of the position nft
+    pub token_program: Interface<'info, TokenInterface>,
+    /// To create new program accounts
+    pub system_program: Program<'info, System>,
+    /// Sysvar for token account
+    pub rent: Sysvar<'info, Rent>,
+
+    /// Unique token mint address of the receipt, a random keypair, required to mint a receipt
+    #[account(mut)]
+    pub receipt_mint: Option<Signer<'info>>,
+
+    /// CHECK: The owner's associated token account of the receipt, created by the instruction
+    #[account(mut)]
+    pub receipt_account: Option<UncheckedAccount<'info>>,
+
+    /// Token program 2022 of the receipt
+    pub token_program_2022: Option<Program<'info, Token2022>>,
+
+    /// Program to create the receipt's associated token account
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+}
+
+pub fn lock_position(ctx: Context<LockPosition>, unlock_time: u64) -> Result<()> {
+    let block_timestamp = u64::try_from(Clock::get()?.unix_timestamp).unwrap();
+    require_gt!(unlock_time, block_timestamp, ErrorCode::InvalidUnlockTime);
+
+    let locked_position = ctx.accounts.locked_position.as_mut();
+    locked_position.bump = [ctx.bumps.locked_position];
+    locked_position.owner = ctx.accounts.nft_owner.key();
+    locked_position.position_nft_mint = ctx.accounts.position_nft_mint.key();
+    locked_position.personal_position = ctx.accounts.personal_position.key();
+    locked_position.locked_nft_account = ctx.accounts.locked_nft_account.key();
+    locked_position.unlock_time = unlock_time;
+    if let Some(receipt_mint) = &ctx.accounts.receipt_mint {
+        locked_position.receipt_mint = receipt_mint.key();
+        mint_receipt(ctx.accounts)?;
+    }
+    let locked_position = ctx.accounts.locked_position.as_ref();
+
+    transfer_position_nft(
+        &ctx.accounts.nft_owner.to_account_info(),
+        &ctx.accounts.position_nft_account.to_account_info(),
+        &ctx.accounts.locked_nft_account.to_account_info(),
+        &ctx.accounts.position_nft_mint.to_account_info(),
+        &ctx.accounts.token_program.to_account_info(),
+        &[],
+    )?;
+
+    emit!(LockPositionEvent {
+        position_nft_mint: locked_position.position_nft_mint,
+        owner: locked_position.owner,
+        unlock_time,
+    });
+    Ok(())
+}
+
+/// Mints the non-transferable receipt of the locked position to the owner, carrying the same
+/// metadata as the position nft so wallets keep displaying the position
+fn mint_receipt(accounts: &LockPosition) -> Result<()> {
+    let (
+        Some(receipt_mint),
+        Some(receipt_account),
+        Some(token_program_2022),
+        Some(associated_token_program),
+    ) = (
+        &accounts.receipt_mint,
+        &accounts.receipt_account,
+        &accounts.token_program_2022,
+        &accounts.associated_token_program,
+    )
+    else {
+        return err!(ErrorCode::AccountLack);
+    };
+    let locked_position = accounts.locked_position.as_ref();
+    let locked_position_info = locked_position.to_account_info();
+    let receipt_mint_info = receipt_mint.to_account_info();
+    let seeds = locked_position.seeds();
+
+    // the lock closes the receipt mint on unlock
+    create_mint_with_extensions(
+        &accounts.nft_owner,
+        &receipt_mint_info,
+        &locked_position_info,
+        &locked_position_info,
+        &accounts.system_program,
+        token_program_2022,
+        &[
+            ExtensionType::MintCloseAuthority,
+            ExtensionType::MetadataPointer,
+            ExtensionType::NonTransferable,
+        ],
+    )?;
+    let (name, symbol, uri) = get_metadata_data(locked_position.personal_position);
+    initialize_token_metadata_extension(
+        &accounts.nft_owner,
+        &receipt_mint_info,
+        &locked_position_info,
+        &accounts.personal_position.to_account_info(),
+        token_program_2022,
+        name,
+        symbol,
+        uri,
+        &[&seeds],
+    )?;
+
+    create(CpiContext::new(
+        associated_token_program.to_account_info(),
+        Create {
+            payer: accounts.nft_owner.to_account_info(),
+            associated_token: receipt_account.to_account_info(),
+            authority: accounts.nft_owner.to_account_info(),
+            mint: receipt_mint_info.clone(),
+            system_program: accounts.system_program.to_account_info(),
+            token_program: token_program_2022.to_account_info(),
+        },
+    ))?;
+    token_2022::mint_to(
+        CpiContext::new_with_signer(
+            token_program_2022.to_account_info(),
+            token_2022::MintTo {
+                mint: receipt_mint_info.clone(),
+                to: receipt_account.to_account_info(),
+                authority: locked_position_info.clone(),
+            },
+            &[&seeds],
+        ),
+        1,
+    )?;
+    token_2022::set_authority(
+        CpiContext::new_with_signer(
+            token_program_2022.to_account_info(),
+            token_2022::SetAuthority {
+                current_authority: locked_position_info,
+                account_or_mint: receipt_mint_info,
+            },
+            &[&seeds],
+        ),
+        AuthorityType::MintTokens,
+        None,
+    )
+}
@@ -10,30 +10,110 @@ pub use open_position_v2::*;
 pub mod open_position_with_token22_nft;
 pub use open_position_with_token22_nft::*;
 
+pub mod create_position_ladder;
+pub use create_position_ladder::*;
+
+pub mod create_position_ladder_with_preset;
+pub use create_position_ladder_with_preset::*;
+
+pub mod create_strategy_preset;
+pub use create_strategy_preset::*;
+
+pub mod close_strategy_preset;
+pub use close_strategy_preset::*;
+
 pub mod close_position;
 pub use close_position::*;
 
+pub mod sweep_empty_position;
+pub use sweep_empty_position::*;
+
+pub mod initialize_position_bundle;
+pub use initialize_position_bundle::*;
+
+pub mod open_bundled_position;
+pub use open_bundled_position::*;
+
+pub mod close_bundled_position;
+pub use close_bundled_position::*;
+
+pub mod delete_position_bundle;
+pub use delete_position_bundle::*;
+
+pub mod rebalance_bundle;
+pub use rebalance_bundle::*;
+
+pub mod rebalance_position;
+pub use rebalance_position::*;
+
 pub mod increase_liquidity;
 pub use increase_liquidity::*;
 
 pub mod increase_liquidity_v2;
 pub use increase_liquidity_v2::*;
 
+pub mod increase_liquidity_with_sol;
+pub use increase_liquidity_with_sol::*;
+
+pub mod swap_and_increase_liquidity;
+pub use swap_and_increase_liquidity::*;
+
+pub mod create_position_zap;
+pub use create_position_zap::*;
+
+pub mod compound_fees;
+pub use compound_fees::*;
+
+pub mod collect_fee_batch;
+pub use collect_fee_batch::*;
+
+pub mod set_position_operator;
+pub use set_position_operator::*;
+
+pub mod collect_all;
+pub use collect_all::*;
+
+pub mod transfer_position;
+pub use transfer_position::*;
+
 pub mod decrease_liquidity;
 pub use decrease_liquidity::*;
 
 pub mod decrease_liquidity_v2;
 pub use decrease_liquidity_v2::*;
 
+pub mod decrease_liquidity_with_sol;
+pub use decrease_liquidity_with_sol::*;
+
+pub mod decrease_liquidity_to_single_token;
+pub use decrease_liquidity_to_single_token::*;
+
+pub mod donate;
+pub use donate::*;
+
 pub mod swap;
 pub use swap::*;
 
+pub mod swap_with_options;
+pub use swap_with_options::*;
+
+#[cfg(feature = "client")]
+pub mod swap_accounts;
+#[cfg(feature = "client")]
+pub use swap_accounts::*;
+
 pub mod swap_v2;
 pub use swap_v2::*;
 
+pub mod swap_v2_fee_exempt;
+pub use swap_v2_fee_exempt::*;
+
 pub mod swap_router_base_in;
 pub use swap_router_base_in::*;
 
+pub mod swap_router_base_out;
+pub use swap_router_base_out::*;
+
 pub mod update_reward_info;
 pub use update_reward_info::*;
 
@@ -46,5 +126,128 @@ pub use set_reward_params::*;
 pub mod collect_remaining_rewards;
 pub use collect_remaining_rewards::*;
 
+pub mod set_quote_orientation;
+pub use set_quote_orientation::*;
+
+pub mod set_observation_event;
+pub use set_observation_event::*;
+
+pub mod update_fee_growth_snapshot;
+pub use update_fee_growth_snapshot::*;
+
+pub mod crank_buyback;
+pub use crank_buyback::*;
+
+pub mod commit_liquidity_snapshot;
+pub use commit_liquidity_snapshot::*;
+
+pub mod crank_epoch_fee_report;
+pub use crank_epoch_fee_report::*;
+
+pub mod poke_observation;
+pub use poke_observation::*;
+
+pub mod validate_pool;
+pub use validate_pool::*;
+
+pub mod register_oracle_consumer;
+pub use register_oracle_consumer::*;
+
+pub mod deregister_oracle_consumer;
+pub use deregister_oracle_consumer::*;
+
+pub mod get_pool_price;
+pub use get_pool_price::*;
+
+pub mod get_position_state;
+pub use get_position_state::*;
+
+pub mod get_price_impact;
+pub use get_price_impact::*;
+
+pub mod get_twap;
+pub use get_twap::*;
+
+pub mod get_protocol_fee_report;
+pub use get_protocol_fee_report::*;
+
+pub mod set_position_incentive;
+pub use set_position_incentive::*;
+
+pub mod lock_position;
+pub use lock_position::*;
+
+pub mod extend_position_lock;
+pub use extend_position_lock::*;
+
+pub mod unlock_position;
+pub use unlock_position::*;
+
+pub mod collect_locked_position_fee;
+pub use collect_locked_position_fee::*;
+
+pub mod lease_position;
+pub use lease_position::*;
+
+pub mod collect_leased_position_fee;
+pub use collect_leased_position_fee::*;
+
+pub mod end_position_lease;
+pub use end_position_lease::*;
+
+pub mod place_stop_order;
+pub use place_stop_order::*;
+
+pub mod cancel_stop_order;
+pub use cancel_stop_order::*;
+
+pub mod settle_stop_order;
+pub use settle_stop_order::*;
+
+pub mod stake_fee_distributor;
+pub use stake_fee_distributor::*;
+
+pub mod unstake_fee_distributor;
+pub use unstake_fee_distributor::*;
+
+pub mod claim_distributor_reward;
+pub use claim_distributor_reward::*;
+
+pub mod set_pool_withdraw_only;
+pub use set_pool_withdraw_only::*;
+
+pub mod collect_partner_fee;
+pub use collect_partner_fee::*;
+
+pub mod create_referral_account;
+pub use create_referral_account::*;
+
+pub mod collect_referral_fee;
+pub use collect_referral_fee::*;
+
+pub mod shift_launch_position;
+pub use shift_launch_position::*;
+
+pub mod swap_split;
+pub use swap_split::*;
+
+pub mod swap_batch;
+pub use swap_batch::*;
+
+pub mod create_user_stats;
+pub use create_user_stats::*;
+
+pub mod collect_volume_rebate;
+pub use collect_volume_rebate::*;
+
+pub mod set_max_price_impact;
+pub use set_max_price_impact::*;
+
+pub mod set_min_swap_amount;
+pub use set_min_swap_amount::*;
+
+pub mod set_swap_hook;
+pub use set_swap_hook::*;
+
 pub mod admin;
 pub use admin::*;
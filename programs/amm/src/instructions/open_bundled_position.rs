@@ -0,0 +1,174 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::{is_authorized_for_token, AccountLoad};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+#[derive(Accounts)]
+#[instruction(bundle_index: u16, tick_lower_index: i32, tick_upper_index: i32)]
+pub struct OpenBundledPosition<'info> {
+    /// Pays to create the position accounts
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The bundle NFT owner or delegate
+    pub bundle_authority: Signer<'info>,
+
+    /// The token account holding the bundle NFT
+    #[account(
+        constraint = is_authorized_for_token(
+            &bundle_authority.key(),
+            &position_bundle_token_account.to_account_info(),
+            &position_bundle.position_bundle_mint
+        ) @ ErrorCode::NotApproved
+    )]
+    pub position_bundle_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The bundle to open the position in
+    #[account(mut)]
+    pub position_bundle: Box<Account<'info, PositionBundle>>,
+
+    /// Open the position in this pool
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The pool's config, liquidity can't be added while it is paused
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// Store the information of market marking in range
+    #[account(
+        init_if_needed,
+        seeds = [
+            POSITION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &tick_lower_index.to_be_bytes(),
+            &tick_upper_index.to_be_bytes(),
+        ],
+        bump,
+        payer = payer,
+        space = ProtocolPositionState::LEN
+    )]
+    pub protocol_position: Box<Account<'info, ProtocolPositionState>>,
+
+    /// CHECK: Account to mark the lower tick as initialized
+    #[account(mut)]
+    pub tick_array_lower: UncheckedAccount<'info>,
+
+    /// CHECK: Account to store data for the position's upper tick
+    #[account(mut)]
+    pub tick_array_upper: UncheckedAccount<'info>,
+
+    /// The bundled position, keyed by the bundle mint and the slot
+    #[account(
+        init,
+        seeds = [
+            POSITION_SEED.as_bytes(),
+            position_bundle.position_bundle_mint.as_ref(),
+            &bundle_index.to_be_bytes(),
+        ],
+        bump,
+        payer = payer,
+        space = PersonalPositionState::LEN
+    )]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    /// Program to create the position accounts
+    pub system_program: Program<'info, System>,
+}
+
+/// Open an empty position in slot `bundle_index` of the bundle, liquidity is then added with
+/// `increase_liquidity_v2` signed by the bundle NFT holder.
+pub fn open_bundled_position<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, OpenBundledPosition<'info>>,
+    bundle_index: u16,
+    tick_lower_index: i32,
+    tick_upper_index: i32,
+    tick_array_lower_start_index: i32,
+    tick_array_upper_start_index: i32,
+) -> Result<()> {
+    ctx.accounts.position_bundle.open_slot(bundle_index)?;
+
+    let pool_state = ctx.accounts.pool_state.load()?;
+    if !pool_state.get_status_by_bit(PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity)
+        || !ctx
+            .accounts
+            .amm_config
+            .get_status_by_bit(PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity)
+    {
+        return err!(ErrorCode::NotApproved);
+    }
+    check_ticks_order(tick_lower_index, tick_upper_index)?;
+    check_tick_array_start_index(
+        tick_array_lower_start_index,
+        tick_lower_index,
+        pool_state.tick_spacing,
+    )?;
+    check_tick_array_start_index(
+        tick_array_upper_start_index,
+        tick_upper_index,
+        pool_state.tick_spacing,
+    )?;
+
+    let tick_array_lower_loader = TickArrayState::get_or_create_tick_array(
+        ctx.accounts.payer.to_account_info(),
+        ctx.accounts.tick_array_lower.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.pool_state,
+        tick_array_lower_start_index,
+        pool_state.tick_spacing,
+    )?;
+    let tick_array_upper_loader = if tick_array_lower_start_index == tick_array_upper_start_index {
+        AccountLoad::<TickArrayState>::try_from(&ctx.accounts.tick_array_upper.to_account_info())?
+    } else {
+        TickArrayState::get_or_create_tick_array(
+            ctx.accounts.payer.to_account_info(),
+            ctx.accounts.tick_array_upper.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            &ctx.accounts.pool_state,
+            tick_array_upper_start_index,
+            pool_state.tick_spacing,
+        )?
+    };
+
+    let protocol_position = ctx.accounts.protocol_position.as_mut();
+    if protocol_position.pool_id == Pubkey::default() {
+        protocol_position.bump = ctx.bumps.protocol_position;
+        protocol_position.pool_id = ctx.accounts.pool_state.key();
+        protocol_position.tick_lower_index = tick_lower_index;
+        protocol_position.tick_upper_index = tick_upper_index;
+        tick_array_lower_loader
+            .load_mut()?
+            .get_tick_state_mut(tick_lower_index, pool_state.tick_spacing)?
+            .tick = tick_lower_index;
+        tick_array_upper_loader
+            .load_mut()?
+            .get_tick_state_mut(tick_upper_index, pool_state.tick_spacing)?
+            .tick = tick_upper_index;
+    }
+
+    let personal_position = ctx.accounts.personal_position.as_mut();
+    personal_position.bump = [ctx.bumps.personal_position];
+    personal_position.nft_mint = ctx.accounts.position_bundle.position_bundle_mint;
+    personal_position.pool_id = ctx.accounts.pool_state.key();
+    personal_position.tick_lower_index = tick_lower_index;
+    personal_position.tick_upper_index = tick_upper_index;
+    personal_position.fee_growth_inside_0_last_x64 = protocol_position.fee_growth_inside_0_last_x64;
+    personal_position.fee_growth_inside_1_last_x64 = protocol_position.fee_growth_inside_1_last_x64;
+    personal_position.update_rewards(protocol_position.reward_growth_inside, false)?;
+
+    emit!(CreatePersonalPositionEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        minter: ctx.accounts.payer.key(),
+        nft_owner: ctx.accounts.position_bundle_token_account.owner,
+        tick_lower_index,
+        tick_upper_index,
+        liquidity: 0,
+        deposit_amount_0: 0,
+        deposit_amount_1: 0,
+        deposit_amount_0_transfer_fee: 0,
+        deposit_amount_1_transfer_fee: 0,
+        position_hash: personal_position.position_hash(),
+    });
+    Ok(())
+}
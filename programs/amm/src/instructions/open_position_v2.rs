@@ -1,4 +1,5 @@
 use super::open_position::open_position;
+use crate::error::ErrorCode;
 use crate::states::*;
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
@@ -42,6 +43,10 @@ pub struct OpenPositionV2<'info> {
     #[account(mut)]
     pub pool_state: AccountLoader<'info, PoolState>,
 
+    /// The pool's config, liquidity can't be added while it is paused
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
     /// Store the information of market marking in range
     #[account(
         init_if_needed,
@@ -137,12 +142,20 @@ pub struct OpenPositionV2<'info> {
     pub token_program_2022: Program<'info, Token2022>,
     /// The mint of token vault 0
     #[account(
-        address = token_vault_0.mint
+        address = token_vault_0.mint,
+        constraint = pool_state.load()?.is_valid_token_program(
+            &vault_0_mint.key(),
+            vault_0_mint.to_account_info().owner
+        ) @ ErrorCode::InvalidTokenProgram
     )]
     pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
     /// The mint of token vault 1
     #[account(
-        address = token_vault_1.mint
+        address = token_vault_1.mint,
+        constraint = pool_state.load()?.is_valid_token_program(
+            &vault_1_mint.key(),
+            vault_1_mint.to_account_info().owner
+        ) @ ErrorCode::InvalidTokenProgram
     )]
     pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
     // remaining account
@@ -175,6 +188,7 @@ pub fn open_position_v2<'a, 'b, 'c: 'info, 'info>(
         &ctx.accounts.position_nft_account.to_account_info(),
         Some(&ctx.accounts.metadata_account),
         &ctx.accounts.pool_state,
+        &ctx.accounts.amm_config,
         &ctx.accounts.tick_array_lower,
         &ctx.accounts.tick_array_upper,
         &mut ctx.accounts.protocol_position,
@@ -0,0 +1,85 @@
+use crate::error::ErrorCode;
+use crate::libraries::tick_math;
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_spl::token_interface::TokenAccount;
+
+#[derive(Accounts)]
+pub struct PlaceStopOrder<'info> {
+    /// The position nft owner
+    #[account(mut)]
+    pub nft_owner: Signer<'info>,
+
+    /// The token account holding the position nft
+    #[account(
+        token::mint = personal_position.nft_mint,
+        token::authority = nft_owner,
+        constraint = nft_account.amount == 1,
+    )]
+    pub nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    #[account(address = personal_position.pool_id)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Initialize an account to store the stop order
+    #[account(
+        init,
+        seeds = [STOP_ORDER_SEED.as_bytes(), personal_position.key().as_ref()],
+        bump,
+        payer = nft_owner,
+        space = StopOrderState::LEN
+    )]
+    pub stop_order: Box<Account<'info, StopOrderState>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn place_stop_order(
+    ctx: Context<PlaceStopOrder>,
+    stop_tick: i32,
+    trigger_below: bool,
+    amount_0_min: u64,
+    amount_1_min: u64,
+    keeper_reward: u64,
+    twap_window: u32,
+) -> Result<()> {
+    require!(
+        (tick_math::MIN_TICK..=tick_math::MAX_TICK).contains(&stop_tick)
+            && keeper_reward <= MAX_STOP_ORDER_KEEPER_REWARD
+            && twap_window > 0,
+        ErrorCode::InvalidStopOrderParam
+    );
+
+    let stop_order = ctx.accounts.stop_order.as_mut();
+    stop_order.bump = ctx.bumps.stop_order;
+    stop_order.owner = ctx.accounts.nft_owner.key();
+    stop_order.personal_position = ctx.accounts.personal_position.key();
+    stop_order.stop_tick = stop_tick;
+    stop_order.trigger_below = trigger_below;
+    stop_order.amount_0_min = amount_0_min;
+    stop_order.amount_1_min = amount_1_min;
+    stop_order.keeper_reward = keeper_reward;
+    stop_order.twap_window = twap_window;
+    // an order that would settle immediately is a mistake
+    require!(
+        !stop_order.is_triggered(ctx.accounts.pool_state.load()?.tick_current),
+        ErrorCode::InvalidStopOrderParam
+    );
+
+    if keeper_reward > 0 {
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.nft_owner.to_account_info(),
+                    to: ctx.accounts.stop_order.to_account_info(),
+                },
+            ),
+            keeper_reward,
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct PokeObservation<'info> {
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The oracle of the pool
+    #[account(mut, address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+}
+
+pub fn poke_observation(ctx: Context<PokeObservation>) -> Result<()> {
+    let pool_state = ctx.accounts.pool_state.load()?;
+    let mut observation_state = ctx.accounts.observation_state.load_mut()?;
+    require!(
+        observation_state.poke(
+            oracle::block_timestamp(),
+            pool_state.tick_current,
+            pool_state.observation_poke_min_age()
+        ),
+        ErrorCode::ObservationNotStale
+    );
+    if pool_state.is_observation_event_enabled() {
+        let observation_index = observation_state.observation_index;
+        let observation = observation_state.observations[observation_index as usize];
+        emit!(ObservationEvent {
+            pool_state: observation_state.pool_id,
+            observation_index,
+            block_timestamp: observation.block_timestamp,
+            tick_cumulative: observation.tick_cumulative,
+        });
+    }
+    Ok(())
+}
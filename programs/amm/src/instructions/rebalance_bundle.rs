@@ -0,0 +1,448 @@
+use super::decrease_liquidity::{
+    check_unclaimed_fees_and_vault, decrease_liquidity_and_update_position,
+};
+use super::increase_liquidity::increase_liquidity;
+use crate::error::ErrorCode;
+use crate::libraries::tick_math;
+use crate::states::*;
+use crate::util::{
+    create_or_allocate_account, get_token_account_amount, is_authorized_for_token,
+    transfer_from_pool_vault_to_user,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+/// Accounts passed in remaining accounts for each slot
+const ACCOUNTS_PER_SLOT: usize = 7;
+
+/// The range and liquidity every out of range slot is reopened with
+#[derive(Copy, Clone, AnchorSerialize, AnchorDeserialize, Debug, PartialEq)]
+pub struct BundleRebalanceTemplate {
+    /// The lower tick of the new range relative to the pool's current tick
+    pub tick_lower_offset: i32,
+    /// The upper tick of the new range relative to the pool's current tick
+    pub tick_upper_offset: i32,
+    /// The liquidity added to each reopened position
+    pub liquidity: u128,
+}
+
+impl BundleRebalanceTemplate {
+    /// Returns the new range around `tick_current`, both ends rounded down to `tick_spacing`
+    pub fn range(&self, tick_current: i32, tick_spacing: u16) -> Result<(i32, i32)> {
+        let tick_spacing = i32::from(tick_spacing);
+        let tick_lower_index = tick_current
+            .checked_add(self.tick_lower_offset)
+            .ok_or(ErrorCode::InvalidRebalanceTemplate)?
+            .div_euclid(tick_spacing)
+            * tick_spacing;
+        let tick_upper_index = tick_current
+            .checked_add(self.tick_upper_offset)
+            .ok_or(ErrorCode::InvalidRebalanceTemplate)?
+            .div_euclid(tick_spacing)
+            * tick_spacing;
+        require!(
+            tick_lower_index < tick_upper_index
+                && tick_lower_index >= tick_math::MIN_TICK
+                && tick_upper_index <= tick_math::MAX_TICK,
+            ErrorCode::InvalidRebalanceTemplate
+        );
+        Ok((tick_lower_index, tick_upper_index))
+    }
+}
+
+#[derive(Accounts)]
+pub struct RebalanceBundle<'info> {
+    /// The bundle NFT owner or delegate, pays for new accounts and the added liquidity
+    #[account(mut)]
+    pub bundle_authority: Signer<'info>,
+
+    /// The token account holding the bundle NFT
+    #[account(
+        constraint = is_authorized_for_token(
+            &bundle_authority.key(),
+            &position_bundle_token_account.to_account_info(),
+            &position_bundle.position_bundle_mint
+        ) @ ErrorCode::NotApproved
+    )]
+    pub position_bundle_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The bundle holding the positions
+    pub position_bundle: Box<Account<'info, PositionBundle>>,
+
+    /// The pool of the rebalanced positions
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The pool's config, liquidity can't be added while it is paused
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// Receives the withdrawn token_0 and pays the token_0 deposits
+    #[account(
+        mut,
+        token::mint = token_vault_0.mint
+    )]
+    pub token_account_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Receives the withdrawn token_1 and pays the token_1 deposits
+    #[account(
+        mut,
+        token::mint = token_vault_1.mint
+    )]
+    pub token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        mut,
+        constraint = token_vault_0.key() == pool_state.load()?.token_vault_0
+    )]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        mut,
+        constraint = token_vault_1.key() == pool_state.load()?.token_vault_1
+    )]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Program to create the new position and tick array accounts
+    pub system_program: Program<'info, System>,
+
+    /// SPL program to transfer out tokens
+    pub token_program: Program<'info, Token>,
+
+    /// Token program 2022
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// The mint of token vault 0
+    #[account(
+        address = token_vault_0.mint,
+        constraint = pool_state.load()?.is_valid_token_program(
+            &vault_0_mint.key(),
+            vault_0_mint.to_account_info().owner
+        ) @ ErrorCode::InvalidTokenProgram
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token vault 1
+    #[account(
+        address = token_vault_1.mint,
+        constraint = pool_state.load()?.is_valid_token_program(
+            &vault_1_mint.key(),
+            vault_1_mint.to_account_info().owner
+        ) @ ErrorCode::InvalidTokenProgram
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+    // remaining accounts
+    // optional tick_array_bitmap extension, followed by one group per slot:
+    // personal_position, protocol_position, tick_array_lower, tick_array_upper,
+    // new_protocol_position, new_tick_array_lower, new_tick_array_upper
+}
+
+/// Close out the liquidity of every slot in `bundle_indexes` that is out of range and reopen it
+/// around the current price as described by `template`. Slots in range are left untouched.
+/// The net amounts taken from the token accounts are bounded by `amount_0_max` and `amount_1_max`.
+pub fn rebalance_bundle<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, RebalanceBundle<'info>>,
+    bundle_indexes: Vec<u16>,
+    template: BundleRebalanceTemplate,
+    amount_0_max: u64,
+    amount_1_max: u64,
+) -> Result<()> {
+    require_gt!(template.liquidity, 0, ErrorCode::InvalidRebalanceTemplate);
+    let accounts = ctx.accounts;
+    let pool_id = accounts.pool_state.key();
+    let (tick_current, tick_spacing) = {
+        let pool_state = accounts.pool_state.load()?;
+        require!(
+            pool_state.get_status_by_bit(PoolStatusBitIndex::DecreaseLiquidity),
+            ErrorCode::NotApproved
+        );
+        (pool_state.tick_current, pool_state.tick_spacing)
+    };
+    let (new_tick_lower_index, new_tick_upper_index) =
+        template.range(tick_current, tick_spacing)?;
+    let new_tick_array_lower_start_index =
+        TickArrayState::get_array_start_index(new_tick_lower_index, tick_spacing);
+    let new_tick_array_upper_start_index =
+        TickArrayState::get_array_start_index(new_tick_upper_index, tick_spacing);
+
+    let mut remaining_accounts = ctx.remaining_accounts;
+    let bitmap_extension_accounts = match remaining_accounts.first() {
+        Some(account) if account.key() == TickArrayBitmapExtension::key(pool_id) => {
+            let (bitmap, rest) = remaining_accounts.split_at(1);
+            remaining_accounts = rest;
+            bitmap
+        }
+        _ => &remaining_accounts[..0],
+    };
+    require!(
+        remaining_accounts.len() == bundle_indexes.len() * ACCOUNTS_PER_SLOT,
+        ErrorCode::AccountLack
+    );
+
+    let token_account_0 = accounts.token_account_0.to_account_info();
+    let token_account_1 = accounts.token_account_1.to_account_info();
+    let token_vault_0 = accounts.token_vault_0.to_account_info();
+    let token_vault_1 = accounts.token_vault_1.to_account_info();
+    let balance_0_before = get_token_account_amount(&token_account_0)?;
+    let balance_1_before = get_token_account_amount(&token_account_1)?;
+
+    for (bundle_index, slot_accounts) in bundle_indexes
+        .iter()
+        .zip(remaining_accounts.chunks(ACCOUNTS_PER_SLOT))
+    {
+        require!(
+            accounts.position_bundle.is_open(*bundle_index),
+            ErrorCode::InvalidBundleIndex
+        );
+        let mut personal_position = Box::new(Account::<PersonalPositionState>::try_from(
+            &slot_accounts[0],
+        )?);
+        let personal_position_key = Pubkey::create_program_address(
+            &[
+                POSITION_SEED.as_bytes(),
+                accounts.position_bundle.position_bundle_mint.as_ref(),
+                &bundle_index.to_be_bytes(),
+                &personal_position.bump,
+            ],
+            &crate::id(),
+        )
+        .map_err(|_| ErrorCode::InvalidBundleIndex)?;
+        require_keys_eq!(personal_position.key(), personal_position_key);
+        require_keys_eq!(personal_position.pool_id, pool_id);
+
+        let tick_lower_index = personal_position.tick_lower_index;
+        let tick_upper_index = personal_position.tick_upper_index;
+        let position_hash = personal_position.position_hash();
+        if tick_current >= tick_lower_index && tick_current < tick_upper_index {
+            continue;
+        }
+
+        // close out the old range, the principal and fees go to the token accounts
+        let mut protocol_position = Box::new(Account::<ProtocolPositionState>::try_from(
+            &slot_accounts[1],
+        )?);
+        require!(
+            protocol_position.pool_id == pool_id
+                && protocol_position.tick_lower_index == tick_lower_index
+                && protocol_position.tick_upper_index == tick_upper_index,
+            ErrorCode::NotApproved
+        );
+        let tick_array_lower = AccountLoader::<TickArrayState>::try_from(&slot_accounts[2])?;
+        let tick_array_upper = AccountLoader::<TickArrayState>::try_from(&slot_accounts[3])?;
+        require!(
+            tick_array_lower.load()?.start_tick_index
+                == TickArrayState::get_array_start_index(tick_lower_index, tick_spacing)
+                && tick_array_upper.load()?.start_tick_index
+                    == TickArrayState::get_array_start_index(tick_upper_index, tick_spacing),
+            ErrorCode::InvalidTickArray
+        );
+        let liquidity_removed = personal_position.liquidity;
+        let (amount_0, fee_amount_0, amount_1, fee_amount_1) =
+            decrease_liquidity_and_update_position(
+                &accounts.pool_state,
+                &mut protocol_position,
+                &mut personal_position,
+                &tick_array_lower,
+                &tick_array_upper,
+                bitmap_extension_accounts.first(),
+                liquidity_removed,
+            )?;
+        transfer_from_pool_vault_to_user(
+            &accounts.pool_state,
+            &token_vault_0,
+            &token_account_0,
+            Some(accounts.vault_0_mint.clone()),
+            &accounts.token_program,
+            Some(accounts.token_program_2022.to_account_info()),
+            amount_0 + fee_amount_0,
+        )?;
+        transfer_from_pool_vault_to_user(
+            &accounts.pool_state,
+            &token_vault_1,
+            &token_account_1,
+            Some(accounts.vault_1_mint.clone()),
+            &accounts.token_program,
+            Some(accounts.token_program_2022.to_account_info()),
+            amount_1 + fee_amount_1,
+        )?;
+        check_unclaimed_fees_and_vault(&accounts.pool_state, &token_vault_0, &token_vault_1)?;
+        protocol_position.exit(&crate::id())?;
+
+        // reopen the slot on the new range, unclaimed rewards stay owed to the position
+        for (tick_array, start_index) in [
+            (&slot_accounts[5], new_tick_array_lower_start_index),
+            (&slot_accounts[6], new_tick_array_upper_start_index),
+        ] {
+            let tick_array = TickArrayState::get_or_create_tick_array(
+                accounts.bundle_authority.to_account_info(),
+                tick_array.clone(),
+                accounts.system_program.to_account_info(),
+                &accounts.pool_state,
+                start_index,
+                tick_spacing,
+            )?;
+            let tick_array = tick_array.load()?;
+            require!(
+                tick_array.pool_id == pool_id && tick_array.start_tick_index == start_index,
+                ErrorCode::InvalidTickArray
+            );
+        }
+        let new_tick_array_lower = AccountLoader::<TickArrayState>::try_from(&slot_accounts[5])?;
+        let new_tick_array_upper = AccountLoader::<TickArrayState>::try_from(&slot_accounts[6])?;
+
+        let mut new_protocol_position = get_or_create_protocol_position(
+            accounts.bundle_authority.to_account_info(),
+            accounts.system_program.to_account_info(),
+            pool_id,
+            &slot_accounts[4],
+            new_tick_lower_index,
+            new_tick_upper_index,
+        )?;
+        personal_position.tick_lower_index = new_tick_lower_index;
+        personal_position.tick_upper_index = new_tick_upper_index;
+        increase_liquidity(
+            &accounts.bundle_authority,
+            &accounts.pool_state,
+            &accounts.amm_config,
+            &mut new_protocol_position,
+            &mut personal_position,
+            &new_tick_array_lower,
+            &new_tick_array_upper,
+            &token_account_0,
+            &token_account_1,
+            &token_vault_0,
+            &token_vault_1,
+            &accounts.token_program,
+            Some(&accounts.token_program_2022),
+            Some(accounts.vault_0_mint.clone()),
+            Some(accounts.vault_1_mint.clone()),
+            bitmap_extension_accounts,
+            template.liquidity,
+            u64::MAX,
+            u64::MAX,
+            None,
+        )?;
+
+        // the positions are not part of the accounts struct, persist them here
+        new_protocol_position.exit(&crate::id())?;
+        personal_position.exit(&crate::id())?;
+
+        emit!(RebalanceBundledPositionEvent {
+            position_bundle: accounts.position_bundle.key(),
+            bundle_index: *bundle_index,
+            pool_state: pool_id,
+            tick_lower_index,
+            tick_upper_index,
+            new_tick_lower_index,
+            new_tick_upper_index,
+            liquidity_removed,
+            liquidity_added: template.liquidity,
+            amount_0,
+            amount_1,
+            fee_amount_0,
+            fee_amount_1,
+            position_hash,
+            new_position_hash: personal_position.position_hash(),
+        });
+    }
+
+    // the withdrawals are credited against the deposits
+    let balance_0_after = get_token_account_amount(&token_account_0)?;
+    let balance_1_after = get_token_account_amount(&token_account_1)?;
+    require_gte!(
+        amount_0_max,
+        balance_0_before.saturating_sub(balance_0_after),
+        ErrorCode::PriceSlippageCheck
+    );
+    require_gte!(
+        amount_1_max,
+        balance_1_before.saturating_sub(balance_1_after),
+        ErrorCode::PriceSlippageCheck
+    );
+    Ok(())
+}
+
+/// Load the protocol position of the new range, creating it if it does not exist yet
+pub(crate) fn get_or_create_protocol_position<'info>(
+    payer: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    pool_id: Pubkey,
+    protocol_position_info: &'info AccountInfo<'info>,
+    tick_lower_index: i32,
+    tick_upper_index: i32,
+) -> Result<Box<Account<'info, ProtocolPositionState>>> {
+    let (protocol_position_key, protocol_position_bump) = Pubkey::find_program_address(
+        &[
+            POSITION_SEED.as_bytes(),
+            pool_id.as_ref(),
+            &tick_lower_index.to_be_bytes(),
+            &tick_upper_index.to_be_bytes(),
+        ],
+        &crate::id(),
+    );
+    require_keys_eq!(protocol_position_info.key(), protocol_position_key);
+    if protocol_position_info.owner == &crate::id() {
+        return Ok(Box::new(Account::<ProtocolPositionState>::try_from(
+            protocol_position_info,
+        )?));
+    }
+    create_or_allocate_account(
+        &crate::id(),
+        payer,
+        system_program,
+        protocol_position_info.clone(),
+        &[
+            POSITION_SEED.as_bytes(),
+            pool_id.as_ref(),
+            &tick_lower_index.to_be_bytes(),
+            &tick_upper_index.to_be_bytes(),
+            &[protocol_position_bump],
+        ],
+        ProtocolPositionState::LEN,
+    )?;
+    let mut protocol_position = Box::new(Account::<ProtocolPositionState>::try_from_unchecked(
+        protocol_position_info,
+    )?);
+    protocol_position.bump = protocol_position_bump;
+    protocol_position.pool_id = pool_id;
+    protocol_position.tick_lower_index = tick_lower_index;
+    protocol_position.tick_upper_index = tick_upper_index;
+    Ok(protocol_position)
+}
+
+#[cfg(test)]
+mod rebalance_bundle_test {
+    use super::*;
+
+    #[test]
+    fn template_range_test() {
+        let template = BundleRebalanceTemplate {
+            tick_lower_offset: -100,
+            tick_upper_offset: 100,
+            liquidity: 1,
+        };
+        assert_eq!(template.range(0, 10).unwrap(), (-100, 100));
+        // both ends round down to the tick spacing
+        assert_eq!(template.range(-5, 10).unwrap(), (-110, 90));
+        assert_eq!(template.range(5, 60).unwrap(), (-120, 60));
+        // collapses to an empty range
+        let narrow = BundleRebalanceTemplate {
+            tick_lower_offset: 1,
+            tick_upper_offset: 10,
+            liquidity: 1,
+        };
+        assert!(narrow.range(0, 60).is_err());
+        // past the max tick
+        assert!(template.range(tick_math::MAX_TICK - 50, 10).is_err());
+
+        let inverted = BundleRebalanceTemplate {
+            tick_lower_offset: 100,
+            tick_upper_offset: -100,
+            liquidity: 1,
+        };
+        assert!(inverted.range(0, 10).is_err());
+    }
+}
@@ -0,0 +1,52 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct RegisterOracleConsumer<'info> {
+    /// The consumer's authority, pays the rent of the registration
+    #[account(mut)]
+    pub consumer: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Initialize an account to store the registration
+    #[account(
+        init,
+        seeds = [
+            ORACLE_CONSUMER_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            consumer.key().as_ref(),
+        ],
+        bump,
+        payer = consumer,
+        space = OracleConsumer::LEN
+    )]
+    pub oracle_consumer: Box<Account<'info, OracleConsumer>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn register_oracle_consumer(
+    ctx: Context<RegisterOracleConsumer>,
+    max_staleness: u32,
+) -> Result<()> {
+    ctx.accounts.oracle_consumer.initialize(
+        ctx.bumps.oracle_consumer,
+        ctx.accounts.pool_state.key(),
+        ctx.accounts.consumer.key(),
+        max_staleness,
+        Clock::get()?.unix_timestamp as u64,
+    )?;
+
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    pool_state.oracle_consumer_count = pool_state.oracle_consumer_count.checked_add(1).unwrap();
+    emit!(OracleConsumerEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        consumer: ctx.accounts.consumer.key(),
+        max_staleness,
+        registered: true,
+        consumer_count: pool_state.oracle_consumer_count,
+    });
+    Ok(())
+}
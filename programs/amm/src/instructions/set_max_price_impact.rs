@@ -0,0 +1,31 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::require_not_cpi;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetMaxPriceImpact<'info> {
+    /// The pool creator or the admin
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+pub fn set_max_price_impact(
+    ctx: Context<SetMaxPriceImpact>,
+    max_price_impact_bps: u16,
+) -> Result<()> {
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    let authority = ctx.accounts.authority.key();
+    require!(
+        authority == crate::admin::id() || authority == pool_state.owner,
+        ErrorCode::NotApproved
+    );
+    // the pool creator may be a program, the admin must sign a top level instruction
+    if authority == crate::admin::id() {
+        require_not_cpi()?;
+    }
+    pool_state.max_price_impact_bps = max_price_impact_bps;
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::require_not_cpi;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetMinSwapAmount<'info> {
+    /// The pool creator or the admin
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+pub fn set_min_swap_amount(
+    ctx: Context<SetMinSwapAmount>,
+    min_swap_amount_0: u64,
+    min_swap_amount_1: u64,
+) -> Result<()> {
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    let authority = ctx.accounts.authority.key();
+    require!(
+        authority == crate::admin::id() || authority == pool_state.owner,
+        ErrorCode::NotApproved
+    );
+    // the pool creator may be a program, the admin must sign a top level instruction
+    if authority == crate::admin::id() {
+        require_not_cpi()?;
+    }
+    pool_state.min_swap_amount_0 = min_swap_amount_0;
+    pool_state.min_swap_amount_1 = min_swap_amount_1;
+    Ok(())
+}
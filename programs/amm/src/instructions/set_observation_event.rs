@@ -0,0 +1,19 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetObservationEvent<'info> {
+    /// The pool creator
+    #[account(address = pool_state.load()?.owner @ ErrorCode::NotApproved)]
+    pub pool_creator: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+pub fn set_observation_event(ctx: Context<SetObservationEvent>, enabled: bool) -> Result<()> {
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    pool_state.observation_event_enabled = enabled as u8;
+    Ok(())
+}
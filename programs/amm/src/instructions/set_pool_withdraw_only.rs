@@ -0,0 +1,34 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetPoolWithdrawOnly<'info> {
+    /// The guardian of the switch, or anyone if it has none
+    pub caller: Signer<'info>,
+
+    /// The dead man's switch, must have tripped
+    #[account(
+        seeds = [ADMIN_HEARTBEAT_SEED.as_bytes()],
+        bump = admin_heartbeat.bump,
+        constraint = admin_heartbeat.can_trip(
+            &caller.key(),
+            u64::try_from(Clock::get()?.unix_timestamp).unwrap()
+        ) @ ErrorCode::NotApproved
+    )]
+    pub admin_heartbeat: Box<Account<'info, AdminHeartbeat>>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+pub fn set_pool_withdraw_only(ctx: Context<SetPoolWithdrawOnly>) -> Result<()> {
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    pool_state.set_withdraw_only();
+    msg!(
+        "pool {} set withdraw-only, last admin heartbeat at {}",
+        ctx.accounts.pool_state.key(),
+        ctx.accounts.admin_heartbeat.last_heartbeat
+    );
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::{get_cpi_caller, is_authorized_for_token};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+use anchor_spl::token_interface::TokenAccount;
+
+#[derive(Accounts)]
+pub struct SetPositionIncentive<'info> {
+    /// The position owner or delegated authority
+    pub nft_owner: Signer<'info>,
+
+    /// The token account for the tokenized position
+    #[account(
+        constraint = is_authorized_for_token(
+            &nft_owner.key(),
+            &nft_account.to_account_info(),
+            &personal_position.nft_mint
+        ) @ ErrorCode::NotApproved,
+    )]
+    pub nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The position to enroll or withdraw
+    #[account(mut, constraint = personal_position.pool_id == pool_state.key())]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The incentive programs approved for the pool's config
+    #[account(
+        seeds = [INCENTIVE_PROGRAMS_SEED.as_bytes(), pool_state.load()?.amm_config.as_ref()],
+        bump = incentive_programs.bump,
+    )]
+    pub incentive_programs: Box<Account<'info, IncentivePrograms>>,
+
+    /// CHECK: Sysvar to find the calling program
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+}
+
+/// Called through CPI by an approved incentive program to enroll the position in its campaign,
+/// or to withdraw it. The owner can withdraw the position directly once the program it is
+/// enrolled in is no longer approved
+pub fn set_position_incentive(ctx: Context<SetPositionIncentive>, enrolled: bool) -> Result<()> {
+    let caller = get_cpi_caller(&ctx.accounts.instructions_sysvar)?;
+    let incentive_programs = &ctx.accounts.incentive_programs;
+    let personal_position = ctx.accounts.personal_position.as_mut();
+    if enrolled {
+        let incentive_program = match caller {
+            Some(program_id) if incentive_programs.is_approved(&program_id) => program_id,
+            _ => return err!(ErrorCode::NotApproved),
+        };
+        personal_position.enroll(incentive_program)?;
+    } else {
+        let incentive_program = personal_position.incentive_program;
+        require!(
+            caller == Some(incentive_program)
+                || !incentive_programs.is_approved(&incentive_program),
+            ErrorCode::NotApproved
+        );
+        personal_position.incentive_program = Pubkey::default();
+    }
+
+    emit!(PositionIncentiveEvent {
+        position_nft_mint: personal_position.nft_mint,
+        incentive_program: personal_position.incentive_program,
+    });
+    Ok(())
+}
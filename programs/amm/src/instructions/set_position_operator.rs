@@ -0,0 +1,54 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetPositionOperator<'info> {
+    /// The holder of the positions, pays for the delegation
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: the keeper granted the scope, it doesn't sign
+    pub operator: UncheckedAccount<'info>,
+
+    /// The delegation, created on first use and closed on revocation
+    #[account(
+        init_if_needed,
+        seeds = [
+            POSITION_DELEGATE_SEED.as_bytes(),
+            owner.key().as_ref(),
+            operator.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = PositionDelegate::LEN
+    )]
+    pub position_delegate: Box<Account<'info, PositionDelegate>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Grants `operator` the `scope` over every position nft the owner holds, replacing its previous
+/// scope. A scope of 0 revokes the operator and returns the rent
+pub fn set_position_operator(ctx: Context<SetPositionOperator>, scope: u8) -> Result<()> {
+    PositionDelegate::check_scope(scope)?;
+    let owner = ctx.accounts.owner.key();
+    let operator = ctx.accounts.operator.key();
+    if scope == 0 {
+        ctx.accounts
+            .position_delegate
+            .close(ctx.accounts.owner.to_account_info())?;
+    } else {
+        let position_delegate = ctx.accounts.position_delegate.as_mut();
+        position_delegate.bump = ctx.bumps.position_delegate;
+        position_delegate.owner = owner;
+        position_delegate.operator = operator;
+        position_delegate.scope = scope;
+    }
+
+    emit!(SetPositionOperatorEvent {
+        owner,
+        operator,
+        scope,
+    });
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetQuoteOrientation<'info> {
+    /// The pool creator
+    #[account(address = pool_state.load()?.owner @ ErrorCode::NotApproved)]
+    pub pool_creator: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+pub fn set_quote_orientation(
+    ctx: Context<SetQuoteOrientation>,
+    quote_orientation: u8,
+) -> Result<()> {
+    require!(
+        quote_orientation == QuoteOrientation::Token1PerToken0 as u8
+            || quote_orientation == QuoteOrientation::Token0PerToken1 as u8,
+        ErrorCode::InvalidQuoteOrientation
+    );
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    pool_state.quote_orientation = quote_orientation;
+    Ok(())
+}
@@ -2,7 +2,7 @@ use crate::error::ErrorCode;
 use crate::libraries::{fixed_point_64, full_math::MulDiv, U256};
 use crate::states::pool::{reward_period_limit, PoolState, REWARD_NUM};
 use crate::states::*;
-use crate::util::transfer_from_user_to_pool_vault;
+use crate::util::{require_not_cpi, transfer_from_user_to_pool_vault};
 use anchor_lang::prelude::*;
 use anchor_spl::token::Token;
 use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
@@ -58,7 +58,10 @@ pub fn set_reward_params<'a, 'b, 'c: 'info, 'info>(
 
     let mut pool_state = ctx.accounts.pool_state.load_mut()?;
 
-    if !admin_operator {
+    // the pool creator may be a program, the admin must sign a top level instruction
+    if admin_operator {
+        require_not_cpi()?;
+    } else {
         require_keys_eq!(ctx.accounts.authority.key(), pool_state.owner);
     }
 
@@ -0,0 +1,37 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::require_not_cpi;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetSwapHook<'info> {
+    /// The pool creator or the admin
+    pub authority: Signer<'info>,
+
+    /// The config whitelisting the hook program
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+pub fn set_swap_hook(ctx: Context<SetSwapHook>, swap_hook_program: Pubkey) -> Result<()> {
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    let authority = ctx.accounts.authority.key();
+    require!(
+        authority == crate::admin::id() || authority == pool_state.owner,
+        ErrorCode::NotApproved
+    );
+    // the pool creator may be a program, the admin must sign a top level instruction
+    if authority == crate::admin::id() {
+        require_not_cpi()?;
+    }
+    require!(
+        swap_hook_program == Pubkey::default()
+            || swap_hook_program == ctx.accounts.amm_config.swap_hook_program,
+        ErrorCode::SwapHookNotWhitelisted
+    );
+    pool_state.swap_hook_program = swap_hook_program;
+    Ok(())
+}
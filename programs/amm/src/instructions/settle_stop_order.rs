@@ -0,0 +1,195 @@
+use super::decrease_liquidity::decrease_liquidity;
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::check_payouts_to_holder;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::Mint;
+use anchor_spl::token_interface::{Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct SettleStopOrder<'info> {
+    /// Anyone can settle a triggered order and receive the keeper reward
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+
+    /// CHECK: receives the rent of the stop order
+    #[account(mut, address = stop_order.owner)]
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [STOP_ORDER_SEED.as_bytes(), personal_position.key().as_ref()],
+        bump = stop_order.bump,
+        close = owner
+    )]
+    pub stop_order: Box<Account<'info, StopOrderState>>,
+
+    /// The owner must still hold the position nft itself, a delegate can't keep an order alive
+    /// since anyone can settle it
+    #[account(
+        token::mint = personal_position.nft_mint,
+        token::authority = owner,
+        constraint = nft_account.amount == 1,
+    )]
+    pub nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        address = stop_order.personal_position,
+        constraint = personal_position.pool_id == pool_state.key()
+    )]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The pool's observation account the trigger tick is averaged from
+    #[account(address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+
+    #[account(
+        mut,
+        seeds = [
+            POSITION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &personal_position.tick_lower_index.to_be_bytes(),
+            &personal_position.tick_upper_index.to_be_bytes(),
+        ],
+        bump,
+        constraint = protocol_position.pool_id == pool_state.key(),
+    )]
+    pub protocol_position: Box<Account<'info, ProtocolPositionState>>,
+
+    /// Token_0 vault
+    #[account(
+        mut,
+        constraint = token_vault_0.key() == pool_state.load()?.token_vault_0
+    )]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token_1 vault
+    #[account(
+        mut,
+        constraint = token_vault_1.key() == pool_state.load()?.token_vault_1
+    )]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Stores init state for the lower tick
+    #[account(mut, constraint = tick_array_lower.load()?.pool_id == pool_state.key())]
+    pub tick_array_lower: AccountLoader<'info, TickArrayState>,
+
+    /// Stores init state for the upper tick
+    #[account(mut, constraint = tick_array_upper.load()?.pool_id == pool_state.key())]
+    pub tick_array_upper: AccountLoader<'info, TickArrayState>,
+
+    /// The owner's token account to receive amount_0
+    #[account(
+        mut,
+        token::mint = token_vault_0.mint,
+        token::authority = stop_order.owner
+    )]
+    pub recipient_token_account_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The owner's token account to receive amount_1
+    #[account(
+        mut,
+        token::mint = token_vault_1.mint,
+        token::authority = stop_order.owner
+    )]
+    pub recipient_token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// SPL program to transfer out tokens
+    pub token_program: Program<'info, Token>,
+    /// Token program 2022
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// memo program
+    /// CHECK:
+    #[account(
+        address = spl_memo::id()
+    )]
+    pub memo_program: UncheckedAccount<'info>,
+
+    /// The mint of token vault 0
+    #[account(
+        address = token_vault_0.mint,
+        constraint = pool_state.load()?.is_valid_token_program(
+            &vault_0_mint.key(),
+            vault_0_mint.to_account_info().owner
+        ) @ ErrorCode::InvalidTokenProgram
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token vault 1
+    #[account(
+        address = token_vault_1.mint,
+        constraint = pool_state.load()?.is_valid_token_program(
+            &vault_1_mint.key(),
+            vault_1_mint.to_account_info().owner
+        ) @ ErrorCode::InvalidTokenProgram
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+}
+
+/// Remove all liquidity of a position whose stop tick has been crossed by the pool's time
+/// weighted average tick to its owner, paying the escrowed keeper reward to the caller.
+pub fn settle_stop_order<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SettleStopOrder<'info>>,
+) -> Result<()> {
+    let twap_tick = ctx.accounts.stop_order.triggered_tick(
+        &*ctx.accounts.observation_state.load()?,
+        oracle::block_timestamp(),
+    )?;
+    // reward vaults and recipients are passed in remaining accounts, the keeper must not be
+    // able to redirect the owner's rewards
+    check_payouts_to_holder(
+        &ctx.accounts.stop_order.owner,
+        &ctx.accounts.pool_state.key(),
+        ctx.remaining_accounts,
+    )?;
+
+    let liquidity = ctx.accounts.personal_position.liquidity;
+    decrease_liquidity(
+        &ctx.accounts.pool_state,
+        &mut ctx.accounts.protocol_position,
+        &mut ctx.accounts.personal_position,
+        &ctx.accounts.token_vault_0.to_account_info(),
+        &ctx.accounts.token_vault_1.to_account_info(),
+        &ctx.accounts.tick_array_lower,
+        &ctx.accounts.tick_array_upper,
+        &ctx.accounts.recipient_token_account_0.to_account_info(),
+        &ctx.accounts.recipient_token_account_1.to_account_info(),
+        &ctx.accounts.token_program,
+        Some(ctx.accounts.token_program_2022.clone()),
+        Some(ctx.accounts.memo_program.clone()),
+        Some(ctx.accounts.vault_0_mint.clone()),
+        Some(ctx.accounts.vault_1_mint.clone()),
+        ctx.remaining_accounts,
+        liquidity,
+        ctx.accounts.stop_order.amount_0_min,
+        ctx.accounts.stop_order.amount_1_min,
+    )?;
+
+    let keeper_reward = ctx.accounts.stop_order.keeper_reward;
+    if keeper_reward > 0 {
+        ctx.accounts
+            .stop_order
+            .to_account_info()
+            .sub_lamports(keeper_reward)?;
+        ctx.accounts
+            .keeper
+            .to_account_info()
+            .add_lamports(keeper_reward)?;
+    }
+
+    emit!(SettleStopOrderEvent {
+        personal_position: ctx.accounts.personal_position.key(),
+        owner: ctx.accounts.stop_order.owner,
+        keeper: ctx.accounts.keeper.key(),
+        twap_tick,
+        liquidity,
+        keeper_reward,
+    });
+    Ok(())
+}
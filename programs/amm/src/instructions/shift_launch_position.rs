@@ -0,0 +1,266 @@
+use super::decrease_liquidity::{
+    check_unclaimed_fees_and_vault, decrease_liquidity_and_update_position,
+};
+use super::increase_liquidity::credit_position_liquidity;
+use super::open_position::add_liquidity_to_range;
+use super::rebalance_bundle::get_or_create_protocol_position;
+use crate::error::ErrorCode;
+use crate::libraries::{liquidity_math, tick_math};
+use crate::states::*;
+use crate::util::transfer_from_pool_vault_to_user;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct ShiftLaunchPosition<'info> {
+    /// Anyone can crank the schedule, pays for the new range's accounts
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The schedule of the position
+    #[account(has_one = personal_position)]
+    pub launch_schedule: Box<Account<'info, LaunchScheduleState>>,
+
+    /// The pool of the position
+    #[account(mut, address = launch_schedule.pool_id)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The pool's config, liquidity can't be added while it is paused
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// The escrowed position shifted down the schedule
+    #[account(mut)]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    /// The protocol position of the current range
+    #[account(
+        mut,
+        seeds = [
+            POSITION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &personal_position.tick_lower_index.to_be_bytes(),
+            &personal_position.tick_upper_index.to_be_bytes(),
+        ],
+        bump,
+        constraint = protocol_position.pool_id == pool_state.key(),
+    )]
+    pub protocol_position: Box<Account<'info, ProtocolPositionState>>,
+
+    /// Stores init state for the lower tick of the current range
+    #[account(mut, constraint = tick_array_lower.load()?.pool_id == pool_state.key())]
+    pub tick_array_lower: AccountLoader<'info, TickArrayState>,
+
+    /// Stores init state for the upper tick of the current range
+    #[account(mut, constraint = tick_array_upper.load()?.pool_id == pool_state.key())]
+    pub tick_array_upper: AccountLoader<'info, TickArrayState>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        mut,
+        constraint = token_vault_0.key() == pool_state.load()?.token_vault_0
+    )]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        mut,
+        constraint = token_vault_1.key() == pool_state.load()?.token_vault_1
+    )]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The beneficiary's token_0 account, receives the fees and the token_0 not redeposited
+    #[account(
+        mut,
+        token::mint = token_vault_0.mint,
+        token::authority = launch_schedule.beneficiary,
+    )]
+    pub recipient_token_account_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The beneficiary's token_1 account, receives the fees and the token_1 not redeposited
+    #[account(
+        mut,
+        token::mint = token_vault_1.mint,
+        token::authority = launch_schedule.beneficiary,
+    )]
+    pub recipient_token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of token vault 0
+    #[account(address = token_vault_0.mint)]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token vault 1
+    #[account(address = token_vault_1.mint)]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// SPL program to transfer out tokens
+    pub token_program: Program<'info, Token>,
+
+    /// Token program 2022
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// Program to create the new range's accounts
+    pub system_program: Program<'info, System>,
+    // remaining accounts
+    // new_protocol_position, new_tick_array_lower, new_tick_array_upper, created if needed,
+    // followed by the optional tick_array_bitmap extension
+}
+
+/// Move the launch position down to the range its schedule has reached, redepositing as much of
+/// the withdrawn liquidity as the new range takes. The tokens never leave the pool vaults except
+/// for the fees and the remainder, which go to the schedule's beneficiary.
+pub fn shift_launch_position<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, ShiftLaunchPosition<'info>>,
+) -> Result<()> {
+    let accounts = ctx.accounts;
+    let pool_id = accounts.pool_state.key();
+    let block_timestamp = u64::try_from(Clock::get()?.unix_timestamp).unwrap();
+    let tick_spacing = {
+        let pool_state = accounts.pool_state.load()?;
+        require!(
+            pool_state.get_status_by_bit(PoolStatusBitIndex::DecreaseLiquidity)
+                && pool_state
+                    .get_status_by_bit(PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity)
+                && accounts
+                    .amm_config
+                    .get_status_by_bit(PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity),
+            ErrorCode::NotApproved
+        );
+        pool_state.tick_spacing
+    };
+
+    let launch_schedule = accounts.launch_schedule.as_ref();
+    let tick_lower_index = accounts.personal_position.tick_lower_index;
+    let tick_upper_index = accounts.personal_position.tick_upper_index;
+    let new_tick_lower_index = launch_schedule.scheduled_tick_lower(block_timestamp);
+    let new_tick_upper_index = new_tick_lower_index + launch_schedule.tick_width;
+    require_gt!(
+        tick_lower_index,
+        new_tick_lower_index,
+        ErrorCode::LaunchShiftNotDue
+    );
+
+    require_gte!(ctx.remaining_accounts.len(), 3, ErrorCode::AccountLack);
+    let (new_range_accounts, remaining_accounts) = ctx.remaining_accounts.split_at(3);
+    let tick_array_bitmap_extension = remaining_accounts
+        .first()
+        .filter(|account_info| account_info.key() == TickArrayBitmapExtension::key(pool_id));
+
+    // close out the current range, the tokens stay in the vaults
+    require!(
+        accounts.tick_array_lower.load()?.start_tick_index
+            == TickArrayState::get_array_start_index(tick_lower_index, tick_spacing)
+            && accounts.tick_array_upper.load()?.start_tick_index
+                == TickArrayState::get_array_start_index(tick_upper_index, tick_spacing),
+        ErrorCode::InvalidTickArray
+    );
+    let liquidity_removed = accounts.personal_position.liquidity;
+    let (amount_0, fee_amount_0, amount_1, fee_amount_1) = decrease_liquidity_and_update_position(
+        &accounts.pool_state,
+        &mut accounts.protocol_position,
+        &mut accounts.personal_position,
+        &accounts.tick_array_lower,
+        &accounts.tick_array_upper,
+        tick_array_bitmap_extension,
+        liquidity_removed,
+    )?;
+
+    // reopen the position on the new range with what was withdrawn
+    let new_tick_array_lower = TickArrayState::get_or_create_tick_array(
+        accounts.payer.to_account_info(),
+        new_range_accounts[1].clone(),
+        accounts.system_program.to_account_info(),
+        &accounts.pool_state,
+        TickArrayState::get_array_start_index(new_tick_lower_index, tick_spacing),
+        tick_spacing,
+    )?;
+    let new_tick_array_upper = TickArrayState::get_or_create_tick_array(
+        accounts.payer.to_account_info(),
+        new_range_accounts[2].clone(),
+        accounts.system_program.to_account_info(),
+        &accounts.pool_state,
+        TickArrayState::get_array_start_index(new_tick_upper_index, tick_spacing),
+        tick_spacing,
+    )?;
+    let mut new_protocol_position = get_or_create_protocol_position(
+        accounts.payer.to_account_info(),
+        accounts.system_program.to_account_info(),
+        pool_id,
+        &new_range_accounts[0],
+        new_tick_lower_index,
+        new_tick_upper_index,
+    )?;
+
+    // the deposit rounds up, leave a unit of slack so it never exceeds the withdrawal
+    let liquidity_added = liquidity_math::get_liquidity_from_amounts(
+        accounts.pool_state.load()?.sqrt_price_x64,
+        tick_math::get_sqrt_price_at_tick(new_tick_lower_index)?,
+        tick_math::get_sqrt_price_at_tick(new_tick_upper_index)?,
+        amount_0.saturating_sub(1),
+        amount_1.saturating_sub(1),
+    );
+    let personal_position = accounts.personal_position.as_mut();
+    personal_position.tick_lower_index = new_tick_lower_index;
+    personal_position.tick_upper_index = new_tick_upper_index;
+    let (mut amount_0_added, mut amount_1_added) = (0, 0);
+    if liquidity_added > 0 {
+        (amount_0_added, amount_1_added) = add_liquidity_to_range(
+            &new_tick_array_lower,
+            &new_tick_array_upper,
+            &mut new_protocol_position,
+            tick_array_bitmap_extension,
+            &mut accounts.pool_state.load_mut()?,
+            liquidity_added,
+            new_tick_lower_index,
+            new_tick_upper_index,
+        )?;
+    }
+    credit_position_liquidity(personal_position, &new_protocol_position, liquidity_added)?;
+    new_protocol_position.exit(&crate::id())?;
+
+    // pay out the fees and what the new range did not take
+    let amount_0 = (amount_0 + fee_amount_0)
+        .checked_sub(amount_0_added)
+        .unwrap();
+    let amount_1 = (amount_1 + fee_amount_1)
+        .checked_sub(amount_1_added)
+        .unwrap();
+    transfer_from_pool_vault_to_user(
+        &accounts.pool_state,
+        &accounts.token_vault_0.to_account_info(),
+        &accounts.recipient_token_account_0.to_account_info(),
+        Some(accounts.vault_0_mint.clone()),
+        &accounts.token_program,
+        Some(accounts.token_program_2022.to_account_info()),
+        amount_0,
+    )?;
+    transfer_from_pool_vault_to_user(
+        &accounts.pool_state,
+        &accounts.token_vault_1.to_account_info(),
+        &accounts.recipient_token_account_1.to_account_info(),
+        Some(accounts.vault_1_mint.clone()),
+        &accounts.token_program,
+        Some(accounts.token_program_2022.to_account_info()),
+        amount_1,
+    )?;
+    check_unclaimed_fees_and_vault(
+        &accounts.pool_state,
+        &accounts.token_vault_0.to_account_info(),
+        &accounts.token_vault_1.to_account_info(),
+    )?;
+
+    emit!(LaunchShiftEvent {
+        launch_schedule: accounts.launch_schedule.key(),
+        pool_state: pool_id,
+        tick_lower_index,
+        tick_upper_index,
+        new_tick_lower_index,
+        new_tick_upper_index,
+        liquidity_removed,
+        liquidity_added,
+        amount_0,
+        amount_1,
+    });
+    Ok(())
+}
@@ -0,0 +1,99 @@
+use crate::states::*;
+use crate::util::transfer_from_user_to_pool_vault;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct StakeFeeDistributor<'info> {
+    /// The staker
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mut)]
+    pub fee_distributor: Box<Account<'info, FeeDistributorState>>,
+
+    /// Stores the staked amount and reward checkpoint of the owner
+    #[account(
+        init_if_needed,
+        seeds = [
+            FEE_STAKER_SEED.as_bytes(),
+            fee_distributor.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = FeeStakerState::LEN
+    )]
+    pub fee_staker: Box<Account<'info, FeeStakerState>>,
+
+    /// The token account to pay the staked tokens
+    #[account(
+        mut,
+        token::mint = stake_mint,
+    )]
+    pub owner_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The vault holding staked tokens
+    #[account(
+        mut,
+        address = fee_distributor.stake_vault
+    )]
+    pub stake_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of the staked token
+    #[account(
+        address = fee_distributor.stake_mint,
+        mint::token_program = token_program,
+    )]
+    pub stake_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Spl token program or token program 2022 of stake mint
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn stake_fee_distributor(ctx: Context<StakeFeeDistributor>, amount: u64) -> Result<()> {
+    let block_timestamp = u64::try_from(Clock::get()?.unix_timestamp).unwrap();
+    let fee_distributor = ctx.accounts.fee_distributor.as_mut();
+    fee_distributor.update_epoch(block_timestamp);
+
+    let fee_staker = ctx.accounts.fee_staker.as_mut();
+    if fee_staker.owner == Pubkey::default() {
+        fee_staker.bump = ctx.bumps.fee_staker;
+        fee_staker.fee_distributor = fee_distributor.key();
+        fee_staker.owner = ctx.accounts.owner.key();
+    }
+    fee_staker.settle(fee_distributor.reward_per_share_x64);
+
+    // the vault receives the amount net of transfer fee
+    let vault_amount_before = ctx.accounts.stake_vault.amount;
+    let token_program = ctx.accounts.token_program.to_account_info();
+    transfer_from_user_to_pool_vault(
+        &ctx.accounts.owner,
+        &ctx.accounts.owner_token_account.to_account_info(),
+        &ctx.accounts.stake_vault.to_account_info(),
+        Some(ctx.accounts.stake_mint.clone()),
+        &token_program,
+        Some(token_program.clone()),
+        amount,
+    )?;
+    ctx.accounts.stake_vault.reload()?;
+    let received_amount = ctx
+        .accounts
+        .stake_vault
+        .amount
+        .checked_sub(vault_amount_before)
+        .unwrap();
+
+    let fee_staker = ctx.accounts.fee_staker.as_mut();
+    fee_staker.staked_amount = fee_staker
+        .staked_amount
+        .checked_add(received_amount)
+        .unwrap();
+    let fee_distributor = ctx.accounts.fee_distributor.as_mut();
+    fee_distributor.total_staked = fee_distributor
+        .total_staked
+        .checked_add(received_amount)
+        .unwrap();
+    Ok(())
+}
@@ -1,12 +1,16 @@
 use crate::error::ErrorCode;
 use crate::libraries::{
-    big_num::U128, fixed_point_64, full_math::MulDiv, liquidity_math, swap_math, tick_math,
+    big_num::{U128, U256},
+    fixed_point_64,
+    full_math::MulDiv,
+    liquidity_math, swap_math, tick_math,
 };
 use crate::states::*;
 use crate::util::*;
 use anchor_lang::prelude::*;
+use anchor_lang::ZeroCopy;
 use anchor_spl::token::{Token, TokenAccount};
-use std::cell::RefMut;
+use std::cell::{RefCell, RefMut};
 use std::collections::VecDeque;
 #[cfg(feature = "enable-log")]
 use std::convert::identity;
@@ -82,6 +86,12 @@ pub struct SwapAccounts<'b, 'info> {
 
     /// The program account for the oracle observation
     pub observation_state: &'b mut AccountLoader<'info, ObservationState>,
+
+    /// The referral account credited with the referral fee, if the swap is referred
+    pub referral_account: Option<&'b mut Box<Account<'info, ReferralAccount>>>,
+
+    /// The signer's volume in the pool, if the swap counts towards volume rebates
+    pub user_stats: Option<&'b mut Box<Account<'info, UserStats>>>,
 }
 
 // the top level state of the swap, the results of which are recorded in storage at the end
@@ -103,10 +113,21 @@ pub struct SwapState {
     pub protocol_fee: u64,
     // amount of input token paid as fund fee
     pub fund_fee: u64,
+    // amount of input token paid as partner fee
+    pub partner_fee: u64,
+    // amount of input token paid as referral fee
+    pub referral_fee: u64,
     // the current liquidity in range
     pub liquidity: u128,
 }
 
+impl SwapState {
+    /// The whole trade fee charged by the swap, before it is split
+    pub fn trade_fee(&self) -> u64 {
+        self.fee_amount + self.protocol_fee + self.fund_fee + self.partner_fee + self.referral_fee
+    }
+}
+
 #[derive(Default)]
 struct StepComputations {
     // the price at the beginning of the step
@@ -136,9 +157,49 @@ pub fn swap_internal<'b, 'info>(
     zero_for_one: bool,
     is_base_input: bool,
     block_timestamp: u32,
+) -> Result<(u64, u64)> {
+    swap_internal_with_referral(
+        amm_config,
+        pool_state,
+        tick_array_states,
+        observation_state,
+        tickarray_bitmap_extension,
+        amount_specified,
+        sqrt_price_limit_x64,
+        zero_for_one,
+        is_base_input,
+        block_timestamp,
+        0,
+    )
+}
+
+/// Same as `swap_internal`, but also sets `referral_fee_rate` of the trade fee aside for a
+/// referrer. The cut is added to the pool's `referral_fees_token_0/1`, the caller credits it to
+/// the referral account
+pub fn swap_internal_with_referral(
+    amm_config: &AmmConfig,
+    pool_state: &mut RefMut<PoolState>,
+    tick_array_states: &mut VecDeque<RefMut<TickArrayState>>,
+    observation_state: &mut RefMut<ObservationState>,
+    tickarray_bitmap_extension: &Option<TickArrayBitmapExtension>,
+    amount_specified: u64,
+    sqrt_price_limit_x64: u128,
+    zero_for_one: bool,
+    is_base_input: bool,
+    block_timestamp: u32,
+    referral_fee_rate: u32,
 ) -> Result<(u64, u64)> {
     require!(amount_specified != 0, ErrorCode::ZeroAmountSpecified);
-    if !pool_state.get_status_by_bit(PoolStatusBitIndex::Swap) {
+    if is_base_input {
+        require_gte!(
+            amount_specified,
+            pool_state.min_swap_amount(zero_for_one),
+            ErrorCode::SwapAmountTooSmall
+        );
+    }
+    if !pool_state.get_status_by_bit(PoolStatusBitIndex::Swap)
+        || !amm_config.get_status_by_bit(PoolStatusBitIndex::Swap)
+    {
         return err!(ErrorCode::NotApproved);
     }
     require!(
@@ -153,6 +214,7 @@ pub fn swap_internal<'b, 'info>(
     );
 
     let liquidity_start = pool_state.liquidity;
+    let trade_fee_rate = pool_state.trade_fee_rate(amm_config);
 
     let updated_reward_infos = pool_state.update_reward_infos(block_timestamp as u64)?;
 
@@ -169,6 +231,8 @@ pub fn swap_internal<'b, 'info>(
         fee_amount: 0,
         protocol_fee: 0,
         fund_fee: 0,
+        partner_fee: 0,
+        referral_fee: 0,
         liquidity: liquidity_start,
     };
 
@@ -198,6 +262,10 @@ pub fn swap_internal<'b, 'info>(
         ErrorCode::InvalidFirstTickArrayAccount
     );
 
+    // the last tick crossed and the next initialized tick after it, for the near tick metrics
+    let mut crossed_tick: Option<TickState> = None;
+    let mut next_tick: Option<TickState> = None;
+
     // continue swapping as long as we haven't used the entire input/output and haven't
     // reached the price limit
     while state.amount_specified_remaining != 0 && state.sqrt_price_x64 != sqrt_price_limit_x64 {
@@ -264,6 +332,11 @@ pub fn swap_internal<'b, 'info>(
         }
         step.tick_next = next_initialized_tick.tick;
         step.initialized = next_initialized_tick.is_initialized();
+        next_tick = if step.initialized {
+            Some(*next_initialized_tick)
+        } else {
+            None
+        };
 
         if step.tick_next < tick_math::MIN_TICK {
             step.tick_next = tick_math::MIN_TICK;
@@ -302,7 +375,7 @@ pub fn swap_internal<'b, 'info>(
             target_price,
             state.liquidity,
             state.amount_specified_remaining,
-            amm_config.trade_fee_rate,
+            trade_fee_rate,
             is_base_input,
             zero_for_one,
             block_timestamp,
@@ -318,6 +391,16 @@ pub fn swap_internal<'b, 'info>(
         step.amount_in = swap_step.amount_in;
         step.amount_out = swap_step.amount_out;
         step.fee_amount = swap_step.fee_amount;
+        crate::log_kv!(
+            "swap_step",
+            sqrt_price_start_x64 = step.sqrt_price_start_x64,
+            sqrt_price_next_x64 = state.sqrt_price_x64,
+            tick_next = step.tick_next,
+            liquidity = state.liquidity,
+            amount_in = step.amount_in,
+            amount_out = step.amount_out,
+            fee_amount = step.fee_amount,
+        );
 
         if is_base_input {
             state.amount_specified_remaining = state
@@ -367,6 +450,31 @@ pub fn swap_internal<'b, 'info>(
             step.fee_amount = step.fee_amount.checked_sub(delta).unwrap();
             state.fund_fee = state.fund_fee.checked_add(delta).unwrap();
         }
+        // if the pool has a partner, calculate how much is owed, decrement fee_amount, and increment partner_fee,
+        // the config's rates may have been raised since the partner was set so the partner takes at most what is left
+        if pool_state.partner_fee_rate > 0 {
+            let delta = U128::from(step_fee_amount)
+                .checked_mul(pool_state.partner_fee_rate.into())
+                .unwrap()
+                .checked_div(FEE_RATE_DENOMINATOR_VALUE.into())
+                .unwrap()
+                .as_u64()
+                .min(step.fee_amount);
+            step.fee_amount = step.fee_amount.checked_sub(delta).unwrap();
+            state.partner_fee = state.partner_fee.checked_add(delta).unwrap();
+        }
+        // if the swap was referred, the referrer likewise takes at most what is left after the partner
+        if referral_fee_rate > 0 {
+            let delta = U128::from(step_fee_amount)
+                .checked_mul(referral_fee_rate.into())
+                .unwrap()
+                .checked_div(FEE_RATE_DENOMINATOR_VALUE.into())
+                .unwrap()
+                .as_u64()
+                .min(step.fee_amount);
+            step.fee_amount = step.fee_amount.checked_sub(delta).unwrap();
+            state.referral_fee = state.referral_fee.checked_add(delta).unwrap();
+        }
 
         // update global fee tracker
         if state.liquidity > 0 {
@@ -418,6 +526,8 @@ pub fn swap_internal<'b, 'info>(
                     liquidity_net = liquidity_net.neg();
                 }
                 state.liquidity = liquidity_math::add_delta(state.liquidity, liquidity_net)?;
+                crossed_tick = Some(*next_initialized_tick);
+                next_tick = None;
             }
 
             state.tick = if zero_for_one {
@@ -460,13 +570,50 @@ pub fn swap_internal<'b, 'info>(
         //     zero_for_one,
         // });
     }
+    crate::log_kv!(
+        "swap_end",
+        tick = state.tick,
+        sqrt_price_x64 = state.sqrt_price_x64,
+        liquidity = state.liquidity,
+        amount_specified_remaining = state.amount_specified_remaining,
+        amount_calculated = state.amount_calculated,
+    );
+    if let Some(max_price_impact) = pool_state.max_price_impact() {
+        require_gte!(
+            max_price_impact,
+            price_impact(pool_state.sqrt_price_x64, state.sqrt_price_x64),
+            ErrorCode::PriceImpactTooHigh
+        );
+    }
+    // a base output swap only knows its input once the swap loop has run
+    if !is_base_input {
+        require_gte!(
+            state.amount_calculated,
+            pool_state.min_swap_amount(zero_for_one),
+            ErrorCode::SwapAmountTooSmall
+        );
+    }
     // update tick
     if state.tick != pool_state.tick_current {
         // update the previous tick to the observation
-        observation_state.update(block_timestamp, pool_state.tick_current);
+        if observation_state.update(block_timestamp, pool_state.tick_current)
+            && pool_state.is_observation_event_enabled()
+        {
+            let observation_index = observation_state.observation_index;
+            let observation = observation_state.observations[observation_index as usize];
+            emit!(ObservationEvent {
+                pool_state: observation_state.pool_id,
+                observation_index,
+                block_timestamp: observation.block_timestamp,
+                tick_cumulative: observation.tick_cumulative,
+            });
+        }
         pool_state.tick_current = state.tick;
     }
     pool_state.sqrt_price_x64 = state.sqrt_price_x64;
+    if let Some(crossed_tick) = crossed_tick {
+        pool_state.update_near_ticks(zero_for_one, &crossed_tick, next_tick.as_ref());
+    }
 
     if liquidity_start != state.liquidity {
         pool_state.liquidity = state.liquidity;
@@ -507,6 +654,24 @@ pub fn swap_internal<'b, 'info>(
                 .checked_add(state.fund_fee)
                 .unwrap();
         }
+        if state.partner_fee > 0 {
+            pool_state.partner_fees_token_0 = pool_state
+                .partner_fees_token_0
+                .checked_add(state.partner_fee)
+                .unwrap();
+        }
+        if state.referral_fee > 0 {
+            pool_state.referral_fees_token_0 = pool_state
+                .referral_fees_token_0
+                .checked_add(state.referral_fee)
+                .unwrap();
+        }
+        pool_state.cumulative_trade_fees_token_0 = pool_state
+            .cumulative_trade_fees_token_0
+            .wrapping_add(state.trade_fee());
+        pool_state.cumulative_protocol_fees_token_0 = pool_state
+            .cumulative_protocol_fees_token_0
+            .wrapping_add(state.protocol_fee);
         pool_state.swap_in_amount_token_0 = pool_state
             .swap_in_amount_token_0
             .checked_add(u128::from(amount_0))
@@ -534,6 +699,24 @@ pub fn swap_internal<'b, 'info>(
                 .checked_add(state.fund_fee)
                 .unwrap();
         }
+        if state.partner_fee > 0 {
+            pool_state.partner_fees_token_1 = pool_state
+                .partner_fees_token_1
+                .checked_add(state.partner_fee)
+                .unwrap();
+        }
+        if state.referral_fee > 0 {
+            pool_state.referral_fees_token_1 = pool_state
+                .referral_fees_token_1
+                .checked_add(state.referral_fee)
+                .unwrap();
+        }
+        pool_state.cumulative_trade_fees_token_1 = pool_state
+            .cumulative_trade_fees_token_1
+            .wrapping_add(state.trade_fee());
+        pool_state.cumulative_protocol_fees_token_1 = pool_state
+            .cumulative_protocol_fees_token_1
+            .wrapping_add(state.protocol_fee);
         pool_state.swap_in_amount_token_1 = pool_state
             .swap_in_amount_token_1
             .checked_add(u128::from(amount_1))
@@ -547,15 +730,112 @@ pub fn swap_internal<'b, 'info>(
     Ok((amount_0, amount_1))
 }
 
-/// Performs a single exact input/output swap
-/// if is_base_input = true, return vaule is the max_amount_out, otherwise is min_amount_in
+/// Heap copies of the accounts a swap writes to, so the swap can be run against the current
+/// pool state without mutating any account
+pub struct SwapSimulation {
+    pool_state: RefCell<Vec<u8>>,
+    observation_state: RefCell<Vec<u8>>,
+    tick_array_states: Vec<RefCell<Vec<u8>>>,
+    tickarray_bitmap_extension: Option<TickArrayBitmapExtension>,
+}
+
+impl SwapSimulation {
+    /// Copies the pool and its observation account, `remaining_accounts` are the tick arrays in
+    /// swap order and optionally the tick array bitmap extension
+    pub fn new<'info>(
+        pool_state_info: &AccountInfo<'info>,
+        observation_state_info: &AccountInfo<'info>,
+        remaining_accounts: impl IntoIterator<Item = &'info AccountInfo<'info>>,
+    ) -> Result<Self> {
+        let tick_array_bitmap_extension_key = TickArrayBitmapExtension::key(pool_state_info.key());
+        let mut tickarray_bitmap_extension = None;
+        let mut tick_array_states = Vec::new();
+        for account_info in remaining_accounts.into_iter() {
+            if account_info.key().eq(&tick_array_bitmap_extension_key) {
+                tickarray_bitmap_extension = Some(
+                    *(AccountLoader::<TickArrayBitmapExtension>::try_from(account_info)?
+                        .load()?
+                        .deref()),
+                );
+                continue;
+            }
+            tick_array_states.push(RefCell::new(copy_account_data::<TickArrayState>(
+                account_info,
+            )?));
+        }
+        Ok(Self {
+            pool_state: RefCell::new(copy_account_data::<PoolState>(pool_state_info)?),
+            observation_state: RefCell::new(copy_account_data::<ObservationState>(
+                observation_state_info,
+            )?),
+            tick_array_states,
+            tickarray_bitmap_extension,
+        })
+    }
+
+    /// The copied pool state, as left by the last simulated swap
+    pub fn pool_state(&self) -> RefMut<'_, PoolState> {
+        load_copy(&self.pool_state)
+    }
+
+    /// Runs `swap_internal` against the copies, a zero `sqrt_price_limit_x64` means no limit
+    pub fn swap(
+        &self,
+        amm_config: &AmmConfig,
+        amount_specified: u64,
+        sqrt_price_limit_x64: u128,
+        zero_for_one: bool,
+        is_base_input: bool,
+    ) -> Result<(u64, u64)> {
+        let tick_array_states = &mut self
+            .tick_array_states
+            .iter()
+            .map(load_copy::<TickArrayState>)
+            .collect::<VecDeque<_>>();
+        swap_internal(
+            amm_config,
+            &mut self.pool_state(),
+            tick_array_states,
+            &mut load_copy(&self.observation_state),
+            &self.tickarray_bitmap_extension,
+            amount_specified,
+            if sqrt_price_limit_x64 == 0 {
+                if zero_for_one {
+                    tick_math::MIN_SQRT_PRICE_X64 + 1
+                } else {
+                    tick_math::MAX_SQRT_PRICE_X64 - 1
+                }
+            } else {
+                sqrt_price_limit_x64
+            },
+            zero_for_one,
+            is_base_input,
+            oracle::block_timestamp(),
+        )
+    }
+}
+
+/// Copies the data of a zero copy account after checking its owner and discriminator
+fn copy_account_data<T: ZeroCopy + Owner>(account_info: &AccountInfo) -> Result<Vec<u8>> {
+    AccountLoad::<T>::try_from(account_info)?;
+    let data = account_info.try_borrow_data()?;
+    Ok(data[8..std::mem::size_of::<T>() + 8].to_vec())
+}
+
+fn load_copy<T: ZeroCopy>(data: &RefCell<Vec<u8>>) -> RefMut<'_, T> {
+    RefMut::map(data.borrow_mut(), |data| bytemuck::from_bytes_mut(data))
+}
+
+/// Performs the swap and its transfers, returning the filled part of `amount_specified` and the
+/// other amount
 pub fn exact_internal<'b, 'c: 'info, 'info>(
     ctx: &mut SwapAccounts<'b, 'info>,
     remaining_accounts: &'c [AccountInfo<'info>],
     amount_specified: u64,
     sqrt_price_limit_x64: u128,
     is_base_input: bool,
-) -> Result<u64> {
+    allow_partial_fill: bool,
+) -> Result<(u64, u64)> {
     let block_timestamp = solana_program::clock::Clock::get()?.unix_timestamp as u64;
 
     let amount_0;
@@ -566,6 +846,23 @@ pub fn exact_internal<'b, 'c: 'info, 'info>(
     let input_balance_before = ctx.input_vault.amount;
     let output_balance_before = ctx.output_vault.amount;
 
+    let (remaining_accounts, hook_accounts) = split_swap_hook_accounts(
+        remaining_accounts,
+        ctx.pool_state.load()?.swap_hook(ctx.amm_config),
+    )?;
+    invoke_swap_hook(
+        hook_accounts,
+        &ctx.pool_state.to_account_info(),
+        &ctx.signer.to_account_info(),
+        BEFORE_SWAP_HOOK,
+        BeforeSwapArgs {
+            zero_for_one: ctx.input_vault.mint == ctx.pool_state.load()?.token_mint_0,
+            is_base_input,
+            amount_specified,
+            sqrt_price_limit_x64,
+        },
+    )?;
+
     {
         swap_price_before = ctx.pool_state.load()?.sqrt_price_x64;
         let pool_state = &mut ctx.pool_state.load_mut()?;
@@ -601,7 +898,14 @@ pub fn exact_internal<'b, 'c: 'info, 'info>(
             tick_array_states.push_back(AccountLoad::load_data_mut(account_info)?);
         }
 
-        (amount_0, amount_1) = swap_internal(
+        let referral_fee_rate = if ctx.referral_account.is_some() {
+            ctx.amm_config.referral_fee_rate
+        } else {
+            0
+        };
+        let referral_fees_before = pool_state.referral_fees(zero_for_one);
+        let protocol_fees_before = pool_state.protocol_fees(zero_for_one);
+        (amount_0, amount_1) = swap_internal_with_referral(
             &ctx.amm_config,
             pool_state,
             tick_array_states,
@@ -620,7 +924,28 @@ pub fn exact_internal<'b, 'c: 'info, 'info>(
             zero_for_one,
             is_base_input,
             oracle::block_timestamp(),
+            referral_fee_rate,
         )?;
+        if let Some(referral_account) = ctx.referral_account.as_mut() {
+            referral_account.credit(
+                zero_for_one,
+                pool_state.referral_fees(zero_for_one) - referral_fees_before,
+            );
+        }
+        // the rebate tier only counts past epochs, the swap's own volume is recorded after
+        if let Some(user_stats) = ctx.user_stats.as_mut() {
+            user_stats.roll(get_recent_epoch()?, pool_state.volume_rebate_threshold);
+            let tier = user_stats.tier(ctx.amm_config.volume_rebate_min_epochs);
+            if tier > 0 && pool_state.volume_rebate_threshold > 0 {
+                let rebate = pool_state.take_volume_rebate(
+                    zero_for_one,
+                    protocol_fees_before,
+                    ctx.amm_config.volume_rebate_rate * tier,
+                );
+                user_stats.credit(zero_for_one, rebate);
+            }
+            user_stats.record_volume(amount_0);
+        }
 
         #[cfg(feature = "enable-log")]
         msg!(
@@ -629,6 +954,12 @@ pub fn exact_internal<'b, 'c: 'info, 'info>(
             amount_0,
             amount_1
         );
+        crate::log_kv!(
+            "exact_swap",
+            is_base_input = is_base_input,
+            amount_0 = amount_0,
+            amount_1 = amount_1,
+        );
         require!(
             amount_0 != 0 && amount_1 != 0,
             ErrorCode::TooSmallInputOrOutputAmount
@@ -708,6 +1039,7 @@ pub fn exact_internal<'b, 'c: 'info, 'info>(
         sender: ctx.signer.key(),
         token_account_0: token_account_0.key(),
         token_account_1: token_account_1.key(),
+        recipient: ctx.output_token_account.owner,
         amount_0,
         transfer_fee_0: 0,
         amount_1,
@@ -722,7 +1054,7 @@ pub fn exact_internal<'b, 'c: 'info, 'info>(
     } else {
         require_gt!(pool_state.sqrt_price_x64, swap_price_before);
     }
-    if sqrt_price_limit_x64 == 0 {
+    if sqrt_price_limit_x64 == 0 && !allow_partial_fill {
         // Does't allow partial filled without specified limit_price.
         if is_base_input {
             if zero_for_one {
@@ -738,28 +1070,110 @@ pub fn exact_internal<'b, 'c: 'info, 'info>(
             }
         }
     }
+    let after_swap_args = AfterSwapArgs {
+        zero_for_one,
+        is_base_input,
+        amount_0,
+        amount_1,
+        sqrt_price_x64: pool_state.sqrt_price_x64,
+        tick: pool_state.tick_current,
+    };
+    drop(pool_state);
+    invoke_swap_hook(
+        hook_accounts,
+        &ctx.pool_state.to_account_info(),
+        &ctx.signer.to_account_info(),
+        AFTER_SWAP_HOOK,
+        after_swap_args,
+    )?;
 
+    let amount_in = ctx
+        .input_vault
+        .amount
+        .checked_sub(input_balance_before)
+        .unwrap();
+    let amount_out = output_balance_before
+        .checked_sub(ctx.output_vault.amount)
+        .unwrap();
     if is_base_input {
-        Ok(output_balance_before
-            .checked_sub(ctx.output_vault.amount)
-            .unwrap())
+        Ok((amount_in, amount_out))
     } else {
-        Ok(ctx
-            .input_vault
-            .amount
-            .checked_sub(input_balance_before)
-            .unwrap())
+        Ok((amount_out, amount_in))
+    }
+}
+
+/// The outcome of a swap, written to return data so callers chaining CPIs
+/// don't need to re-read the pool account
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SwapResult {
+    /// The amount paid by the user, including the token transfer fee
+    pub amount_in: u64,
+    /// The amount received by the user, excluding the token transfer fee
+    pub amount_out: u64,
+    /// The pool sqrt price after the swap, as a Q64.64
+    pub sqrt_price_x64_after: u128,
+    /// The pool tick after the swap
+    pub tick_after: i32,
+    /// The trade fee charged in the input token, protocol, fund, partner and referral shares and
+    /// the volume rebate included
+    pub fee_paid: u64,
+}
+
+impl SwapResult {
+    pub fn new(
+        pool_state: &PoolState,
+        amount_specified: u64,
+        other_amount: u64,
+        is_base_input: bool,
+        fee_paid: u64,
+    ) -> Self {
+        let (amount_in, amount_out) = if is_base_input {
+            (amount_specified, other_amount)
+        } else {
+            (other_amount, amount_specified)
+        };
+        SwapResult {
+            amount_in,
+            amount_out,
+            sqrt_price_x64_after: pool_state.sqrt_price_x64,
+            tick_after: pool_state.tick_current,
+            fee_paid,
+        }
     }
 }
 
+/// The slippage threshold for the filled part of a swap: the same average price as asked for
+/// `amount_specified`, rounded in the pool's favor
+pub fn partial_fill_threshold(
+    other_amount_threshold: u64,
+    amount_specified: u64,
+    amount_filled: u64,
+    is_base_input: bool,
+) -> u64 {
+    if amount_specified == 0 {
+        return other_amount_threshold;
+    }
+    let threshold = U128::from(other_amount_threshold);
+    let scaled = if is_base_input {
+        threshold.mul_div_ceil(U128::from(amount_filled), U128::from(amount_specified))
+    } else {
+        threshold.mul_div_floor(U128::from(amount_filled), U128::from(amount_specified))
+    };
+    scaled.unwrap().as_u64()
+}
+
+/// Swaps across a single pool. With `allow_partial_fill`, a swap stopped by the price limit
+/// settles what was filled: the unconsumed input stays with the payer and the slippage
+/// threshold is prorated to the filled amount. A swap running out of liquidity still fails
 pub fn swap<'a, 'b, 'c: 'info, 'info>(
     ctx: Context<'a, 'b, 'c, 'info, SwapSingle<'info>>,
     amount: u64,
     other_amount_threshold: u64,
     sqrt_price_limit_x64: u128,
     is_base_input: bool,
-) -> Result<()> {
-    let amount = exact_internal(
+    allow_partial_fill: bool,
+) -> Result<SwapResult> {
+    swap_single(
         &mut SwapAccounts {
             signer: ctx.accounts.payer.clone(),
             amm_config: &ctx.accounts.amm_config,
@@ -771,12 +1185,68 @@ pub fn swap<'a, 'b, 'c: 'info, 'info>(
             pool_state: &mut ctx.accounts.pool_state,
             tick_array_state: &mut ctx.accounts.tick_array,
             observation_state: &mut ctx.accounts.observation_state,
+            referral_account: None,
+            user_stats: None,
         },
+        None,
         ctx.remaining_accounts,
         amount,
+        other_amount_threshold,
+        sqrt_price_limit_x64,
+        is_base_input,
+        allow_partial_fill,
+    )
+}
+
+/// Performs a single pool swap and checks its slippage, see `swap`. With `system_program`, the
+/// signer's native mint token accounts are funded from its lamports up to the most the swap can
+/// take in, and closed back to it at the end. Those accounts must already exist, no temporary
+/// account is created
+pub fn swap_single<'b, 'c: 'info, 'info>(
+    accounts: &mut SwapAccounts<'b, 'info>,
+    system_program: Option<&Program<'info, System>>,
+    remaining_accounts: &'c [AccountInfo<'info>],
+    amount: u64,
+    other_amount_threshold: u64,
+    sqrt_price_limit_x64: u128,
+    is_base_input: bool,
+    allow_partial_fill: bool,
+) -> Result<SwapResult> {
+    let amount_specified = amount;
+    let zero_for_one = accounts.input_vault.mint == accounts.pool_state.load()?.token_mint_0;
+    let fees_before = accounts.pool_state.load()?.trade_fees(zero_for_one);
+    let signer = accounts.signer.to_account_info();
+    if let Some(system_program) = system_program {
+        wrap_sol(
+            &signer,
+            &accounts.input_token_account,
+            &accounts.token_program,
+            system_program,
+            if is_base_input {
+                amount
+            } else {
+                other_amount_threshold
+            },
+        )?;
+    }
+    let (amount_filled, amount) = exact_internal(
+        accounts,
+        remaining_accounts,
+        amount,
         sqrt_price_limit_x64,
         is_base_input,
+        allow_partial_fill,
     )?;
+    let other_amount_threshold = if allow_partial_fill {
+        partial_fill_threshold(
+            other_amount_threshold,
+            amount_specified,
+            amount_filled,
+            is_base_input,
+        )
+    } else {
+        other_amount_threshold
+    };
     if is_base_input {
         require!(
             amount >= other_amount_threshold,
@@ -788,7 +1258,152 @@ pub fn swap<'a, 'b, 'c: 'info, 'info>(
             ErrorCode::TooMuchInputPaid
         );
     }
+    if system_program.is_some() {
+        // an output account of another owner is left untouched
+        for token_account in [
+            &accounts.input_token_account,
+            &accounts.output_token_account,
+        ] {
+            unwrap_sol(&signer, token_account, &accounts.token_program)?;
+        }
+    }
+
+    let pool_state = accounts.pool_state.load()?;
+    Ok(SwapResult::new(
+        &pool_state,
+        amount_filled,
+        amount,
+        is_base_input,
+        pool_state.trade_fees(zero_for_one) - fees_before,
+    ))
+}
+
+#[derive(Accounts)]
+pub struct QuoteSwap<'info> {
+    /// The factory state to read the fee rates
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// The pool to quote, left untouched
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The program account for the most recent oracle observation
+    #[account(address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+    // remaining accounts
+    // tickarray_bitmap_extension: must add account if need regardless the sequence
+    // tick_array_account_1
+    // tick_array_account_2
+    // tick_array_account_...
+}
+
+/// The outcome a swap would have against the current pool state, token transfer fees excluded
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SwapQuote {
+    /// The amount the pool would take in
+    pub amount_in: u64,
+    /// The amount the pool would pay out
+    pub amount_out: u64,
+    /// The trade fee charged in the input token, protocol, fund, partner and referral shares and
+    /// the volume rebate included
+    pub fee_amount: u64,
+    /// The pool sqrt price after the swap, as a Q64.64
+    pub sqrt_price_x64_after: u128,
+    /// The pool tick after the swap
+    pub tick_after: i32,
+    /// How far the swap moves the price, in hundredths of a bip of the price before
+    pub price_impact: u64,
+}
+
+/// Runs the swap loop against copies of the pool, tick array and bitmap accounts and returns the
+/// expected amounts, without mutating any account or transferring tokens
+pub fn quote_swap<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, QuoteSwap<'info>>,
+    amount: u64,
+    sqrt_price_limit_x64: u128,
+    zero_for_one: bool,
+    is_base_input: bool,
+) -> Result<SwapQuote> {
+    let (sqrt_price_x64_before, fees_before) = {
+        let pool_state = ctx.accounts.pool_state.load()?;
+        (
+            pool_state.sqrt_price_x64,
+            pool_state.trade_fees(zero_for_one),
+        )
+    };
+    let simulation = SwapSimulation::new(
+        &ctx.accounts.pool_state.to_account_info(),
+        &ctx.accounts.observation_state.to_account_info(),
+        ctx.remaining_accounts,
+    )?;
+    let (amount_0, amount_1) = simulation.swap(
+        &ctx.accounts.amm_config,
+        amount,
+        sqrt_price_limit_x64,
+        zero_for_one,
+        is_base_input,
+    )?;
+    let (amount_in, amount_out) = if zero_for_one {
+        (amount_0, amount_1)
+    } else {
+        (amount_1, amount_0)
+    };
+    let pool_state = simulation.pool_state();
+    Ok(SwapQuote {
+        amount_in,
+        amount_out,
+        fee_amount: pool_state.trade_fees(zero_for_one) - fees_before,
+        sqrt_price_x64_after: pool_state.sqrt_price_x64,
+        tick_after: pool_state.tick_current,
+        price_impact: price_impact(sqrt_price_x64_before, pool_state.sqrt_price_x64),
+    })
+}
 
+/// The relative price move from `sqrt_price_x64_before` to `sqrt_price_x64_after`, in
+/// hundredths of a bip, saturating at `u64::MAX`
+pub fn price_impact(sqrt_price_x64_before: u128, sqrt_price_x64_after: u128) -> u64 {
+    let price_before = U256::from(sqrt_price_x64_before) * U256::from(sqrt_price_x64_before);
+    let price_after = U256::from(sqrt_price_x64_after) * U256::from(sqrt_price_x64_after);
+    let price_delta = if price_after > price_before {
+        price_after - price_before
+    } else {
+        price_before - price_after
+    };
+    match price_delta.mul_div_floor(U256::from(FEE_RATE_DENOMINATOR_VALUE), price_before) {
+        Some(price_impact) if price_impact <= U256::from(u64::MAX) => price_impact.as_u64(),
+        _ => u64::MAX,
+    }
+}
+
+/// Swaps for exactly `amount_out` of the output token across a single pool. Only the input
+/// the swap math requires is pulled from the payer, so nothing of `amount_in_maximum` is left
+/// to refund. A swap reaching `sqrt_price_limit_x64` before `amount_out` is filled fails.
+pub fn swap_base_output<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SwapSingle<'info>>,
+    amount_out: u64,
+    amount_in_maximum: u64,
+    sqrt_price_limit_x64: u128,
+) -> Result<SwapResult> {
+    let swap_result = swap(
+        ctx,
+        amount_out,
+        amount_in_maximum,
+        sqrt_price_limit_x64,
+        false,
+        false,
+    )?;
+    require_amount_out_filled(amount_out, swap_result.amount_out)?;
+    Ok(swap_result)
+}
+
+/// `swap` settles a swap stopped by a nonzero price limit, an exact output swap must still
+/// deliver all of `amount_out`
+fn require_amount_out_filled(amount_out: u64, amount_out_filled: u64) -> Result<()> {
+    require_eq!(
+        amount_out_filled,
+        amount_out,
+        ErrorCode::TooLittleOutputReceived
+    );
     Ok(())
 }
 
@@ -819,6 +1434,41 @@ mod swap_test {
         RefCell::new(tick_array_states)
     }
 
+    #[test]
+    fn price_impact_test() {
+        let sqrt_price_x64 = tick_math::get_sqrt_price_at_tick(0).unwrap();
+        assert_eq!(price_impact(sqrt_price_x64, sqrt_price_x64), 0);
+        // a tick is a 1 bip price move
+        let impact = price_impact(
+            sqrt_price_x64,
+            tick_math::get_sqrt_price_at_tick(1).unwrap(),
+        );
+        assert!(impact.abs_diff(100) <= 1);
+        let impact = price_impact(
+            sqrt_price_x64,
+            tick_math::get_sqrt_price_at_tick(-1).unwrap(),
+        );
+        assert!(impact.abs_diff(100) <= 1);
+        // the price halves
+        assert_eq!(price_impact(sqrt_price_x64 * 2, sqrt_price_x64), 750_000);
+        assert_eq!(
+            price_impact(tick_math::MIN_SQRT_PRICE_X64, tick_math::MAX_SQRT_PRICE_X64),
+            u64::MAX
+        );
+    }
+
+    #[test]
+    fn partial_fill_threshold_test() {
+        // fully filled keeps the threshold
+        assert_eq!(partial_fill_threshold(900, 1000, 1000, true), 900);
+        assert_eq!(partial_fill_threshold(1100, 1000, 1000, false), 1100);
+        // a minimum output rounds up, a maximum input rounds down
+        assert_eq!(partial_fill_threshold(999, 1000, 333, true), 333);
+        assert_eq!(partial_fill_threshold(1001, 1000, 333, false), 333);
+        assert_eq!(partial_fill_threshold(u64::MAX, u64::MAX, 1, true), 1);
+        assert_eq!(partial_fill_threshold(900, 1000, 0, true), 0);
+    }
+
     fn build_swap_param<'info>(
         tick_current: i32,
         tick_spacing: u16,
@@ -1880,6 +2530,159 @@ mod swap_test {
         }
     }
 
+    mod referral_fee_test {
+        use super::*;
+
+        #[test]
+        fn referral_fee_test() {
+            let tick_current = -5;
+            let liquidity = 1_000_000_000_000;
+            let sqrt_price_x64 = tick_math::get_sqrt_price_at_tick(tick_current).unwrap();
+            let build = || {
+                build_swap_param(
+                    tick_current,
+                    10,
+                    sqrt_price_x64,
+                    liquidity,
+                    vec![TickArrayInfo {
+                        start_tick_index: -600,
+                        ticks: vec![build_tick(-600, liquidity, liquidity as i128).take()],
+                    }],
+                )
+            };
+            let swap = |referral_fee_rate| {
+                let (amm_config, pool_state, tick_array_states, observation_state) = build();
+                let amounts = swap_internal_with_referral(
+                    &amm_config,
+                    &mut pool_state.borrow_mut(),
+                    &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
+                    &mut observation_state.borrow_mut(),
+                    &None,
+                    1_000_000,
+                    tick_math::get_sqrt_price_at_tick(-600).unwrap(),
+                    true,
+                    true,
+                    oracle::block_timestamp_mock() as u32,
+                    referral_fee_rate,
+                )
+                .unwrap();
+                let pool_state = pool_state.borrow();
+                (
+                    amounts,
+                    pool_state.trade_fees(true),
+                    pool_state.referral_fees(true),
+                )
+            };
+
+            let (amounts, trade_fees, referral_fees) = swap(0);
+            assert_eq!(trade_fees, 1000);
+            assert_eq!(referral_fees, 0);
+            // the referrer's cut comes out of the same trade fee, the price is unchanged
+            let (referred_amounts, referred_trade_fees, referral_fees) = swap(100_000);
+            assert_eq!(referred_amounts, amounts);
+            assert_eq!(referred_trade_fees, trade_fees);
+            assert_eq!(referral_fees, 100);
+        }
+    }
+
+    mod min_swap_amount_test {
+        use super::*;
+
+        #[test]
+        fn min_swap_amount_test() {
+            let tick_current = -5;
+            let liquidity = 1_000_000_000_000;
+            let sqrt_price_x64 = tick_math::get_sqrt_price_at_tick(tick_current).unwrap();
+            let swap = |amount, is_base_input| {
+                let (amm_config, pool_state, tick_array_states, observation_state) =
+                    build_swap_param(
+                        tick_current,
+                        10,
+                        sqrt_price_x64,
+                        liquidity,
+                        vec![TickArrayInfo {
+                            start_tick_index: -600,
+                            ticks: vec![build_tick(-600, liquidity, liquidity as i128).take()],
+                        }],
+                    );
+                pool_state.borrow_mut().min_swap_amount_0 = 1000;
+                let amounts = swap_internal(
+                    &amm_config,
+                    &mut pool_state.borrow_mut(),
+                    &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
+                    &mut observation_state.borrow_mut(),
+                    &None,
+                    amount,
+                    tick_math::get_sqrt_price_at_tick(-600).unwrap(),
+                    true,
+                    is_base_input,
+                    oracle::block_timestamp_mock() as u32,
+                );
+                amounts
+            };
+
+            assert!(swap(999, true).is_err());
+            assert!(swap(1000, true).is_ok());
+            // the input of a base output swap is checked once the swap has priced it
+            assert!(swap(900, false).is_err());
+            let (amount_0, _) = swap(1000, false).unwrap();
+            assert!(amount_0 >= 1000);
+        }
+    }
+
+    mod swap_base_output_test {
+        use super::*;
+
+        #[test]
+        fn price_limit_hit_before_amount_out_filled_test() {
+            let tick_current = -5;
+            let liquidity = 1_000_000_000_000;
+            let sqrt_price_x64 = tick_math::get_sqrt_price_at_tick(tick_current).unwrap();
+            let swap = |amount_out, sqrt_price_limit_x64| {
+                let (amm_config, pool_state, tick_array_states, observation_state) =
+                    build_swap_param(
+                        tick_current,
+                        10,
+                        sqrt_price_x64,
+                        liquidity,
+                        vec![TickArrayInfo {
+                            start_tick_index: -600,
+                            ticks: vec![build_tick(-600, liquidity, liquidity as i128).take()],
+                        }],
+                    );
+                let (_, amount_1) = swap_internal(
+                    &amm_config,
+                    &mut pool_state.borrow_mut(),
+                    &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
+                    &mut observation_state.borrow_mut(),
+                    &None,
+                    amount_out,
+                    sqrt_price_limit_x64,
+                    true,
+                    false,
+                    oracle::block_timestamp_mock() as u32,
+                )
+                .unwrap();
+                amount_1
+            };
+
+            let amount_out = 100_000_000;
+            let amount_out_filled =
+                swap(amount_out, tick_math::get_sqrt_price_at_tick(-600).unwrap());
+            assert_eq!(amount_out_filled, amount_out);
+            assert!(require_amount_out_filled(amount_out, amount_out_filled).is_ok());
+
+            // the limit one tick away stops the swap short of amount_out
+            let amount_out_filled =
+                swap(amount_out, tick_math::get_sqrt_price_at_tick(-6).unwrap());
+            assert!(amount_out_filled > 0 && amount_out_filled < amount_out);
+            assert_eq!(
+                require_amount_out_filled(amount_out, amount_out_filled).unwrap_err(),
+                ErrorCode::TooLittleOutputReceived.into()
+            );
+        }
+    }
+
     #[cfg(test)]
     mod sqrt_price_limit_optimization_min_specified_test {
         use super::*;
@@ -0,0 +1,238 @@
+use super::swap::swap_internal;
+use crate::libraries::tick_math;
+use crate::states::*;
+use anchor_lang::prelude::*;
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+
+/// The accounts a swap reads besides the fixed ones, in the order it expects them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedSwapAccounts {
+    pub observation_state: Pubkey,
+    /// Only set when the swap reads the bitmap beyond the pool's default bitmap, it goes first
+    /// in remaining accounts
+    pub tickarray_bitmap_extension: Option<Pubkey>,
+    /// The tick arrays the swap crosses, in swap order
+    pub tick_arrays: Vec<Pubkey>,
+}
+
+/// Resolves the exact accounts a swap needs by running it against copies of the pool state and
+/// its tick arrays, walking them in the same order the program does.
+///
+/// `tick_arrays` holds the pool's tick arrays by start index, fetched by the caller. The swap
+/// fails with `NotEnoughTickArrayAccount` if it runs past the arrays given.
+pub fn resolve_swap_accounts(
+    amm_config: &AmmConfig,
+    pool_state: &PoolState,
+    tickarray_bitmap_extension: Option<&TickArrayBitmapExtension>,
+    tick_arrays: &HashMap<i32, TickArrayState>,
+    amount: u64,
+    sqrt_price_limit_x64: u128,
+    zero_for_one: bool,
+    is_base_input: bool,
+    block_timestamp: u32,
+) -> Result<ResolvedSwapAccounts> {
+    let pool_id = pool_state.key();
+    let resolve = |extension: &Option<TickArrayBitmapExtension>| {
+        swap_tick_arrays(
+            amm_config,
+            pool_state,
+            extension,
+            tick_arrays,
+            amount,
+            sqrt_price_limit_x64,
+            zero_for_one,
+            is_base_input,
+            block_timestamp,
+        )
+    };
+    // the extension is only passed when a swap without it fails
+    let without_extension =
+        if pool_state.is_overflow_default_tickarray_bitmap(vec![pool_state.tick_current]) {
+            None
+        } else {
+            resolve(&None).ok()
+        };
+    let (start_indexes, uses_extension) = match (without_extension, tickarray_bitmap_extension) {
+        (Some(start_indexes), _) => (start_indexes, false),
+        (None, Some(extension)) => (resolve(&Some(*extension))?, true),
+        (None, None) => (resolve(&None)?, false),
+    };
+
+    Ok(ResolvedSwapAccounts {
+        observation_state: pool_state.observation_key,
+        tickarray_bitmap_extension: uses_extension.then(|| TickArrayBitmapExtension::key(pool_id)),
+        tick_arrays: start_indexes
+            .into_iter()
+            .map(|start_index| {
+                Pubkey::find_program_address(
+                    &[
+                        TICK_ARRAY_SEED.as_bytes(),
+                        pool_id.as_ref(),
+                        &start_index.to_be_bytes(),
+                    ],
+                    &crate::id(),
+                )
+                .0
+            })
+            .collect(),
+    })
+}
+
+/// Runs the swap against copies of the initialized tick arrays in swap direction and returns
+/// the start indexes of the ones it took
+fn swap_tick_arrays(
+    amm_config: &AmmConfig,
+    pool_state: &PoolState,
+    tickarray_bitmap_extension: &Option<TickArrayBitmapExtension>,
+    tick_arrays: &HashMap<i32, TickArrayState>,
+    amount: u64,
+    sqrt_price_limit_x64: u128,
+    zero_for_one: bool,
+    is_base_input: bool,
+    block_timestamp: u32,
+) -> Result<Vec<i32>> {
+    let mut start_indexes = Vec::new();
+    let (_, mut start_index) =
+        pool_state.get_first_initialized_tick_array(tickarray_bitmap_extension, zero_for_one)?;
+    while tick_arrays.contains_key(&start_index) {
+        start_indexes.push(start_index);
+        match pool_state.next_initialized_tick_array_start_index(
+            tickarray_bitmap_extension,
+            start_index,
+            zero_for_one,
+        ) {
+            Ok(Some(next_start_index)) => start_index = next_start_index,
+            _ => break,
+        }
+    }
+
+    let tick_array_states: Vec<RefCell<TickArrayState>> = start_indexes
+        .iter()
+        .map(|start_index| RefCell::new(tick_arrays[start_index]))
+        .collect();
+    let mut tick_array_refs: VecDeque<_> = tick_array_states
+        .iter()
+        .map(|tick_array| tick_array.borrow_mut())
+        .collect();
+    let pool_state = RefCell::new(*pool_state);
+    let observation_state = RefCell::new(ObservationState {
+        pool_id: pool_state.borrow().key(),
+        ..Default::default()
+    });
+    swap_internal(
+        amm_config,
+        &mut pool_state.borrow_mut(),
+        &mut tick_array_refs,
+        &mut observation_state.borrow_mut(),
+        tickarray_bitmap_extension,
+        amount,
+        if sqrt_price_limit_x64 == 0 {
+            if zero_for_one {
+                tick_math::MIN_SQRT_PRICE_X64 + 1
+            } else {
+                tick_math::MAX_SQRT_PRICE_X64 - 1
+            }
+        } else {
+            sqrt_price_limit_x64
+        },
+        zero_for_one,
+        is_base_input,
+        block_timestamp,
+    )?;
+    // the swap pops the tick arrays it moves into, the rest are left untouched
+    start_indexes.truncate(start_indexes.len() - tick_array_refs.len());
+    Ok(start_indexes)
+}
+
+#[cfg(test)]
+mod swap_accounts_test {
+    use super::*;
+    use crate::states::oracle::block_timestamp_mock;
+    use crate::states::pool_test::build_pool;
+    use crate::states::tick_array_test::{build_tick, build_tick_array_with_tick_states};
+
+    #[test]
+    fn resolve_swap_accounts_test() {
+        let tick_spacing = 10;
+        let liquidity = 1_000_000_000_000;
+        let amm_config = AmmConfig {
+            trade_fee_rate: 1000,
+            tick_spacing,
+            ..Default::default()
+        };
+        let pool_state = build_pool(
+            -5,
+            tick_spacing,
+            tick_math::get_sqrt_price_at_tick(-5).unwrap(),
+            liquidity,
+        );
+        let pool_id = pool_state.borrow().key();
+        let mut tick_arrays = HashMap::new();
+        let mut tick_array_keys = HashMap::new();
+        // the pool's liquidity ends at -600 and another range starts at -1200
+        for (start_index, liquidity_net) in [
+            (-600, liquidity as i128),
+            (-1200, -(liquidity as i128)),
+            (-1800, liquidity as i128),
+        ] {
+            let tick_array = build_tick_array_with_tick_states(
+                pool_id,
+                start_index,
+                tick_spacing,
+                vec![build_tick(start_index, liquidity, liquidity_net).take()],
+            )
+            .take();
+            tick_array_keys.insert(start_index, tick_array.key());
+            tick_arrays.insert(start_index, tick_array);
+            pool_state
+                .borrow_mut()
+                .flip_tick_array_bit(None, start_index)
+                .unwrap();
+        }
+        let pool_state = pool_state.take();
+        let resolve = |amount, sqrt_price_limit_x64| {
+            resolve_swap_accounts(
+                &amm_config,
+                &pool_state,
+                None,
+                &tick_arrays,
+                amount,
+                sqrt_price_limit_x64,
+                true,
+                true,
+                block_timestamp_mock() as u32,
+            )
+        };
+
+        // a small swap stays in the current tick array
+        let accounts = resolve(1_000_000, 0).unwrap();
+        assert_eq!(accounts.observation_state, pool_state.observation_key);
+        assert_eq!(accounts.tickarray_bitmap_extension, None);
+        assert_eq!(accounts.tick_arrays, vec![tick_array_keys[&-600]]);
+
+        // a swap down to the next array's first tick needs that array too
+        let accounts =
+            resolve(u64::MAX, tick_math::get_sqrt_price_at_tick(-1200).unwrap()).unwrap();
+        assert_eq!(
+            accounts.tick_arrays,
+            vec![tick_array_keys[&-600], tick_array_keys[&-1200]]
+        );
+
+        // the swap runs past the tick arrays given
+        let mut tick_arrays = tick_arrays.clone();
+        tick_arrays.remove(&-1200);
+        assert!(resolve_swap_accounts(
+            &amm_config,
+            &pool_state,
+            None,
+            &tick_arrays,
+            u64::MAX,
+            tick_math::get_sqrt_price_at_tick(-1200).unwrap(),
+            true,
+            true,
+            block_timestamp_mock() as u32,
+        )
+        .is_err());
+    }
+}
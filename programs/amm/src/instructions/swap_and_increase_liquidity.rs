@@ -0,0 +1,349 @@
+use super::increase_liquidity::increase_liquidity;
+use crate::error::ErrorCode;
+use crate::libraries::{
+    big_num::U256, fixed_point_64, full_math::MulDiv, liquidity_math, tick_math,
+};
+use crate::states::*;
+use crate::swap_v2::{exact_internal_v2, SwapSingleV2};
+use crate::util::{get_transfer_fee, is_authorized_for_token};
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct SwapAndIncreaseLiquidity<'info> {
+    /// The position owner or delegated authority, pays the input token
+    pub nft_owner: Signer<'info>,
+
+    /// The token account for nft
+    #[account(
+        constraint = is_authorized_for_token(
+            &nft_owner.key(),
+            &nft_account.to_account_info(),
+            &personal_position.nft_mint
+        ) @ ErrorCode::NotApproved,
+    )]
+    pub nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The factory state to read protocol fees
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        mut,
+        seeds = [
+            POSITION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &personal_position.tick_lower_index.to_be_bytes(),
+            &personal_position.tick_upper_index.to_be_bytes(),
+        ],
+        bump,
+        constraint = protocol_position.pool_id == pool_state.key(),
+    )]
+    pub protocol_position: Box<Account<'info, ProtocolPositionState>>,
+
+    /// Increase liquidity for this position
+    #[account(mut, constraint = personal_position.pool_id == pool_state.key())]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    /// Stores init state for the lower tick
+    #[account(mut, constraint = tick_array_lower.load()?.pool_id == pool_state.key())]
+    pub tick_array_lower: AccountLoader<'info, TickArrayState>,
+
+    /// Stores init state for the upper tick
+    #[account(mut, constraint = tick_array_upper.load()?.pool_id == pool_state.key())]
+    pub tick_array_upper: AccountLoader<'info, TickArrayState>,
+
+    /// The payer's token account for token_0
+    #[account(
+        mut,
+        token::mint = token_vault_0.mint
+    )]
+    pub token_account_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The payer's token account for token_1
+    #[account(
+        mut,
+        token::mint = token_vault_1.mint
+    )]
+    pub token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        mut,
+        constraint = token_vault_0.key() == pool_state.load()?.token_vault_0
+    )]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        mut,
+        constraint = token_vault_1.key() == pool_state.load()?.token_vault_1
+    )]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The program account for the most recent oracle observation
+    #[account(mut, address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+
+    /// SPL program for token transfers
+    pub token_program: Program<'info, Token>,
+
+    /// SPL program 2022 for token transfers
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// CHECK:
+    #[account(
+        address = spl_memo::id()
+    )]
+    pub memo_program: UncheckedAccount<'info>,
+
+    /// The mint of token vault 0
+    #[account(
+        address = token_vault_0.mint,
+        constraint = pool_state.load()?.is_valid_token_program(
+            &vault_0_mint.key(),
+            vault_0_mint.to_account_info().owner
+        ) @ ErrorCode::InvalidTokenProgram
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token vault 1
+    #[account(
+        address = token_vault_1.mint,
+        constraint = pool_state.load()?.is_valid_token_program(
+            &vault_1_mint.key(),
+            vault_1_mint.to_account_info().owner
+        ) @ ErrorCode::InvalidTokenProgram
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+    // remaining accounts
+    // the tick array bitmap extension if the swap or the position needs it, then the tick
+    // arrays of the swap
+}
+
+/// Deposits `amount` of a single token into the position: part of it is swapped in the pool
+/// so the rest and the swap output match the position's range ratio, what the deposit can't
+/// use stays in the token accounts
+pub fn swap_and_increase_liquidity<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SwapAndIncreaseLiquidity<'info>>,
+    amount: u64,
+    is_token_0: bool,
+    liquidity_min: u128,
+) -> Result<()> {
+    let accounts = ctx.accounts;
+    let tick_lower_index = accounts.personal_position.tick_lower_index;
+    let tick_upper_index = accounts.personal_position.tick_upper_index;
+    let sqrt_price_lower_x64 = tick_math::get_sqrt_price_at_tick(tick_lower_index)?;
+    let sqrt_price_upper_x64 = tick_math::get_sqrt_price_at_tick(tick_upper_index)?;
+
+    let swap_amount = {
+        let pool_state = accounts.pool_state.load()?;
+        get_swap_amount_for_range(
+            amount,
+            is_token_0,
+            pool_state.sqrt_price_x64,
+            sqrt_price_lower_x64,
+            sqrt_price_upper_x64,
+            pool_state.trade_fee_rate(&accounts.amm_config),
+        )
+    };
+
+    let (input_token_account, output_token_account) = if is_token_0 {
+        (&accounts.token_account_0, &accounts.token_account_1)
+    } else {
+        (&accounts.token_account_1, &accounts.token_account_0)
+    };
+    let output_balance_before = output_token_account.amount;
+    if swap_amount != 0 {
+        let (input_vault, output_vault, input_vault_mint, output_vault_mint) = if is_token_0 {
+            (
+                &accounts.token_vault_0,
+                &accounts.token_vault_1,
+                &accounts.vault_0_mint,
+                &accounts.vault_1_mint,
+            )
+        } else {
+            (
+                &accounts.token_vault_1,
+                &accounts.token_vault_0,
+                &accounts.vault_1_mint,
+                &accounts.vault_0_mint,
+            )
+        };
+        exact_internal_v2(
+            &mut SwapSingleV2 {
+                payer: accounts.nft_owner.clone(),
+                amm_config: accounts.amm_config.clone(),
+                pool_state: accounts.pool_state.clone(),
+                input_token_account: input_token_account.clone(),
+                output_token_account: output_token_account.clone(),
+                input_vault: input_vault.clone(),
+                output_vault: output_vault.clone(),
+                observation_state: accounts.observation_state.clone(),
+                token_program: accounts.token_program.clone(),
+                token_program_2022: accounts.token_program_2022.clone(),
+                memo_program: accounts.memo_program.clone(),
+                input_vault_mint: input_vault_mint.clone(),
+                output_vault_mint: output_vault_mint.clone(),
+            },
+            ctx.remaining_accounts,
+            swap_amount,
+            0,
+            true,
+        )?;
+        accounts.token_account_0.reload()?;
+        accounts.token_account_1.reload()?;
+    }
+
+    // the input left after the swap and the swap output, as received
+    let (amount_0_max, amount_1_max) = if is_token_0 {
+        (
+            amount - swap_amount,
+            accounts.token_account_1.amount - output_balance_before,
+        )
+    } else {
+        (
+            accounts.token_account_0.amount - output_balance_before,
+            amount - swap_amount,
+        )
+    };
+    let liquidity = liquidity_math::get_liquidity_from_amounts(
+        accounts.pool_state.load()?.sqrt_price_x64,
+        sqrt_price_lower_x64,
+        sqrt_price_upper_x64,
+        amount_0_max - get_transfer_fee(accounts.vault_0_mint.clone(), amount_0_max)?,
+        amount_1_max - get_transfer_fee(accounts.vault_1_mint.clone(), amount_1_max)?,
+    );
+    require_gte!(liquidity, liquidity_min, ErrorCode::PriceSlippageCheck);
+
+    increase_liquidity(
+        &accounts.nft_owner,
+        &accounts.pool_state,
+        &accounts.amm_config,
+        &mut accounts.protocol_position,
+        &mut accounts.personal_position,
+        &accounts.tick_array_lower,
+        &accounts.tick_array_upper,
+        &accounts.token_account_0.to_account_info(),
+        &accounts.token_account_1.to_account_info(),
+        &accounts.token_vault_0.to_account_info(),
+        &accounts.token_vault_1.to_account_info(),
+        &accounts.token_program,
+        Some(&accounts.token_program_2022),
+        Some(accounts.vault_0_mint.clone()),
+        Some(accounts.vault_1_mint.clone()),
+        ctx.remaining_accounts,
+        liquidity,
+        amount_0_max,
+        amount_1_max,
+        None,
+    )
+}
+
+/// The part of `amount` to swap so the rest and the swap output are in the ratio of the range
+/// at the current price, after the trade fee. The price impact of the swap is not accounted for
+pub fn get_swap_amount_for_range(
+    amount: u64,
+    is_token_0: bool,
+    sqrt_price_x64: u128,
+    sqrt_price_lower_x64: u128,
+    sqrt_price_upper_x64: u128,
+    trade_fee_rate: u32,
+) -> u64 {
+    let q64 = U256::from(fixed_point_64::Q64);
+    let sqrt_price = U256::from(sqrt_price_x64.clamp(sqrt_price_lower_x64, sqrt_price_upper_x64));
+    // the amounts of one unit of liquidity, as Q64.64
+    let amount_0_x64 = (q64 * q64)
+        .mul_div_floor(
+            U256::from(sqrt_price_upper_x64) - sqrt_price,
+            U256::from(sqrt_price_upper_x64),
+        )
+        .unwrap()
+        / sqrt_price;
+    let amount_1_x64 = sqrt_price - U256::from(sqrt_price_lower_x64);
+    // the value of amount_0 in token_1
+    let value_0_x64 = amount_0_x64
+        .mul_div_floor(sqrt_price, q64)
+        .unwrap()
+        .mul_div_floor(sqrt_price, q64)
+        .unwrap();
+    let fee_denominator = U256::from(FEE_RATE_DENOMINATOR_VALUE);
+    let after_fee = fee_denominator - U256::from(trade_fee_rate);
+
+    let (numerator, denominator) = if is_token_0 {
+        // swapping x token_0 returns x * price * (1 - fee) token_1
+        (
+            amount_1_x64,
+            amount_1_x64
+                + value_0_x64
+                    .mul_div_floor(after_fee, fee_denominator)
+                    .unwrap(),
+        )
+    } else {
+        // swapping x token_1 returns x / price * (1 - fee) token_0
+        (
+            value_0_x64,
+            value_0_x64
+                + amount_1_x64
+                    .mul_div_floor(after_fee, fee_denominator)
+                    .unwrap(),
+        )
+    };
+    if denominator.is_zero() {
+        return 0;
+    }
+    U256::from(amount)
+        .mul_div_floor(numerator, denominator)
+        .unwrap()
+        .as_u64()
+}
+
+#[cfg(test)]
+mod swap_and_increase_liquidity_test {
+    use super::*;
+
+    #[test]
+    fn get_swap_amount_for_range_test() {
+        let sqrt_price_x64 = tick_math::get_sqrt_price_at_tick(0).unwrap();
+        let lower = tick_math::get_sqrt_price_at_tick(-1000).unwrap();
+        let upper = tick_math::get_sqrt_price_at_tick(1000).unwrap();
+
+        // a range centered on the price takes both tokens in equal value
+        let swap_amount =
+            get_swap_amount_for_range(1_000_000, true, sqrt_price_x64, lower, upper, 0);
+        assert!(swap_amount.abs_diff(500_000) <= 1);
+        let swap_amount =
+            get_swap_amount_for_range(1_000_000, false, sqrt_price_x64, lower, upper, 0);
+        assert!(swap_amount.abs_diff(500_000) <= 1);
+
+        // the fee reduces the output, so a bit more is swapped
+        let swap_amount =
+            get_swap_amount_for_range(1_000_000, true, sqrt_price_x64, lower, upper, 10_000);
+        assert!(swap_amount > 500_000 && swap_amount < 505_000);
+
+        // below the range only token_0 is deposited
+        let below = tick_math::get_sqrt_price_at_tick(-2000).unwrap();
+        assert_eq!(
+            get_swap_amount_for_range(1_000_000, true, below, lower, upper, 0),
+            0
+        );
+        assert_eq!(
+            get_swap_amount_for_range(1_000_000, false, below, lower, upper, 0),
+            1_000_000
+        );
+        // above the range only token_1 is deposited
+        let above = tick_math::get_sqrt_price_at_tick(2000).unwrap();
+        assert_eq!(
+            get_swap_amount_for_range(1_000_000, true, above, lower, upper, 0),
+            1_000_000
+        );
+        assert_eq!(
+            get_swap_amount_for_range(1_000_000, false, above, lower, upper, 0),
+            0
+        );
+    }
+}
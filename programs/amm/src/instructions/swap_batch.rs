@@ -0,0 +1,161 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::swap_v2::{swap_v2_internal, SwapSingleV2};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token::Token,
+    token_interface::{Mint, Token2022, TokenAccount},
+};
+
+/// The most swaps a batch can execute
+pub const MAX_BATCH_SWAPS: usize = 8;
+/// The accounts every batched swap starts with: amm_config, pool_state, input_token_account,
+/// output_token_account, input_vault, output_vault, input_vault_mint, output_vault_mint and
+/// observation_state
+pub const BATCH_SWAP_FIXED_ACCOUNTS: usize = 9;
+
+/// One swap of a batch, the swaps' accounts follow each other in remaining accounts
+#[derive(Copy, Clone, AnchorSerialize, AnchorDeserialize, Debug, PartialEq)]
+pub struct BatchSwap {
+    /// Arranged in pairs with other_amount_threshold. (amount_in, amount_out_minimum) or
+    /// (amount_out, amount_in_maximum)
+    pub amount: u64,
+    /// For slippage check
+    pub other_amount_threshold: u64,
+    /// The Q64.64 sqrt price limit, 0 for none
+    pub sqrt_price_limit_x64: u128,
+    /// swap base input or swap base output
+    pub is_base_input: bool,
+    /// The number of accounts after the fixed ones: the tick array bitmap extension if needed
+    /// and the tick arrays
+    pub accounts_len: u8,
+}
+
+impl BatchSwap {
+    /// Checks the batch is not empty and its swaps cover exactly `remaining_accounts_len`
+    pub fn check_swaps(swaps: &[BatchSwap], remaining_accounts_len: usize) -> Result<()> {
+        require!(
+            !swaps.is_empty() && swaps.len() <= MAX_BATCH_SWAPS,
+            ErrorCode::InvalidBatchSwaps
+        );
+        let accounts_len: usize = swaps
+            .iter()
+            .map(|swap| BATCH_SWAP_FIXED_ACCOUNTS + usize::from(swap.accounts_len))
+            .sum();
+        require_eq!(
+            accounts_len,
+            remaining_accounts_len,
+            ErrorCode::InvalidBatchSwaps
+        );
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct SwapBatch<'info> {
+    /// The user performing the swaps, owns every input token account
+    pub payer: Signer<'info>,
+
+    /// SPL program for token transfers
+    pub token_program: Program<'info, Token>,
+    /// SPL program 2022 for token transfers
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// CHECK:
+    #[account(
+        address = spl_memo::id()
+    )]
+    pub memo_program: UncheckedAccount<'info>,
+}
+
+/// Executes independent single-pool swaps in order, any swap missing its threshold fails the
+/// whole batch
+pub fn swap_batch<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SwapBatch<'info>>,
+    swaps: Vec<BatchSwap>,
+) -> Result<()> {
+    BatchSwap::check_swaps(&swaps, ctx.remaining_accounts.len())?;
+
+    let mut accounts: &'c [AccountInfo<'info>] = ctx.remaining_accounts;
+    for swap in swaps {
+        let (swap_accounts, rest) =
+            accounts.split_at(BATCH_SWAP_FIXED_ACCOUNTS + usize::from(swap.accounts_len));
+        accounts = rest;
+        let amm_config = Box::new(Account::<AmmConfig>::try_from(&swap_accounts[0])?);
+        let pool_state = AccountLoader::<PoolState>::try_from(&swap_accounts[1])?;
+        let observation_state = AccountLoader::<ObservationState>::try_from(&swap_accounts[8])?;
+        {
+            let pool = pool_state.load()?;
+            require_keys_eq!(pool.amm_config, amm_config.key());
+            require_keys_eq!(pool.observation_key, observation_state.key());
+        }
+        // each swap deserializes its token accounts afresh, so a token account shared with an
+        // earlier swap of the batch is read with its updated balance
+        let mut swap_single = SwapSingleV2 {
+            payer: ctx.accounts.payer.clone(),
+            amm_config,
+            pool_state,
+            input_token_account: Box::new(InterfaceAccount::<TokenAccount>::try_from(
+                &swap_accounts[2],
+            )?),
+            output_token_account: Box::new(InterfaceAccount::<TokenAccount>::try_from(
+                &swap_accounts[3],
+            )?),
+            input_vault: Box::new(InterfaceAccount::<TokenAccount>::try_from(
+                &swap_accounts[4],
+            )?),
+            output_vault: Box::new(InterfaceAccount::<TokenAccount>::try_from(
+                &swap_accounts[5],
+            )?),
+            input_vault_mint: Box::new(InterfaceAccount::<Mint>::try_from(&swap_accounts[6])?),
+            output_vault_mint: Box::new(InterfaceAccount::<Mint>::try_from(&swap_accounts[7])?),
+            observation_state,
+            token_program: ctx.accounts.token_program.clone(),
+            token_program_2022: ctx.accounts.token_program_2022.clone(),
+            memo_program: ctx.accounts.memo_program.clone(),
+        };
+        require_keys_eq!(
+            swap_single.input_vault_mint.key(),
+            swap_single.input_vault.mint
+        );
+        require_keys_eq!(
+            swap_single.output_vault_mint.key(),
+            swap_single.output_vault.mint
+        );
+        swap_v2_internal(
+            &mut swap_single,
+            &swap_accounts[BATCH_SWAP_FIXED_ACCOUNTS..],
+            swap.amount,
+            swap.other_amount_threshold,
+            swap.sqrt_price_limit_x64,
+            swap.is_base_input,
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod swap_batch_test {
+    use super::*;
+
+    #[test]
+    fn check_swaps_test() {
+        let swap = BatchSwap {
+            amount: 100,
+            other_amount_threshold: 90,
+            sqrt_price_limit_x64: 0,
+            is_base_input: true,
+            accounts_len: 2,
+        };
+        BatchSwap::check_swaps(&[swap, swap], 2 * BATCH_SWAP_FIXED_ACCOUNTS + 4).unwrap();
+        // accounts not covered by the swaps
+        assert!(BatchSwap::check_swaps(&[swap], BATCH_SWAP_FIXED_ACCOUNTS + 3).is_err());
+        assert!(BatchSwap::check_swaps(&[swap], BATCH_SWAP_FIXED_ACCOUNTS + 1).is_err());
+        assert!(BatchSwap::check_swaps(&[], 0).is_err());
+        assert!(BatchSwap::check_swaps(
+            &[swap; MAX_BATCH_SWAPS + 1],
+            (MAX_BATCH_SWAPS + 1) * (BATCH_SWAP_FIXED_ACCOUNTS + 2)
+        )
+        .is_err());
+    }
+}
@@ -1,12 +1,76 @@
 use crate::error::ErrorCode;
 use crate::states::*;
-use crate::swap_v2::{exact_internal_v2, SwapSingleV2};
+use crate::swap::SwapSimulation;
+use crate::swap_v2::{exact_internal_v2_with_referral, SwapSingleV2};
+use crate::util::{check_max_slot, get_transfer_fee};
 use anchor_lang::prelude::*;
 use anchor_spl::{
     token::Token,
     token_interface::{Mint, Token2022, TokenAccount},
 };
 
+/// The accounts every clmm hop starts with: amm_config, pool_state, output_token_account,
+/// input_vault, output_vault, output_token_mint and observation_state
+pub const ROUTE_HOP_FIXED_ACCOUNTS: usize = 7;
+/// A hop through a clmm pool, the only hop type supported so far
+pub const ROUTE_HOP_TYPE_CLMM: u8 = 0;
+/// The hop is referred, its first account after the fixed ones is the pool's `ReferralAccount`
+pub const ROUTE_HOP_FLAG_REFERRAL: u8 = 0x80;
+
+/// Describes one hop of a route, the hops' accounts follow each other in remaining accounts
+#[derive(Copy, Clone, AnchorSerialize, AnchorDeserialize, Debug, PartialEq)]
+pub struct RouteHop {
+    /// The number of accounts after the fixed ones: the referral account if the hop is referred,
+    /// the tick array bitmap extension if needed, the tick arrays and the swap hook program with
+    /// its accounts if the pool has a hook
+    pub accounts_len: u8,
+    /// The hop type in the low bits, see `ROUTE_HOP_TYPE_CLMM`, and `ROUTE_HOP_FLAG_REFERRAL`
+    pub flags: u8,
+    /// The minimum output of the hop
+    pub min_out: u64,
+}
+
+impl RouteHop {
+    /// Checks the route is made of supported hops covering exactly `remaining_accounts_len`
+    pub fn check_route(route: &[RouteHop], remaining_accounts_len: usize) -> Result<()> {
+        require!(!route.is_empty(), ErrorCode::InvalidRouteHop);
+        let mut accounts_len = 0;
+        for hop in route {
+            require_eq!(
+                hop.flags & !ROUTE_HOP_FLAG_REFERRAL,
+                ROUTE_HOP_TYPE_CLMM,
+                ErrorCode::InvalidRouteHop
+            );
+            require!(
+                !hop.is_referred() || hop.accounts_len > 0,
+                ErrorCode::InvalidRouteHop
+            );
+            accounts_len += ROUTE_HOP_FIXED_ACCOUNTS + usize::from(hop.accounts_len);
+        }
+        require_eq!(
+            accounts_len,
+            remaining_accounts_len,
+            ErrorCode::InvalidRouteHop
+        );
+        Ok(())
+    }
+
+    pub fn is_referred(&self) -> bool {
+        self.flags & ROUTE_HOP_FLAG_REFERRAL != 0
+    }
+}
+
+/// The amounts of a routed swap, written to return data by the dry run router instructions
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct RouteResult {
+    /// The amount paid by the user into the first hop, including the token transfer fee
+    pub amount_in: u64,
+    /// The amount received by the user from the last hop, excluding the token transfer fee
+    pub amount_out: u64,
+    /// The amount received from each hop in route order, excluding the token transfer fee
+    pub hop_amounts_out: Vec<u64>,
+}
+
 #[derive(Accounts)]
 pub struct SwapRouterBaseIn<'info> {
     /// The user performing the swap
@@ -37,10 +101,118 @@ pub fn swap_router_base_in<'a, 'b, 'c: 'info, 'info>(
     amount_in: u64,
     amount_out_minimum: u64,
 ) -> Result<()> {
+    swap_router_base_in_internal(ctx, amount_in, amount_out_minimum, &[], &[], &[], false)?;
+    Ok(())
+}
+
+/// Same as `swap_router_base_in`, but only quotes the route: every hop is validated and swapped
+/// against copies of its accounts, no token is transferred and no account is written
+pub fn swap_router_base_in_dry_run<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SwapRouterBaseIn<'info>>,
+    amount_in: u64,
+    amount_out_minimum: u64,
+) -> Result<RouteResult> {
+    swap_router_base_in_internal(ctx, amount_in, amount_out_minimum, &[], &[], &[], true)
+}
+
+/// Same as `swap_router_base_in`, additionally checks the output of each hop against
+/// `hop_amount_out_minimums`, which must have one entry per hop
+pub fn swap_router_base_in_with_hop_minimums<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SwapRouterBaseIn<'info>>,
+    amount_in: u64,
+    amount_out_minimum: u64,
+    hop_amount_out_minimums: Vec<u64>,
+) -> Result<()> {
+    require!(
+        !hop_amount_out_minimums.is_empty(),
+        ErrorCode::InvalidHopAmountOutMinimums
+    );
+    swap_router_base_in_internal(
+        ctx,
+        amount_in,
+        amount_out_minimum,
+        &hop_amount_out_minimums,
+        &[],
+        &[],
+        false,
+    )?;
+    Ok(())
+}
+
+/// Same as `swap_router_base_in`, with the route described by `route` instead of being
+/// inferred from the remaining accounts
+pub fn swap_router_base_in_with_route<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SwapRouterBaseIn<'info>>,
+    amount_in: u64,
+    amount_out_minimum: u64,
+    route: Vec<RouteHop>,
+) -> Result<()> {
+    RouteHop::check_route(&route, ctx.remaining_accounts.len())?;
+    let hop_amount_out_minimums: Vec<u64> = route.iter().map(|hop| hop.min_out).collect();
+    swap_router_base_in_internal(
+        ctx,
+        amount_in,
+        amount_out_minimum,
+        &hop_amount_out_minimums,
+        &route,
+        &[],
+        false,
+    )?;
+    Ok(())
+}
+
+/// Same as `swap_router_base_in`, but fails once the current slot is past `max_slot`
+pub fn swap_router_base_in_with_max_slot<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SwapRouterBaseIn<'info>>,
+    amount_in: u64,
+    amount_out_minimum: u64,
+    max_slot: u64,
+) -> Result<()> {
+    check_max_slot(max_slot)?;
+    swap_router_base_in_internal(ctx, amount_in, amount_out_minimum, &[], &[], &[], false)?;
+    Ok(())
+}
+
+/// Same as `swap_router_base_in`, each hop stops at its entry of `hop_sqrt_price_limits_x64`,
+/// which must have one entry per hop. A limit of 0 leaves the hop unbounded
+pub fn swap_router_base_in_with_price_limits<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SwapRouterBaseIn<'info>>,
+    amount_in: u64,
+    amount_out_minimum: u64,
+    hop_sqrt_price_limits_x64: Vec<u128>,
+) -> Result<()> {
+    require!(
+        !hop_sqrt_price_limits_x64.is_empty(),
+        ErrorCode::InvalidHopSqrtPriceLimits
+    );
+    swap_router_base_in_internal(
+        ctx,
+        amount_in,
+        amount_out_minimum,
+        &[],
+        &[],
+        &hop_sqrt_price_limits_x64,
+        false,
+    )?;
+    Ok(())
+}
+
+fn swap_router_base_in_internal<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SwapRouterBaseIn<'info>>,
+    amount_in: u64,
+    amount_out_minimum: u64,
+    hop_amount_out_minimums: &[u64],
+    route: &[RouteHop],
+    hop_sqrt_price_limits_x64: &[u128],
+    dry_run: bool,
+) -> Result<RouteResult> {
     let mut amount_in_internal = amount_in;
+    let mut hop_amounts_out = Vec::new();
+    let mut hop_index = 0;
     let mut input_token_account = Box::new(ctx.accounts.input_token_account.clone());
     let mut input_token_mint = Box::new(ctx.accounts.input_token_mint.clone());
     let mut accounts: &[AccountInfo] = ctx.remaining_accounts;
+    let mut route_pools = Vec::new();
     while !accounts.is_empty() {
         let mut remaining_accounts = accounts.iter();
         let account_info = remaining_accounts.next().unwrap();
@@ -75,39 +247,228 @@ pub fn swap_router_base_in<'a, 'b, 'c: 'info, 'info>(
             // check ammConfig account is associate with the pool
             require_keys_eq!(pool_state.amm_config, amm_config.key());
         }
+        check_route_hop(
+            &mut route_pools,
+            pool_state_loader.key(),
+            input_token_mint.key(),
+            input_vault.mint,
+        )?;
 
         // solana_program::log::sol_log_compute_units();
         accounts = remaining_accounts.as_slice();
-        amount_in_internal = exact_internal_v2(
-            &mut SwapSingleV2 {
-                payer: ctx.accounts.payer.clone(),
-                amm_config,
-                input_token_account: input_token_account.clone(),
-                pool_state: pool_state_loader,
-                output_token_account: output_token_account.clone(),
-                input_vault: input_vault.clone(),
-                output_vault: output_vault.clone(),
-                input_vault_mint: input_token_mint.clone(),
-                output_vault_mint: output_token_mint.clone(),
-                observation_state,
-                token_program: ctx.accounts.token_program.clone(),
-                token_program_2022: ctx.accounts.token_program_2022.clone(),
-                memo_program: ctx.accounts.memo_program.clone(),
-            },
-            accounts,
-            amount_in_internal,
-            0,
-            true,
-        )?;
+        // a hop only sees its own tick arrays and swap hook accounts, without a route they run
+        // until the next hop's amm config
+        let mut referral_account = None;
+        let hop_accounts = match route.get(hop_index) {
+            Some(hop) => {
+                let (mut hop_accounts, rest) = accounts.split_at(usize::from(hop.accounts_len));
+                accounts = rest;
+                if hop.is_referred() {
+                    let account = Box::new(Account::<ReferralAccount>::try_from(&hop_accounts[0])?);
+                    require_keys_eq!(
+                        account.pool_id,
+                        pool_state_loader.key(),
+                        ErrorCode::InvalidReferralAccount
+                    );
+                    referral_account = Some(account);
+                    hop_accounts = &hop_accounts[1..];
+                }
+                hop_accounts
+            }
+            None => {
+                let hop_accounts_len = accounts
+                    .iter()
+                    .position(|account_info| account_info.data_len() == AmmConfig::LEN)
+                    .unwrap_or(accounts.len());
+                let (hop_accounts, rest) = accounts.split_at(hop_accounts_len);
+                accounts = rest;
+                hop_accounts
+            }
+        };
+        let sqrt_price_limit_x64 = if hop_sqrt_price_limits_x64.is_empty() {
+            0
+        } else {
+            *hop_sqrt_price_limits_x64
+                .get(hop_index)
+                .ok_or(ErrorCode::InvalidHopSqrtPriceLimits)?
+        };
+        amount_in_internal = if dry_run {
+            // the quote leaves the pool's swap hook out
+            let (tick_arrays, _) = split_swap_hook_accounts(
+                hop_accounts,
+                pool_state_loader.load()?.swap_hook(&amm_config),
+            )?;
+            quote_hop_amount_out(
+                &amm_config,
+                &pool_state_loader,
+                &observation_state,
+                &input_vault,
+                &input_token_mint,
+                &output_token_mint,
+                tick_arrays,
+                amount_in_internal,
+                sqrt_price_limit_x64,
+            )?
+        } else {
+            exact_internal_v2_with_referral(
+                &mut SwapSingleV2 {
+                    payer: ctx.accounts.payer.clone(),
+                    amm_config,
+                    input_token_account: input_token_account.clone(),
+                    pool_state: pool_state_loader,
+                    output_token_account: output_token_account.clone(),
+                    input_vault: input_vault.clone(),
+                    output_vault: output_vault.clone(),
+                    input_vault_mint: input_token_mint.clone(),
+                    output_vault_mint: output_token_mint.clone(),
+                    observation_state,
+                    token_program: ctx.accounts.token_program.clone(),
+                    token_program_2022: ctx.accounts.token_program_2022.clone(),
+                    memo_program: ctx.accounts.memo_program.clone(),
+                },
+                hop_accounts,
+                amount_in_internal,
+                sqrt_price_limit_x64,
+                true,
+                referral_account
+                    .as_deref_mut()
+                    .map(|account| &mut **account),
+            )?
+        };
+        // the referral account is loaded from remaining accounts, persist its credit by hand
+        if let (false, Some(referral_account)) = (dry_run, &referral_account) {
+            referral_account.exit(&crate::id())?;
+        }
+        hop_amounts_out.push(amount_in_internal);
+        if !hop_amount_out_minimums.is_empty() {
+            let hop_amount_out_minimum = *hop_amount_out_minimums
+                .get(hop_index)
+                .ok_or(ErrorCode::InvalidHopAmountOutMinimums)?;
+            require_gte!(
+                amount_in_internal,
+                hop_amount_out_minimum,
+                ErrorCode::TooLittleOutputReceived
+            );
+        }
+        hop_index += 1;
         // output token is the new swap input token
         input_token_account = output_token_account;
         input_token_mint = output_token_mint;
     }
+    require!(
+        hop_amount_out_minimums.is_empty() || hop_amount_out_minimums.len() == hop_index,
+        ErrorCode::InvalidHopAmountOutMinimums
+    );
+    require!(
+        hop_sqrt_price_limits_x64.is_empty() || hop_sqrt_price_limits_x64.len() == hop_index,
+        ErrorCode::InvalidHopSqrtPriceLimits
+    );
     require_gte!(
         amount_in_internal,
         amount_out_minimum,
         ErrorCode::TooLittleOutputReceived
     );
 
+    Ok(RouteResult {
+        amount_in,
+        amount_out: amount_in_internal,
+        hop_amounts_out,
+    })
+}
+
+/// The amount a base input hop pays into the user's output token account, net of both token
+/// transfer fees, swapped against copies of the hop's accounts
+fn quote_hop_amount_out<'info>(
+    amm_config: &AmmConfig,
+    pool_state_loader: &AccountLoader<'info, PoolState>,
+    observation_state: &AccountLoader<'info, ObservationState>,
+    input_vault: &InterfaceAccount<'info, TokenAccount>,
+    input_token_mint: &InterfaceAccount<'info, Mint>,
+    output_token_mint: &InterfaceAccount<'info, Mint>,
+    tick_arrays: &'info [AccountInfo<'info>],
+    amount_in: u64,
+    sqrt_price_limit_x64: u128,
+) -> Result<u64> {
+    let zero_for_one = input_vault.mint == pool_state_loader.load()?.token_mint_0;
+    let amount_in_net =
+        amount_in - get_transfer_fee(Box::new(input_token_mint.clone()), amount_in)?;
+    let simulation = SwapSimulation::new(
+        &pool_state_loader.to_account_info(),
+        &observation_state.to_account_info(),
+        tick_arrays,
+    )?;
+    let (amount_0, amount_1) = simulation.swap(
+        amm_config,
+        amount_in_net,
+        sqrt_price_limit_x64,
+        zero_for_one,
+        true,
+    )?;
+    require!(
+        amount_0 != 0 && amount_1 != 0,
+        ErrorCode::TooSmallInputOrOutputAmount
+    );
+    let amount_out = if zero_for_one { amount_1 } else { amount_0 };
+    Ok(amount_out - get_transfer_fee(Box::new(output_token_mint.clone()), amount_out)?)
+}
+
+/// Checks a pool is used once per route, and the hop's input is the previous hop's output
+pub(crate) fn check_route_hop(
+    route_pools: &mut Vec<Pubkey>,
+    pool_id: Pubkey,
+    input_token_mint: Pubkey,
+    input_vault_mint: Pubkey,
+) -> Result<()> {
+    require!(
+        !route_pools.contains(&pool_id),
+        ErrorCode::DuplicatePoolInRoute
+    );
+    require_keys_eq!(
+        input_vault_mint,
+        input_token_mint,
+        ErrorCode::RouteMintMismatch
+    );
+    route_pools.push(pool_id);
     Ok(())
 }
+
+#[cfg(test)]
+mod swap_router_base_in_test {
+    use super::*;
+
+    #[test]
+    fn check_route_test() {
+        let hop = |accounts_len, flags| RouteHop {
+            accounts_len,
+            flags,
+            min_out: 0,
+        };
+        RouteHop::check_route(&[hop(3, 0)], 10).unwrap();
+        RouteHop::check_route(&[hop(3, 0), hop(1, 0)], 18).unwrap();
+        assert!(RouteHop::check_route(&[], 0).is_err());
+        // accounts don't match the route
+        assert!(RouteHop::check_route(&[hop(3, 0)], 11).is_err());
+        // unsupported hop type
+        assert!(RouteHop::check_route(&[hop(3, 1)], 10).is_err());
+        // a referred hop carries its referral account
+        RouteHop::check_route(&[hop(3, ROUTE_HOP_FLAG_REFERRAL)], 10).unwrap();
+        assert!(RouteHop::check_route(&[hop(0, ROUTE_HOP_FLAG_REFERRAL)], 7).is_err());
+        assert!(RouteHop::check_route(&[hop(3, ROUTE_HOP_FLAG_REFERRAL | 1)], 10).is_err());
+    }
+
+    #[test]
+    fn check_route_hop_test() {
+        let (mint_a, mint_b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (pool_ab, pool_bc) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let route_pools = &mut Vec::new();
+        check_route_hop(route_pools, pool_ab, mint_a, mint_a).unwrap();
+        check_route_hop(route_pools, pool_bc, mint_b, mint_b).unwrap();
+        // pool already in the route
+        assert_eq!(
+            check_route_hop(route_pools, pool_ab, mint_a, mint_a).unwrap_err(),
+            ErrorCode::DuplicatePoolInRoute.into()
+        );
+        // the hop doesn't take the previous output
+        assert!(check_route_hop(route_pools, Pubkey::new_unique(), mint_b, mint_a).is_err());
+    }
+}
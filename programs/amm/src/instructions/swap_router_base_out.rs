@@ -0,0 +1,219 @@
+use super::swap_router_base_in::{check_route_hop, RouteResult, ROUTE_HOP_FIXED_ACCOUNTS};
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::swap::SwapSimulation;
+use crate::swap_v2::{exact_internal_v2, SwapSingleV2};
+use crate::util::get_transfer_inverse_fee;
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token::Token,
+    token_interface::{Mint, Token2022, TokenAccount},
+};
+
+#[derive(Accounts)]
+pub struct SwapRouterBaseOut<'info> {
+    /// The user performing the swap
+    pub payer: Signer<'info>,
+
+    /// The token account that pays input tokens for the swap
+    #[account(mut)]
+    pub input_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The mint of input token
+    #[account(mut)]
+    pub input_token_mint: InterfaceAccount<'info, Mint>,
+
+    /// SPL program for token transfers
+    pub token_program: Program<'info, Token>,
+    /// SPL program 2022 for token transfers
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// CHECK:
+    pub memo_program: UncheckedAccount<'info>,
+    // remaining accounts
+    // the hops in path order, laid out as for `swap_router_base_in`
+}
+
+/// The accounts of one hop, see `ROUTE_HOP_FIXED_ACCOUNTS`
+struct RouteHopAccounts<'info> {
+    amm_config: Box<Account<'info, AmmConfig>>,
+    pool_state: AccountLoader<'info, PoolState>,
+    output_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    input_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    output_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    output_token_mint: Box<InterfaceAccount<'info, Mint>>,
+    observation_state: AccountLoader<'info, ObservationState>,
+    /// The tick arrays and the tick array bitmap extension if needed
+    tick_arrays: &'info [AccountInfo<'info>],
+}
+
+/// Swaps along the path for exactly `amount_out` of the last output token. The input each hop
+/// needs is worked out from the last hop back to the first against copies of the pools, then
+/// the hops are executed in path order
+pub fn swap_router_base_out<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SwapRouterBaseOut<'info>>,
+    amount_out: u64,
+    amount_in_maximum: u64,
+) -> Result<()> {
+    swap_router_base_out_internal(ctx, amount_out, amount_in_maximum, false)?;
+    Ok(())
+}
+
+/// Same as `swap_router_base_out`, but stops after quoting the route
+pub fn swap_router_base_out_dry_run<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SwapRouterBaseOut<'info>>,
+    amount_out: u64,
+    amount_in_maximum: u64,
+) -> Result<RouteResult> {
+    swap_router_base_out_internal(ctx, amount_out, amount_in_maximum, true)
+}
+
+fn swap_router_base_out_internal<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SwapRouterBaseOut<'info>>,
+    amount_out: u64,
+    amount_in_maximum: u64,
+    dry_run: bool,
+) -> Result<RouteResult> {
+    let input_token_mint = Box::new(ctx.accounts.input_token_mint.clone());
+    let hops = parse_route_hops(ctx.remaining_accounts, input_token_mint.key())?;
+
+    // the output of each hop is the input the next one needs
+    let mut hop_amounts_out = vec![0; hops.len()];
+    let mut amount = amount_out;
+    for (hop_index, hop) in hops.iter().enumerate().rev() {
+        hop_amounts_out[hop_index] = amount;
+        let hop_input_mint = match hop_index {
+            0 => &input_token_mint,
+            _ => &hops[hop_index - 1].output_token_mint,
+        };
+        amount = quote_hop_amount_in(hop, hop_input_mint, amount)?;
+    }
+    require_gte!(amount_in_maximum, amount, ErrorCode::TooMuchInputPaid);
+    if dry_run {
+        return Ok(RouteResult {
+            amount_in: amount,
+            amount_out,
+            hop_amounts_out,
+        });
+    }
+
+    let mut input_token_account = Box::new(ctx.accounts.input_token_account.clone());
+    let mut input_token_mint = input_token_mint;
+    let mut amount_in = 0;
+    for (hop_index, hop) in hops.into_iter().enumerate() {
+        let hop_amount_in = exact_internal_v2(
+            &mut SwapSingleV2 {
+                payer: ctx.accounts.payer.clone(),
+                amm_config: hop.amm_config,
+                input_token_account: input_token_account.clone(),
+                pool_state: hop.pool_state,
+                output_token_account: hop.output_token_account.clone(),
+                input_vault: hop.input_vault,
+                output_vault: hop.output_vault,
+                input_vault_mint: input_token_mint.clone(),
+                output_vault_mint: hop.output_token_mint.clone(),
+                observation_state: hop.observation_state,
+                token_program: ctx.accounts.token_program.clone(),
+                token_program_2022: ctx.accounts.token_program_2022.clone(),
+                memo_program: ctx.accounts.memo_program.clone(),
+            },
+            hop.tick_arrays,
+            hop_amounts_out[hop_index],
+            0,
+            false,
+        )?;
+        if hop_index == 0 {
+            amount_in = hop_amount_in;
+        }
+        // output token is the new swap input token
+        input_token_account = hop.output_token_account;
+        input_token_mint = hop.output_token_mint;
+    }
+    require_gte!(amount_in_maximum, amount_in, ErrorCode::TooMuchInputPaid);
+
+    Ok(RouteResult {
+        amount_in,
+        amount_out,
+        hop_amounts_out,
+    })
+}
+
+/// Splits the remaining accounts into hops, a hop's tick arrays run until the next amm config
+fn parse_route_hops<'info>(
+    remaining_accounts: &'info [AccountInfo<'info>],
+    input_token_mint: Pubkey,
+) -> Result<Vec<RouteHopAccounts<'info>>> {
+    let mut hops = Vec::new();
+    let mut route_pools = Vec::new();
+    let mut hop_input_mint = input_token_mint;
+    let mut accounts = remaining_accounts;
+    while !accounts.is_empty() {
+        require_gte!(
+            accounts.len(),
+            ROUTE_HOP_FIXED_ACCOUNTS,
+            ErrorCode::InvalidRouteHop
+        );
+        let (fixed_accounts, rest) = accounts.split_at(ROUTE_HOP_FIXED_ACCOUNTS);
+        let tick_arrays_len = rest
+            .iter()
+            .position(|account_info| account_info.data_len() == AmmConfig::LEN)
+            .unwrap_or(rest.len());
+        let (tick_arrays, rest) = rest.split_at(tick_arrays_len);
+        accounts = rest;
+
+        let hop = RouteHopAccounts {
+            amm_config: Box::new(Account::<AmmConfig>::try_from(&fixed_accounts[0])?),
+            pool_state: AccountLoader::<PoolState>::try_from(&fixed_accounts[1])?,
+            output_token_account: Box::new(InterfaceAccount::<TokenAccount>::try_from(
+                &fixed_accounts[2],
+            )?),
+            input_vault: Box::new(InterfaceAccount::<TokenAccount>::try_from(
+                &fixed_accounts[3],
+            )?),
+            output_vault: Box::new(InterfaceAccount::<TokenAccount>::try_from(
+                &fixed_accounts[4],
+            )?),
+            output_token_mint: Box::new(InterfaceAccount::<Mint>::try_from(&fixed_accounts[5])?),
+            observation_state: AccountLoader::<ObservationState>::try_from(&fixed_accounts[6])?,
+            tick_arrays,
+        };
+        {
+            let pool_state = hop.pool_state.load()?;
+            // check observation account is owned by the pool
+            require_keys_eq!(pool_state.observation_key, hop.observation_state.key());
+            // check ammConfig account is associate with the pool
+            require_keys_eq!(pool_state.amm_config, hop.amm_config.key());
+        }
+        check_route_hop(
+            &mut route_pools,
+            hop.pool_state.key(),
+            hop_input_mint,
+            hop.input_vault.mint,
+        )?;
+        hop_input_mint = hop.output_token_mint.key();
+        hops.push(hop);
+    }
+    require!(!hops.is_empty(), ErrorCode::InvalidRouteHop);
+    Ok(hops)
+}
+
+/// The amount the hop pulls from the user's input token account, transfer fee included, to pay
+/// out exactly `amount_out` net of the output transfer fee
+fn quote_hop_amount_in(
+    hop: &RouteHopAccounts,
+    input_token_mint: &InterfaceAccount<Mint>,
+    amount_out: u64,
+) -> Result<u64> {
+    let zero_for_one = hop.input_vault.mint == hop.pool_state.load()?.token_mint_0;
+    let amount_out_with_fee =
+        amount_out + get_transfer_inverse_fee(hop.output_token_mint.clone(), amount_out)?;
+    let simulation = SwapSimulation::new(
+        &hop.pool_state.to_account_info(),
+        &hop.observation_state.to_account_info(),
+        hop.tick_arrays,
+    )?;
+    let (amount_0, amount_1) =
+        simulation.swap(&hop.amm_config, amount_out_with_fee, 0, zero_for_one, false)?;
+    let amount_in = if zero_for_one { amount_0 } else { amount_1 };
+    Ok(amount_in + get_transfer_inverse_fee(Box::new(input_token_mint.clone()), amount_in)?)
+}
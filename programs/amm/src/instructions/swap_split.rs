@@ -0,0 +1,216 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::swap_v2::{exact_internal_v2, SwapSingleV2};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token::Token,
+    token_interface::{Mint, Token2022, TokenAccount},
+};
+
+/// The most pools a split swap can spread its input over
+pub const MAX_SPLIT_LEGS: usize = 4;
+/// The accounts every leg starts with: amm_config, pool_state, input_vault, output_vault and
+/// observation_state
+pub const SPLIT_LEG_FIXED_ACCOUNTS: usize = 5;
+/// The shares of a split swap's legs add up to this
+pub const SPLIT_SHARE_DENOMINATOR: u16 = 10_000;
+
+/// One leg of a split swap, the legs' accounts follow each other in remaining accounts
+#[derive(Copy, Clone, AnchorSerialize, AnchorDeserialize, Debug, PartialEq)]
+pub struct SplitLeg {
+    /// The share of the input swapped through this leg's pool, in basis points
+    pub share_bps: u16,
+    /// The number of accounts after the fixed ones: the tick array bitmap extension if needed
+    /// and the tick arrays
+    pub accounts_len: u8,
+}
+
+impl SplitLeg {
+    /// Checks the legs split the whole input and cover exactly `remaining_accounts_len`
+    pub fn check_legs(legs: &[SplitLeg], remaining_accounts_len: usize) -> Result<()> {
+        require!(
+            !legs.is_empty() && legs.len() <= MAX_SPLIT_LEGS,
+            ErrorCode::InvalidSplitLegs
+        );
+        require!(
+            legs.iter().all(|leg| leg.share_bps > 0)
+                && legs.iter().map(|leg| u32::from(leg.share_bps)).sum::<u32>()
+                    == u32::from(SPLIT_SHARE_DENOMINATOR),
+            ErrorCode::InvalidSplitLegs
+        );
+        let accounts_len: usize = legs
+            .iter()
+            .map(|leg| SPLIT_LEG_FIXED_ACCOUNTS + usize::from(leg.accounts_len))
+            .sum();
+        require_eq!(
+            accounts_len,
+            remaining_accounts_len,
+            ErrorCode::InvalidSplitLegs
+        );
+        Ok(())
+    }
+
+    /// Splits `amount_in` by the legs' shares, the last leg takes the rounding remainder
+    pub fn split_amount(legs: &[SplitLeg], amount_in: u64) -> Vec<u64> {
+        let mut amounts: Vec<u64> = legs
+            .iter()
+            .map(|leg| {
+                (u128::from(amount_in) * u128::from(leg.share_bps)
+                    / u128::from(SPLIT_SHARE_DENOMINATOR)) as u64
+            })
+            .collect();
+        let split: u64 = amounts.iter().sum();
+        if let Some(last) = amounts.last_mut() {
+            *last += amount_in - split;
+        }
+        amounts
+    }
+}
+
+#[derive(Accounts)]
+pub struct SwapSplit<'info> {
+    /// The user performing the swap
+    pub payer: Signer<'info>,
+
+    /// The token account that pays input tokens for the swap
+    #[account(mut)]
+    pub input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The token account that receives output tokens from every leg
+    #[account(mut)]
+    pub output_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of input token
+    #[account(address = input_token_account.mint)]
+    pub input_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of output token
+    #[account(address = output_token_account.mint)]
+    pub output_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// SPL program for token transfers
+    pub token_program: Program<'info, Token>,
+    /// SPL program 2022 for token transfers
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// CHECK:
+    #[account(
+        address = spl_memo::id()
+    )]
+    pub memo_program: UncheckedAccount<'info>,
+}
+
+/// Swap `amount_in` through several pools of the same pair, each leg getting its share of the
+/// input, base input. Only the total output is checked against `amount_out_minimum`
+pub fn swap_split<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SwapSplit<'info>>,
+    amount_in: u64,
+    amount_out_minimum: u64,
+    legs: Vec<SplitLeg>,
+) -> Result<()> {
+    SplitLeg::check_legs(&legs, ctx.remaining_accounts.len())?;
+    let input_token_mint = ctx.accounts.input_token_mint.key();
+    let output_token_mint = ctx.accounts.output_token_mint.key();
+    require_keys_neq!(input_token_mint, output_token_mint);
+
+    let mut accounts: &'c [AccountInfo<'info>] = ctx.remaining_accounts;
+    let mut pools = Vec::with_capacity(legs.len());
+    let mut amount_out: u64 = 0;
+    for (leg, leg_amount_in) in legs.iter().zip(SplitLeg::split_amount(&legs, amount_in)) {
+        let (leg_accounts, rest) =
+            accounts.split_at(SPLIT_LEG_FIXED_ACCOUNTS + usize::from(leg.accounts_len));
+        accounts = rest;
+        let amm_config = Box::new(Account::<AmmConfig>::try_from(&leg_accounts[0])?);
+        let pool_state = AccountLoader::<PoolState>::try_from(&leg_accounts[1])?;
+        let input_vault = Box::new(InterfaceAccount::<TokenAccount>::try_from(
+            &leg_accounts[2],
+        )?);
+        let output_vault = Box::new(InterfaceAccount::<TokenAccount>::try_from(
+            &leg_accounts[3],
+        )?);
+        let observation_state = AccountLoader::<ObservationState>::try_from(&leg_accounts[4])?;
+        {
+            let pool = pool_state.load()?;
+            require_keys_eq!(pool.amm_config, amm_config.key());
+            require_keys_eq!(pool.observation_key, observation_state.key());
+        }
+        // every leg trades the same pair through a different pool, the vaults themselves are
+        // checked against the pool by the swap
+        require!(
+            !pools.contains(&pool_state.key()),
+            ErrorCode::DuplicatePoolInRoute
+        );
+        pools.push(pool_state.key());
+        require_keys_eq!(input_vault.mint, input_token_mint);
+        require_keys_eq!(output_vault.mint, output_token_mint);
+
+        let leg_amount_out = exact_internal_v2(
+            &mut SwapSingleV2 {
+                payer: ctx.accounts.payer.clone(),
+                amm_config,
+                input_token_account: ctx.accounts.input_token_account.clone(),
+                pool_state,
+                output_token_account: ctx.accounts.output_token_account.clone(),
+                input_vault,
+                output_vault,
+                input_vault_mint: ctx.accounts.input_token_mint.clone(),
+                output_vault_mint: ctx.accounts.output_token_mint.clone(),
+                observation_state,
+                token_program: ctx.accounts.token_program.clone(),
+                token_program_2022: ctx.accounts.token_program_2022.clone(),
+                memo_program: ctx.accounts.memo_program.clone(),
+            },
+            &leg_accounts[SPLIT_LEG_FIXED_ACCOUNTS..],
+            leg_amount_in,
+            0,
+            true,
+        )?;
+        amount_out = amount_out.checked_add(leg_amount_out).unwrap();
+        // the token accounts are cloned into each leg, reload the balances the next leg checks
+        ctx.accounts.input_token_account.reload()?;
+        ctx.accounts.output_token_account.reload()?;
+    }
+    require_gte!(
+        amount_out,
+        amount_out_minimum,
+        ErrorCode::TooLittleOutputReceived
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod swap_split_test {
+    use super::*;
+
+    #[test]
+    fn split_legs_test() {
+        let legs = [
+            SplitLeg {
+                share_bps: 3_333,
+                accounts_len: 1,
+            },
+            SplitLeg {
+                share_bps: 6_667,
+                accounts_len: 3,
+            },
+        ];
+        SplitLeg::check_legs(&legs, 2 * SPLIT_LEG_FIXED_ACCOUNTS + 4).unwrap();
+        // the last leg takes the remainder
+        assert_eq!(SplitLeg::split_amount(&legs, 100), vec![33, 67]);
+        assert_eq!(SplitLeg::split_amount(&legs, 1), vec![0, 1]);
+        assert_eq!(
+            SplitLeg::split_amount(&legs, u64::MAX).iter().sum::<u64>(),
+            u64::MAX
+        );
+
+        // accounts not covered by the legs
+        assert!(SplitLeg::check_legs(&legs, 2 * SPLIT_LEG_FIXED_ACCOUNTS + 5).is_err());
+        // the shares do not add up to the whole input
+        let partial = [SplitLeg {
+            share_bps: 5_000,
+            accounts_len: 1,
+        }];
+        assert!(SplitLeg::check_legs(&partial, SPLIT_LEG_FIXED_ACCOUNTS + 1).is_err());
+        assert!(SplitLeg::check_legs(&[], 0).is_err());
+    }
+}
@@ -3,7 +3,7 @@ use std::ops::Deref;
 
 use crate::error::ErrorCode;
 use crate::libraries::tick_math;
-use crate::swap::swap_internal;
+use crate::swap::{swap_internal_with_referral, SwapResult};
 use crate::util::*;
 use crate::{states::*, util};
 use anchor_lang::prelude::*;
@@ -59,13 +59,21 @@ pub struct SwapSingleV2<'info> {
 
     /// The mint of token vault 0
     #[account(
-        address = input_vault.mint
+        address = input_vault.mint,
+        constraint = pool_state.load()?.is_valid_token_program(
+            &input_vault_mint.key(),
+            input_vault_mint.to_account_info().owner
+        ) @ ErrorCode::InvalidTokenProgram
     )]
     pub input_vault_mint: Box<InterfaceAccount<'info, Mint>>,
 
     /// The mint of token vault 1
     #[account(
-        address = output_vault.mint
+        address = output_vault.mint,
+        constraint = pool_state.load()?.is_valid_token_program(
+            &output_vault_mint.key(),
+            output_vault_mint.to_account_info().owner
+        ) @ ErrorCode::InvalidTokenProgram
     )]
     pub output_vault_mint: Box<InterfaceAccount<'info, Mint>>,
     // remaining accounts
@@ -77,12 +85,31 @@ pub struct SwapSingleV2<'info> {
 
 /// Performs a single exact input/output swap
 /// if is_base_input = true, return vaule is the max_amount_out, otherwise is min_amount_in
-pub fn exact_internal_v2<'c: 'info, 'info>(
+pub fn exact_internal_v2<'info>(
     ctx: &mut SwapSingleV2<'info>,
-    remaining_accounts: &'c [AccountInfo<'info>],
+    remaining_accounts: impl IntoIterator<Item = &'info AccountInfo<'info>>,
     amount_specified: u64,
     sqrt_price_limit_x64: u128,
     is_base_input: bool,
+) -> Result<u64> {
+    exact_internal_v2_with_referral(
+        ctx,
+        remaining_accounts,
+        amount_specified,
+        sqrt_price_limit_x64,
+        is_base_input,
+        None,
+    )
+}
+
+/// Same as `exact_internal_v2`, crediting `referral_account` with the referral fee of the swap
+pub fn exact_internal_v2_with_referral<'info>(
+    ctx: &mut SwapSingleV2<'info>,
+    remaining_accounts: impl IntoIterator<Item = &'info AccountInfo<'info>>,
+    amount_specified: u64,
+    sqrt_price_limit_x64: u128,
+    is_base_input: bool,
+    referral_account: Option<&mut ReferralAccount>,
 ) -> Result<u64> {
     // invoke_memo_instruction(SWAP_MEMO_MSG, ctx.memo_program.to_account_info())?;
 
@@ -108,6 +135,25 @@ pub fn exact_internal_v2<'c: 'info, 'info>(
         amount_specified + transfer_fee
     };
 
+    let remaining_accounts: Vec<&'info AccountInfo<'info>> =
+        remaining_accounts.into_iter().collect();
+    let (remaining_accounts, hook_accounts) = split_swap_hook_accounts(
+        &remaining_accounts,
+        ctx.pool_state.load()?.swap_hook(&ctx.amm_config),
+    )?;
+    invoke_swap_hook(
+        hook_accounts,
+        &ctx.pool_state.to_account_info(),
+        &ctx.payer.to_account_info(),
+        BEFORE_SWAP_HOOK,
+        BeforeSwapArgs {
+            zero_for_one: ctx.input_vault.mint == ctx.pool_state.load()?.token_mint_0,
+            is_base_input,
+            amount_specified,
+            sqrt_price_limit_x64,
+        },
+    )?;
+
     {
         swap_price_before = ctx.pool_state.load()?.sqrt_price_x64;
         let pool_state = &mut ctx.pool_state.load_mut()?;
@@ -130,7 +176,7 @@ pub fn exact_internal_v2<'c: 'info, 'info>(
         let tick_array_states = &mut VecDeque::new();
 
         let tick_array_bitmap_extension_key = TickArrayBitmapExtension::key(pool_state.key());
-        for account_info in remaining_accounts.into_iter() {
+        for account_info in remaining_accounts.iter().copied() {
             if account_info.key().eq(&tick_array_bitmap_extension_key) {
                 tickarray_bitmap_extension = Some(
                     *(AccountLoader::<TickArrayBitmapExtension>::try_from(account_info)?
@@ -142,7 +188,13 @@ pub fn exact_internal_v2<'c: 'info, 'info>(
             tick_array_states.push_back(AccountLoad::load_data_mut(account_info)?);
         }
 
-        (amount_0, amount_1) = swap_internal(
+        let referral_fee_rate = if referral_account.is_some() {
+            ctx.amm_config.referral_fee_rate
+        } else {
+            0
+        };
+        let referral_fees_before = pool_state.referral_fees(zero_for_one);
+        (amount_0, amount_1) = swap_internal_with_referral(
             &ctx.amm_config,
             pool_state,
             tick_array_states,
@@ -161,7 +213,14 @@ pub fn exact_internal_v2<'c: 'info, 'info>(
             zero_for_one,
             is_base_input,
             oracle::block_timestamp(),
+            referral_fee_rate,
         )?;
+        if let Some(referral_account) = referral_account {
+            referral_account.credit(
+                zero_for_one,
+                pool_state.referral_fees(zero_for_one) - referral_fees_before,
+            );
+        }
 
         #[cfg(feature = "enable-log")]
         msg!(
@@ -291,6 +350,7 @@ pub fn exact_internal_v2<'c: 'info, 'info>(
         sender: ctx.payer.key(),
         token_account_0: token_account_0.key(),
         token_account_1: token_account_1.key(),
+        recipient: ctx.output_token_account.owner,
         amount_0: amount_0_without_fee,
         transfer_fee_0,
         amount_1: amount_1_without_fee,
@@ -307,20 +367,31 @@ pub fn exact_internal_v2<'c: 'info, 'info>(
     }
     if sqrt_price_limit_x64 == 0 {
         // Does't allow partial filled without specified limit_price.
-        if is_base_input {
-            if zero_for_one {
-                require_eq!(amount_specified, transfer_amount_0);
-            } else {
-                require_eq!(amount_specified, transfer_amount_1);
-            }
+        // The transfer fees are outside of the swap math, compare the amounts the pool takes in
+        // or pays out with the specified amount net of the fee
+        let amount_swapped = if is_base_input == zero_for_one {
+            amount_0
         } else {
-            if zero_for_one {
-                require_eq!(amount_specified, transfer_amount_1);
-            } else {
-                require_eq!(amount_specified, transfer_amount_0);
-            }
-        }
+            amount_1
+        };
+        require_eq!(amount_calculate_specified, amount_swapped);
     }
+    let after_swap_args = AfterSwapArgs {
+        zero_for_one,
+        is_base_input,
+        amount_0,
+        amount_1,
+        sqrt_price_x64: pool_state.sqrt_price_x64,
+        tick: pool_state.tick_current,
+    };
+    drop(pool_state);
+    invoke_swap_hook(
+        hook_accounts,
+        &ctx.pool_state.to_account_info(),
+        &ctx.payer.to_account_info(),
+        AFTER_SWAP_HOOK,
+        after_swap_args,
+    )?;
 
     if is_base_input {
         Ok(ctx
@@ -341,11 +412,31 @@ pub fn swap_v2<'a, 'b, 'c: 'info, 'info>(
     other_amount_threshold: u64,
     sqrt_price_limit_x64: u128,
     is_base_input: bool,
-) -> Result<()> {
-    let amount_result = exact_internal_v2(
+) -> Result<SwapResult> {
+    swap_v2_internal(
         ctx.accounts,
         ctx.remaining_accounts,
         amount,
+        other_amount_threshold,
+        sqrt_price_limit_x64,
+        is_base_input,
+    )
+}
+
+pub fn swap_v2_internal<'info>(
+    accounts: &mut SwapSingleV2<'info>,
+    remaining_accounts: impl IntoIterator<Item = &'info AccountInfo<'info>>,
+    amount: u64,
+    other_amount_threshold: u64,
+    sqrt_price_limit_x64: u128,
+    is_base_input: bool,
+) -> Result<SwapResult> {
+    let zero_for_one = accounts.input_vault.mint == accounts.pool_state.load()?.token_mint_0;
+    let fees_before = accounts.pool_state.load()?.trade_fees(zero_for_one);
+    let amount_result = exact_internal_v2(
+        accounts,
+        remaining_accounts,
+        amount,
         sqrt_price_limit_x64,
         is_base_input,
     )?;
@@ -363,5 +454,144 @@ pub fn swap_v2<'a, 'b, 'c: 'info, 'info>(
         );
     }
 
-    Ok(())
+    let pool_state = accounts.pool_state.load()?;
+    Ok(SwapResult::new(
+        &pool_state,
+        amount,
+        amount_result,
+        is_base_input,
+        pool_state.trade_fees(zero_for_one) - fees_before,
+    ))
+}
+
+/// Same as `swap_v2`, but fails once the current slot is past `max_slot`
+pub fn swap_v2_with_max_slot<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SwapSingleV2<'info>>,
+    amount: u64,
+    other_amount_threshold: u64,
+    sqrt_price_limit_x64: u128,
+    is_base_input: bool,
+    max_slot: u64,
+) -> Result<SwapResult> {
+    check_max_slot(max_slot)?;
+    swap_v2(
+        ctx,
+        amount,
+        other_amount_threshold,
+        sqrt_price_limit_x64,
+        is_base_input,
+    )
+}
+
+/// Same as `swap_v2` with base input, but `amount_in` is what must arrive in the input vault:
+/// the transfer is grossed up by the input mint's transfer fee so the pool swaps exactly
+/// `amount_in`
+pub fn swap_v2_net_input<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SwapSingleV2<'info>>,
+    amount_in: u64,
+    amount_out_minimum: u64,
+    sqrt_price_limit_x64: u128,
+) -> Result<SwapResult> {
+    let transfer_fee =
+        util::get_transfer_inverse_fee(ctx.accounts.input_vault_mint.clone(), amount_in)?;
+    swap_v2(
+        ctx,
+        amount_in.checked_add(transfer_fee).unwrap(),
+        amount_out_minimum,
+        sqrt_price_limit_x64,
+        true,
+    )
+}
+
+/// Same as `swap_v2`, but the remaining accounts can be any superset of the accounts the swap
+/// needs: the bitmap extension and the tick arrays in the swap direction are derived from the
+/// pool's current tick and picked out of them by address.
+pub fn swap_v2_with_resolved_tick_arrays<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SwapSingleV2<'info>>,
+    amount: u64,
+    other_amount_threshold: u64,
+    sqrt_price_limit_x64: u128,
+    is_base_input: bool,
+) -> Result<SwapResult> {
+    let resolved_accounts = {
+        let pool_state = ctx.accounts.pool_state.load()?;
+        let zero_for_one = ctx.accounts.input_vault.mint == pool_state.token_mint_0;
+        // the hook accounts are passed through as they are
+        let (remaining_accounts, hook_accounts) = split_swap_hook_accounts(
+            ctx.remaining_accounts,
+            pool_state.swap_hook(&ctx.accounts.amm_config),
+        )?;
+        let mut resolved_accounts =
+            resolve_swap_tick_arrays(&pool_state, remaining_accounts, zero_for_one)?;
+        resolved_accounts.extend(hook_accounts);
+        resolved_accounts
+    };
+    swap_v2_internal(
+        ctx.accounts,
+        resolved_accounts,
+        amount,
+        other_amount_threshold,
+        sqrt_price_limit_x64,
+        is_base_input,
+    )
+}
+
+/// Picks the accounts a swap in the given direction traverses out of `accounts`: the bitmap
+/// extension if provided, followed by the initialized tick arrays from the current one onwards,
+/// stopping at the first one not provided
+pub fn resolve_swap_tick_arrays<'info>(
+    pool_state: &PoolState,
+    accounts: &'info [AccountInfo<'info>],
+    zero_for_one: bool,
+) -> Result<Vec<&'info AccountInfo<'info>>> {
+    let pool_id = pool_state.key();
+    let find_account = |key: Pubkey| accounts.iter().find(|account| account.key() == key);
+
+    let mut resolved_accounts = Vec::new();
+    let mut tickarray_bitmap_extension = None;
+    if let Some(account_info) = find_account(TickArrayBitmapExtension::key(pool_id)) {
+        tickarray_bitmap_extension = Some(
+            *(AccountLoader::<TickArrayBitmapExtension>::try_from(account_info)?
+                .load()?
+                .deref()),
+        );
+        resolved_accounts.push(account_info);
+    } else if pool_state.is_overflow_default_tickarray_bitmap(vec![pool_state.tick_current]) {
+        return err!(ErrorCode::MissingTickArrayBitmapExtensionAccount);
+    }
+
+    let (_, mut start_index) =
+        pool_state.get_first_initialized_tick_array(&tickarray_bitmap_extension, zero_for_one)?;
+    loop {
+        let (tick_array_key, _) = Pubkey::find_program_address(
+            &[
+                TICK_ARRAY_SEED.as_bytes(),
+                pool_id.as_ref(),
+                &start_index.to_be_bytes(),
+            ],
+            &crate::id(),
+        );
+        match find_account(tick_array_key) {
+            Some(account_info) => resolved_accounts.push(account_info),
+            None => break,
+        }
+        // past the default bitmap without the extension, the next array can't be known
+        match pool_state
+            .next_initialized_tick_array_start_index(
+                &tickarray_bitmap_extension,
+                start_index,
+                zero_for_one,
+            )
+            .ok()
+            .flatten()
+        {
+            Some(next_start_index) => start_index = next_start_index,
+            None => break,
+        }
+    }
+    require!(
+        resolved_accounts.len() > usize::from(tickarray_bitmap_extension.is_some()),
+        ErrorCode::NotEnoughTickArrayAccount
+    );
+    Ok(resolved_accounts)
 }
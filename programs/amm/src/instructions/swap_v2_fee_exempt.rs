@@ -0,0 +1,50 @@
+use crate::error::ErrorCode;
+use crate::instructions::swap_v2::*;
+use crate::states::*;
+use crate::swap::SwapResult;
+use crate::util::get_cpi_caller;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+
+#[derive(Accounts)]
+pub struct SwapSingleV2FeeExempt<'info> {
+    /// The swap, same accounts as `swap_v2`
+    pub swap: SwapSingleV2<'info>,
+
+    /// The programs exempted from the protocol fee of the pool's config
+    #[account(
+        seeds = [FEE_EXEMPTION_SEED.as_bytes(), swap.amm_config.key().as_ref()],
+        bump = fee_exemption.bump,
+    )]
+    pub fee_exemption: Box<Account<'info, FeeExemption>>,
+
+    /// CHECK: Sysvar to find the calling program
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+}
+
+/// Same as `swap_v2`, without protocol fee, for exempted programs swapping through CPI.
+/// The protocol's share of the trade fee is left to the liquidity providers.
+pub fn swap_v2_fee_exempt<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SwapSingleV2FeeExempt<'info>>,
+    amount: u64,
+    other_amount_threshold: u64,
+    sqrt_price_limit_x64: u128,
+    is_base_input: bool,
+) -> Result<SwapResult> {
+    let exempt = match get_cpi_caller(&ctx.accounts.instructions_sysvar)? {
+        Some(program_id) => ctx.accounts.fee_exemption.is_exempt(&program_id),
+        None => false,
+    };
+    require!(exempt, ErrorCode::NotFeeExempt);
+    // the config is read only, only this swap sees the zero rate
+    ctx.accounts.swap.amm_config.protocol_fee_rate = 0;
+    swap_v2_internal(
+        &mut ctx.accounts.swap,
+        ctx.remaining_accounts,
+        amount,
+        other_amount_threshold,
+        sqrt_price_limit_x64,
+        is_base_input,
+    )
+}
@@ -0,0 +1,122 @@
+use super::swap::{swap_single, SwapAccounts, SwapResult};
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+#[derive(Accounts)]
+pub struct SwapSingleWithOptions<'info> {
+    /// The user performing the swap, funds and receives the SOL of its native mint token
+    /// accounts when system_program is given
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The factory state to read protocol fees
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// The program account of the pool in which the swap will be performed
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The user token account for input token
+    #[account(mut)]
+    pub input_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// The user token account for output token, receives the output unless a
+    /// recipient_token_account is given
+    #[account(mut)]
+    pub output_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// The vault token account for input token
+    #[account(mut)]
+    pub input_vault: Box<Account<'info, TokenAccount>>,
+
+    /// The vault token account for output token
+    #[account(mut)]
+    pub output_vault: Box<Account<'info, TokenAccount>>,
+
+    /// The program account for the most recent oracle observation
+    #[account(mut, address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+
+    /// SPL program for token transfers
+    pub token_program: Program<'info, Token>,
+
+    #[account(mut, constraint = tick_array.load()?.pool_id == pool_state.key())]
+    pub tick_array: AccountLoader<'info, TickArrayState>,
+
+    /// System program to wrap native SOL. When present, the payer's native mint token accounts
+    /// are funded from its lamports and closed back to it at the end. Those accounts must
+    /// already exist, e.g. created by the client earlier in the transaction
+    pub system_program: Option<Program<'info, System>>,
+
+    /// The referrer credited with `referral_fee_rate` of the trade fee, the swap is not referred
+    /// when absent
+    #[account(
+        mut,
+        constraint = referral_account.pool_id == pool_state.key() @ ErrorCode::InvalidReferralAccount
+    )]
+    pub referral_account: Option<Box<Account<'info, ReferralAccount>>>,
+
+    /// The payer's volume in the pool, the swap counts towards volume rebates and earns the
+    /// rebate of the payer's tier when present
+    #[account(
+        mut,
+        seeds = [
+            USER_STATS_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            payer.key().as_ref(),
+        ],
+        bump = user_stats.bump,
+    )]
+    pub user_stats: Option<Box<Account<'info, UserStats>>>,
+
+    /// A token account of any owner that receives the output in place of output_token_account
+    #[account(mut, token::mint = output_vault.mint)]
+    pub recipient_token_account: Option<Box<Account<'info, TokenAccount>>>,
+    // remaining accounts
+    // tickarray_bitmap_extension: must add account if need regardless the sequence
+    // tick_array_account_1
+    // tick_array_account_...
+}
+
+/// Swaps across a single pool as `swap` does, with the optional native SOL wrapping, referral,
+/// volume rebate and output recipient of `SwapSingleWithOptions`
+pub fn swap_with_options<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SwapSingleWithOptions<'info>>,
+    amount: u64,
+    other_amount_threshold: u64,
+    sqrt_price_limit_x64: u128,
+    is_base_input: bool,
+    allow_partial_fill: bool,
+) -> Result<SwapResult> {
+    let accounts = ctx.accounts;
+    swap_single(
+        &mut SwapAccounts {
+            signer: accounts.payer.clone(),
+            amm_config: &accounts.amm_config,
+            input_token_account: accounts.input_token_account.clone(),
+            output_token_account: accounts
+                .recipient_token_account
+                .as_ref()
+                .unwrap_or(&accounts.output_token_account)
+                .clone(),
+            input_vault: accounts.input_vault.clone(),
+            output_vault: accounts.output_vault.clone(),
+            token_program: accounts.token_program.clone(),
+            pool_state: &mut accounts.pool_state,
+            tick_array_state: &mut accounts.tick_array,
+            observation_state: &mut accounts.observation_state,
+            referral_account: accounts.referral_account.as_mut(),
+            user_stats: accounts.user_stats.as_mut(),
+        },
+        accounts.system_program.as_ref(),
+        ctx.remaining_accounts,
+        amount,
+        other_amount_threshold,
+        sqrt_price_limit_x64,
+        is_base_input,
+        allow_partial_fill,
+    )
+}
@@ -0,0 +1,70 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::get_recent_epoch;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+#[derive(Accounts)]
+pub struct SweepEmptyPosition<'info> {
+    /// Anyone can sweep an abandoned position and receive half of its rent
+    #[account(mut)]
+    pub sweeper: Signer<'info>,
+
+    /// CHECK: the holder of the position nft, receives the other half of the rent
+    #[account(mut, address = nft_account.owner)]
+    pub nft_owner: UncheckedAccount<'info>,
+
+    /// The token account holding the position nft
+    #[account(
+        token::mint = personal_position.nft_mint,
+        constraint = nft_account.amount == 1,
+    )]
+    pub nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = personal_position.pool_id == pool_state.key(),
+        close = sweeper
+    )]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The config setting the grace period of the pool's positions
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+}
+
+/// Closes the state of a position left with no liquidity and nothing owed past the config's
+/// grace period. The nft stays with its holder, it can no longer be used with the pool
+pub fn sweep_empty_position(ctx: Context<SweepEmptyPosition>) -> Result<()> {
+    let personal_position = &ctx.accounts.personal_position;
+    require!(
+        personal_position.is_sweepable(
+            get_recent_epoch()?,
+            ctx.accounts.amm_config.empty_position_grace_epochs
+        ),
+        ErrorCode::PositionNotSweepable
+    );
+
+    // the sweeper gets the rest when the account is closed
+    let rent = personal_position.to_account_info().lamports();
+    let nft_owner_rent = rent / 2;
+    personal_position
+        .to_account_info()
+        .sub_lamports(nft_owner_rent)?;
+    ctx.accounts
+        .nft_owner
+        .to_account_info()
+        .add_lamports(nft_owner_rent)?;
+
+    emit!(SweepEmptyPositionEvent {
+        position_nft_mint: personal_position.nft_mint,
+        pool_state: personal_position.pool_id,
+        sweeper: ctx.accounts.sweeper.key(),
+        nft_owner: ctx.accounts.nft_owner.key(),
+        sweeper_rent: rent - nft_owner_rent,
+        nft_owner_rent,
+    });
+    Ok(())
+}
@@ -0,0 +1,202 @@
+use super::decrease_liquidity::{
+    check_unclaimed_fees_and_vault, collect_rewards, decrease_liquidity_and_update_position,
+};
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::{transfer_from_pool_vault_to_user, transfer_position_nft};
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct TransferPosition<'info> {
+    /// The position nft owner, receives the position's fees and rewards
+    pub nft_owner: Signer<'info>,
+
+    /// Mint address bound to the personal position.
+    #[account(
+        address = personal_position.nft_mint,
+        mint::token_program = nft_token_program,
+    )]
+    pub position_nft_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The token account holding the position nft
+    #[account(
+        mut,
+        token::mint = position_nft_mint,
+        token::authority = nft_owner,
+        constraint = nft_account.amount == 1,
+        token::token_program = nft_token_program,
+    )]
+    pub nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The new holder's token account for the position nft, none settles the position without
+    /// moving the nft
+    #[account(
+        mut,
+        token::mint = position_nft_mint,
+        token::token_program = nft_token_program,
+    )]
+    pub recipient_nft_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(mut, constraint = personal_position.pool_id == pool_state.key())]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        mut,
+        seeds = [
+            POSITION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &personal_position.tick_lower_index.to_be_bytes(),
+            &personal_position.tick_upper_index.to_be_bytes(),
+        ],
+        bump,
+        constraint = protocol_position.pool_id == pool_state.key(),
+    )]
+    pub protocol_position: Box<Account<'info, ProtocolPositionState>>,
+
+    /// Token_0 vault
+    #[account(
+        mut,
+        constraint = token_vault_0.key() == pool_state.load()?.token_vault_0
+    )]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token_1 vault
+    #[account(
+        mut,
+        constraint = token_vault_1.key() == pool_state.load()?.token_vault_1
+    )]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Stores init state for the lower tick
+    #[account(mut, constraint = tick_array_lower.load()?.pool_id == pool_state.key())]
+    pub tick_array_lower: AccountLoader<'info, TickArrayState>,
+
+    /// Stores init state for the upper tick
+    #[account(mut, constraint = tick_array_upper.load()?.pool_id == pool_state.key())]
+    pub tick_array_upper: AccountLoader<'info, TickArrayState>,
+
+    /// The owner's token account receiving the token_0 fees
+    #[account(
+        mut,
+        token::mint = token_vault_0.mint
+    )]
+    pub recipient_token_account_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The owner's token account receiving the token_1 fees
+    #[account(
+        mut,
+        token::mint = token_vault_1.mint
+    )]
+    pub recipient_token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of token vault 0
+    #[account(
+        address = token_vault_0.mint,
+        constraint = pool_state.load()?.is_valid_token_program(
+            &vault_0_mint.key(),
+            vault_0_mint.to_account_info().owner
+        ) @ ErrorCode::InvalidTokenProgram
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token vault 1
+    #[account(
+        address = token_vault_1.mint,
+        constraint = pool_state.load()?.is_valid_token_program(
+            &vault_1_mint.key(),
+            vault_1_mint.to_account_info().owner
+        ) @ ErrorCode::InvalidTokenProgram
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// SPL program to transfer out tokens
+    pub token_program: Program<'info, Token>,
+    /// Token program 2022
+    pub token_program_2022: Program<'info, Token2022>,
+    /// Token/Token2022 program of the position nft
+    pub nft_token_program: Interface<'info, TokenInterface>,
+    // remaining accounts
+    // the reward vault, the owner's reward token account and the reward mint of each
+    // initialized reward
+}
+
+/// Pays all the fees and rewards owed to the position out to its current owner, then hands the
+/// nft to the recipient if one is given. The new holder starts with nothing owed
+pub fn transfer_position<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, TransferPosition<'info>>,
+) -> Result<()> {
+    let accounts = ctx.accounts;
+    // no liquidity is removed, so the tick array bitmap extension is never needed
+    let (_, fees_0, _, fees_1) = decrease_liquidity_and_update_position(
+        &accounts.pool_state,
+        &mut accounts.protocol_position,
+        &mut accounts.personal_position,
+        &accounts.tick_array_lower,
+        &accounts.tick_array_upper,
+        None,
+        0,
+    )?;
+
+    transfer_from_pool_vault_to_user(
+        &accounts.pool_state,
+        &accounts.token_vault_0.to_account_info(),
+        &accounts.recipient_token_account_0.to_account_info(),
+        Some(accounts.vault_0_mint.clone()),
+        &accounts.token_program,
+        Some(accounts.token_program_2022.to_account_info()),
+        fees_0,
+    )?;
+    transfer_from_pool_vault_to_user(
+        &accounts.pool_state,
+        &accounts.token_vault_1.to_account_info(),
+        &accounts.recipient_token_account_1.to_account_info(),
+        Some(accounts.vault_1_mint.clone()),
+        &accounts.token_program,
+        Some(accounts.token_program_2022.to_account_info()),
+        fees_1,
+    )?;
+    check_unclaimed_fees_and_vault(
+        &accounts.pool_state,
+        &accounts.token_vault_0.to_account_info(),
+        &accounts.token_vault_1.to_account_info(),
+    )?;
+
+    let reward_accounts: Vec<&'info AccountInfo<'info>> = ctx.remaining_accounts.iter().collect();
+    let reward_amounts = collect_rewards(
+        &accounts.pool_state,
+        &reward_accounts,
+        &accounts.token_program,
+        Some(accounts.token_program_2022.to_account_info()),
+        &mut accounts.personal_position,
+        true,
+        [u64::MAX; REWARD_NUM],
+    )?;
+
+    let mut new_owner = accounts.nft_owner.key();
+    if let Some(recipient_nft_account) = &accounts.recipient_nft_account {
+        transfer_position_nft(
+            &accounts.nft_owner.to_account_info(),
+            &accounts.nft_account.to_account_info(),
+            &recipient_nft_account.to_account_info(),
+            &accounts.position_nft_mint.to_account_info(),
+            &accounts.nft_token_program.to_account_info(),
+            &[],
+        )?;
+        new_owner = recipient_nft_account.owner;
+    }
+
+    emit!(TransferPositionEvent {
+        position_nft_mint: accounts.personal_position.nft_mint,
+        previous_owner: accounts.nft_owner.key(),
+        new_owner,
+        fee_amount_0: fees_0,
+        fee_amount_1: fees_1,
+        reward_amounts,
+    });
+    Ok(())
+}
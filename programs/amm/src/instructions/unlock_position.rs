@@ -0,0 +1,182 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::{close_spl_account, transfer_position_nft};
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::{self, Token2022};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct UnlockPosition<'info> {
+    /// The owner of the locked position
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = locked_position.owner == owner.key() @ ErrorCode::NotApproved,
+        close = owner
+    )]
+    pub locked_position: Box<Account<'info, LockedPositionState>>,
+
+    /// Mint address of the locked position nft
+    #[account(
+        address = locked_position.position_nft_mint,
+        mint::token_program = token_program,
+    )]
+    pub position_nft_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The escrow token account holding the position nft
+    #[account(
+        mut,
+        address = locked_position.locked_nft_account,
+    )]
+    pub locked_nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The token account to receive the position nft
+    #[account(
+        mut,
+        token::mint = position_nft_mint,
+        token::token_program = token_program,
+    )]
+    pub position_nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token/Token2022 program of the position nft
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// The receipt mint, closed on unlock once the receipt is burned
+    #[account(mut, address = locked_position.receipt_mint)]
+    pub receipt_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// The owner's receipt token account, burned and closed if passed. The position can be
+    /// unlocked without it if the receipt was already burned or its account closed
+    #[account(
+        mut,
+        token::mint = locked_position.receipt_mint,
+        token::authority = owner,
+    )]
+    pub receipt_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Token program 2022 of the receipt
+    pub token_program_2022: Option<Program<'info, Token2022>>,
+}
+
+pub fn unlock_position(ctx: Context<UnlockPosition>) -> Result<()> {
+    let block_timestamp = u64::try_from(Clock::get()?.unix_timestamp).unwrap();
+    let locked_position = ctx.accounts.locked_position.as_ref();
+    require!(
+        !locked_position.is_locked(block_timestamp),
+        ErrorCode::PositionStillLocked
+    );
+
+    let locked_position_info = locked_position.to_account_info();
+    let token_program = ctx.accounts.token_program.to_account_info();
+    let locked_nft_account = ctx.accounts.locked_nft_account.to_account_info();
+    transfer_position_nft(
+        &locked_position_info,
+        &locked_nft_account,
+        &ctx.accounts.position_nft_account.to_account_info(),
+        &ctx.accounts.position_nft_mint.to_account_info(),
+        &token_program,
+        &[&locked_position.seeds()],
+    )?;
+
+    // close the escrow token account
+    close_spl_account(
+        &locked_position_info,
+        &ctx.accounts.owner.to_account_info(),
+        &locked_nft_account,
+        &token_program,
+        &[&locked_position.seeds()],
+    )?;
+
+    if locked_position.has_receipt() {
+        burn_receipt(ctx.accounts)?;
+    }
+
+    emit!(UnlockPositionEvent {
+        position_nft_mint: locked_position.position_nft_mint,
+        owner: locked_position.owner,
+    });
+    Ok(())
+}
+
+/// Burns the owner's receipt and closes its token account and mint. The holder may already
+/// have burned the receipt or closed its account, which must not keep the position in escrow:
+/// what is left of the receipt is cleaned up, the mint only once nothing is outstanding
+fn burn_receipt(accounts: &UnlockPosition) -> Result<()> {
+    let (burn, close_mint) = receipt_cleanup(
+        accounts
+            .receipt_account
+            .as_ref()
+            .map(|receipt_account| receipt_account.amount),
+        accounts
+            .receipt_mint
+            .as_ref()
+            .map(|receipt_mint| receipt_mint.supply),
+    );
+    if accounts.receipt_account.is_none() && !close_mint {
+        return Ok(());
+    }
+    let Some(token_program_2022) = &accounts.token_program_2022 else {
+        return err!(ErrorCode::AccountLack);
+    };
+    let locked_position = accounts.locked_position.as_ref();
+    let owner = accounts.owner.to_account_info();
+    let token_program_2022 = token_program_2022.to_account_info();
+    if let Some(receipt_account) = &accounts.receipt_account {
+        let receipt_account = receipt_account.to_account_info();
+        if burn {
+            let Some(receipt_mint) = &accounts.receipt_mint else {
+                return err!(ErrorCode::AccountLack);
+            };
+            token_2022::burn(
+                CpiContext::new(
+                    token_program_2022.clone(),
+                    token_2022::Burn {
+                        mint: receipt_mint.to_account_info(),
+                        from: receipt_account.clone(),
+                        authority: owner.clone(),
+                    },
+                ),
+                1,
+            )?;
+        }
+        close_spl_account(&owner, &owner, &receipt_account, &token_program_2022, &[])?;
+    }
+    if close_mint {
+        close_spl_account(
+            &locked_position.to_account_info(),
+            &owner,
+            &accounts.receipt_mint.as_ref().unwrap().to_account_info(),
+            &token_program_2022,
+            &[&locked_position.seeds()],
+        )?;
+    }
+    Ok(())
+}
+
+/// Whether the receipt is to be burned from the owner's receipt account holding `receipt_amount`,
+/// and whether the receipt mint with `receipt_supply` can be closed afterwards. None for an
+/// account that isn't passed
+fn receipt_cleanup(receipt_amount: Option<u64>, receipt_supply: Option<u64>) -> (bool, bool) {
+    let burn = receipt_amount.is_some_and(|amount| amount > 0);
+    let close_mint = receipt_supply.is_some_and(|supply| supply == u64::from(burn));
+    (burn, close_mint)
+}
+
+#[cfg(test)]
+mod unlock_position_test {
+    use super::receipt_cleanup;
+
+    #[test]
+    fn receipt_cleanup_test() {
+        // the owner still holds the receipt
+        assert_eq!(receipt_cleanup(Some(1), Some(1)), (true, true));
+        // the owner already burned the receipt, or burned it and closed its account
+        assert_eq!(receipt_cleanup(Some(0), Some(0)), (false, true));
+        assert_eq!(receipt_cleanup(None, Some(0)), (false, true));
+        // the receipt is still out, the mint stays open
+        assert_eq!(receipt_cleanup(None, Some(1)), (false, false));
+        assert_eq!(receipt_cleanup(Some(0), None), (false, false));
+    }
+}
@@ -0,0 +1,71 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::transfer_from_distributor_vault_to_user;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct UnstakeFeeDistributor<'info> {
+    /// The staker
+    pub owner: Signer<'info>,
+
+    #[account(mut)]
+    pub fee_distributor: Box<Account<'info, FeeDistributorState>>,
+
+    #[account(
+        mut,
+        seeds = [
+            FEE_STAKER_SEED.as_bytes(),
+            fee_distributor.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump = fee_staker.bump,
+        constraint = fee_staker.owner == owner.key() @ ErrorCode::NotApproved
+    )]
+    pub fee_staker: Box<Account<'info, FeeStakerState>>,
+
+    /// The token account to receive the unstaked tokens
+    #[account(
+        mut,
+        token::mint = stake_mint,
+    )]
+    pub recipient_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The vault holding staked tokens
+    #[account(
+        mut,
+        address = fee_distributor.stake_vault
+    )]
+    pub stake_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of the staked token
+    #[account(
+        address = fee_distributor.stake_mint,
+        mint::token_program = token_program,
+    )]
+    pub stake_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Spl token program or token program 2022 of stake mint
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn unstake_fee_distributor(ctx: Context<UnstakeFeeDistributor>, amount: u64) -> Result<()> {
+    let block_timestamp = u64::try_from(Clock::get()?.unix_timestamp).unwrap();
+    let fee_distributor = ctx.accounts.fee_distributor.as_mut();
+    fee_distributor.update_epoch(block_timestamp);
+
+    let fee_staker = ctx.accounts.fee_staker.as_mut();
+    require_gte!(fee_staker.staked_amount, amount);
+    fee_staker.settle(fee_distributor.reward_per_share_x64);
+    fee_staker.staked_amount = fee_staker.staked_amount.checked_sub(amount).unwrap();
+    fee_distributor.total_staked = fee_distributor.total_staked.checked_sub(amount).unwrap();
+
+    transfer_from_distributor_vault_to_user(
+        &ctx.accounts.fee_distributor,
+        &ctx.accounts.stake_vault.to_account_info(),
+        &ctx.accounts.recipient_token_account.to_account_info(),
+        &ctx.accounts.stake_mint,
+        &ctx.accounts.token_program.to_account_info(),
+        amount,
+    )
+}
@@ -0,0 +1,42 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateFeeGrowthSnapshot<'info> {
+    /// Anyone can crank, pays for the snapshot account on the first call
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Stores the fee growth snapshots of the pool
+    #[account(
+        init_if_needed,
+        seeds = [
+            FEE_GROWTH_SNAPSHOT_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = FeeGrowthSnapshotState::LEN
+    )]
+    pub fee_growth_snapshot: Box<Account<'info, FeeGrowthSnapshotState>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn update_fee_growth_snapshot(ctx: Context<UpdateFeeGrowthSnapshot>) -> Result<()> {
+    let block_timestamp = u64::try_from(Clock::get()?.unix_timestamp).unwrap();
+    let pool_state = ctx.accounts.pool_state.load()?;
+    let fee_growth_snapshot = ctx.accounts.fee_growth_snapshot.as_mut();
+    if fee_growth_snapshot.pool_id == Pubkey::default() {
+        fee_growth_snapshot.bump = ctx.bumps.fee_growth_snapshot;
+        fee_growth_snapshot.pool_id = ctx.accounts.pool_state.key();
+    }
+    fee_growth_snapshot.update(
+        block_timestamp,
+        pool_state.fee_growth_global_0_x64,
+        pool_state.fee_growth_global_1_x64,
+    );
+    Ok(())
+}
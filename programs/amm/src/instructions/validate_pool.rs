@@ -0,0 +1,222 @@
+use crate::error::ErrorCode;
+use crate::libraries::tick_math;
+use crate::states::*;
+use crate::util::AccountLoad;
+use anchor_lang::prelude::*;
+
+/// The largest gap between the pool liquidity and the liquidity recomputed from its ticks that
+/// `validate_pool` repairs, in bips of the recomputed liquidity
+pub const MAX_LIQUIDITY_REPAIR_DRIFT_BPS: u128 = 1;
+
+#[derive(Accounts)]
+pub struct ValidatePool<'info> {
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The pool's tick array bitmap extension, read when the walk leaves the default bitmap
+    #[account(
+        seeds = [POOL_TICK_ARRAY_BITMAP_SEED.as_bytes(), pool_state.key().as_ref()],
+        bump
+    )]
+    pub tickarray_bitmap_extension: AccountLoader<'info, TickArrayBitmapExtension>,
+    // remaining accounts
+    // the initialized tick arrays at and below the current tick in descending order, writable
+}
+
+/// Whether `tick_current` is the tick of `sqrt_price_x64`, a swap stopping right on a tick while
+/// moving down leaves the pool one tick below it
+pub fn is_tick_consistent(tick_current: i32, sqrt_price_x64: u128) -> Result<bool> {
+    let tick = tick_math::get_tick_at_sqrt_price(sqrt_price_x64)?;
+    Ok(tick_current == tick
+        || (tick_current == tick - 1 && sqrt_price_x64 == tick_math::get_sqrt_price_at_tick(tick)?))
+}
+
+/// Counts the ticks of the array referenced by any position
+pub fn count_initialized_ticks(tick_array: &TickArrayState) -> u8 {
+    tick_array
+        .ticks
+        .iter()
+        .filter(|tick| tick.liquidity_gross != 0)
+        .count() as u8
+}
+
+/// Adds the net liquidity of the array's ticks at or below `tick_current` to `liquidity`, none if
+/// the sum overflows
+pub fn add_liquidity_net_below(
+    liquidity: i128,
+    tick_array: &TickArrayState,
+    tick_current: i32,
+    tick_spacing: u16,
+) -> Option<i128> {
+    let start_tick_index = tick_array.start_tick_index;
+    tick_array
+        .ticks
+        .iter()
+        .enumerate()
+        .take_while(|(i, _)| start_tick_index + *i as i32 * i32::from(tick_spacing) <= tick_current)
+        .try_fold(liquidity, |liquidity, (_, tick)| {
+            liquidity.checked_add(tick.liquidity_net)
+        })
+}
+
+/// Whether the pool liquidity is close enough to the liquidity recomputed from its ticks to be
+/// overwritten with it
+pub fn is_liquidity_drift_repairable(liquidity: u128, expected_liquidity: u128) -> bool {
+    liquidity.abs_diff(expected_liquidity)
+        <= expected_liquidity / 10_000 * MAX_LIQUIDITY_REPAIR_DRIFT_BPS
+}
+
+/// Recomputes the pool's derived state from its price and ticks, and reports it.
+///
+/// The initialized tick counts of the tick arrays given are always repaired. The pool liquidity
+/// is checked when the arrays cover every initialized array at and below the current tick, and
+/// is only repaired when the tick agrees with the price and the drift is within
+/// `MAX_LIQUIDITY_REPAIR_DRIFT_BPS`. A tick that disagrees with the price is only reported
+pub fn validate_pool<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, ValidatePool<'info>>,
+) -> Result<()> {
+    let pool_id = ctx.accounts.pool_state.key();
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    let tick_consistent = is_tick_consistent(pool_state.tick_current, pool_state.sqrt_price_x64)?;
+
+    let tickarray_bitmap_extension = Some(*ctx.accounts.tickarray_bitmap_extension.load()?);
+
+    // walks down the bitmap from the current tick, none once no initialized array is left
+    let mut next_start_index =
+        match pool_state.get_first_initialized_tick_array(&tickarray_bitmap_extension, true) {
+            Ok((_, start_index)) => Some(start_index),
+            Err(error) if error == ErrorCode::InsufficientLiquidityForDirection.into() => None,
+            Err(error) => return Err(error),
+        };
+    let mut liquidity_net_sum: Option<i128> = Some(0);
+    let mut tick_arrays_checked: u8 = 0;
+    let mut tick_counts_repaired: u8 = 0;
+    for account_info in ctx.remaining_accounts {
+        let mut tick_array = AccountLoad::<TickArrayState>::load_data_mut(account_info)?;
+        require_keys_eq!(tick_array.pool_id, pool_id);
+        let start_index = tick_array.start_tick_index;
+        require!(
+            next_start_index == Some(start_index),
+            ErrorCode::InvalidTickArray
+        );
+
+        let initialized_tick_count = count_initialized_ticks(&tick_array);
+        if tick_array.initialized_tick_count != initialized_tick_count {
+            tick_array.initialized_tick_count = initialized_tick_count;
+            tick_counts_repaired += 1;
+        }
+        liquidity_net_sum = liquidity_net_sum.and_then(|liquidity| {
+            add_liquidity_net_below(
+                liquidity,
+                &tick_array,
+                pool_state.tick_current,
+                pool_state.tick_spacing,
+            )
+        });
+        tick_arrays_checked += 1;
+
+        next_start_index = pool_state.next_initialized_tick_array_start_index(
+            &tickarray_bitmap_extension,
+            start_index,
+            true,
+        )?;
+    }
+
+    // the liquidity is only known once no initialized array is left below the ones given
+    let expected_liquidity = if next_start_index.is_none() {
+        liquidity_net_sum.and_then(|liquidity| u128::try_from(liquidity).ok())
+    } else {
+        None
+    };
+    let liquidity = pool_state.liquidity;
+    let mut liquidity_repaired = false;
+    if let Some(expected_liquidity) = expected_liquidity {
+        if tick_consistent
+            && liquidity != expected_liquidity
+            && is_liquidity_drift_repairable(liquidity, expected_liquidity)
+        {
+            pool_state.liquidity = expected_liquidity;
+            liquidity_repaired = true;
+        }
+    }
+
+    emit!(ValidatePoolEvent {
+        pool_state: pool_id,
+        tick_current: pool_state.tick_current,
+        sqrt_price_x64: pool_state.sqrt_price_x64,
+        tick_consistent,
+        liquidity,
+        liquidity_checked: expected_liquidity.is_some(),
+        expected_liquidity: expected_liquidity.unwrap_or_default(),
+        liquidity_repaired,
+        tick_arrays_checked,
+        tick_counts_repaired,
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod validate_pool_test {
+    use super::*;
+    use crate::states::tick_array_test::{build_tick, build_tick_array_with_tick_states};
+
+    #[test]
+    fn is_tick_consistent_test() {
+        let sqrt_price_x64 = tick_math::get_sqrt_price_at_tick(100).unwrap();
+        assert!(is_tick_consistent(100, sqrt_price_x64).unwrap());
+        // moving down onto the tick leaves the pool just below it
+        assert!(is_tick_consistent(99, sqrt_price_x64).unwrap());
+        assert!(!is_tick_consistent(101, sqrt_price_x64).unwrap());
+        assert!(is_tick_consistent(100, sqrt_price_x64 + 1).unwrap());
+        assert!(!is_tick_consistent(99, sqrt_price_x64 + 1).unwrap());
+    }
+
+    #[test]
+    fn tick_array_recount_test() {
+        let tick_spacing = 10;
+        let mut tick_array = build_tick_array_with_tick_states(
+            Pubkey::new_unique(),
+            0,
+            tick_spacing,
+            vec![
+                build_tick(10, 300, 300).take(),
+                build_tick(100, 200, 200).take(),
+                build_tick(200, 500, -500).take(),
+            ],
+        )
+        .take();
+        tick_array.initialized_tick_count = 1;
+        assert_eq!(count_initialized_ticks(&tick_array), 3);
+
+        assert_eq!(
+            add_liquidity_net_below(0, &tick_array, -1, tick_spacing),
+            Some(0)
+        );
+        assert_eq!(
+            add_liquidity_net_below(0, &tick_array, 10, tick_spacing),
+            Some(300)
+        );
+        assert_eq!(
+            add_liquidity_net_below(1000, &tick_array, 150, tick_spacing),
+            Some(1500)
+        );
+        assert_eq!(
+            add_liquidity_net_below(0, &tick_array, 200, tick_spacing),
+            Some(0)
+        );
+        assert_eq!(
+            add_liquidity_net_below(i128::MAX, &tick_array, 10, tick_spacing),
+            None
+        );
+    }
+
+    #[test]
+    fn is_liquidity_drift_repairable_test() {
+        assert!(is_liquidity_drift_repairable(1_000_000, 1_000_000));
+        assert!(is_liquidity_drift_repairable(1_000_100, 1_000_000));
+        assert!(is_liquidity_drift_repairable(999_900, 1_000_000));
+        assert!(!is_liquidity_drift_repairable(1_000_101, 1_000_000));
+        // below 10_000 only an exact match passes
+        assert!(!is_liquidity_drift_repairable(1, 0));
+    }
+}
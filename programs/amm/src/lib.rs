@@ -45,7 +45,7 @@ pub mod amm_v3 {
     /// * `index` - The index of amm config, there may be multiple config.
     /// * `tick_spacing` - The tickspacing binding with config, cannot be changed.
     /// * `trade_fee_rate` - Trade fee rate, can be changed.
-    /// * `protocol_fee_rate` - The rate of protocol fee within tarde fee.
+    /// * `protocol_fee_rate` - The rate of protocol fee within tarde fee, between `MIN_PROTOCOL_FEE_RATE` and `MAX_PROTOCOL_FEE_RATE`.
     /// * `fund_fee_rate` - The rate of fund fee within tarde fee.
     ///
     pub fn create_amm_config(
@@ -57,9 +57,7 @@ pub mod amm_v3 {
         fund_fee_rate: u32,
     ) -> Result<()> {
         assert!(trade_fee_rate < FEE_RATE_DENOMINATOR_VALUE);
-        assert!(protocol_fee_rate <= FEE_RATE_DENOMINATOR_VALUE);
         assert!(fund_fee_rate <= FEE_RATE_DENOMINATOR_VALUE);
-        assert!(fund_fee_rate + protocol_fee_rate <= FEE_RATE_DENOMINATOR_VALUE);
         instructions::create_amm_config(
             ctx,
             index,
@@ -77,16 +75,83 @@ pub mod amm_v3 {
     ///
     /// * `ctx`- The context of accounts
     /// * `trade_fee_rate`- The new trade fee rate of amm config, be set when `param` is 0
-    /// * `protocol_fee_rate`- The new protocol fee rate of amm config, be set when `param` is 1
+    /// * `protocol_fee_rate`- The new protocol fee rate of amm config, be set when `param` is 1,
+    ///    moves by at most `MAX_PROTOCOL_FEE_RATE_STEP` once per `PROTOCOL_FEE_RATE_TIMELOCK`
     /// * `fund_fee_rate`- The new fund fee rate of amm config, be set when `param` is 2
     /// * `new_owner`- The config's new owner, be set when `param` is 3
     /// * `new_fund_owner`- The config's new fund owner, be set when `param` is 4
-    /// * `param`- The vaule can be 0 | 1 | 2 | 3 | 4, otherwise will report a error
+    /// * `custom_fee_min_rate`- The lower bound of custom pool trade fee, be set when `param` is 5
+    /// * `custom_fee_max_rate`- The upper bound of custom pool trade fee, be set when `param` is 6,
+    ///    0 disables custom fee pools for the config
+    /// * `referral_fee_rate`- The referrer's cut of the trade fee on referred swaps, be set when `param` is 8
+    /// * `volume_rebate_rate`- The share of the protocol fee rebated per volume tier, be set when `param` is 9
+    /// * `volume_rebate_min_epochs`- The consecutive qualifying epochs per volume tier, be set when `param` is 10
+    /// * `paused`- Whether every pool of the config is in withdraw-only mode, be set when `param` is 11
+    /// * `swap_hook_program`- The hook program the config's pools may call around swaps, be set when `param` is 12
+    /// * `empty_position_grace_epochs`- The epochs before an empty position can be swept, be set when `param` is 13
+    /// * `param`- The vaule can be 0 | 1 | 2 | 3 | 4 | 5 | 6 | 8 | 9 | 10 | 11 | 12 | 13, otherwise will report a error
     ///
     pub fn update_amm_config(ctx: Context<UpdateAmmConfig>, param: u8, value: u32) -> Result<()> {
         instructions::update_amm_config(ctx, param, value)
     }
 
+    /// Stages a batch of `update_amm_config` calls, executed in chunks by `execute_admin_batch`
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `batch_id`- The id of the batch, part of its address
+    /// * `entries`- The first entries of the batch, more can be appended until it is executed
+    ///
+    pub fn create_admin_batch(
+        ctx: Context<CreateAdminBatch>,
+        batch_id: u64,
+        entries: Vec<AdminBatchEntry>,
+    ) -> Result<()> {
+        instructions::create_admin_batch(ctx, batch_id, entries)
+    }
+
+    /// Appends entries to a batch not executed yet
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `entries`- The entries to append
+    ///
+    pub fn append_admin_batch(
+        ctx: Context<AppendAdminBatch>,
+        entries: Vec<AdminBatchEntry>,
+    ) -> Result<()> {
+        instructions::append_admin_batch(ctx, entries)
+    }
+
+    /// Executes the next chunk of a batch, the amm configs of the chunk are passed in remaining
+    /// accounts. No entry can be appended once a chunk has been executed
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `entries_hash`- The keccak hash of every entry of the batch, as reviewed
+    /// * `max_entries`- The most entries to execute in this chunk
+    ///
+    pub fn execute_admin_batch<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, ExecuteAdminBatch<'info>>,
+        entries_hash: [u8; 32],
+        max_entries: u16,
+    ) -> Result<()> {
+        instructions::execute_admin_batch(ctx, entries_hash, max_entries)
+    }
+
+    /// Closes a batch, whether or not all of it was executed
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn close_admin_batch(ctx: Context<CloseAdminBatch>) -> Result<()> {
+        instructions::close_admin_batch(ctx)
+    }
+
     /// Creates a pool for the given token pair and the initial price
     ///
     /// # Arguments
@@ -102,6 +167,286 @@ pub mod amm_v3 {
         instructions::create_pool(ctx, sqrt_price_x64, open_time)
     }
 
+    /// Creates a pool with a bespoke trade fee, only for whitelisted creators
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `sqrt_price_x64` - the initial sqrt price (amount_token_1 / amount_token_0) of the pool as a Q64.64
+    /// * `open_time` - The timestamp allowed for swap in the pool
+    /// * `trade_fee_rate` - The trade fee of the pool, must be within the custom fee bounds of amm config
+    ///
+    pub fn create_custom_fee_pool(
+        ctx: Context<CreateCustomFeePool>,
+        sqrt_price_x64: u128,
+        open_time: u64,
+        trade_fee_rate: u32,
+    ) -> Result<()> {
+        instructions::create_custom_fee_pool(ctx, sqrt_price_x64, open_time, trade_fee_rate)
+    }
+
+    /// Whitelist a creator to create custom fee pools
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `creator`- The pool creator to be whitelisted
+    ///
+    pub fn create_custom_fee_creator(
+        ctx: Context<CreateCustomFeeCreator>,
+        creator: Pubkey,
+    ) -> Result<()> {
+        instructions::create_custom_fee_creator(ctx, creator)
+    }
+
+    /// Remove a creator from the custom fee whitelist
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn close_custom_fee_creator(ctx: Context<CloseCustomFeeCreator>) -> Result<()> {
+        instructions::close_custom_fee_creator(ctx)
+    }
+
+    /// Set the preferred quote orientation of the pool price, only for the pool creator
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `quote_orientation` - 0: token_1 per token_0, 1: token_0 per token_1
+    ///
+    pub fn set_quote_orientation(
+        ctx: Context<SetQuoteOrientation>,
+        quote_orientation: u8,
+    ) -> Result<()> {
+        instructions::set_quote_orientation(ctx, quote_orientation)
+    }
+
+    /// Enable or disable the ObservationEvent emitted when a new observation is written,
+    /// only for the pool creator
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `enabled` - Whether to emit the event
+    ///
+    pub fn set_observation_event(ctx: Context<SetObservationEvent>, enabled: bool) -> Result<()> {
+        instructions::set_observation_event(ctx, enabled)
+    }
+
+    /// Set the largest price move a single swap may cause in the pool, swaps moving the price
+    /// further fail. Only for the pool creator or the admin
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `max_price_impact_bps` - The ceiling in bips of the price before the swap, 0 removes it
+    ///
+    pub fn set_max_price_impact(
+        ctx: Context<SetMaxPriceImpact>,
+        max_price_impact_bps: u16,
+    ) -> Result<()> {
+        instructions::set_max_price_impact(ctx, max_price_impact_bps)
+    }
+
+    /// Set the smallest input a swap of the pool may take in each token, smaller swaps fail.
+    /// Only for the pool creator or the admin
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `min_swap_amount_0` - The minimum token_0 input in its base units, 0 removes it
+    /// * `min_swap_amount_1` - The minimum token_1 input in its base units, 0 removes it
+    ///
+    pub fn set_min_swap_amount(
+        ctx: Context<SetMinSwapAmount>,
+        min_swap_amount_0: u64,
+        min_swap_amount_1: u64,
+    ) -> Result<()> {
+        instructions::set_min_swap_amount(ctx, min_swap_amount_0, min_swap_amount_1)
+    }
+
+    /// Set the hook program called before and after each swap of the pool, it must be the one whitelisted by the pool's config.
+    /// Swaps pass the hook program and at most `MAX_SWAP_HOOK_ACCOUNTS` accounts for it at the end of their remaining accounts.
+    /// Only for the pool creator or the admin
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `swap_hook_program` - The hook program, default removes the hook
+    ///
+    pub fn set_swap_hook(ctx: Context<SetSwapHook>, swap_hook_program: Pubkey) -> Result<()> {
+        instructions::set_swap_hook(ctx, swap_hook_program)
+    }
+
+    /// Write a new fee growth snapshot of the pool if the snapshot interval has passed,
+    /// callable by anyone
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn update_fee_growth_snapshot(ctx: Context<UpdateFeeGrowthSnapshot>) -> Result<()> {
+        instructions::update_fee_growth_snapshot(ctx)
+    }
+
+    /// Swap the configured share of the pool's protocol fee into the buyback token,
+    /// callable by anyone once the buyback interval has passed
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn crank_buyback<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CrankBuyback<'info>>,
+    ) -> Result<()> {
+        instructions::crank_buyback(ctx)
+    }
+
+    /// Commit the merkle root of `(owner, in-range liquidity)` for the pool at the current epoch,
+    /// the root is final once the challenge window passes without a dispute
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `merkle_root` - The root of the tree of `(owner, liquidity)` leaves
+    ///
+    pub fn commit_liquidity_snapshot(
+        ctx: Context<CommitLiquiditySnapshot>,
+        merkle_root: [u8; 32],
+    ) -> Result<()> {
+        instructions::commit_liquidity_snapshot(ctx, merkle_root)
+    }
+
+    /// Emit an `EpochFeeReportEvent` with the pool's volume and fees since the last report once
+    /// its epoch is over, callable by anyone. The first call only starts the reported epoch
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn crank_epoch_fee_report(ctx: Context<CrankEpochFeeReport>) -> Result<()> {
+        instructions::crank_epoch_fee_report(ctx)
+    }
+
+    /// Get the decimal adjusted pool price in both orientations, returned through return data
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn get_pool_price(ctx: Context<GetPoolPrice>) -> Result<PoolPrice> {
+        instructions::get_pool_price(ctx)
+    }
+
+    /// Write a new observation at the current tick of a pool that hasn't swapped for a while,
+    /// so its twap keeps following the price. Anyone can call it once the newest observation
+    /// is `OBSERVATION_POKE_MIN_AGE` seconds old, or `OBSERVATION_UPDATE_DURATION_DEFAULT`
+    /// seconds if the pool has oracle consumers
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn poke_observation(ctx: Context<PokeObservation>) -> Result<()> {
+        instructions::poke_observation(ctx)
+    }
+
+    /// Recompute a pool's tick from its price and its liquidity from its ticks, and report any
+    /// drift. Anyone can call it, small liquidity drift and stale tick array counts are repaired
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn validate_pool<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, ValidatePool<'info>>,
+    ) -> Result<()> {
+        instructions::validate_pool(ctx)
+    }
+
+    /// Register the signer as a consumer of the pool's oracle, paying the rent of the
+    /// registration. Pools with consumers can have their observations poked more often
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `max_staleness` - The oldest the newest observation can be for the consumer, in seconds
+    ///
+    pub fn register_oracle_consumer(
+        ctx: Context<RegisterOracleConsumer>,
+        max_staleness: u32,
+    ) -> Result<()> {
+        instructions::register_oracle_consumer(ctx, max_staleness)
+    }
+
+    /// Deregister an oracle consumer, returning the rent to it
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn deregister_oracle_consumer(ctx: Context<DeregisterOracleConsumer>) -> Result<()> {
+        instructions::deregister_oracle_consumer(ctx)
+    }
+
+    /// Get the pool's time weighted average tick over the last `window_seconds`, returned through
+    /// return data with a flag telling whether the observations back it well enough
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `window_seconds` - The seconds of pool history to average over
+    ///
+    pub fn get_twap(ctx: Context<GetTwap>, window_seconds: u32) -> Result<TwapResult> {
+        instructions::get_twap(ctx, window_seconds)
+    }
+
+    /// Sum the accrued, uncollected protocol fees of the pools passed in remaining accounts per
+    /// mint, returned through return data. The pools can belong to any config
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts, remaining accounts are the pools to report
+    ///
+    pub fn get_protocol_fee_report<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, GetProtocolFeeReport>,
+    ) -> Result<ProtocolFeeReport> {
+        instructions::get_protocol_fee_report(ctx)
+    }
+
+    /// Approve the incentive programs that can enroll positions of a config's pools in their
+    /// campaigns, replacing the previous list
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `programs` - The approved programs, at most `INCENTIVE_PROGRAMS_MAX`
+    ///
+    pub fn set_incentive_programs(
+        ctx: Context<SetIncentivePrograms>,
+        programs: Vec<Pubkey>,
+    ) -> Result<()> {
+        instructions::set_incentive_programs(ctx, programs)
+    }
+
+    /// Enroll a position in the campaign of the calling incentive program, or withdraw it.
+    /// The incentive program must invoke it directly from a top level instruction.
+    /// A position is enrolled in one campaign at a time
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `enrolled` - Whether to enroll or withdraw the position
+    ///
+    pub fn set_position_incentive(
+        ctx: Context<SetPositionIncentive>,
+        enrolled: bool,
+    ) -> Result<()> {
+        instructions::set_position_incentive(ctx, enrolled)
+    }
+
     /// Update pool status for given vaule
     ///
     /// # Arguments
@@ -113,6 +458,43 @@ pub mod amm_v3 {
         instructions::update_pool_status(ctx, status)
     }
 
+    /// Configure the dead man's switch and record a heartbeat
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `inactivity_period` - The seconds without a heartbeat after which pools can be set
+    /// withdraw-only, 0 disables the switch
+    /// * `guardian` - Who can set pools withdraw-only, the default pubkey lets anyone do it
+    ///
+    pub fn set_admin_heartbeat(
+        ctx: Context<SetAdminHeartbeat>,
+        inactivity_period: u64,
+        guardian: Pubkey,
+    ) -> Result<()> {
+        instructions::set_admin_heartbeat(ctx, inactivity_period, guardian)
+    }
+
+    /// Record that the admin is still active
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn send_admin_heartbeat(ctx: Context<SendAdminHeartbeat>) -> Result<()> {
+        instructions::send_admin_heartbeat(ctx)
+    }
+
+    /// Put a pool in withdraw-only mode once the admin has missed its heartbeat
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn set_pool_withdraw_only(ctx: Context<SetPoolWithdrawOnly>) -> Result<()> {
+        instructions::set_pool_withdraw_only(ctx)
+    }
+
     /// Creates an operation account for the program
     ///
     /// # Arguments
@@ -258,62 +640,384 @@ pub mod amm_v3 {
         instructions::collect_fund_fee(ctx, amount_0_requested, amount_1_requested)
     }
 
-    /// #[deprecated(note = "Use `open_position_with_token22_nft` instead.")]
-    /// Creates a new position wrapped in a NFT
+    /// Register the partner receiving a share of the pool's trade fees, or remove it with the
+    /// default pubkey and a zero rate
     ///
     /// # Arguments
     ///
     /// * `ctx` - The context of accounts
-    /// * `tick_lower_index` - The low boundary of market
-    /// * `tick_upper_index` - The upper boundary of market
-    /// * `tick_array_lower_start_index` - The start index of tick array which include tick low
-    /// * `tick_array_upper_start_index` - The start index of tick array which include tick upper
-    /// * `liquidity` - The liquidity to be added
-    /// * `amount_0_max` - The max amount of token_0 to spend, which serves as a slippage check
-    /// * `amount_1_max` - The max amount of token_1 to spend, which serves as a slippage check
+    /// * `partner` - The partner, can be replaced once its fees are collected
+    /// * `partner_fee_rate` - The partner's share of trade fees, in hundredths of a bip
     ///
-    pub fn open_position<'a, 'b, 'c: 'info, 'info>(
-        ctx: Context<'a, 'b, 'c, 'info, OpenPosition<'info>>,
-        tick_lower_index: i32,
-        tick_upper_index: i32,
-        tick_array_lower_start_index: i32,
-        tick_array_upper_start_index: i32,
-        liquidity: u128,
-        amount_0_max: u64,
-        amount_1_max: u64,
+    pub fn set_pool_partner(
+        ctx: Context<SetPoolPartner>,
+        partner: Pubkey,
+        partner_fee_rate: u32,
     ) -> Result<()> {
-        instructions::open_position_v1(
-            ctx,
-            liquidity,
-            amount_0_max,
-            amount_1_max,
-            tick_lower_index,
-            tick_upper_index,
-            tick_array_lower_start_index,
-            tick_array_upper_start_index,
-            true,
-            None,
-        )
+        instructions::set_pool_partner(ctx, partner, partner_fee_rate)
     }
 
-    /// #[deprecated(note = "Use `open_position_with_token22_nft` instead.")]
-    /// Creates a new position wrapped in a NFT, support Token2022
+    /// Set the token_0 volume a trader must swap in an epoch of the pool for it to count
+    /// toward a volume rebate tier, zero disables the rebates
     ///
     /// # Arguments
     ///
     /// * `ctx` - The context of accounts
-    /// * `tick_lower_index` - The low boundary of market
-    /// * `tick_upper_index` - The upper boundary of market
-    /// * `tick_array_lower_start_index` - The start index of tick array which include tick low
-    /// * `tick_array_upper_start_index` - The start index of tick array which include tick upper
-    /// * `liquidity` - The liquidity to be added, if zero, and the base_flage is specified, calculate liquidity base amount_0_max or amount_1_max according base_flag, otherwise open position with zero liquidity
-    /// * `amount_0_max` - The max amount of token_0 to spend, which serves as a slippage check
-    /// * `amount_1_max` - The max amount of token_1 to spend, which serves as a slippage check
-    /// * `with_metadata` - The flag indicating whether to create NFT mint metadata
-    /// * `base_flag` - if the liquidity specified as zero, true: calculate liquidity base amount_0_max otherwise base amount_1_max
+    /// * `threshold` - The qualifying epoch volume, in token_0
     ///
-    pub fn open_position_v2<'a, 'b, 'c: 'info, 'info>(
-        ctx: Context<'a, 'b, 'c, 'info, OpenPositionV2<'info>>,
+    pub fn set_volume_rebate_threshold(
+        ctx: Context<SetVolumeRebateThreshold>,
+        threshold: u64,
+    ) -> Result<()> {
+        instructions::set_volume_rebate_threshold(ctx, threshold)
+    }
+
+    /// Resize amm configs created with an older layout to their current size, must be called by
+    /// the admin right after the program upgrade that grew the layout: until then the configs
+    /// fail to deserialize. More configs can be passed as remaining accounts
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn migrate_account_space<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, MigrateAccountSpace<'info>>,
+    ) -> Result<()> {
+        instructions::migrate_account_space(ctx)
+    }
+
+    /// Rewrite the fee growth snapshots of a position left stale by a past bug, must be called by the admin.
+    /// Each snapshot can only be moved forward, up to the current fee growth inside the position's range
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `fee_growth_inside_0_last_x64` - The token_0 fee growth snapshot to write
+    /// * `fee_growth_inside_1_last_x64` - The token_1 fee growth snapshot to write
+    ///
+    pub fn repair_position_checkpoint(
+        ctx: Context<RepairPositionCheckpoint>,
+        fee_growth_inside_0_last_x64: u128,
+        fee_growth_inside_1_last_x64: u128,
+    ) -> Result<()> {
+        instructions::repair_position_checkpoint(
+            ctx,
+            fee_growth_inside_0_last_x64,
+            fee_growth_inside_1_last_x64,
+        )
+    }
+
+    /// Collect the partner fee accrued to the pool, signed by the partner
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount_0_requested` - The maximum amount of token_0 to send, can be 0 to collect fees in only token_1
+    /// * `amount_1_requested` - The maximum amount of token_1 to send, can be 0 to collect fees in only token_0
+    ///
+    pub fn collect_partner_fee(
+        ctx: Context<CollectPartnerFee>,
+        amount_0_requested: u64,
+        amount_1_requested: u64,
+    ) -> Result<()> {
+        instructions::collect_partner_fee(ctx, amount_0_requested, amount_1_requested)
+    }
+
+    /// Creates the referral account of the signer in a pool, swaps passing it credit the
+    /// signer with the config's `referral_fee_rate` of their trade fee
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn create_referral_account(ctx: Context<CreateReferralAccount>) -> Result<()> {
+        instructions::create_referral_account(ctx)
+    }
+
+    /// Collect the referral fee credited to a referral account, signed by the referrer
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount_0_requested` - The maximum amount of token_0 to send, can be 0 to collect fees in only token_1
+    /// * `amount_1_requested` - The maximum amount of token_1 to send, can be 0 to collect fees in only token_0
+    ///
+    pub fn collect_referral_fee(
+        ctx: Context<CollectReferralFee>,
+        amount_0_requested: u64,
+        amount_1_requested: u64,
+    ) -> Result<()> {
+        instructions::collect_referral_fee(ctx, amount_0_requested, amount_1_requested)
+    }
+
+    /// Creates the user stats of the signer in a pool, swaps passing it track the signer's
+    /// volume per epoch and rebate part of the protocol fee once enough epochs qualify
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn create_user_stats(ctx: Context<CreateUserStats>) -> Result<()> {
+        instructions::create_user_stats(ctx)
+    }
+
+    /// Collect the volume rebates credited to a user stats, signed by its owner
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount_0_requested` - The maximum amount of token_0 to send, can be 0 to collect rebates in only token_1
+    /// * `amount_1_requested` - The maximum amount of token_1 to send, can be 0 to collect rebates in only token_0
+    ///
+    pub fn collect_volume_rebate(
+        ctx: Context<CollectVolumeRebate>,
+        amount_0_requested: u64,
+        amount_1_requested: u64,
+    ) -> Result<()> {
+        instructions::collect_volume_rebate(ctx, amount_0_requested, amount_1_requested)
+    }
+
+    /// Escrow a position nft under a launch schedule, which moves the position's range down
+    /// by `tick_shift` every `shift_interval` seconds until its lower tick reaches the floor
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `start_time` - The timestamp the schedule counts the intervals from
+    /// * `shift_interval` - The seconds between two shifts
+    /// * `tick_shift` - The ticks each shift moves the range down, a multiple of the tick spacing
+    /// * `tick_lower_floor` - The lowest lower tick of the range
+    /// * `beneficiary` - The owner of the token accounts receiving the fees and the amounts not redeposited
+    ///
+    pub fn create_launch_schedule(
+        ctx: Context<CreateLaunchSchedule>,
+        start_time: u64,
+        shift_interval: u64,
+        tick_shift: i32,
+        tick_lower_floor: i32,
+        beneficiary: Pubkey,
+    ) -> Result<()> {
+        instructions::create_launch_schedule(
+            ctx,
+            start_time,
+            shift_interval,
+            tick_shift,
+            tick_lower_floor,
+            beneficiary,
+        )
+    }
+
+    /// Close a launch schedule and return the position nft to the admin
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn close_launch_schedule(ctx: Context<CloseLaunchSchedule>) -> Result<()> {
+        instructions::close_launch_schedule(ctx)
+    }
+
+    /// Permissionless crank moving a launch position to the range its schedule has reached
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn shift_launch_position<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, ShiftLaunchPosition<'info>>,
+    ) -> Result<()> {
+        instructions::shift_launch_position(ctx)
+    }
+
+    /// Swap accrued protocol fee into the treasury token through one of the program's pools
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount_requested` - The maximum amount of fee token to convert
+    /// * `twap_window` - The seconds of swap pool history the reference price is averaged over
+    /// * `max_slippage_bps` - The most the output may trail the twap price, in basis points
+    ///
+    pub fn convert_protocol_fee<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, ConvertProtocolFee<'info>>,
+        amount_requested: u64,
+        twap_window: u32,
+        max_slippage_bps: u16,
+    ) -> Result<()> {
+        instructions::convert_protocol_fee(ctx, amount_requested, twap_window, max_slippage_bps)
+    }
+
+    /// Create a buyback funded by a share of the pool's protocol fee
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `protocol_fee_share_rate` - The share of the other token's protocol fee swapped per crank, denominated in hundredths of a bip
+    /// * `interval` - The minimum seconds between two cranks
+    /// * `twap_window` - The seconds of pool history the reference price is averaged over
+    /// * `max_slippage_bps` - The most a buyback may trail the twap price, in basis points
+    /// * `burn` - Burn the bought tokens if true, keep them locked in the buyback vault otherwise
+    ///
+    pub fn create_buyback(
+        ctx: Context<CreateBuyback>,
+        protocol_fee_share_rate: u32,
+        interval: u64,
+        twap_window: u32,
+        max_slippage_bps: u16,
+        burn: bool,
+    ) -> Result<()> {
+        instructions::create_buyback(
+            ctx,
+            protocol_fee_share_rate,
+            interval,
+            twap_window,
+            max_slippage_bps,
+            burn,
+        )
+    }
+
+    /// Reject a liquidity snapshot still in its challenge window, closing it
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn dispute_liquidity_snapshot(ctx: Context<DisputeLiquiditySnapshot>) -> Result<()> {
+        instructions::dispute_liquidity_snapshot(ctx)
+    }
+
+    /// Set the programs whose swaps through CPI pay no protocol fee in the pools of a config
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `programs` - The exempted program ids, replacing the current list
+    ///
+    pub fn set_fee_exempt_programs(
+        ctx: Context<SetFeeExemptPrograms>,
+        programs: Vec<Pubkey>,
+    ) -> Result<()> {
+        instructions::set_fee_exempt_programs(ctx, programs)
+    }
+
+    /// Create a distributor sharing the protocol fee of one token with the stakers of another token
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `protocol_fee_share_rate` - The share of protocol fee sent to the distributor
+    /// * `epoch_duration` - The length of a distribution epoch in seconds
+    ///
+    pub fn create_fee_distributor(
+        ctx: Context<CreateFeeDistributor>,
+        protocol_fee_share_rate: u32,
+        epoch_duration: u64,
+    ) -> Result<()> {
+        instructions::create_fee_distributor(ctx, protocol_fee_share_rate, epoch_duration)
+    }
+
+    /// Send the configured share of a pool's protocol fee to the distributor
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn distribute_protocol_fee(ctx: Context<DistributeProtocolFee>) -> Result<()> {
+        instructions::distribute_protocol_fee(ctx)
+    }
+
+    /// Stake tokens into a fee distributor
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount` - The amount of tokens to stake
+    ///
+    pub fn stake_fee_distributor(ctx: Context<StakeFeeDistributor>, amount: u64) -> Result<()> {
+        instructions::stake_fee_distributor(ctx, amount)
+    }
+
+    /// Unstake tokens from a fee distributor
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount` - The amount of tokens to unstake
+    ///
+    pub fn unstake_fee_distributor(ctx: Context<UnstakeFeeDistributor>, amount: u64) -> Result<()> {
+        instructions::unstake_fee_distributor(ctx, amount)
+    }
+
+    /// Claim the distributed protocol fee of a staker
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn claim_distributor_reward(ctx: Context<ClaimDistributorReward>) -> Result<()> {
+        instructions::claim_distributor_reward(ctx)
+    }
+
+    /// #[deprecated(note = "Use `open_position_with_token22_nft` instead.")]
+    /// Creates a new position wrapped in a NFT
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `tick_lower_index` - The low boundary of market
+    /// * `tick_upper_index` - The upper boundary of market
+    /// * `tick_array_lower_start_index` - The start index of tick array which include tick low
+    /// * `tick_array_upper_start_index` - The start index of tick array which include tick upper
+    /// * `liquidity` - The liquidity to be added
+    /// * `amount_0_max` - The max amount of token_0 to spend, which serves as a slippage check
+    /// * `amount_1_max` - The max amount of token_1 to spend, which serves as a slippage check
+    /// * `deadline` - The unix timestamp after which the transaction is rejected
+    ///
+    pub fn open_position<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, OpenPosition<'info>>,
+        tick_lower_index: i32,
+        tick_upper_index: i32,
+        tick_array_lower_start_index: i32,
+        tick_array_upper_start_index: i32,
+        liquidity: u128,
+        amount_0_max: u64,
+        amount_1_max: u64,
+        deadline: u64,
+    ) -> Result<()> {
+        util::check_deadline(deadline)?;
+        instructions::open_position_v1(
+            ctx,
+            liquidity,
+            amount_0_max,
+            amount_1_max,
+            tick_lower_index,
+            tick_upper_index,
+            tick_array_lower_start_index,
+            tick_array_upper_start_index,
+            true,
+            None,
+        )
+    }
+
+    /// #[deprecated(note = "Use `open_position_with_token22_nft` instead.")]
+    /// Creates a new position wrapped in a NFT, support Token2022
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `tick_lower_index` - The low boundary of market
+    /// * `tick_upper_index` - The upper boundary of market
+    /// * `tick_array_lower_start_index` - The start index of tick array which include tick low
+    /// * `tick_array_upper_start_index` - The start index of tick array which include tick upper
+    /// * `liquidity` - The liquidity to be added, if zero, and the base_flage is specified, calculate liquidity base amount_0_max or amount_1_max according base_flag, otherwise open position with zero liquidity
+    /// * `amount_0_max` - The max amount of token_0 to spend, which serves as a slippage check
+    /// * `amount_1_max` - The max amount of token_1 to spend, which serves as a slippage check
+    /// * `with_metadata` - The flag indicating whether to create NFT mint metadata
+    /// * `base_flag` - if the liquidity specified as zero, true: calculate liquidity base amount_0_max otherwise base amount_1_max
+    /// * `deadline` - The unix timestamp after which the transaction is rejected, not checked if none
+    ///
+    pub fn open_position_v2<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, OpenPositionV2<'info>>,
         tick_lower_index: i32,
         tick_upper_index: i32,
         tick_array_lower_start_index: i32,
@@ -323,7 +1027,11 @@ pub mod amm_v3 {
         amount_1_max: u64,
         with_metadata: bool,
         base_flag: Option<bool>,
+        deadline: Option<u64>,
     ) -> Result<()> {
+        if let Some(deadline) = deadline {
+            util::check_deadline(deadline)?;
+        }
         instructions::open_position_v2(
             ctx,
             liquidity,
@@ -338,158 +1046,981 @@ pub mod amm_v3 {
         )
     }
 
-    /// Creates a new position wrapped in a Token2022 NFT without relying on metadata_program and metadata_account, reduce the cost for user to create a personal position.
+    /// Creates a new position wrapped in a Token2022 NFT without relying on metadata_program and metadata_account, reduce the cost for user to create a personal position.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `tick_lower_index` - The low boundary of market
+    /// * `tick_upper_index` - The upper boundary of market
+    /// * `tick_array_lower_start_index` - The start index of tick array which include tick low
+    /// * `tick_array_upper_start_index` - The start index of tick array which include tick upper
+    /// * `liquidity` - The liquidity to be added, if zero, and the base_flage is specified, calculate liquidity base amount_0_max or amount_1_max according base_flag, otherwise open position with zero liquidity
+    /// * `amount_0_max` - The max amount of token_0 to spend, which serves as a slippage check
+    /// * `amount_1_max` - The max amount of token_1 to spend, which serves as a slippage check
+    /// * `with_metadata` - The flag indicating whether to create NFT mint metadata
+    /// * `base_flag` - if the liquidity specified as zero, true: calculate liquidity base amount_0_max otherwise base amount_1_max
+    ///
+    pub fn open_position_with_token22_nft<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, OpenPositionWithToken22Nft<'info>>,
+        tick_lower_index: i32,
+        tick_upper_index: i32,
+        tick_array_lower_start_index: i32,
+        tick_array_upper_start_index: i32,
+        liquidity: u128,
+        amount_0_max: u64,
+        amount_1_max: u64,
+        with_metadata: bool,
+        base_flag: Option<bool>,
+    ) -> Result<()> {
+        instructions::open_position_with_token22_nft(
+            ctx,
+            liquidity,
+            amount_0_max,
+            amount_1_max,
+            tick_lower_index,
+            tick_upper_index,
+            tick_array_lower_start_index,
+            tick_array_upper_start_index,
+            with_metadata,
+            base_flag,
+        )
+    }
+
+    /// Opens a ladder of adjacent positions from one deposit, each rung wrapped in its own Token2022 NFT.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `tick_lower_index` - The low boundary of the first rung
+    /// * `rung_tick_width` - The tick width of every rung, must be a multiple of tick spacing
+    /// * `liquidity` - The total liquidity to be added across all rungs
+    /// * `weights` - The share of `liquidity` given to each rung, one entry per rung
+    /// * `amount_0_max` - The max total amount of token_0 to spend, which serves as a slippage check
+    /// * `amount_1_max` - The max total amount of token_1 to spend, which serves as a slippage check
+    /// * `with_metadata` - The flag indicating whether to create NFT mint metadata
+    ///
+    pub fn create_position_ladder<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CreatePositionLadder<'info>>,
+        tick_lower_index: i32,
+        rung_tick_width: i32,
+        liquidity: u128,
+        weights: Vec<u32>,
+        amount_0_max: u64,
+        amount_1_max: u64,
+        with_metadata: bool,
+    ) -> Result<()> {
+        instructions::create_position_ladder(
+            ctx,
+            tick_lower_index,
+            rung_tick_width,
+            liquidity,
+            weights,
+            amount_0_max,
+            amount_1_max,
+            with_metadata,
+        )
+    }
+
+    /// Opens the ladder described by a strategy preset, centered on the pool's current tick.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `liquidity` - The total liquidity to be added across all rungs
+    /// * `amount_0_max` - The max total amount of token_0 to spend, which serves as a slippage check
+    /// * `amount_1_max` - The max total amount of token_1 to spend, which serves as a slippage check
+    /// * `with_metadata` - The flag indicating whether to create NFT mint metadata
+    ///
+    pub fn create_position_ladder_with_preset<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CreatePositionLadderWithPreset<'info>>,
+        liquidity: u128,
+        amount_0_max: u64,
+        amount_1_max: u64,
+        with_metadata: bool,
+    ) -> Result<()> {
+        instructions::create_position_ladder_with_preset(
+            ctx,
+            liquidity,
+            amount_0_max,
+            amount_1_max,
+            with_metadata,
+        )
+    }
+
+    /// Create a named strategy preset owned by the signer
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `name` - The preset name, utf8 padded with zeros
+    /// * `tick_width` - The total width in ticks of the band around the current tick
+    /// * `weights` - The liquidity share of each rung, from the lowest range to the highest
+    /// * `flags` - Bit flags for off-chain executors, bit 0 requests auto-compounding
+    ///
+    pub fn create_strategy_preset(
+        ctx: Context<CreateStrategyPreset>,
+        name: [u8; 32],
+        tick_width: i32,
+        weights: Vec<u32>,
+        flags: u8,
+    ) -> Result<()> {
+        instructions::create_strategy_preset(ctx, name, tick_width, weights, flags)
+    }
+
+    /// Close a strategy preset, returning the rent to its creator
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn close_strategy_preset(ctx: Context<CloseStrategyPreset>) -> Result<()> {
+        instructions::close_strategy_preset(ctx)
+    }
+
+    /// Close the user's position and NFT account. If the NFT mint belongs to token2022, it will also be closed and the funds returned to the NFT owner,
+    /// or to the optional `rent_recipient` account when a sponsor paid for the position.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn close_position<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, ClosePosition<'info>>,
+    ) -> Result<()> {
+        instructions::close_position(ctx)
+    }
+
+    /// Close a position that has held no liquidity and nothing owed for the config's grace
+    /// period. Anyone can call it, the reclaimed rent is split between the caller and the nft holder.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn sweep_empty_position(ctx: Context<SweepEmptyPosition>) -> Result<()> {
+        instructions::sweep_empty_position(ctx)
+    }
+
+    /// Creates a position bundle, a single Token2022 NFT that can hold many positions.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn initialize_position_bundle(ctx: Context<InitializePositionBundle>) -> Result<()> {
+        instructions::initialize_position_bundle(ctx)
+    }
+
+    /// Opens an empty position in a bundle slot, managed afterwards by the bundle NFT holder
+    /// through the v2 liquidity instructions.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `bundle_index` - The bundle slot to open the position in
+    /// * `tick_lower_index` - The low boundary of market
+    /// * `tick_upper_index` - The upper boundary of market
+    /// * `tick_array_lower_start_index` - The start index of tick array which include tick low
+    /// * `tick_array_upper_start_index` - The start index of tick array which include tick upper
+    ///
+    pub fn open_bundled_position<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, OpenBundledPosition<'info>>,
+        bundle_index: u16,
+        tick_lower_index: i32,
+        tick_upper_index: i32,
+        tick_array_lower_start_index: i32,
+        tick_array_upper_start_index: i32,
+    ) -> Result<()> {
+        instructions::open_bundled_position(
+            ctx,
+            bundle_index,
+            tick_lower_index,
+            tick_upper_index,
+            tick_array_lower_start_index,
+            tick_array_upper_start_index,
+        )
+    }
+
+    /// Closes an empty bundled position and frees its slot.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `bundle_index` - The bundle slot of the position
+    ///
+    pub fn close_bundled_position(
+        ctx: Context<CloseBundledPosition>,
+        bundle_index: u16,
+    ) -> Result<()> {
+        instructions::close_bundled_position(ctx, bundle_index)
+    }
+
+    /// Burns the bundle NFT and closes the bundle, all its positions must be closed.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn delete_position_bundle(ctx: Context<DeletePositionBundle>) -> Result<()> {
+        instructions::delete_position_bundle(ctx)
+    }
+
+    /// Moves every out of range position among the given bundle slots to a new range around
+    /// the current price, withdrawing its liquidity and fees and depositing the template liquidity.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `bundle_indexes` - The bundle slots to check, one group of remaining accounts per slot
+    /// * `template` - The new range, relative to the current tick, and the liquidity of each reopened position
+    /// * `amount_0_max` - The max net amount of token_0 to spend, which serves as a slippage check
+    /// * `amount_1_max` - The max net amount of token_1 to spend, which serves as a slippage check
+    ///
+    pub fn rebalance_bundle<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, RebalanceBundle<'info>>,
+        bundle_indexes: Vec<u16>,
+        template: BundleRebalanceTemplate,
+        amount_0_max: u64,
+        amount_1_max: u64,
+    ) -> Result<()> {
+        instructions::rebalance_bundle(ctx, bundle_indexes, template, amount_0_max, amount_1_max)
+    }
+
+    /// Move a position's liquidity to a new range in place, keeping its nft. The fees are paid
+    /// out along with the withdrawn tokens the new range doesn't take
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `new_tick_lower_index` - The lower tick of the new range
+    /// * `new_tick_upper_index` - The upper tick of the new range
+    /// * `liquidity_min` - The least liquidity the new range must receive, which serves as a slippage check
+    ///
+    pub fn rebalance_position<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, RebalancePosition<'info>>,
+        new_tick_lower_index: i32,
+        new_tick_upper_index: i32,
+        liquidity_min: u128,
+    ) -> Result<()> {
+        instructions::rebalance_position(
+            ctx,
+            new_tick_lower_index,
+            new_tick_upper_index,
+            liquidity_min,
+        )
+    }
+
+    /// Lock a position nft into the program escrow until `unlock_time`. When the receipt accounts
+    /// are passed, a non-transferable receipt with the position's metadata is minted to the owner
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `unlock_time` - The timestamp after which the position can be unlocked
+    ///
+    pub fn lock_position(ctx: Context<LockPosition>, unlock_time: u64) -> Result<()> {
+        instructions::lock_position(ctx, unlock_time)
+    }
+
+    /// Extend the unlock time of a locked position, the unlock time can't be shortened
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `unlock_time` - The new unlock timestamp, must be later than the current one
+    ///
+    pub fn extend_position_lock(ctx: Context<ExtendPositionLock>, unlock_time: u64) -> Result<()> {
+        instructions::extend_position_lock(ctx, unlock_time)
+    }
+
+    /// Return the position nft to the owner once the unlock time has passed
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn unlock_position(ctx: Context<UnlockPosition>) -> Result<()> {
+        instructions::unlock_position(ctx)
+    }
+
+    /// Collect fees and rewards of a locked position to the original owner
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn collect_locked_position_fee<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CollectLockedPositionFee<'info>>,
+    ) -> Result<()> {
+        instructions::collect_locked_position_fee(ctx)
+    }
+
+    /// Lease a position to a lessee until `expires_at`. The nft is held in the program escrow
+    /// and the lessee can collect the position's fees and rewards until the lease expires, the
+    /// fees and rewards accrued before are collected to the owner
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `expires_at` - The timestamp the lessee's rights end at
+    ///
+    pub fn lease_position<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, LeasePosition<'info>>,
+        expires_at: u64,
+    ) -> Result<()> {
+        instructions::lease_position(ctx, expires_at)
+    }
+
+    /// Collect fees and rewards of a leased position to the lessee while the lease is active
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn collect_leased_position_fee<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CollectLeasedPositionFee<'info>>,
+    ) -> Result<()> {
+        instructions::collect_leased_position_fee(ctx)
+    }
+
+    /// Return the nft of an expired lease to the owner and close the lease
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn end_position_lease(ctx: Context<EndPositionLease>) -> Result<()> {
+        instructions::end_position_lease(ctx)
+    }
+
+    /// Place a stop-loss on a position, removing all liquidity to the owner once the
+    /// pool's time weighted average tick crosses `stop_tick`
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `stop_tick` - The tick that triggers the order
+    /// * `trigger_below` - Trigger when the average tick is at or below `stop_tick`, at or above otherwise
+    /// * `amount_0_min` - The minimum amount of token_0 to withdraw on settlement
+    /// * `amount_1_min` - The minimum amount of token_1 to withdraw on settlement
+    /// * `keeper_reward` - Lamports paid to the keeper settling the order
+    /// * `twap_window` - The seconds of pool history the trigger tick is averaged over
+    ///
+    pub fn place_stop_order(
+        ctx: Context<PlaceStopOrder>,
+        stop_tick: i32,
+        trigger_below: bool,
+        amount_0_min: u64,
+        amount_1_min: u64,
+        keeper_reward: u64,
+        twap_window: u32,
+    ) -> Result<()> {
+        instructions::place_stop_order(
+            ctx,
+            stop_tick,
+            trigger_below,
+            amount_0_min,
+            amount_1_min,
+            keeper_reward,
+            twap_window,
+        )
+    }
+
+    /// Cancel a stop order, returning the rent and keeper reward to the owner
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn cancel_stop_order(ctx: Context<CancelStopOrder>) -> Result<()> {
+        instructions::cancel_stop_order(ctx)
+    }
+
+    /// Settle a triggered stop order, callable by anyone
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn settle_stop_order<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SettleStopOrder<'info>>,
+    ) -> Result<()> {
+        instructions::settle_stop_order(ctx)
+    }
+
+    /// #[deprecated(note = "Use `increase_liquidity_v2` instead.")]
+    /// Increases liquidity with a exist position, with amount paid by `payer`
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `liquidity` - The desired liquidity to be added, can't be zero
+    /// * `amount_0_max` - The max amount of token_0 to spend, which serves as a slippage check
+    /// * `amount_1_max` - The max amount of token_1 to spend, which serves as a slippage check
+    /// * `deadline` - The unix timestamp after which the transaction is rejected
+    ///
+    pub fn increase_liquidity<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, IncreaseLiquidity<'info>>,
+        liquidity: u128,
+        amount_0_max: u64,
+        amount_1_max: u64,
+        deadline: u64,
+    ) -> Result<()> {
+        assert!(liquidity != 0);
+        util::check_deadline(deadline)?;
+        instructions::increase_liquidity_v1(ctx, liquidity, amount_0_max, amount_1_max, None)
+    }
+
+    /// Increases liquidity with a exist position, with amount paid by `payer`, support Token2022
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `liquidity` - The desired liquidity to be added, if zero, calculate liquidity base amount_0 or amount_1 according base_flag
+    /// * `amount_0_max` - The max amount of token_0 to spend, which serves as a slippage check
+    /// * `amount_1_max` - The max amount of token_1 to spend, which serves as a slippage check
+    /// * `base_flag` - must be specified if liquidity is zero, true: calculate liquidity base amount_0_max otherwise base amount_1_max
+    /// * `deadline` - The unix timestamp after which the transaction is rejected, not checked if none
+    ///
+    pub fn increase_liquidity_v2<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, IncreaseLiquidityV2<'info>>,
+        liquidity: u128,
+        amount_0_max: u64,
+        amount_1_max: u64,
+        base_flag: Option<bool>,
+        deadline: Option<u64>,
+    ) -> Result<()> {
+        if let Some(deadline) = deadline {
+            util::check_deadline(deadline)?;
+        }
+        if liquidity == 0 {
+            assert!(base_flag.is_some());
+        }
+        instructions::increase_liquidity_v2(ctx, liquidity, amount_0_max, amount_1_max, base_flag)
+    }
+
+    /// Increases liquidity with a exist position as `increase_liquidity`, wrapping the SOL of
+    /// the nft owner's native mint token accounts. Those accounts must already exist, e.g.
+    /// created by the client earlier in the transaction, and are closed at the end
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `liquidity` - The desired liquidity to be added, can't be zero
+    /// * `amount_0_max` - The max amount of token_0 to spend, which serves as a slippage check
+    /// * `amount_1_max` - The max amount of token_1 to spend, which serves as a slippage check
+    /// * `deadline` - The unix timestamp after which the transaction is rejected
+    ///
+    pub fn increase_liquidity_with_sol<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, IncreaseLiquidityWithSol<'info>>,
+        liquidity: u128,
+        amount_0_max: u64,
+        amount_1_max: u64,
+        deadline: u64,
+    ) -> Result<()> {
+        assert!(liquidity != 0);
+        util::check_deadline(deadline)?;
+        instructions::increase_liquidity_with_sol(ctx, liquidity, amount_0_max, amount_1_max)
+    }
+
+    /// Creates a new position wrapped in a Token2022 NFT from a single token, part of which is swapped in the pool to match the range
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `tick_lower_index` - The low boundary of market
+    /// * `tick_upper_index` - The upper boundary of market
+    /// * `tick_array_lower_start_index` - The start index of tick array which include tick low
+    /// * `tick_array_upper_start_index` - The start index of tick array which include tick upper
+    /// * `amount` - The amount of the input token to deposit, what the position can't use stays with the payer
+    /// * `is_token_0` - Whether the input token is token_0
+    /// * `liquidity_min` - The minimum liquidity of the position, which serves as a slippage check on the swap and the deposit
+    /// * `with_metadata` - The flag indicating whether to create NFT mint metadata
+    ///
+    pub fn create_position_zap<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CreatePositionZap<'info>>,
+        tick_lower_index: i32,
+        tick_upper_index: i32,
+        tick_array_lower_start_index: i32,
+        tick_array_upper_start_index: i32,
+        amount: u64,
+        is_token_0: bool,
+        liquidity_min: u128,
+        with_metadata: bool,
+    ) -> Result<()> {
+        instructions::create_position_zap(
+            ctx,
+            tick_lower_index,
+            tick_upper_index,
+            tick_array_lower_start_index,
+            tick_array_upper_start_index,
+            amount,
+            is_token_0,
+            liquidity_min,
+            with_metadata,
+        )
+    }
+
+    /// Increases liquidity of an exist position from a single token, part of which is swapped in
+    /// the pool to match the range, support Token2022
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount` - The amount of the input token to deposit, what the deposit can't use stays with the payer
+    /// * `is_token_0` - Whether the input token is token_0
+    /// * `liquidity_min` - The minimum liquidity to add, which serves as a slippage check
+    ///
+    pub fn swap_and_increase_liquidity<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SwapAndIncreaseLiquidity<'info>>,
+        amount: u64,
+        is_token_0: bool,
+        liquidity_min: u128,
+    ) -> Result<()> {
+        instructions::swap_and_increase_liquidity(ctx, amount, is_token_0, liquidity_min)
+    }
+
+    /// Adds the fees owed to a position back to its liquidity, without moving tokens out of the vaults.
+    /// What the range can't take at the current price stays owed to the position
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn compound_fees<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CompoundFees<'info>>,
+    ) -> Result<()> {
+        instructions::compound_fees(ctx)
+    }
+
+    /// Collects the fees owed to many positions of a pool in one transaction, the positions
+    /// are passed in remaining accounts and their fees all go to the same recipients
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn collect_fee_batch<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CollectFeeBatch<'info>>,
+    ) -> Result<()> {
+        instructions::collect_fee_batch(ctx)
+    }
+
+    /// Grant an operator a scope over every position nft the signer holds, so a keeper can
+    /// collect, decrease or compound them without holding the nfts. The operator's payouts
+    /// always go to the holder
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `scope` - The `POSITION_DELEGATE_SCOPE_*` bits granted, 0 revokes the operator
+    ///
+    pub fn set_position_operator(ctx: Context<SetPositionOperator>, scope: u8) -> Result<()> {
+        instructions::set_position_operator(ctx, scope)
+    }
+
+    /// #[deprecated(note = "Use `decrease_liquidity_v2` instead.")]
+    /// Decreases liquidity with a exist position
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` -  The context of accounts
+    /// * `liquidity` - The amount by which liquidity will be decreased
+    /// * `amount_0_min` - The minimum amount of token_0 that should be accounted for the burned liquidity
+    /// * `amount_1_min` - The minimum amount of token_1 that should be accounted for the burned liquidity
+    /// * `deadline` - The unix timestamp after which the transaction is rejected
+    ///
+    pub fn decrease_liquidity<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, DecreaseLiquidity<'info>>,
+        liquidity: u128,
+        amount_0_min: u64,
+        amount_1_min: u64,
+        deadline: u64,
+    ) -> Result<()> {
+        util::check_deadline(deadline)?;
+        instructions::decrease_liquidity_v1(ctx, liquidity, amount_0_min, amount_1_min)
+    }
+
+    /// Decreases liquidity with a exist position, support Token2022
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` -  The context of accounts
+    /// * `liquidity` - The amount by which liquidity will be decreased
+    /// * `amount_0_min` - The minimum amount of token_0 that should be accounted for the burned liquidity
+    /// * `amount_1_min` - The minimum amount of token_1 that should be accounted for the burned liquidity
+    /// * `deadline` - The unix timestamp after which the transaction is rejected, not checked if none
+    ///
+    pub fn decrease_liquidity_v2<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, DecreaseLiquidityV2<'info>>,
+        liquidity: u128,
+        amount_0_min: u64,
+        amount_1_min: u64,
+        deadline: Option<u64>,
+    ) -> Result<()> {
+        if let Some(deadline) = deadline {
+            util::check_deadline(deadline)?;
+        }
+        instructions::decrease_liquidity_v2(ctx, liquidity, amount_0_min, amount_1_min)
+    }
+
+    /// Decreases liquidity with a exist position as `decrease_liquidity`, unwrapping the SOL
+    /// received in the nft owner's native mint token accounts. Those accounts must already
+    /// exist, e.g. created by the client earlier in the transaction
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` -  The context of accounts
+    /// * `liquidity` - The amount by which liquidity will be decreased
+    /// * `amount_0_min` - The minimum amount of token_0 that should be accounted for the burned liquidity
+    /// * `amount_1_min` - The minimum amount of token_1 that should be accounted for the burned liquidity
+    /// * `deadline` - The unix timestamp after which the transaction is rejected
+    ///
+    pub fn decrease_liquidity_with_sol<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, DecreaseLiquidityWithSol<'info>>,
+        liquidity: u128,
+        amount_0_min: u64,
+        amount_1_min: u64,
+        deadline: u64,
+    ) -> Result<()> {
+        util::check_deadline(deadline)?;
+        instructions::decrease_liquidity_with_sol(ctx, liquidity, amount_0_min, amount_1_min)
+    }
+
+    /// Collects the fees and every initialized reward owed to a position in one instruction,
+    /// support Token2022. The remaining accounts are the reward vault, recipient token account
+    /// and reward mint of each initialized reward
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` -  The context of accounts
+    /// * `amount_0_max` - The most token_0 fees to collect
+    /// * `amount_1_max` - The most token_1 fees to collect
+    /// * `reward_amounts_max` - The most of each reward token to collect
+    ///
+    pub fn collect_all<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, DecreaseLiquidityV2<'info>>,
+        amount_0_max: u64,
+        amount_1_max: u64,
+        reward_amounts_max: [u64; REWARD_NUM],
+    ) -> Result<()> {
+        instructions::collect_all(ctx, amount_0_max, amount_1_max, reward_amounts_max)
+    }
+
+    /// Pays the fees and rewards owed to a position out to its owner, then optionally moves the
+    /// position nft to a new holder, so the buyer of a position takes over no owed amounts.
+    /// The remaining accounts are the reward vault, owner's token account and reward mint of
+    /// each initialized reward
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` -  The context of accounts
+    ///
+    pub fn transfer_position<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, TransferPosition<'info>>,
+    ) -> Result<()> {
+        instructions::transfer_position(ctx)
+    }
+
+    /// Decreases liquidity with a exist position and swaps the received token_1 into token_0, or
+    /// the other way round, returning a single token, support Token2022
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` -  The context of accounts
+    /// * `liquidity` - The amount by which liquidity will be decreased
+    /// * `to_token_0` - Whether to return token_0, otherwise token_1
+    /// * `amount_out_min` - The minimum amount of the returned token, from the withdrawal and the swap together
+    ///
+    pub fn decrease_liquidity_to_single_token<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, DecreaseLiquidityToSingleToken<'info>>,
+        liquidity: u128,
+        to_token_0: bool,
+        amount_out_min: u64,
+    ) -> Result<()> {
+        instructions::decrease_liquidity_to_single_token(ctx, liquidity, to_token_0, amount_out_min)
+    }
+
+    /// Donates tokens to the liquidity providers currently in range, credited as trading fees.
+    /// Each amount can be at most the in-range liquidity
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount_0` - The amount of token_0 to donate
+    /// * `amount_1` - The amount of token_1 to donate
+    ///
+    pub fn donate(ctx: Context<Donate>, amount_0: u64, amount_1: u64) -> Result<()> {
+        instructions::donate(ctx, amount_0, amount_1)
+    }
+
+    /// #[deprecated(note = "Use `swap_v2` instead.")]
+    /// Swaps one token for as much as possible of another token across a single pool
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount` - Arranged in pairs with other_amount_threshold. (amount_in, amount_out_minimum) or (amount_out, amount_in_maximum)
+    /// * `other_amount_threshold` - For slippage check
+    /// * `sqrt_price_limit` - The Q64.64 sqrt price √P limit. If zero for one, the price cannot
+    /// * `is_base_input` - swap base input or swap base output
+    /// * `deadline` - The unix timestamp after which the transaction is rejected
+    ///
+    pub fn swap<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SwapSingle<'info>>,
+        amount: u64,
+        other_amount_threshold: u64,
+        sqrt_price_limit_x64: u128,
+        is_base_input: bool,
+        deadline: u64,
+    ) -> Result<SwapResult> {
+        util::check_deadline(deadline)?;
+        instructions::swap(
+            ctx,
+            amount,
+            other_amount_threshold,
+            sqrt_price_limit_x64,
+            is_base_input,
+            false,
+        )
+    }
+
+    /// Same as `swap`, settles the filled part if the swap stops at `sqrt_price_limit_x64`,
+    /// leaving the rest of the input with the payer. A swap running out of liquidity still fails
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount` - Arranged in pairs with other_amount_threshold. (amount_in, amount_out_minimum) or (amount_out, amount_in_maximum)
+    /// * `other_amount_threshold` - For slippage check, prorated to the filled part of `amount`
+    /// * `sqrt_price_limit` - The Q64.64 sqrt price √P limit. If zero for one, the price cannot
+    /// * `is_base_input` - swap base input or swap base output
+    ///
+    pub fn swap_with_partial_fill<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SwapSingle<'info>>,
+        amount: u64,
+        other_amount_threshold: u64,
+        sqrt_price_limit_x64: u128,
+        is_base_input: bool,
+    ) -> Result<SwapResult> {
+        instructions::swap(
+            ctx,
+            amount,
+            other_amount_threshold,
+            sqrt_price_limit_x64,
+            is_base_input,
+            true,
+        )
+    }
+
+    /// Swaps across a single pool as `swap`, with the optional accounts of
+    /// `SwapSingleWithOptions`: native SOL wrapping of the payer's existing native mint token
+    /// accounts, a referrer, the payer's volume rebate stats and an output recipient
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount` - Arranged in pairs with other_amount_threshold. (amount_in, amount_out_minimum) or (amount_out, amount_in_maximum)
+    /// * `other_amount_threshold` - For slippage check, prorated to the filled part of `amount` with `allow_partial_fill`
+    /// * `sqrt_price_limit` - The Q64.64 sqrt price √P limit. If zero for one, the price cannot
+    /// * `is_base_input` - swap base input or swap base output
+    /// * `allow_partial_fill` - Whether a swap stopped by the price limit settles the filled part
+    /// * `deadline` - The unix timestamp after which the transaction is rejected
+    ///
+    pub fn swap_with_options<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SwapSingleWithOptions<'info>>,
+        amount: u64,
+        other_amount_threshold: u64,
+        sqrt_price_limit_x64: u128,
+        is_base_input: bool,
+        allow_partial_fill: bool,
+        deadline: u64,
+    ) -> Result<SwapResult> {
+        util::check_deadline(deadline)?;
+        instructions::swap_with_options(
+            ctx,
+            amount,
+            other_amount_threshold,
+            sqrt_price_limit_x64,
+            is_base_input,
+            allow_partial_fill,
+        )
+    }
+
+    /// Quote a swap against the current pool state without executing it, the quote is written to return data
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount` - The amount in if `is_base_input`, otherwise the amount out
+    /// * `sqrt_price_limit_x64` - The Q64.64 sqrt price √P limit, zero for no limit
+    /// * `zero_for_one` - Whether token_0 is swapped for token_1
+    /// * `is_base_input` - swap base input or swap base output
+    ///
+    pub fn quote_swap<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, QuoteSwap<'info>>,
+        amount: u64,
+        sqrt_price_limit_x64: u128,
+        zero_for_one: bool,
+        is_base_input: bool,
+    ) -> Result<SwapQuote> {
+        instructions::quote_swap(
+            ctx,
+            amount,
+            sqrt_price_limit_x64,
+            zero_for_one,
+            is_base_input,
+        )
+    }
+
+    /// Quote the expected execution price, ending price and price impact of a trade, without
+    /// executing it. The result is written to return data
     ///
     /// # Arguments
     ///
     /// * `ctx` - The context of accounts
-    /// * `tick_lower_index` - The low boundary of market
-    /// * `tick_upper_index` - The upper boundary of market
-    /// * `tick_array_lower_start_index` - The start index of tick array which include tick low
-    /// * `tick_array_upper_start_index` - The start index of tick array which include tick upper
-    /// * `liquidity` - The liquidity to be added, if zero, and the base_flage is specified, calculate liquidity base amount_0_max or amount_1_max according base_flag, otherwise open position with zero liquidity
-    /// * `amount_0_max` - The max amount of token_0 to spend, which serves as a slippage check
-    /// * `amount_1_max` - The max amount of token_1 to spend, which serves as a slippage check
-    /// * `with_metadata` - The flag indicating whether to create NFT mint metadata
-    /// * `base_flag` - if the liquidity specified as zero, true: calculate liquidity base amount_0_max otherwise base amount_1_max
+    /// * `amount` - The amount in if `is_base_input`, otherwise the amount out
+    /// * `zero_for_one` - Whether token_0 is swapped for token_1
+    /// * `is_base_input` - swap base input or swap base output
     ///
-    pub fn open_position_with_token22_nft<'a, 'b, 'c: 'info, 'info>(
-        ctx: Context<'a, 'b, 'c, 'info, OpenPositionWithToken22Nft<'info>>,
-        tick_lower_index: i32,
-        tick_upper_index: i32,
-        tick_array_lower_start_index: i32,
-        tick_array_upper_start_index: i32,
-        liquidity: u128,
-        amount_0_max: u64,
-        amount_1_max: u64,
-        with_metadata: bool,
-        base_flag: Option<bool>,
-    ) -> Result<()> {
-        instructions::open_position_with_token22_nft(
-            ctx,
-            liquidity,
-            amount_0_max,
-            amount_1_max,
-            tick_lower_index,
-            tick_upper_index,
-            tick_array_lower_start_index,
-            tick_array_upper_start_index,
-            with_metadata,
-            base_flag,
-        )
+    pub fn get_price_impact<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, QuoteSwap<'info>>,
+        amount: u64,
+        zero_for_one: bool,
+        is_base_input: bool,
+    ) -> Result<PriceImpact> {
+        instructions::get_price_impact(ctx, amount, zero_for_one, is_base_input)
     }
 
-    /// Close the user's position and NFT account. If the NFT mint belongs to token2022, it will also be closed and the funds returned to the NFT owner.
+    /// Get the fees and rewards a position would collect and the tokens its liquidity would
+    /// withdraw at the current price, returned through return data
     ///
     /// # Arguments
     ///
     /// * `ctx` - The context of accounts
     ///
-    pub fn close_position<'a, 'b, 'c, 'info>(
-        ctx: Context<'a, 'b, 'c, 'info, ClosePosition<'info>>,
-    ) -> Result<()> {
-        instructions::close_position(ctx)
+    pub fn get_position_state(ctx: Context<GetPositionState>) -> Result<PositionSnapshot> {
+        instructions::get_position_state(ctx)
     }
 
-    /// #[deprecated(note = "Use `increase_liquidity_v2` instead.")]
-    /// Increases liquidity with a exist position, with amount paid by `payer`
+    /// Swaps as little as possible of one token for an exact amount of another token across a single pool.
+    /// Fails if the price limit is reached before `amount_out` is filled
     ///
     /// # Arguments
     ///
     /// * `ctx` - The context of accounts
-    /// * `liquidity` - The desired liquidity to be added, can't be zero
-    /// * `amount_0_max` - The max amount of token_0 to spend, which serves as a slippage check
-    /// * `amount_1_max` - The max amount of token_1 to spend, which serves as a slippage check
+    /// * `amount_out` - The exact amount of the output token to receive
+    /// * `amount_in_maximum` - The maximum amount of the input token to pay
+    /// * `sqrt_price_limit` - The Q64.64 sqrt price √P limit. If zero for one, the price cannot
     ///
-    pub fn increase_liquidity<'a, 'b, 'c: 'info, 'info>(
-        ctx: Context<'a, 'b, 'c, 'info, IncreaseLiquidity<'info>>,
-        liquidity: u128,
-        amount_0_max: u64,
-        amount_1_max: u64,
-    ) -> Result<()> {
-        assert!(liquidity != 0);
-        instructions::increase_liquidity_v1(ctx, liquidity, amount_0_max, amount_1_max, None)
+    pub fn swap_base_output<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SwapSingle<'info>>,
+        amount_out: u64,
+        amount_in_maximum: u64,
+        sqrt_price_limit_x64: u128,
+    ) -> Result<SwapResult> {
+        instructions::swap_base_output(ctx, amount_out, amount_in_maximum, sqrt_price_limit_x64)
     }
 
-    /// Increases liquidity with a exist position, with amount paid by `payer`, support Token2022
+    /// Swaps one token for as much as possible of another token across a single pool, support token program 2022
     ///
     /// # Arguments
     ///
     /// * `ctx` - The context of accounts
-    /// * `liquidity` - The desired liquidity to be added, if zero, calculate liquidity base amount_0 or amount_1 according base_flag
-    /// * `amount_0_max` - The max amount of token_0 to spend, which serves as a slippage check
-    /// * `amount_1_max` - The max amount of token_1 to spend, which serves as a slippage check
-    /// * `base_flag` - must be specified if liquidity is zero, true: calculate liquidity base amount_0_max otherwise base amount_1_max
+    /// * `amount` - Arranged in pairs with other_amount_threshold. (amount_in, amount_out_minimum) or (amount_out, amount_in_maximum)
+    /// * `other_amount_threshold` - For slippage check
+    /// * `sqrt_price_limit` - The Q64.64 sqrt price √P limit. If zero for one, the price cannot
+    /// * `is_base_input` - swap base input or swap base output
+    /// * `deadline` - The unix timestamp after which the transaction is rejected, not checked if none
     ///
-    pub fn increase_liquidity_v2<'a, 'b, 'c: 'info, 'info>(
-        ctx: Context<'a, 'b, 'c, 'info, IncreaseLiquidityV2<'info>>,
-        liquidity: u128,
-        amount_0_max: u64,
-        amount_1_max: u64,
-        base_flag: Option<bool>,
-    ) -> Result<()> {
-        if liquidity == 0 {
-            assert!(base_flag.is_some());
+    pub fn swap_v2<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SwapSingleV2<'info>>,
+        amount: u64,
+        other_amount_threshold: u64,
+        sqrt_price_limit_x64: u128,
+        is_base_input: bool,
+        deadline: Option<u64>,
+    ) -> Result<SwapResult> {
+        if let Some(deadline) = deadline {
+            util::check_deadline(deadline)?;
         }
-        instructions::increase_liquidity_v2(ctx, liquidity, amount_0_max, amount_1_max, base_flag)
+        instructions::swap_v2(
+            ctx,
+            amount,
+            other_amount_threshold,
+            sqrt_price_limit_x64,
+            is_base_input,
+        )
     }
 
-    /// #[deprecated(note = "Use `decrease_liquidity_v2` instead.")]
-    /// Decreases liquidity with a exist position
+    /// Same as `swap_v2`, expires once the current slot is past `max_slot`
     ///
     /// # Arguments
     ///
-    /// * `ctx` -  The context of accounts
-    /// * `liquidity` - The amount by which liquidity will be decreased
-    /// * `amount_0_min` - The minimum amount of token_0 that should be accounted for the burned liquidity
-    /// * `amount_1_min` - The minimum amount of token_1 that should be accounted for the burned liquidity
+    /// * `ctx` - The context of accounts
+    /// * `amount` - Arranged in pairs with other_amount_threshold. (amount_in, amount_out_minimum) or (amount_out, amount_in_maximum)
+    /// * `other_amount_threshold` - For slippage check
+    /// * `sqrt_price_limit` - The Q64.64 sqrt price √P limit
+    /// * `is_base_input` - swap base input or swap base output
+    /// * `max_slot` - The last slot in which the swap can be executed
     ///
-    pub fn decrease_liquidity<'a, 'b, 'c: 'info, 'info>(
-        ctx: Context<'a, 'b, 'c, 'info, DecreaseLiquidity<'info>>,
-        liquidity: u128,
-        amount_0_min: u64,
-        amount_1_min: u64,
-    ) -> Result<()> {
-        instructions::decrease_liquidity_v1(ctx, liquidity, amount_0_min, amount_1_min)
+    pub fn swap_v2_with_max_slot<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SwapSingleV2<'info>>,
+        amount: u64,
+        other_amount_threshold: u64,
+        sqrt_price_limit_x64: u128,
+        is_base_input: bool,
+        max_slot: u64,
+    ) -> Result<SwapResult> {
+        instructions::swap_v2_with_max_slot(
+            ctx,
+            amount,
+            other_amount_threshold,
+            sqrt_price_limit_x64,
+            is_base_input,
+            max_slot,
+        )
     }
 
-    /// Decreases liquidity with a exist position, support Token2022
+    /// Same as `swap_v2` with base input, but for Token-2022 mints with a transfer fee the amount is what arrives in the input vault,
+    /// the program transfers it grossed up by the fee
     ///
     /// # Arguments
     ///
-    /// * `ctx` -  The context of accounts
-    /// * `liquidity` - The amount by which liquidity will be decreased
-    /// * `amount_0_min` - The minimum amount of token_0 that should be accounted for the burned liquidity
-    /// * `amount_1_min` - The minimum amount of token_1 that should be accounted for the burned liquidity
+    /// * `ctx` - The context of accounts
+    /// * `amount_in` - The amount the pool receives after the input mint's transfer fee
+    /// * `amount_out_minimum` - For slippage check
+    /// * `sqrt_price_limit` - The Q64.64 sqrt price √P limit
     ///
-    pub fn decrease_liquidity_v2<'a, 'b, 'c: 'info, 'info>(
-        ctx: Context<'a, 'b, 'c, 'info, DecreaseLiquidityV2<'info>>,
-        liquidity: u128,
-        amount_0_min: u64,
-        amount_1_min: u64,
-    ) -> Result<()> {
-        instructions::decrease_liquidity_v2(ctx, liquidity, amount_0_min, amount_1_min)
+    pub fn swap_v2_net_input<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SwapSingleV2<'info>>,
+        amount_in: u64,
+        amount_out_minimum: u64,
+        sqrt_price_limit_x64: u128,
+    ) -> Result<SwapResult> {
+        instructions::swap_v2_net_input(ctx, amount_in, amount_out_minimum, sqrt_price_limit_x64)
     }
 
-    /// #[deprecated(note = "Use `swap_v2` instead.")]
-    /// Swaps one token for as much as possible of another token across a single pool
+    /// Same as `swap_v2`, but the tick arrays and bitmap extension are resolved on chain, the
+    /// remaining accounts only need to contain them in any order
     ///
     /// # Arguments
     ///
     /// * `ctx` - The context of accounts
     /// * `amount` - Arranged in pairs with other_amount_threshold. (amount_in, amount_out_minimum) or (amount_out, amount_in_maximum)
     /// * `other_amount_threshold` - For slippage check
-    /// * `sqrt_price_limit` - The Q64.64 sqrt price √P limit. If zero for one, the price cannot
+    /// * `sqrt_price_limit` - The Q64.64 sqrt price √P limit
     /// * `is_base_input` - swap base input or swap base output
     ///
-    pub fn swap<'a, 'b, 'c: 'info, 'info>(
-        ctx: Context<'a, 'b, 'c, 'info, SwapSingle<'info>>,
+    pub fn swap_v2_with_resolved_tick_arrays<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SwapSingleV2<'info>>,
         amount: u64,
         other_amount_threshold: u64,
         sqrt_price_limit_x64: u128,
         is_base_input: bool,
-    ) -> Result<()> {
-        instructions::swap(
+    ) -> Result<SwapResult> {
+        instructions::swap_v2_with_resolved_tick_arrays(
             ctx,
             amount,
             other_amount_threshold,
@@ -498,24 +2029,25 @@ pub mod amm_v3 {
         )
     }
 
-    /// Swaps one token for as much as possible of another token across a single pool, support token program 2022
+    /// Same as `swap_v2`, without protocol fee, only callable through CPI by a program exempted
+    /// for the pool's config, invoked directly from a top level instruction
     ///
     /// # Arguments
     ///
     /// * `ctx` - The context of accounts
     /// * `amount` - Arranged in pairs with other_amount_threshold. (amount_in, amount_out_minimum) or (amount_out, amount_in_maximum)
     /// * `other_amount_threshold` - For slippage check
-    /// * `sqrt_price_limit` - The Q64.64 sqrt price √P limit. If zero for one, the price cannot
+    /// * `sqrt_price_limit` - The Q64.64 sqrt price √P limit
     /// * `is_base_input` - swap base input or swap base output
     ///
-    pub fn swap_v2<'a, 'b, 'c: 'info, 'info>(
-        ctx: Context<'a, 'b, 'c, 'info, SwapSingleV2<'info>>,
+    pub fn swap_v2_fee_exempt<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SwapSingleV2FeeExempt<'info>>,
         amount: u64,
         other_amount_threshold: u64,
         sqrt_price_limit_x64: u128,
         is_base_input: bool,
-    ) -> Result<()> {
-        instructions::swap_v2(
+    ) -> Result<SwapResult> {
+        instructions::swap_v2_fee_exempt(
             ctx,
             amount,
             other_amount_threshold,
@@ -531,12 +2063,205 @@ pub mod amm_v3 {
     /// * `ctx` - The context of accounts
     /// * `amount_in` - Token amount to be swapped in
     /// * `amount_out_minimum` - Panic if output amount is below minimum amount. For slippage.
+    /// * `deadline` - The unix timestamp after which the transaction is rejected
     ///
     pub fn swap_router_base_in<'a, 'b, 'c: 'info, 'info>(
         ctx: Context<'a, 'b, 'c, 'info, SwapRouterBaseIn<'info>>,
         amount_in: u64,
         amount_out_minimum: u64,
+        deadline: u64,
     ) -> Result<()> {
+        util::check_deadline(deadline)?;
         instructions::swap_router_base_in(ctx, amount_in, amount_out_minimum)
     }
+
+    /// Swap as little as possible of a token for an exact amount of another token across the path provided, base output
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount_out` - Token amount to be swapped out of the last pool
+    /// * `amount_in_maximum` - Panic if input amount is above maximum amount. For slippage.
+    ///
+    pub fn swap_router_base_out<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SwapRouterBaseOut<'info>>,
+        amount_out: u64,
+        amount_in_maximum: u64,
+    ) -> Result<()> {
+        instructions::swap_router_base_out(ctx, amount_out, amount_in_maximum)
+    }
+
+    /// Validates and quotes a `swap_router_base_in` route without transferring tokens or writing
+    /// accounts, the amounts are returned via return data
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount_in` - Token amount to be swapped in
+    /// * `amount_out_minimum` - Panic if output amount is below minimum amount. For slippage.
+    ///
+    pub fn swap_router_base_in_dry_run<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SwapRouterBaseIn<'info>>,
+        amount_in: u64,
+        amount_out_minimum: u64,
+    ) -> Result<RouteResult> {
+        instructions::swap_router_base_in_dry_run(ctx, amount_in, amount_out_minimum)
+    }
+
+    /// Validates and quotes a `swap_router_base_out` route without transferring tokens or writing
+    /// accounts, the amounts are returned via return data
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount_out` - Token amount to be swapped out of the last pool
+    /// * `amount_in_maximum` - Panic if input amount is above maximum amount. For slippage.
+    ///
+    pub fn swap_router_base_out_dry_run<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SwapRouterBaseOut<'info>>,
+        amount_out: u64,
+        amount_in_maximum: u64,
+    ) -> Result<RouteResult> {
+        instructions::swap_router_base_out_dry_run(ctx, amount_out, amount_in_maximum)
+    }
+
+    /// Same as `swap_router_base_in`, expires once the current slot is past `max_slot`
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount_in` - Token amount to be swapped in
+    /// * `amount_out_minimum` - Panic if output amount is below minimum amount. For slippage.
+    /// * `max_slot` - The last slot in which the swap can be executed
+    ///
+    pub fn swap_router_base_in_with_max_slot<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SwapRouterBaseIn<'info>>,
+        amount_in: u64,
+        amount_out_minimum: u64,
+        max_slot: u64,
+    ) -> Result<()> {
+        instructions::swap_router_base_in_with_max_slot(
+            ctx,
+            amount_in,
+            amount_out_minimum,
+            max_slot,
+        )
+    }
+
+    /// Swap token for as much as possible of another token across the path provided, base input,
+    /// with a minimum output for every hop
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount_in` - Token amount to be swapped in
+    /// * `amount_out_minimum` - Panic if output amount is below minimum amount. For slippage.
+    /// * `hop_amount_out_minimums` - The minimum output of each hop, one entry per hop in route order
+    /// * `deadline` - The unix timestamp after which the transaction is rejected, not checked if none
+    ///
+    pub fn swap_router_base_in_with_hop_minimums<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SwapRouterBaseIn<'info>>,
+        amount_in: u64,
+        amount_out_minimum: u64,
+        hop_amount_out_minimums: Vec<u64>,
+        deadline: Option<u64>,
+    ) -> Result<()> {
+        if let Some(deadline) = deadline {
+            util::check_deadline(deadline)?;
+        }
+        instructions::swap_router_base_in_with_hop_minimums(
+            ctx,
+            amount_in,
+            amount_out_minimum,
+            hop_amount_out_minimums,
+        )
+    }
+
+    /// Swap token for as much as possible of another token across the path described by `route`,
+    /// base input
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount_in` - Token amount to be swapped in
+    /// * `amount_out_minimum` - Panic if output amount is below minimum amount. For slippage.
+    /// * `route` - One entry per hop in route order, giving its accounts, type and minimum output
+    /// * `deadline` - The unix timestamp after which the transaction is rejected, not checked if none
+    ///
+    pub fn swap_router_base_in_with_route<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SwapRouterBaseIn<'info>>,
+        amount_in: u64,
+        amount_out_minimum: u64,
+        route: Vec<RouteHop>,
+        deadline: Option<u64>,
+    ) -> Result<()> {
+        if let Some(deadline) = deadline {
+            util::check_deadline(deadline)?;
+        }
+        instructions::swap_router_base_in_with_route(ctx, amount_in, amount_out_minimum, route)
+    }
+
+    /// Swap token for as much as possible of another token through several pools of the same
+    /// pair, each pool taking its share of the input, base input
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount_in` - Token amount to be swapped in, split between the legs
+    /// * `amount_out_minimum` - Panic if the total output amount is below minimum amount. For slippage.
+    /// * `legs` - One entry per pool giving its share of the input in basis points and its accounts
+    ///
+    pub fn swap_split<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SwapSplit<'info>>,
+        amount_in: u64,
+        amount_out_minimum: u64,
+        legs: Vec<SplitLeg>,
+    ) -> Result<()> {
+        instructions::swap_split(ctx, amount_in, amount_out_minimum, legs)
+    }
+
+    /// Execute several independent single-pool swaps atomically, the whole batch fails if any
+    /// swap misses its threshold
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `swaps` - One entry per swap giving its amounts, price limit, direction and accounts
+    ///
+    pub fn swap_batch<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SwapBatch<'info>>,
+        swaps: Vec<BatchSwap>,
+    ) -> Result<()> {
+        instructions::swap_batch(ctx, swaps)
+    }
+
+    /// Swap token for as much as possible of another token across the path provided, base input,
+    /// with a price limit for every hop
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount_in` - Token amount to be swapped in
+    /// * `amount_out_minimum` - Panic if output amount is below minimum amount. For slippage.
+    /// * `hop_sqrt_price_limits_x64` - The price each hop's pool can't move past, one entry per
+    ///     hop in route order, 0 for no limit
+    /// * `deadline` - The unix timestamp after which the transaction is rejected, not checked if none
+    ///
+    pub fn swap_router_base_in_with_price_limits<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SwapRouterBaseIn<'info>>,
+        amount_in: u64,
+        amount_out_minimum: u64,
+        hop_sqrt_price_limits_x64: Vec<u128>,
+        deadline: Option<u64>,
+    ) -> Result<()> {
+        if let Some(deadline) = deadline {
+            util::check_deadline(deadline)?;
+        }
+        instructions::swap_router_base_in_with_price_limits(
+            ctx,
+            amount_in,
+            amount_out_minimum,
+            hop_sqrt_price_limits_x64,
+        )
+    }
 }
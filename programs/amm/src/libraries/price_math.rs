@@ -0,0 +1,117 @@
+use super::big_num::U256;
+use super::fixed_point_64;
+use crate::error::ErrorCode;
+use anchor_lang::prelude::*;
+
+fn to_u128(value: U256) -> Result<u128> {
+    require!(value.bits() <= 128, ErrorCode::CalculateOverflow);
+    Ok(value.as_u128())
+}
+
+/// Converts a sqrt price to the raw price of token_0 denominated in token_1 as a Q64.64,
+/// i.e. `P = √P * √P`, not adjusted by the mint decimals
+pub fn sqrt_price_x64_to_price_x64(sqrt_price_x64: u128) -> Result<u128> {
+    to_u128((U256::from(sqrt_price_x64) * U256::from(sqrt_price_x64)) >> fixed_point_64::RESOLUTION)
+}
+
+/// Inverts a Q64.64 price, e.g. token_1/token_0 to token_0/token_1
+pub fn invert_price_x64(price_x64: u128) -> Result<u128> {
+    require!(price_x64 != 0, ErrorCode::CalculateOverflow);
+    to_u128((U256::from(1) << (fixed_point_64::RESOLUTION * 2)) / U256::from(price_x64))
+}
+
+/// Adjusts a raw Q64.64 price of token_0 denominated in token_1 by the mint decimals,
+/// `price * 10^decimals_0 / 10^decimals_1`
+pub fn adjust_price_x64_by_decimals(
+    price_x64: u128,
+    decimals_0: u8,
+    decimals_1: u8,
+) -> Result<u128> {
+    let price_x64 = U256::from(price_x64);
+    if decimals_0 >= decimals_1 {
+        let multiplier = U256::from(10)
+            .checked_pow(U256::from(decimals_0 - decimals_1))
+            .ok_or(ErrorCode::CalculateOverflow)?;
+        to_u128(
+            price_x64
+                .checked_mul(multiplier)
+                .ok_or(ErrorCode::CalculateOverflow)?,
+        )
+    } else {
+        match U256::from(10).checked_pow(U256::from(decimals_1 - decimals_0)) {
+            Some(divisor) => to_u128(price_x64 / divisor),
+            None => Ok(0),
+        }
+    }
+}
+
+/// Computes the decimal adjusted price of the pool as a Q64.64
+///
+/// # Arguments
+///
+/// * `sqrt_price_x64` - The sqrt price of the pool
+/// * `decimals_0` - The decimals of token_0
+/// * `decimals_1` - The decimals of token_1
+/// * `inverse` - false presents the price as token_1/token_0, true as token_0/token_1
+///
+pub fn get_price_x64_with_decimals(
+    sqrt_price_x64: u128,
+    decimals_0: u8,
+    decimals_1: u8,
+    inverse: bool,
+) -> Result<u128> {
+    let price_x64 = adjust_price_x64_by_decimals(
+        sqrt_price_x64_to_price_x64(sqrt_price_x64)?,
+        decimals_0,
+        decimals_1,
+    )?;
+    if inverse {
+        invert_price_x64(price_x64)
+    } else {
+        Ok(price_x64)
+    }
+}
+
+#[cfg(test)]
+mod price_math_test {
+    use super::*;
+    use crate::libraries::fixed_point_64::Q64;
+    use crate::libraries::tick_math;
+
+    #[test]
+    fn sqrt_price_to_price_test() {
+        assert_eq!(sqrt_price_x64_to_price_x64(Q64).unwrap(), Q64);
+        assert_eq!(sqrt_price_x64_to_price_x64(Q64 * 2).unwrap(), Q64 * 4);
+        assert_eq!(sqrt_price_x64_to_price_x64(Q64 / 2).unwrap(), Q64 / 4);
+        assert!(sqrt_price_x64_to_price_x64(tick_math::MIN_SQRT_PRICE_X64).is_ok());
+        assert!(sqrt_price_x64_to_price_x64(tick_math::MAX_SQRT_PRICE_X64).is_ok());
+    }
+
+    #[test]
+    fn invert_price_test() {
+        assert_eq!(invert_price_x64(Q64).unwrap(), Q64);
+        assert_eq!(invert_price_x64(Q64 * 4).unwrap(), Q64 / 4);
+        assert_eq!(invert_price_x64(Q64 / 4).unwrap(), Q64 * 4);
+        assert!(invert_price_x64(0).is_err());
+        assert!(invert_price_x64(1).is_err());
+    }
+
+    #[test]
+    fn adjust_price_by_decimals_test() {
+        assert_eq!(adjust_price_x64_by_decimals(Q64, 6, 6).unwrap(), Q64);
+        assert_eq!(adjust_price_x64_by_decimals(Q64, 9, 6).unwrap(), Q64 * 1000);
+        assert_eq!(adjust_price_x64_by_decimals(Q64 * 1000, 6, 9).unwrap(), Q64);
+        assert_eq!(adjust_price_x64_by_decimals(Q64, 0, 255).unwrap(), 0);
+        assert!(adjust_price_x64_by_decimals(Q64, 255, 0).is_err());
+    }
+
+    #[test]
+    fn get_price_with_decimals_test() {
+        // 1 token_0 (9 decimals) = 2 token_1 (6 decimals), raw price is 2 / 1000
+        let sqrt_price_x64 = (U256::from(Q64 * 2 / 1000) << 64).integer_sqrt().as_u128();
+        let price_x64 = get_price_x64_with_decimals(sqrt_price_x64, 9, 6, false).unwrap();
+        assert!(price_x64.abs_diff(Q64 * 2) < Q64 / 1000);
+        let inverse_price_x64 = get_price_x64_with_decimals(sqrt_price_x64, 9, 6, true).unwrap();
+        assert!(inverse_price_x64.abs_diff(Q64 / 2) < Q64 / 1000);
+    }
+}
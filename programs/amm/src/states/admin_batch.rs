@@ -0,0 +1,142 @@
+use crate::error::ErrorCode;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak;
+
+pub const ADMIN_BATCH_SEED: &str = "admin_batch";
+/// The most entries a batch can hold
+pub const MAX_ADMIN_BATCH_ENTRIES: usize = 32;
+/// The highest `update_amm_config` param a batch entry can carry
+pub const MAX_AMM_CONFIG_PARAM: u8 = 13;
+
+/// One `update_amm_config` call of a batch
+#[derive(Copy, Clone, AnchorSerialize, AnchorDeserialize, Default, Debug, PartialEq)]
+pub struct AdminBatchEntry {
+    /// The amm config to update
+    pub amm_config: Pubkey,
+    /// The `update_amm_config` param
+    pub param: u8,
+    /// The `update_amm_config` value
+    pub value: u32,
+    /// The new owner, fund owner or swap hook program for the params taking a key, default
+    /// otherwise
+    pub key: Pubkey,
+}
+
+impl AdminBatchEntry {
+    pub const LEN: usize = 32 + 1 + 4 + 32;
+}
+
+/// A list of amm config updates staged over several transactions and executed in chunks, so an
+/// admin multisig bound by transaction size limits can review and run it across approvals.
+/// Entries can only be appended until the first chunk is executed, and every execution names
+/// the hash of the whole batch, so an approval can't run a batch that differs from the reviewed one
+///
+/// PDA of `[ADMIN_BATCH_SEED, batch_id]`
+///
+#[account]
+#[derive(Default, Debug)]
+pub struct AdminBatchState {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The id the admin picked for the batch
+    pub batch_id: u64,
+    /// The number of staged entries
+    pub entry_count: u16,
+    /// The number of entries already executed, the batch is sealed once it is non zero
+    pub cursor: u16,
+    /// The staged entries, only the first `entry_count` are used
+    pub entries: [AdminBatchEntry; MAX_ADMIN_BATCH_ENTRIES],
+    // Unused bytes for future upgrades.
+    pub padding: [u64; 4],
+}
+
+impl AdminBatchState {
+    pub const LEN: usize =
+        8 + 1 + 8 + 2 + 2 + AdminBatchEntry::LEN * MAX_ADMIN_BATCH_ENTRIES + 8 * 4;
+
+    pub fn append(&mut self, entries: &[AdminBatchEntry]) -> Result<()> {
+        require!(self.cursor == 0, ErrorCode::InvalidAdminBatch);
+        let start = usize::from(self.entry_count);
+        require_gte!(
+            MAX_ADMIN_BATCH_ENTRIES - start,
+            entries.len(),
+            ErrorCode::InvalidAdminBatch
+        );
+        for entry in entries {
+            require!(
+                entry.param <= MAX_AMM_CONFIG_PARAM,
+                ErrorCode::InvalidUpdateConfigFlag
+            );
+        }
+        self.entries[start..start + entries.len()].copy_from_slice(entries);
+        self.entry_count += entries.len() as u16;
+        Ok(())
+    }
+
+    /// The keccak hash of the staged entries, in order
+    pub fn entries_hash(&self) -> [u8; 32] {
+        let entries: Vec<Vec<u8>> = self.entries[..usize::from(self.entry_count)]
+            .iter()
+            .map(|entry| entry.try_to_vec().unwrap())
+            .collect();
+        let entries: Vec<&[u8]> = entries.iter().map(|entry| entry.as_slice()).collect();
+        keccak::hashv(&entries).0
+    }
+
+    /// Moves the cursor over the next chunk of at most `max_entries` entries and returns it
+    pub fn next_chunk(&mut self, max_entries: u16) -> &[AdminBatchEntry] {
+        let start = self.cursor;
+        let end = self.entry_count.min(start.saturating_add(max_entries));
+        self.cursor = end;
+        &self.entries[usize::from(start)..usize::from(end)]
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.cursor == self.entry_count
+    }
+}
+
+#[cfg(test)]
+mod admin_batch_test {
+    use super::*;
+
+    fn entry(param: u8, value: u32) -> AdminBatchEntry {
+        AdminBatchEntry {
+            amm_config: Pubkey::new_unique(),
+            param,
+            value,
+            key: Pubkey::default(),
+        }
+    }
+
+    #[test]
+    fn stage_and_execute_test() {
+        let mut batch = AdminBatchState::default();
+        let entries = [entry(0, 100), entry(1, 200), entry(2, 300)];
+        batch.append(&entries[..2]).unwrap();
+        let partial_hash = batch.entries_hash();
+        batch.append(&entries[2..]).unwrap();
+        assert_eq!(batch.entry_count, 3);
+        // the hash covers every entry
+        assert_ne!(batch.entries_hash(), partial_hash);
+        assert!(batch.append(&[entry(MAX_AMM_CONFIG_PARAM + 1, 0)]).is_err());
+
+        assert_eq!(batch.next_chunk(2), &entries[..2]);
+        assert!(!batch.is_done());
+        // sealed once a chunk is executed
+        assert!(batch.append(&[entry(0, 0)]).is_err());
+        assert_eq!(batch.next_chunk(2), &entries[2..]);
+        assert!(batch.is_done());
+        assert!(batch.next_chunk(2).is_empty());
+    }
+
+    #[test]
+    fn append_past_capacity_test() {
+        let mut batch = AdminBatchState::default();
+        batch
+            .append(&[entry(0, 0); MAX_ADMIN_BATCH_ENTRIES - 1])
+            .unwrap();
+        assert!(batch.append(&[entry(0, 0); 2]).is_err());
+        batch.append(&[entry(0, 0)]).unwrap();
+    }
+}
@@ -0,0 +1,89 @@
+use crate::error::ErrorCode;
+use crate::states::PoolStatusBitIndex;
+use anchor_lang::prelude::*;
+
+pub const ADMIN_HEARTBEAT_SEED: &str = "admin_heartbeat";
+/// The shortest inactivity period the admin can configure
+pub const MIN_INACTIVITY_PERIOD: u64 = 60 * 60 * 24;
+/// The pool status of the withdraw-only mode: opening positions, increasing liquidity and
+/// swapping are disabled, decreasing liquidity and collecting fees and rewards are enabled
+pub const WITHDRAW_ONLY_STATUS: u8 = (1
+    << PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity as u8)
+    | (1 << PoolStatusBitIndex::Swap as u8);
+
+/// The dead man's switch of the program: once the admin has gone `inactivity_period` seconds
+/// without a heartbeat, the guardian, or anyone if there is none, can put pools in
+/// withdraw-only mode
+///
+/// PDA of `[ADMIN_HEARTBEAT_SEED]`
+///
+#[account]
+#[derive(Default, Debug)]
+pub struct AdminHeartbeat {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The timestamp of the admin's last heartbeat
+    pub last_heartbeat: u64,
+    /// The seconds without a heartbeat after which the switch trips, 0 disables it
+    pub inactivity_period: u64,
+    /// Who can trip the switch, default if anyone can
+    pub guardian: Pubkey,
+    // Unused bytes for future upgrades.
+    pub padding: [u64; 8],
+}
+
+impl AdminHeartbeat {
+    pub const LEN: usize = 8 + 1 + 8 + 8 + 32 + 8 * 8;
+
+    pub fn configure(
+        &mut self,
+        inactivity_period: u64,
+        guardian: Pubkey,
+        curr_timestamp: u64,
+    ) -> Result<()> {
+        require!(
+            inactivity_period == 0 || inactivity_period >= MIN_INACTIVITY_PERIOD,
+            ErrorCode::InvalidHeartbeatParam
+        );
+        self.inactivity_period = inactivity_period;
+        self.guardian = guardian;
+        self.last_heartbeat = curr_timestamp;
+        Ok(())
+    }
+
+    pub fn can_trip(&self, caller: &Pubkey, curr_timestamp: u64) -> bool {
+        self.inactivity_period != 0
+            && curr_timestamp >= self.last_heartbeat.saturating_add(self.inactivity_period)
+            && (self.guardian == Pubkey::default() || self.guardian == *caller)
+    }
+}
+
+#[cfg(test)]
+mod admin_heartbeat_test {
+    use super::*;
+
+    #[test]
+    fn can_trip_test() {
+        let mut heartbeat = AdminHeartbeat::default();
+        let caller = Pubkey::new_unique();
+        // disabled
+        assert!(!heartbeat.can_trip(&caller, u64::MAX));
+        assert!(heartbeat
+            .configure(MIN_INACTIVITY_PERIOD - 1, Pubkey::default(), 0)
+            .is_err());
+
+        heartbeat
+            .configure(MIN_INACTIVITY_PERIOD, Pubkey::default(), 1000)
+            .unwrap();
+        assert!(!heartbeat.can_trip(&caller, 999 + MIN_INACTIVITY_PERIOD));
+        assert!(heartbeat.can_trip(&caller, 1000 + MIN_INACTIVITY_PERIOD));
+
+        // only the guardian once there is one
+        let guardian = Pubkey::new_unique();
+        heartbeat
+            .configure(MIN_INACTIVITY_PERIOD, guardian, 1000)
+            .unwrap();
+        assert!(!heartbeat.can_trip(&caller, 1000 + MIN_INACTIVITY_PERIOD));
+        assert!(heartbeat.can_trip(&guardian, 1000 + MIN_INACTIVITY_PERIOD));
+    }
+}
@@ -0,0 +1,106 @@
+use anchor_lang::prelude::*;
+
+pub const BUYBACK_SEED: &str = "buyback";
+pub const BUYBACK_VAULT_SEED: &str = "buyback_vault";
+
+/// Periodically swaps a share of a pool's protocol fee into the designated token of the pair,
+/// the bought tokens are burned or kept in a vault nothing can withdraw from
+///
+/// PDA of `[BUYBACK_SEED, pool_id]`
+///
+#[account]
+#[derive(Default, Debug)]
+pub struct BuybackState {
+    /// Bump to identify PDA
+    pub bump: [u8; 1],
+    /// The pool whose protocol fee funds the buyback, also the pool swapped through
+    pub pool_id: Pubkey,
+    /// The token bought back, one of the pool tokens
+    pub buyback_mint: Pubkey,
+    /// The vault receiving the bought tokens, owned by this account
+    pub buyback_vault: Pubkey,
+    /// The share of the other token's protocol fee swapped per crank, denominated in hundredths of a bip (10^-6)
+    pub protocol_fee_share_rate: u32,
+    /// The minimum seconds between two cranks
+    pub interval: u64,
+    /// The block timestamp of the last crank
+    pub last_crank_time: u64,
+    /// The seconds of pool history the reference price is averaged over
+    pub twap_window: u32,
+    /// The most a buyback may trail the twap price, in basis points
+    pub max_slippage_bps: u16,
+    /// Burn the bought tokens if true, keep them locked in the vault otherwise
+    pub burn: bool,
+    /// Total protocol fee swapped
+    pub total_amount_in: u64,
+    /// Total tokens received by the vault
+    pub total_amount_out: u64,
+    /// Total tokens burned
+    pub total_burned: u64,
+    // Unused bytes for future upgrades.
+    pub padding: [u64; 8],
+}
+
+impl BuybackState {
+    pub const LEN: usize = 8 + 1 + 32 * 3 + 4 + 8 * 2 + 4 + 2 + 1 + 8 * 3 + 8 * 8;
+
+    pub fn seeds(&self) -> [&[u8]; 3] {
+        [
+            BUYBACK_SEED.as_bytes(),
+            self.pool_id.as_ref(),
+            self.bump.as_ref(),
+        ]
+    }
+
+    pub fn is_due(&self, block_timestamp: u64) -> bool {
+        block_timestamp >= self.last_crank_time.saturating_add(self.interval)
+    }
+}
+
+/// Emitted on each buyback crank
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct BuybackEvent {
+    /// The buyback account
+    #[index]
+    pub buyback_state: Pubkey,
+    /// The pool the fee is swapped through
+    pub pool_state: Pubkey,
+    /// The protocol fee swapped
+    pub amount_in: u64,
+    /// The bought tokens received by the vault
+    pub amount_out: u64,
+    /// The minimum output derived from the twap price
+    pub amount_out_minimum: u64,
+    /// The twap tick the output is bounded by
+    pub twap_tick: i32,
+    /// The tokens burned, zero if the tokens are kept locked
+    pub amount_burned: u64,
+    /// The pool tick after the swap
+    pub tick: i32,
+}
+
+#[cfg(test)]
+mod buyback_test {
+    use super::*;
+
+    #[test]
+    fn is_due_test() {
+        let buyback = BuybackState {
+            interval: 3600,
+            last_crank_time: 1_000,
+            ..Default::default()
+        };
+        assert!(!buyback.is_due(4_599));
+        assert!(buyback.is_due(4_600));
+
+        // saturates instead of overflowing
+        let buyback = BuybackState {
+            interval: u64::MAX,
+            last_crank_time: 1_000,
+            ..Default::default()
+        };
+        assert!(!buyback.is_due(u64::MAX - 1));
+        assert!(buyback.is_due(u64::MAX));
+    }
+}
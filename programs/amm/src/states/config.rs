@@ -1,10 +1,20 @@
 use crate::error::ErrorCode;
+use crate::states::{PoolStatusBitIndex, WITHDRAW_ONLY_STATUS};
 use anchor_lang::prelude::*;
 
 pub const AMM_CONFIG_SEED: &str = "amm_config";
 
 pub const FEE_RATE_DENOMINATOR_VALUE: u32 = 1_000_000;
 
+/// The lowest protocol fee rate a config can be set to, 1% of the trade fee
+pub const MIN_PROTOCOL_FEE_RATE: u32 = 10_000;
+/// The highest protocol fee rate a config can be set to, 50% of the trade fee
+pub const MAX_PROTOCOL_FEE_RATE: u32 = 500_000;
+/// The most the protocol fee rate can move in one update
+pub const MAX_PROTOCOL_FEE_RATE_STEP: u32 = 50_000;
+/// The seconds that must pass between two protocol fee rate updates
+pub const PROTOCOL_FEE_RATE_TIMELOCK: u64 = 7 * 24 * 3600;
+
 /// Holds the current owner of the factory
 #[account]
 #[derive(Default, Debug)]
@@ -25,11 +35,39 @@ pub struct AmmConfig {
     // padding space for upgrade
     pub padding_u32: u32,
     pub fund_owner: Pubkey,
-    pub padding: [u64; 3],
+    /// The lower bound of the trade fee a whitelisted creator can set for a custom fee pool
+    pub custom_fee_min_rate: u32,
+    /// The upper bound of the trade fee a whitelisted creator can set for a custom fee pool,
+    /// 0 means custom fee pools are disabled for this config
+    pub custom_fee_max_rate: u32,
+    /// The block timestamp of the last protocol fee rate change
+    pub protocol_fee_rate_updated_at: u64,
+    /// The referrer's cut of the trade fee on referred swaps, denominated in hundredths of a
+    /// bip (10^-6)
+    pub referral_fee_rate: u32,
+    /// The share of the protocol fee rebated to a trader per volume tier reached, denominated
+    /// in hundredths of a bip (10^-6), see `UserStats`
+    pub volume_rebate_rate: u32,
+    /// The consecutive qualifying epochs a trader needs per volume tier, 0 disables rebates
+    pub volume_rebate_min_epochs: u32,
+    /// The emergency switch of the config, puts all of its pools in withdraw-only mode on top
+    /// of their own status
+    pub paused: bool,
+    pub padding_u8_1: [u8; 3],
+    /// The one hook program pools of the config may call around their swaps, default means
+    /// swap hooks are disabled for the config
+    pub swap_hook_program: Pubkey,
+    /// The epochs a position of the config's pools must sit with no liquidity and nothing owed
+    /// before anyone can sweep it, 0 disables sweeping
+    pub empty_position_grace_epochs: u64,
+    // padding space for upgrade, accounts created before it was extended are resized by
+    // `migrate_account_space`
+    pub padding: [u64; 2],
 }
 
 impl AmmConfig {
-    pub const LEN: usize = 8 + 1 + 2 + 32 + 4 + 4 + 2 + 64;
+    pub const LEN: usize =
+        8 + 1 + 2 + 32 + 4 + 4 + 2 + 4 + 4 + 32 + 4 + 4 + 8 + 4 + 4 + 4 + 1 + 3 + 32 + 8 + 8 * 2;
 
     pub fn is_authorized<'info>(
         &self,
@@ -42,6 +80,57 @@ impl AmmConfig {
         );
         Ok(())
     }
+
+    /// Checks the protocol fee rate and fund fee rate set at creation
+    pub fn check_fee_rates(protocol_fee_rate: u32, fund_fee_rate: u32) -> Result<()> {
+        require!(
+            (MIN_PROTOCOL_FEE_RATE..=MAX_PROTOCOL_FEE_RATE).contains(&protocol_fee_rate)
+                && protocol_fee_rate + fund_fee_rate <= FEE_RATE_DENOMINATOR_VALUE,
+            ErrorCode::InvalidProtocolFeeRate
+        );
+        Ok(())
+    }
+
+    /// Checks a new protocol fee rate is within bounds, and that it moves by at most
+    /// `MAX_PROTOCOL_FEE_RATE_STEP` once per `PROTOCOL_FEE_RATE_TIMELOCK`
+    pub fn check_protocol_fee_rate_update(
+        &self,
+        protocol_fee_rate: u32,
+        block_timestamp: u64,
+    ) -> Result<()> {
+        AmmConfig::check_fee_rates(protocol_fee_rate, self.fund_fee_rate)?;
+        require!(
+            protocol_fee_rate.abs_diff(self.protocol_fee_rate) <= MAX_PROTOCOL_FEE_RATE_STEP
+                && block_timestamp
+                    >= self
+                        .protocol_fee_rate_updated_at
+                        .saturating_add(PROTOCOL_FEE_RATE_TIMELOCK),
+            ErrorCode::ProtocolFeeRateStepExceeded
+        );
+        Ok(())
+    }
+
+    /// Whether the config lets its pools perform the operation, a paused config only lets them
+    /// decrease liquidity and collect fees and rewards. Same semantics as
+    /// `PoolState::get_status_by_bit`
+    pub fn get_status_by_bit(&self, bit: PoolStatusBitIndex) -> bool {
+        !self.paused || WITHDRAW_ONLY_STATUS & (1 << bit as u8) == 0
+    }
+
+    pub fn is_custom_fee_enabled(&self) -> bool {
+        self.custom_fee_max_rate != 0
+    }
+
+    pub fn check_custom_trade_fee_rate(&self, trade_fee_rate: u32) -> Result<()> {
+        require!(
+            self.is_custom_fee_enabled()
+                && trade_fee_rate != 0
+                && trade_fee_rate >= self.custom_fee_min_rate
+                && trade_fee_rate <= self.custom_fee_max_rate,
+            ErrorCode::InvalidCustomTradeFeeRate
+        );
+        Ok(())
+    }
 }
 
 /// Emitted when create or update a config
@@ -57,3 +146,103 @@ pub struct ConfigChangeEvent {
     pub fund_fee_rate: u32,
     pub fund_owner: Pubkey,
 }
+
+#[cfg(test)]
+mod config_test {
+    use super::*;
+
+    #[test]
+    fn check_protocol_fee_rate_update_test() {
+        let amm_config = AmmConfig {
+            protocol_fee_rate: 120_000,
+            fund_fee_rate: 40_000,
+            protocol_fee_rate_updated_at: 1_000,
+            ..Default::default()
+        };
+        let unlocked = 1_000 + PROTOCOL_FEE_RATE_TIMELOCK;
+
+        assert!(amm_config
+            .check_protocol_fee_rate_update(170_000, unlocked)
+            .is_ok());
+        assert!(amm_config
+            .check_protocol_fee_rate_update(70_000, unlocked)
+            .is_ok());
+        // still timelocked
+        assert!(amm_config
+            .check_protocol_fee_rate_update(130_000, unlocked - 1)
+            .is_err());
+        // step too large
+        assert!(amm_config
+            .check_protocol_fee_rate_update(170_001, unlocked)
+            .is_err());
+        // out of bounds
+        let amm_config = AmmConfig {
+            protocol_fee_rate: MAX_PROTOCOL_FEE_RATE,
+            ..Default::default()
+        };
+        assert!(amm_config
+            .check_protocol_fee_rate_update(MAX_PROTOCOL_FEE_RATE + 1, unlocked)
+            .is_err());
+        assert!(AmmConfig::check_fee_rates(MIN_PROTOCOL_FEE_RATE - 1, 0).is_err());
+        assert!(AmmConfig::check_fee_rates(MAX_PROTOCOL_FEE_RATE, 500_001).is_err());
+    }
+
+    #[test]
+    fn paused_status_test() {
+        let mut amm_config = AmmConfig::default();
+        assert!(amm_config.get_status_by_bit(PoolStatusBitIndex::Swap));
+        assert!(amm_config.get_status_by_bit(PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity));
+
+        amm_config.paused = true;
+        assert!(!amm_config.get_status_by_bit(PoolStatusBitIndex::Swap));
+        assert!(!amm_config.get_status_by_bit(PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity));
+        // withdrawals stay open
+        assert!(amm_config.get_status_by_bit(PoolStatusBitIndex::DecreaseLiquidity));
+        assert!(amm_config.get_status_by_bit(PoolStatusBitIndex::CollectFee));
+        assert!(amm_config.get_status_by_bit(PoolStatusBitIndex::CollectReward));
+    }
+
+    #[test]
+    fn legacy_layout_test() {
+        use anchor_lang::Discriminator;
+
+        let owner = Pubkey::new_unique();
+        let fund_owner = Pubkey::new_unique();
+
+        // the 117 bytes of a config created before the layout grew, its padding is zeroed
+        let mut data = Vec::new();
+        data.extend_from_slice(&AmmConfig::DISCRIMINATOR);
+        data.push(254);
+        data.extend_from_slice(&3u16.to_le_bytes());
+        data.extend_from_slice(&owner.to_bytes());
+        data.extend_from_slice(&120_000u32.to_le_bytes());
+        data.extend_from_slice(&2_500u32.to_le_bytes());
+        data.extend_from_slice(&60u16.to_le_bytes());
+        data.extend_from_slice(&40_000u32.to_le_bytes());
+        data.extend_from_slice(&[0; 4]);
+        data.extend_from_slice(&fund_owner.to_bytes());
+        data.extend_from_slice(&[0; 8 * 3]);
+        assert_eq!(data.len(), 117);
+
+        // fails until resized by `migrate_account_space`
+        assert!(AmmConfig::try_deserialize(&mut data.as_slice()).is_err());
+
+        data.resize(AmmConfig::LEN, 0);
+        let amm_config = AmmConfig::try_deserialize(&mut data.as_slice()).unwrap();
+        assert_eq!(amm_config.bump, 254);
+        assert_eq!(amm_config.index, 3);
+        assert_eq!(amm_config.owner, owner);
+        assert_eq!(amm_config.protocol_fee_rate, 120_000);
+        assert_eq!(amm_config.trade_fee_rate, 2_500);
+        assert_eq!(amm_config.tick_spacing, 60);
+        assert_eq!(amm_config.fund_fee_rate, 40_000);
+        assert_eq!(amm_config.fund_owner, fund_owner);
+        // the fields added since are all off
+        assert!(!amm_config.is_custom_fee_enabled());
+        assert_eq!(amm_config.referral_fee_rate, 0);
+        assert_eq!(amm_config.volume_rebate_min_epochs, 0);
+        assert!(!amm_config.paused);
+        assert_eq!(amm_config.swap_hook_program, Pubkey::default());
+        assert_eq!(amm_config.empty_position_grace_epochs, 0);
+    }
+}
@@ -0,0 +1,22 @@
+use anchor_lang::prelude::*;
+
+pub const CUSTOM_FEE_CREATOR_SEED: &str = "custom_fee_creator";
+
+/// Marks a pool creator who is allowed to set a bespoke trade fee at pool creation
+///
+/// PDA of `[CUSTOM_FEE_CREATOR_SEED, creator]`
+///
+#[account]
+#[derive(Default, Debug)]
+pub struct CustomFeeCreatorState {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The whitelisted pool creator
+    pub creator: Pubkey,
+    // padding space for upgrade
+    pub padding: [u64; 4],
+}
+
+impl CustomFeeCreatorState {
+    pub const LEN: usize = 8 + 1 + 32 + 8 * 4;
+}
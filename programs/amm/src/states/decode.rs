@@ -0,0 +1,92 @@
+use crate::states::*;
+use anchor_lang::{prelude::*, Discriminator, ZeroCopy};
+use std::mem;
+
+/// A program account decoded by `decode_any`
+#[derive(Debug)]
+pub enum ProgramAccount {
+    Pool(Box<PoolState>),
+    TickArray(Box<TickArrayState>),
+    TickArrayBitmapExtension(Box<TickArrayBitmapExtension>),
+    Observation(Box<ObservationState>),
+    PersonalPosition(Box<PersonalPositionState>),
+    ProtocolPosition(Box<ProtocolPositionState>),
+    AmmConfig(Box<AmmConfig>),
+}
+
+/// Decodes the data of any pool, tick array, bitmap extension, observation, position or config
+/// account based on its discriminator
+pub fn decode_any(account_data: &[u8]) -> Result<ProgramAccount> {
+    let discriminator = account_data
+        .get(..8)
+        .ok_or(error::ErrorCode::AccountDiscriminatorNotFound)?;
+    let mut data = account_data;
+    Ok(match discriminator {
+        d if d == PoolState::DISCRIMINATOR => {
+            ProgramAccount::Pool(Box::new(decode_zero_copy(account_data)?))
+        }
+        d if d == TickArrayState::DISCRIMINATOR => {
+            ProgramAccount::TickArray(Box::new(decode_zero_copy(account_data)?))
+        }
+        d if d == TickArrayBitmapExtension::DISCRIMINATOR => {
+            ProgramAccount::TickArrayBitmapExtension(Box::new(decode_zero_copy(account_data)?))
+        }
+        d if d == ObservationState::DISCRIMINATOR => {
+            ProgramAccount::Observation(Box::new(decode_zero_copy(account_data)?))
+        }
+        d if d == PersonalPositionState::DISCRIMINATOR => ProgramAccount::PersonalPosition(
+            Box::new(PersonalPositionState::try_deserialize(&mut data)?),
+        ),
+        d if d == ProtocolPositionState::DISCRIMINATOR => ProgramAccount::ProtocolPosition(
+            Box::new(ProtocolPositionState::try_deserialize(&mut data)?),
+        ),
+        d if d == AmmConfig::DISCRIMINATOR => {
+            ProgramAccount::AmmConfig(Box::new(AmmConfig::try_deserialize(&mut data)?))
+        }
+        _ => return err!(error::ErrorCode::AccountDiscriminatorMismatch),
+    })
+}
+
+/// Copies a zero copy account out of its data, which may be longer than the struct
+fn decode_zero_copy<T: ZeroCopy>(account_data: &[u8]) -> Result<T> {
+    let data = account_data
+        .get(8..8 + mem::size_of::<T>())
+        .ok_or(error::ErrorCode::AccountDidNotDeserialize)?;
+    Ok(*bytemuck::from_bytes(data))
+}
+
+#[cfg(test)]
+mod decode_test {
+    use super::*;
+
+    #[test]
+    fn decode_any_test() {
+        let pool_state = PoolState {
+            tick_current: -100,
+            ..Default::default()
+        };
+        let mut account_data = PoolState::DISCRIMINATOR.to_vec();
+        account_data.extend_from_slice(bytemuck::bytes_of(&pool_state));
+        match decode_any(&account_data).unwrap() {
+            ProgramAccount::Pool(decoded) => assert_eq!({ decoded.tick_current }, -100),
+            account => panic!("decoded as {:?}", account),
+        }
+        // truncated zero copy data
+        assert!(decode_any(&account_data[..account_data.len() - 1]).is_err());
+
+        let amm_config = AmmConfig {
+            trade_fee_rate: 2500,
+            ..Default::default()
+        };
+        let mut account_data = Vec::new();
+        amm_config.try_serialize(&mut account_data).unwrap();
+        match decode_any(&account_data).unwrap() {
+            ProgramAccount::AmmConfig(decoded) => assert_eq!(decoded.trade_fee_rate, 2500),
+            account => panic!("decoded as {:?}", account),
+        }
+
+        // unknown or missing discriminators
+        assert!(decode_any(&[0; 16]).is_err());
+        assert!(decode_any(&[0; 4]).is_err());
+    }
+}
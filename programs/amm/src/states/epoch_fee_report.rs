@@ -0,0 +1,141 @@
+use super::pool::PoolState;
+use anchor_lang::prelude::*;
+
+pub const EPOCH_FEE_REPORT_SEED: &str = "epoch_fee_report";
+
+/// The pool's cumulative swap counters at the start of the epoch being reported, a
+/// permissionless crank emits the differences once the epoch is over
+///
+/// PDA of `[EPOCH_FEE_REPORT_SEED, pool_state]`
+///
+#[account]
+#[derive(Default, Debug)]
+pub struct EpochFeeReportState {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The pool being reported
+    pub pool_id: Pubkey,
+    /// The epoch the counters were taken at
+    pub epoch: u64,
+    /// The pool's swap_in_amount_token_0/1 at `epoch`
+    pub swap_in_amount_token_0: u128,
+    pub swap_in_amount_token_1: u128,
+    /// The pool's cumulative_trade_fees_token_0/1 at `epoch`
+    pub trade_fees_token_0: u64,
+    pub trade_fees_token_1: u64,
+    /// The pool's cumulative_protocol_fees_token_0/1 at `epoch`
+    pub protocol_fees_token_0: u64,
+    pub protocol_fees_token_1: u64,
+    // Unused bytes for future upgrades.
+    pub padding: [u64; 8],
+}
+
+impl EpochFeeReportState {
+    pub const LEN: usize = 8 + 1 + 32 + 8 + 16 * 2 + 8 * 4 + 8 * 8;
+
+    /// Takes the pool's counters as the start of `epoch`
+    pub fn snapshot(&mut self, epoch: u64, pool_state: &PoolState) {
+        self.epoch = epoch;
+        self.swap_in_amount_token_0 = pool_state.swap_in_amount_token_0;
+        self.swap_in_amount_token_1 = pool_state.swap_in_amount_token_1;
+        self.trade_fees_token_0 = pool_state.cumulative_trade_fees_token_0;
+        self.trade_fees_token_1 = pool_state.cumulative_trade_fees_token_1;
+        self.protocol_fees_token_0 = pool_state.cumulative_protocol_fees_token_0;
+        self.protocol_fees_token_1 = pool_state.cumulative_protocol_fees_token_1;
+    }
+
+    /// Reports the swaps since the snapshot and starts a new one at `epoch`, or returns None
+    /// while the snapshot's epoch is not over
+    pub fn roll(&mut self, epoch: u64, pool_state: &PoolState) -> Option<EpochFeeReportEvent> {
+        if epoch <= self.epoch {
+            return None;
+        }
+        let report = EpochFeeReportEvent {
+            pool_state: self.pool_id,
+            start_epoch: self.epoch,
+            end_epoch: epoch,
+            volume_token_0: pool_state
+                .swap_in_amount_token_0
+                .wrapping_sub(self.swap_in_amount_token_0),
+            volume_token_1: pool_state
+                .swap_in_amount_token_1
+                .wrapping_sub(self.swap_in_amount_token_1),
+            trade_fees_token_0: pool_state
+                .cumulative_trade_fees_token_0
+                .wrapping_sub(self.trade_fees_token_0),
+            trade_fees_token_1: pool_state
+                .cumulative_trade_fees_token_1
+                .wrapping_sub(self.trade_fees_token_1),
+            protocol_fees_token_0: pool_state
+                .cumulative_protocol_fees_token_0
+                .wrapping_sub(self.protocol_fees_token_0),
+            protocol_fees_token_1: pool_state
+                .cumulative_protocol_fees_token_1
+                .wrapping_sub(self.protocol_fees_token_1),
+        };
+        self.snapshot(epoch, pool_state);
+        Some(report)
+    }
+}
+
+/// Emitted when the crank closes an epoch of a pool, summarizing its swaps
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct EpochFeeReportEvent {
+    /// The pool being reported
+    pub pool_state: Pubkey,
+    /// The reported swaps were made from the start of `start_epoch` to the crank in `end_epoch`,
+    /// more than one epoch is covered if no crank ran in between
+    pub start_epoch: u64,
+    pub end_epoch: u64,
+    /// The input amounts of token_0 and token_1 swapped
+    pub volume_token_0: u128,
+    pub volume_token_1: u128,
+    /// The trade fees charged, in the input token
+    pub trade_fees_token_0: u64,
+    pub trade_fees_token_1: u64,
+    /// The protocol's share of the trade fees
+    pub protocol_fees_token_0: u64,
+    pub protocol_fees_token_1: u64,
+}
+
+#[cfg(test)]
+mod epoch_fee_report_test {
+    use super::*;
+
+    #[test]
+    fn roll_test() {
+        let mut pool_state = PoolState {
+            swap_in_amount_token_0: 1000,
+            cumulative_trade_fees_token_0: u64::MAX - 1,
+            ..Default::default()
+        };
+        let mut report_state = EpochFeeReportState::default();
+        report_state.snapshot(10, &pool_state);
+        assert!(report_state.roll(10, &pool_state).is_none());
+
+        pool_state.swap_in_amount_token_0 += 500;
+        pool_state.swap_in_amount_token_1 += 200;
+        pool_state.cumulative_trade_fees_token_0 = 3;
+        pool_state.cumulative_protocol_fees_token_1 = 1;
+        let report = report_state.roll(12, &pool_state).unwrap();
+        assert_eq!((report.start_epoch, report.end_epoch), (10, 12));
+        assert_eq!((report.volume_token_0, report.volume_token_1), (500, 200));
+        // the cumulative fees wrapped around
+        assert_eq!(
+            (report.trade_fees_token_0, report.trade_fees_token_1),
+            (5, 0)
+        );
+        assert_eq!(
+            (report.protocol_fees_token_0, report.protocol_fees_token_1),
+            (0, 1)
+        );
+
+        // the next epoch starts from the reported counters
+        assert!(report_state.roll(12, &pool_state).is_none());
+        let report = report_state.roll(13, &pool_state).unwrap();
+        assert_eq!(report.start_epoch, 12);
+        assert_eq!(report.volume_token_0, 0);
+        assert_eq!(report.trade_fees_token_0, 0);
+    }
+}
@@ -0,0 +1,290 @@
+use crate::libraries::{big_num::U256, fixed_point_64, full_math::MulDiv};
+use anchor_lang::prelude::*;
+
+pub const FEE_DISTRIBUTOR_SEED: &str = "fee_distributor";
+pub const FEE_DISTRIBUTOR_VAULT_SEED: &str = "fee_distributor_vault";
+pub const FEE_STAKER_SEED: &str = "fee_staker";
+
+/// Distributes a share of the protocol fee of one token to the stakers of a designated token.
+/// Fees received in an epoch are streamed to the stakers over the next epoch, pro-rata to their
+/// stake and to how long it was staked.
+///
+/// PDA of `[FEE_DISTRIBUTOR_SEED, amm_config, stake_mint, reward_mint]`
+///
+#[account]
+#[derive(Default, Debug)]
+pub struct FeeDistributorState {
+    /// Bump to identify PDA
+    pub bump: [u8; 1],
+    /// Protocol fees of pools belonging to this config can be distributed
+    pub amm_config: Pubkey,
+    /// The token to be staked
+    pub stake_mint: Pubkey,
+    /// The protocol fee token to be distributed
+    pub reward_mint: Pubkey,
+    /// The vault holding staked tokens
+    pub stake_vault: Pubkey,
+    /// The vault holding protocol fees to be claimed
+    pub reward_vault: Pubkey,
+    /// The share of protocol fee sent to the distributor, denominated in hundredths of a bip (10^-6)
+    pub protocol_fee_share_rate: u32,
+    /// The length of a distribution epoch in seconds
+    pub epoch_duration: u64,
+    /// The epoch fees are currently accumulated for
+    pub current_epoch: u64,
+    /// Fees received in the current epoch, not yet distributed
+    pub pending_reward: u64,
+    /// Total amount of staked tokens
+    pub total_staked: u64,
+    /// The distributed reward per staked token as a Q64.64
+    pub reward_per_share_x64: u128,
+    /// Fees received in the previous epoch, streamed to the stakers over the current epoch
+    pub streaming_reward: u64,
+    /// The time up to which `streaming_reward` has been streamed
+    pub last_update_time: u64,
+    // Unused bytes for future upgrades.
+    pub padding: [u64; 6],
+}
+
+impl FeeDistributorState {
+    pub const LEN: usize = 8 + 1 + 32 * 5 + 4 + 8 * 4 + 16 + 8 * 2 + 8 * 6;
+
+    pub fn seeds(&self) -> [&[u8]; 5] {
+        [
+            FEE_DISTRIBUTOR_SEED.as_bytes(),
+            self.amm_config.as_ref(),
+            self.stake_mint.as_ref(),
+            self.reward_mint.as_ref(),
+            self.bump.as_ref(),
+        ]
+    }
+
+    /// Stream the fees of the previous epoch up to `block_timestamp` and roll the finished
+    /// epochs. Fees are carried on to the next epoch while nothing is staked
+    pub fn update_epoch(&mut self, block_timestamp: u64) {
+        let epoch = block_timestamp / self.epoch_duration;
+        while self.current_epoch < epoch {
+            self.stream((self.current_epoch + 1) * self.epoch_duration);
+            self.streaming_reward = self.pending_reward;
+            self.pending_reward = 0;
+            self.current_epoch += 1;
+            // nothing streams over the remaining epochs, or nobody can earn it before the next
+            // stake, which is no earlier than now
+            if self.streaming_reward == 0 || self.total_staked == 0 {
+                self.pending_reward = self.streaming_reward;
+                self.streaming_reward = 0;
+                self.current_epoch = epoch;
+            }
+        }
+        self.stream(block_timestamp);
+    }
+
+    /// Release the part of `streaming_reward` that falls between `last_update_time` and
+    /// `timestamp` to the current stakers
+    fn stream(&mut self, timestamp: u64) {
+        let elapsed = timestamp.saturating_sub(self.last_update_time);
+        self.last_update_time = self.last_update_time.max(timestamp);
+        if elapsed == 0 || self.streaming_reward == 0 {
+            return;
+        }
+        if self.total_staked == 0 {
+            let amount = U256::from(self.streaming_reward)
+                .mul_div_floor(U256::from(elapsed), U256::from(self.epoch_duration))
+                .unwrap()
+                .as_u64();
+            self.pending_reward = self.pending_reward.checked_add(amount).unwrap();
+            return;
+        }
+        let reward_per_share_delta_x64 = (U256::from(self.streaming_reward) * U256::from(elapsed))
+            .mul_div_floor(
+                U256::from(fixed_point_64::Q64),
+                U256::from(self.epoch_duration) * U256::from(self.total_staked),
+            )
+            .unwrap()
+            .as_u128();
+        self.reward_per_share_x64 = self
+            .reward_per_share_x64
+            .checked_add(reward_per_share_delta_x64)
+            .unwrap();
+    }
+
+    pub fn add_reward(&mut self, amount: u64) {
+        self.pending_reward = self.pending_reward.checked_add(amount).unwrap();
+    }
+}
+
+/// Staked amount and reward checkpoint of a staker
+///
+/// PDA of `[FEE_STAKER_SEED, fee_distributor, owner]`
+///
+#[account]
+#[derive(Default, Debug)]
+pub struct FeeStakerState {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The distributor staked to
+    pub fee_distributor: Pubkey,
+    /// The staker
+    pub owner: Pubkey,
+    /// The amount of staked tokens
+    pub staked_amount: u64,
+    /// The reward per share of the distributor when the staker was last settled
+    pub reward_per_share_checkpoint_x64: u128,
+    /// The reward claimable by the staker
+    pub reward_owed: u64,
+    // Unused bytes for future upgrades.
+    pub padding: [u64; 4],
+}
+
+impl FeeStakerState {
+    pub const LEN: usize = 8 + 1 + 32 * 2 + 8 + 16 + 8 + 8 * 4;
+
+    /// Accrue the reward distributed since the last checkpoint
+    pub fn settle(&mut self, reward_per_share_x64: u128) {
+        let reward_delta = U256::from(reward_per_share_x64 - self.reward_per_share_checkpoint_x64)
+            .mul_div_floor(
+                U256::from(self.staked_amount),
+                U256::from(fixed_point_64::Q64),
+            )
+            .unwrap()
+            .as_u64();
+        self.reward_owed = self.reward_owed.checked_add(reward_delta).unwrap();
+        self.reward_per_share_checkpoint_x64 = reward_per_share_x64;
+    }
+}
+
+/// Emitted when protocol fees are sent to a distributor
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct DistributeProtocolFeeEvent {
+    #[index]
+    pub fee_distributor: Pubkey,
+    pub pool_state: Pubkey,
+    pub amount: u64,
+    pub epoch: u64,
+}
+
+/// Emitted when a staker claims the distributed fees
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct ClaimDistributorRewardEvent {
+    #[index]
+    pub fee_distributor: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+}
+
+#[cfg(test)]
+mod fee_distributor_test {
+    use super::*;
+
+    #[test]
+    fn distribute_by_epoch_test() {
+        let mut distributor = FeeDistributorState {
+            epoch_duration: 100,
+            ..Default::default()
+        };
+        let mut staker_a = FeeStakerState::default();
+        let mut staker_b = FeeStakerState::default();
+
+        // nothing staked, the fee stays pending
+        distributor.add_reward(1000);
+        distributor.update_epoch(100);
+        assert_eq!(distributor.pending_reward, 1000);
+        assert_eq!(distributor.reward_per_share_x64, 0);
+
+        staker_a.settle(distributor.reward_per_share_x64);
+        staker_a.staked_amount = 100;
+        staker_b.settle(distributor.reward_per_share_x64);
+        staker_b.staked_amount = 300;
+        distributor.total_staked = 400;
+
+        // the same epoch, not distributed yet
+        distributor.update_epoch(199);
+        assert_eq!(distributor.pending_reward, 1000);
+
+        // streamed over the next epoch
+        distributor.update_epoch(200);
+        assert_eq!(distributor.pending_reward, 0);
+        assert_eq!(distributor.streaming_reward, 1000);
+        distributor.update_epoch(250);
+        staker_a.settle(distributor.reward_per_share_x64);
+        staker_b.settle(distributor.reward_per_share_x64);
+        assert_eq!(staker_a.reward_owed, 125);
+        assert_eq!(staker_b.reward_owed, 375);
+
+        distributor.update_epoch(300);
+        staker_a.settle(distributor.reward_per_share_x64);
+        staker_b.settle(distributor.reward_per_share_x64);
+        assert_eq!(staker_a.reward_owed, 250);
+        assert_eq!(staker_b.reward_owed, 750);
+
+        // settle again without new rewards changes nothing
+        distributor.update_epoch(1000);
+        staker_a.settle(distributor.reward_per_share_x64);
+        assert_eq!(staker_a.reward_owed, 250);
+    }
+
+    #[test]
+    fn stake_across_epoch_boundary_test() {
+        let mut distributor = FeeDistributorState {
+            epoch_duration: 100,
+            current_epoch: 1,
+            last_update_time: 100,
+            ..Default::default()
+        };
+        let mut staker_a = FeeStakerState {
+            staked_amount: 100,
+            ..Default::default()
+        };
+        distributor.total_staked = 100;
+        distributor.add_reward(1000);
+
+        // b stakes as much as a one second before the boundary
+        let mut staker_b = FeeStakerState::default();
+        distributor.update_epoch(199);
+        staker_b.settle(distributor.reward_per_share_x64);
+        staker_b.staked_amount = 100;
+        distributor.total_staked += 100;
+
+        // and unstakes one second after it
+        distributor.update_epoch(201);
+        staker_b.settle(distributor.reward_per_share_x64);
+        staker_b.staked_amount = 0;
+        distributor.total_staked -= 100;
+        assert_eq!(staker_b.reward_owed, 4);
+
+        distributor.update_epoch(300);
+        staker_a.settle(distributor.reward_per_share_x64);
+        assert_eq!(staker_a.reward_owed, 994);
+    }
+
+    #[test]
+    fn carry_reward_while_nothing_staked_test() {
+        let mut distributor = FeeDistributorState {
+            epoch_duration: 100,
+            current_epoch: 1,
+            last_update_time: 100,
+            ..Default::default()
+        };
+        let mut staker = FeeStakerState {
+            staked_amount: 100,
+            ..Default::default()
+        };
+        distributor.total_staked = 100;
+        distributor.add_reward(1000);
+
+        // the staker leaves halfway through the streaming epoch
+        distributor.update_epoch(250);
+        staker.settle(distributor.reward_per_share_x64);
+        staker.staked_amount = 0;
+        distributor.total_staked = 0;
+        assert_eq!(staker.reward_owed, 500);
+
+        // the rest is carried on while nothing is staked, however many epochs pass
+        distributor.update_epoch(1_000_000);
+        assert_eq!(distributor.pending_reward, 500);
+        assert_eq!(distributor.streaming_reward, 0);
+        assert_eq!(distributor.current_epoch, 10_000);
+    }
+}
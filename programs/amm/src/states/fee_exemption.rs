@@ -0,0 +1,70 @@
+use crate::error::ErrorCode;
+use anchor_lang::prelude::*;
+
+pub const FEE_EXEMPTION_SEED: &str = "fee_exemption";
+/// The max number of programs exempted from the protocol fee of one config
+pub const FEE_EXEMPT_PROGRAMS_MAX: usize = 8;
+
+/// The programs whose swaps through CPI pay no protocol fee in the pools of a config,
+/// for keepers and vaults whose volume is already taxed elsewhere
+///
+/// PDA of `[FEE_EXEMPTION_SEED, amm_config]`
+///
+#[account]
+#[derive(Default, Debug)]
+pub struct FeeExemption {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The config the exemption applies to
+    pub amm_config: Pubkey,
+    /// The exempted caller programs, unused entries are the default pubkey
+    pub programs: [Pubkey; FEE_EXEMPT_PROGRAMS_MAX],
+    // Unused bytes for future upgrades.
+    pub padding: [u64; 8],
+}
+
+impl FeeExemption {
+    pub const LEN: usize = 8 + 1 + 32 + 32 * FEE_EXEMPT_PROGRAMS_MAX + 8 * 8;
+
+    pub fn set_programs(&mut self, programs: &[Pubkey]) -> Result<()> {
+        require!(
+            programs.len() <= FEE_EXEMPT_PROGRAMS_MAX
+                && programs.iter().all(|p| *p != Pubkey::default()),
+            ErrorCode::InvalidFeeExemptionParam
+        );
+        self.programs = [Pubkey::default(); FEE_EXEMPT_PROGRAMS_MAX];
+        self.programs[..programs.len()].copy_from_slice(programs);
+        Ok(())
+    }
+
+    pub fn is_exempt(&self, program_id: &Pubkey) -> bool {
+        *program_id != Pubkey::default() && self.programs.contains(program_id)
+    }
+}
+
+#[cfg(test)]
+mod fee_exemption_test {
+    use super::*;
+
+    #[test]
+    fn set_programs_test() {
+        let mut fee_exemption = FeeExemption::default();
+        let program = Pubkey::new_unique();
+        assert!(!fee_exemption.is_exempt(&program));
+        assert!(!fee_exemption.is_exempt(&Pubkey::default()));
+
+        fee_exemption.set_programs(&[program]).unwrap();
+        assert!(fee_exemption.is_exempt(&program));
+        assert!(!fee_exemption.is_exempt(&Pubkey::new_unique()));
+        assert!(!fee_exemption.is_exempt(&Pubkey::default()));
+
+        // replaces the whole list
+        fee_exemption.set_programs(&[]).unwrap();
+        assert!(!fee_exemption.is_exempt(&program));
+
+        assert!(fee_exemption
+            .set_programs(&[Pubkey::new_unique(); FEE_EXEMPT_PROGRAMS_MAX + 1])
+            .is_err());
+        assert!(fee_exemption.set_programs(&[Pubkey::default()]).is_err());
+    }
+}
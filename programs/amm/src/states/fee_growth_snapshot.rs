@@ -0,0 +1,129 @@
+use anchor_lang::prelude::*;
+
+pub const FEE_GROWTH_SNAPSHOT_SEED: &str = "fee_growth_snapshot";
+/// Hourly snapshots covering 24 hours, plus the snapshot the window starts from
+pub const FEE_GROWTH_SNAPSHOT_NUM: usize = 25;
+pub const FEE_GROWTH_SNAPSHOT_INTERVAL: u64 = 3600;
+
+/// The fee growth of a pool at a point in time
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct FeeGrowthSnapshot {
+    /// The block timestamp of the snapshot
+    pub timestamp: u64,
+    /// The pool fee_growth_global_0_x64 at the snapshot
+    pub fee_growth_global_0_x64: u128,
+    /// The pool fee_growth_global_1_x64 at the snapshot
+    pub fee_growth_global_1_x64: u128,
+}
+
+impl FeeGrowthSnapshot {
+    pub const LEN: usize = 8 + 16 + 16;
+}
+
+/// A ring of periodic fee growth snapshots of a pool, written by a permissionless crank
+///
+/// PDA of `[FEE_GROWTH_SNAPSHOT_SEED, pool_id]`
+///
+#[account]
+#[derive(Default, Debug)]
+pub struct FeeGrowthSnapshotState {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The pool the snapshots belong to
+    pub pool_id: Pubkey,
+    /// The index of the most recent snapshot
+    pub snapshot_index: u8,
+    /// The number of written snapshots, at most FEE_GROWTH_SNAPSHOT_NUM
+    pub snapshot_count: u8,
+    pub snapshots: [FeeGrowthSnapshot; FEE_GROWTH_SNAPSHOT_NUM],
+    // Unused bytes for future upgrades.
+    pub padding: [u64; 4],
+}
+
+impl FeeGrowthSnapshotState {
+    pub const LEN: usize =
+        8 + 1 + 32 + 1 + 1 + FeeGrowthSnapshot::LEN * FEE_GROWTH_SNAPSHOT_NUM + 8 * 4;
+
+    /// Writes a new snapshot if at least FEE_GROWTH_SNAPSHOT_INTERVAL passed since the
+    /// most recent one, returns whether it was written
+    pub fn update(
+        &mut self,
+        timestamp: u64,
+        fee_growth_global_0_x64: u128,
+        fee_growth_global_1_x64: u128,
+    ) -> bool {
+        if self.snapshot_count > 0 {
+            let last = self.snapshots[self.snapshot_index as usize];
+            if timestamp < last.timestamp + FEE_GROWTH_SNAPSHOT_INTERVAL {
+                return false;
+            }
+            self.snapshot_index =
+                ((self.snapshot_index as usize + 1) % FEE_GROWTH_SNAPSHOT_NUM) as u8;
+        }
+        self.snapshots[self.snapshot_index as usize] = FeeGrowthSnapshot {
+            timestamp,
+            fee_growth_global_0_x64,
+            fee_growth_global_1_x64,
+        };
+        self.snapshot_count = (self.snapshot_count + 1).min(FEE_GROWTH_SNAPSHOT_NUM as u8);
+        true
+    }
+
+    /// The fee growth accumulated between the oldest snapshot no older than `window` seconds
+    /// before the most recent one and the most recent one.
+    /// Returns `(elapsed_seconds, fee_growth_delta_0_x64, fee_growth_delta_1_x64)`,
+    /// or None if fewer than two snapshots fall in the window
+    pub fn fee_growth_delta(&self, window: u64) -> Option<(u64, u128, u128)> {
+        if self.snapshot_count < 2 {
+            return None;
+        }
+        let latest = self.snapshots[self.snapshot_index as usize];
+        let mut oldest = latest;
+        for i in 1..self.snapshot_count as usize {
+            let index = (self.snapshot_index as usize + FEE_GROWTH_SNAPSHOT_NUM - i)
+                % FEE_GROWTH_SNAPSHOT_NUM;
+            let snapshot = self.snapshots[index];
+            if latest.timestamp - snapshot.timestamp > window {
+                break;
+            }
+            oldest = snapshot;
+        }
+        if oldest.timestamp == latest.timestamp {
+            return None;
+        }
+        Some((
+            latest.timestamp - oldest.timestamp,
+            latest
+                .fee_growth_global_0_x64
+                .wrapping_sub(oldest.fee_growth_global_0_x64),
+            latest
+                .fee_growth_global_1_x64
+                .wrapping_sub(oldest.fee_growth_global_1_x64),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn fee_growth_snapshot_ring_test() {
+        let mut state = FeeGrowthSnapshotState::default();
+        assert!(state.fee_growth_delta(24 * 3600).is_none());
+
+        assert!(state.update(1000, 0, 0));
+        assert!(!state.update(1000 + FEE_GROWTH_SNAPSHOT_INTERVAL - 1, 1, 1));
+        assert!(state.fee_growth_delta(24 * 3600).is_none());
+
+        // fill more than the ring, fee growth increases by 10 every hour
+        for i in 1..40u64 {
+            assert!(state.update(1000 + i * FEE_GROWTH_SNAPSHOT_INTERVAL, i as u128 * 10, 0));
+        }
+        assert_eq!(state.snapshot_count as usize, FEE_GROWTH_SNAPSHOT_NUM);
+        assert_eq!(state.fee_growth_delta(24 * 3600), Some((24 * 3600, 240, 0)));
+        assert_eq!(state.fee_growth_delta(3600), Some((3600, 10, 0)));
+        // a window larger than the ring is capped by the oldest snapshot
+        assert_eq!(state.fee_growth_delta(48 * 3600), Some((24 * 3600, 240, 0)));
+    }
+}
@@ -0,0 +1,54 @@
+use crate::error::ErrorCode;
+use anchor_lang::prelude::*;
+
+pub const INCENTIVE_PROGRAMS_SEED: &str = "incentive_programs";
+/// The max number of incentive programs approved for one config
+pub const INCENTIVE_PROGRAMS_MAX: usize = 8;
+
+/// The external liquidity mining programs allowed to enroll positions of a config's pools in
+/// their campaigns
+///
+/// PDA of `[INCENTIVE_PROGRAMS_SEED, amm_config]`
+///
+#[account]
+#[derive(Default, Debug)]
+pub struct IncentivePrograms {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The config the approval applies to
+    pub amm_config: Pubkey,
+    /// The approved programs, unused entries are the default pubkey
+    pub programs: [Pubkey; INCENTIVE_PROGRAMS_MAX],
+    // Unused bytes for future upgrades.
+    pub padding: [u64; 8],
+}
+
+impl IncentivePrograms {
+    pub const LEN: usize = 8 + 1 + 32 + 32 * INCENTIVE_PROGRAMS_MAX + 8 * 8;
+
+    pub fn set_programs(&mut self, programs: &[Pubkey]) -> Result<()> {
+        require!(
+            programs.len() <= INCENTIVE_PROGRAMS_MAX
+                && programs.iter().all(|p| *p != Pubkey::default()),
+            ErrorCode::InvalidIncentiveProgramsParam
+        );
+        self.programs = [Pubkey::default(); INCENTIVE_PROGRAMS_MAX];
+        self.programs[..programs.len()].copy_from_slice(programs);
+        Ok(())
+    }
+
+    pub fn is_approved(&self, program_id: &Pubkey) -> bool {
+        *program_id != Pubkey::default() && self.programs.contains(program_id)
+    }
+}
+
+/// Emitted when a position is enrolled in or withdrawn from an incentive campaign
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct PositionIncentiveEvent {
+    /// The ID of the token bound to the position
+    #[index]
+    pub position_nft_mint: Pubkey,
+    /// The program the position is enrolled in, default once withdrawn
+    pub incentive_program: Pubkey,
+}
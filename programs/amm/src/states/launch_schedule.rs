@@ -0,0 +1,137 @@
+use crate::error::ErrorCode;
+use crate::libraries::tick_math;
+use anchor_lang::prelude::*;
+
+pub const LAUNCH_SCHEDULE_SEED: &str = "launch_schedule";
+pub const LAUNCH_SCHEDULE_VAULT_SEED: &str = "launch_schedule_vault";
+
+/// A schedule shifting a protocol owned position's range down over time, for price discovery
+/// of a newly launched token. The position nft is held in escrow while the schedule exists and
+/// anyone can crank the position down to its scheduled range
+///
+/// PDA of `[LAUNCH_SCHEDULE_SEED, position_nft_mint]`
+///
+#[account]
+#[derive(Default, Debug)]
+pub struct LaunchScheduleState {
+    /// Bump to identify PDA
+    pub bump: [u8; 1],
+    /// The pool of the position
+    pub pool_id: Pubkey,
+    /// Mint address of the escrowed position nft
+    pub position_nft_mint: Pubkey,
+    /// The personal position bound to the nft
+    pub personal_position: Pubkey,
+    /// The escrow token account holding the position nft
+    pub escrow_nft_account: Pubkey,
+    /// The owner of the token accounts receiving what is not redeposited on a shift
+    pub beneficiary: Pubkey,
+    /// The first shift is due `shift_interval` seconds after `start_time`
+    pub start_time: u64,
+    pub shift_interval: u64,
+    /// How far down each shift moves the range, a multiple of the tick spacing
+    pub tick_shift: i32,
+    /// The position's lower tick when the schedule was created
+    pub tick_lower_start: i32,
+    /// The lowest the lower tick is shifted to
+    pub tick_lower_floor: i32,
+    /// The width of the position's range, kept by every shift
+    pub tick_width: i32,
+    // Unused bytes for future upgrades.
+    pub padding: [u64; 8],
+}
+
+impl LaunchScheduleState {
+    pub const LEN: usize = 8 + 1 + 32 * 5 + 8 + 8 + 4 * 4 + 8 * 8;
+
+    pub fn seeds(&self) -> [&[u8]; 3] {
+        [
+            LAUNCH_SCHEDULE_SEED.as_bytes(),
+            self.position_nft_mint.as_ref(),
+            self.bump.as_ref(),
+        ]
+    }
+
+    /// Checks the schedule moves a range `[tick_lower_start, tick_lower_start + tick_width)`
+    /// down in whole tick spacings, without leaving the tick range
+    pub fn check(&self, tick_spacing: u16) -> Result<()> {
+        let tick_spacing = i32::from(tick_spacing);
+        require!(
+            self.shift_interval > 0
+                && self.tick_shift > 0
+                && self.tick_shift % tick_spacing == 0
+                && self.tick_lower_floor % tick_spacing == 0
+                && self.tick_lower_floor < self.tick_lower_start
+                && self.tick_lower_floor >= tick_math::MIN_TICK,
+            ErrorCode::InvalidLaunchSchedule
+        );
+        Ok(())
+    }
+
+    /// The lower tick the position should be at, one `tick_shift` lower per elapsed
+    /// `shift_interval` until the floor
+    pub fn scheduled_tick_lower(&self, block_timestamp: u64) -> i32 {
+        let shifts = block_timestamp.saturating_sub(self.start_time) / self.shift_interval;
+        let tick_lower = i64::from(self.tick_lower_start)
+            - i64::from(self.tick_shift) * shifts.min(u32::MAX.into()) as i64;
+        tick_lower.max(i64::from(self.tick_lower_floor)) as i32
+    }
+}
+
+/// Emitted when the crank shifts a launch position down its schedule
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct LaunchShiftEvent {
+    /// The schedule of the position
+    pub launch_schedule: Pubkey,
+    /// The pool of the position
+    pub pool_state: Pubkey,
+    /// The range before the shift
+    pub tick_lower_index: i32,
+    pub tick_upper_index: i32,
+    /// The range after the shift
+    pub new_tick_lower_index: i32,
+    pub new_tick_upper_index: i32,
+    /// The liquidity withdrawn from the old range and deposited on the new one
+    pub liquidity_removed: u128,
+    pub liquidity_added: u128,
+    /// The fees and the withdrawn amounts not needed by the new range, paid to the beneficiary
+    pub amount_0: u64,
+    pub amount_1: u64,
+}
+
+#[cfg(test)]
+mod launch_schedule_test {
+    use super::*;
+
+    #[test]
+    fn scheduled_tick_lower_test() {
+        let schedule = LaunchScheduleState {
+            start_time: 1000,
+            shift_interval: 100,
+            tick_shift: 60,
+            tick_lower_start: 600,
+            tick_lower_floor: 420,
+            tick_width: 600,
+            ..Default::default()
+        };
+        schedule.check(60).unwrap();
+        // not started yet, then one shift per interval
+        assert_eq!(schedule.scheduled_tick_lower(0), 600);
+        assert_eq!(schedule.scheduled_tick_lower(1099), 600);
+        assert_eq!(schedule.scheduled_tick_lower(1100), 540);
+        assert_eq!(schedule.scheduled_tick_lower(1250), 480);
+        // stops at the floor
+        assert_eq!(schedule.scheduled_tick_lower(1300), 420);
+        assert_eq!(schedule.scheduled_tick_lower(u64::MAX), 420);
+
+        // shifts off the tick spacing
+        assert!(schedule.check(120).is_err());
+        // the floor is not below the start
+        let inverted = LaunchScheduleState {
+            tick_lower_floor: 600,
+            ..schedule
+        };
+        assert!(inverted.check(60).is_err());
+    }
+}
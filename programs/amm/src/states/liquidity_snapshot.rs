@@ -0,0 +1,137 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak;
+
+pub const LIQUIDITY_SNAPSHOT_SEED: &str = "liquidity_snapshot";
+/// Seconds after the commit during which the admin can dispute a snapshot
+pub const SNAPSHOT_CHALLENGE_WINDOW: u64 = 60 * 60 * 24;
+
+/// A merkle root of `(owner, in-range liquidity)` leaves for a pool, committed once per epoch.
+///
+/// The root is not checked when committed. It becomes final once the challenge window has
+/// passed without a dispute, a disputed snapshot is closed so the epoch can be committed again.
+///
+/// PDA of `[LIQUIDITY_SNAPSHOT_SEED, pool_state, epoch]`
+///
+#[account]
+#[derive(Default, Debug)]
+pub struct LiquiditySnapshot {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The pool the snapshot is taken of
+    pub pool_id: Pubkey,
+    /// The epoch the snapshot is taken at
+    pub epoch: u64,
+    /// Who committed the root
+    pub submitter: Pubkey,
+    /// Root of the tree of `(owner, liquidity)` leaves, see `leaf_hash`
+    pub merkle_root: [u8; 32],
+    /// The pool's in-range liquidity when committed, no leaf can exceed it
+    pub pool_liquidity: u128,
+    /// The pool's tick when committed
+    pub tick_current: i32,
+    /// The snapshot is final from this time on
+    pub challenge_end_time: u64,
+    // Unused bytes for future upgrades.
+    pub padding: [u64; 8],
+}
+
+impl LiquiditySnapshot {
+    pub const LEN: usize = 8 + 1 + 32 + 8 + 32 + 32 + 16 + 4 + 8 + 8 * 8;
+
+    pub fn is_final(&self, curr_timestamp: u64) -> bool {
+        curr_timestamp >= self.challenge_end_time
+    }
+
+    /// Checks `(owner, liquidity)` is a leaf of a final snapshot
+    pub fn verify(
+        &self,
+        owner: &Pubkey,
+        liquidity: u128,
+        proof: &[[u8; 32]],
+        curr_timestamp: u64,
+    ) -> bool {
+        self.is_final(curr_timestamp)
+            && liquidity <= self.pool_liquidity
+            && verify_merkle_proof(leaf_hash(owner, liquidity), proof, self.merkle_root)
+    }
+}
+
+pub fn leaf_hash(owner: &Pubkey, liquidity: u128) -> [u8; 32] {
+    keccak::hashv(&[owner.as_ref(), &liquidity.to_le_bytes()]).0
+}
+
+/// Walks the proof up from `leaf`, each pair is hashed in sorted order
+pub fn verify_merkle_proof(leaf: [u8; 32], proof: &[[u8; 32]], root: [u8; 32]) -> bool {
+    let computed = proof.iter().fold(leaf, |node, sibling| {
+        if node <= *sibling {
+            keccak::hashv(&[&node, sibling]).0
+        } else {
+            keccak::hashv(&[sibling, &node]).0
+        }
+    });
+    computed == root
+}
+
+/// Emitted when a liquidity snapshot root is committed
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct LiquiditySnapshotEvent {
+    /// The snapshot account
+    pub liquidity_snapshot: Pubkey,
+    /// The pool the snapshot is taken of
+    pub pool_state: Pubkey,
+    /// The epoch the snapshot is taken at
+    pub epoch: u64,
+    /// The committed root
+    pub merkle_root: [u8; 32],
+    /// The pool's in-range liquidity when committed
+    pub pool_liquidity: u128,
+    /// The snapshot is final from this time on
+    pub challenge_end_time: u64,
+}
+
+#[cfg(test)]
+mod liquidity_snapshot_test {
+    use super::*;
+
+    fn hash_pair(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+        if a <= b {
+            keccak::hashv(&[&a, &b]).0
+        } else {
+            keccak::hashv(&[&b, &a]).0
+        }
+    }
+
+    #[test]
+    fn verify_test() {
+        let owners = [
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        ];
+        let leaves = [
+            leaf_hash(&owners[0], 100),
+            leaf_hash(&owners[1], 200),
+            leaf_hash(&owners[2], 300),
+        ];
+        // the odd leaf is paired with itself
+        let left = hash_pair(leaves[0], leaves[1]);
+        let right = hash_pair(leaves[2], leaves[2]);
+        let snapshot = LiquiditySnapshot {
+            merkle_root: hash_pair(left, right),
+            pool_liquidity: 600,
+            challenge_end_time: 1000,
+            ..Default::default()
+        };
+
+        assert!(snapshot.verify(&owners[0], 100, &[leaves[1], right], 1000));
+        assert!(snapshot.verify(&owners[1], 200, &[leaves[0], right], 1000));
+        assert!(snapshot.verify(&owners[2], 300, &[leaves[2], left], 1000));
+        // wrong liquidity, owner or proof
+        assert!(!snapshot.verify(&owners[0], 101, &[leaves[1], right], 1000));
+        assert!(!snapshot.verify(&owners[1], 100, &[leaves[1], right], 1000));
+        assert!(!snapshot.verify(&owners[0], 100, &[leaves[1]], 1000));
+        // still in the challenge window
+        assert!(!snapshot.verify(&owners[0], 100, &[leaves[1], right], 999));
+    }
+}
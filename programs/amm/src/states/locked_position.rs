@@ -0,0 +1,91 @@
+use anchor_lang::prelude::*;
+
+pub const LOCKED_POSITION_SEED: &str = "locked_position";
+pub const LOCKED_POSITION_VAULT_SEED: &str = "locked_position_vault";
+
+/// Escrow record of a position NFT locked until `unlock_time`
+///
+/// PDA of `[LOCKED_POSITION_SEED, position_nft_mint]`
+///
+#[account]
+#[derive(Default, Debug)]
+pub struct LockedPositionState {
+    /// Bump to identify PDA
+    pub bump: [u8; 1],
+    /// The original owner, who can claim fees and rewards and unlock the position after `unlock_time`
+    pub owner: Pubkey,
+    /// Mint address of the locked position nft
+    pub position_nft_mint: Pubkey,
+    /// The personal position bound to the nft
+    pub personal_position: Pubkey,
+    /// The escrow token account holding the position nft
+    pub locked_nft_account: Pubkey,
+    /// The timestamp after which the position can be unlocked, can only be extended
+    pub unlock_time: u64,
+    /// The non-transferable receipt minted to the owner while the nft is in escrow, default if
+    /// the position was locked without one
+    pub receipt_mint: Pubkey,
+    // Unused bytes for future upgrades.
+    pub padding: [u64; 4],
+}
+
+impl LockedPositionState {
+    pub const LEN: usize = 8 + 1 + 32 * 4 + 8 + 32 + 8 * 4;
+
+    pub fn seeds(&self) -> [&[u8]; 3] {
+        [
+            LOCKED_POSITION_SEED.as_bytes(),
+            self.position_nft_mint.as_ref(),
+            self.bump.as_ref(),
+        ]
+    }
+
+    pub fn is_locked(&self, block_timestamp: u64) -> bool {
+        block_timestamp < self.unlock_time
+    }
+
+    pub fn has_receipt(&self) -> bool {
+        self.receipt_mint != Pubkey::default()
+    }
+}
+
+/// Emitted when a position is locked or the lock is extended
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct LockPositionEvent {
+    /// The locked position nft mint
+    #[index]
+    pub position_nft_mint: Pubkey,
+    /// The owner of the locked position
+    pub owner: Pubkey,
+    /// The timestamp after which the position can be unlocked
+    pub unlock_time: u64,
+}
+
+/// Emitted when a position is unlocked and the nft is returned to the owner
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct UnlockPositionEvent {
+    /// The unlocked position nft mint
+    #[index]
+    pub position_nft_mint: Pubkey,
+    /// The owner who receives the position nft
+    pub owner: Pubkey,
+}
+
+#[cfg(test)]
+mod locked_position_test {
+    use super::*;
+
+    #[test]
+    fn is_locked_test() {
+        let locked_position = LockedPositionState {
+            unlock_time: 100,
+            ..Default::default()
+        };
+        assert!(locked_position.is_locked(99));
+        assert!(!locked_position.is_locked(100));
+        assert!(!locked_position.is_locked(101));
+        assert!(!locked_position.has_receipt());
+    }
+}
@@ -1,17 +1,140 @@
+pub mod admin_batch;
+pub mod admin_heartbeat;
+pub mod buyback;
 pub mod config;
+pub mod custom_fee_creator;
+#[cfg(feature = "client")]
+pub mod decode;
+pub mod epoch_fee_report;
+pub mod fee_distributor;
+pub mod fee_exemption;
+pub mod fee_growth_snapshot;
+pub mod incentive_programs;
+pub mod launch_schedule;
+pub mod liquidity_snapshot;
+pub mod locked_position;
 pub mod operation_account;
 pub mod oracle;
+pub mod oracle_consumer;
 pub mod personal_position;
 pub mod pool;
+pub mod position_bundle;
+pub mod position_delegate;
+pub mod position_lease;
 pub mod protocol_position;
+pub mod referral;
+pub mod stop_order;
+pub mod strategy_preset;
+pub mod swap_hook;
 pub mod tick_array;
 pub mod tickarray_bitmap_extension;
+pub mod user_stats;
 
+pub use admin_batch::*;
+pub use admin_heartbeat::*;
+pub use buyback::*;
 pub use config::*;
+pub use custom_fee_creator::*;
+#[cfg(feature = "client")]
+pub use decode::*;
+pub use epoch_fee_report::*;
+pub use fee_distributor::*;
+pub use fee_exemption::*;
+pub use fee_growth_snapshot::*;
+pub use incentive_programs::*;
+pub use launch_schedule::*;
+pub use liquidity_snapshot::*;
+pub use locked_position::*;
 pub use operation_account::*;
 pub use oracle::*;
+pub use oracle_consumer::*;
 pub use personal_position::*;
 pub use pool::*;
+pub use position_bundle::*;
+pub use position_delegate::*;
+pub use position_lease::*;
 pub use protocol_position::*;
+pub use referral::*;
+pub use stop_order::*;
+pub use strategy_preset::*;
+pub use swap_hook::*;
 pub use tick_array::*;
 pub use tickarray_bitmap_extension::*;
+pub use user_stats::*;
+
+#[cfg(test)]
+mod space_test {
+    use super::*;
+    use anchor_lang::AccountSerialize;
+    use std::mem::size_of;
+
+    fn borsh_space<T: AccountSerialize + Default>() -> usize {
+        let mut data = Vec::new();
+        T::default().try_serialize(&mut data).unwrap();
+        data.len()
+    }
+
+    #[test]
+    fn borsh_account_space_test() {
+        assert_eq!(AdminBatchState::LEN, borsh_space::<AdminBatchState>());
+        assert_eq!(AdminHeartbeat::LEN, borsh_space::<AdminHeartbeat>());
+        assert_eq!(AmmConfig::LEN, borsh_space::<AmmConfig>());
+        assert_eq!(BuybackState::LEN, borsh_space::<BuybackState>());
+        assert_eq!(
+            CustomFeeCreatorState::LEN,
+            borsh_space::<CustomFeeCreatorState>()
+        );
+        assert_eq!(
+            EpochFeeReportState::LEN,
+            borsh_space::<EpochFeeReportState>()
+        );
+        assert_eq!(
+            FeeDistributorState::LEN,
+            borsh_space::<FeeDistributorState>()
+        );
+        assert_eq!(FeeStakerState::LEN, borsh_space::<FeeStakerState>());
+        assert_eq!(FeeExemption::LEN, borsh_space::<FeeExemption>());
+        assert_eq!(
+            FeeGrowthSnapshotState::LEN,
+            borsh_space::<FeeGrowthSnapshotState>()
+        );
+        assert_eq!(IncentivePrograms::LEN, borsh_space::<IncentivePrograms>());
+        assert_eq!(
+            LaunchScheduleState::LEN,
+            borsh_space::<LaunchScheduleState>()
+        );
+        assert_eq!(LiquiditySnapshot::LEN, borsh_space::<LiquiditySnapshot>());
+        assert_eq!(
+            LockedPositionState::LEN,
+            borsh_space::<LockedPositionState>()
+        );
+        assert_eq!(OracleConsumer::LEN, borsh_space::<OracleConsumer>());
+        assert_eq!(
+            PersonalPositionState::LEN,
+            borsh_space::<PersonalPositionState>()
+        );
+        assert_eq!(PositionBundle::LEN, borsh_space::<PositionBundle>());
+        assert_eq!(PositionDelegate::LEN, borsh_space::<PositionDelegate>());
+        assert_eq!(PositionLeaseState::LEN, borsh_space::<PositionLeaseState>());
+        assert_eq!(
+            ProtocolPositionState::LEN,
+            borsh_space::<ProtocolPositionState>()
+        );
+        assert_eq!(ReferralAccount::LEN, borsh_space::<ReferralAccount>());
+        assert_eq!(StopOrderState::LEN, borsh_space::<StopOrderState>());
+        assert_eq!(StrategyPreset::LEN, borsh_space::<StrategyPreset>());
+        assert_eq!(UserStats::LEN, borsh_space::<UserStats>());
+    }
+
+    #[test]
+    fn zero_copy_account_space_test() {
+        assert_eq!(ObservationState::LEN, 8 + size_of::<ObservationState>());
+        assert_eq!(OperationState::LEN, 8 + size_of::<OperationState>());
+        assert_eq!(PoolState::LEN, 8 + size_of::<PoolState>());
+        assert_eq!(TickArrayState::LEN, 8 + size_of::<TickArrayState>());
+        assert_eq!(
+            TickArrayBitmapExtension::LEN,
+            8 + size_of::<TickArrayBitmapExtension>()
+        );
+    }
+}
@@ -9,6 +9,9 @@ pub const OBSERVATION_SEED: &str = "observation";
 // Number of ObservationState element
 pub const OBSERVATION_NUM: usize = 100;
 pub const OBSERVATION_UPDATE_DURATION_DEFAULT: u32 = 15;
+/// The age the newest observation must reach before `poke_observation` can write a new one,
+/// unless the pool has oracle consumers
+pub const OBSERVATION_POKE_MIN_AGE: u32 = 60;
 
 /// The element of observations in ObservationState
 #[zero_copy(unsafe)]
@@ -17,7 +20,7 @@ pub const OBSERVATION_UPDATE_DURATION_DEFAULT: u32 = 15;
 pub struct Observation {
     /// The block timestamp of the observation
     pub block_timestamp: u32,
-    /// the cumulative of tick during the duration time
+    /// the cumulative of tick during the duration time, wraps around on overflow
     pub tick_cumulative: i64,
     /// padding for feature update
     pub padding: [u64; 4],
@@ -25,6 +28,24 @@ pub struct Observation {
 
 impl Observation {
     pub const LEN: usize = 4 + 8 + 8 * 4;
+
+    /// The tick cumulative after `delta_time` seconds at `tick`. Cumulatives use i64 wrapping
+    /// arithmetic, like block timestamps use u32 wrapping arithmetic, so they keep accruing
+    /// past the type bounds
+    pub fn next_tick_cumulative(&self, tick: i32, delta_time: u32) -> i64 {
+        self.tick_cumulative
+            .wrapping_add(i64::from(tick) * i64::from(delta_time))
+    }
+
+    /// The tick cumulative and seconds accrued since the `older` observation. Only these wrapping
+    /// differences are meaningful, and they are exact as long as the observations are less than
+    /// 2^32 seconds apart
+    pub fn delta_since(&self, older: &Observation) -> (i64, u32) {
+        (
+            self.tick_cumulative.wrapping_sub(older.tick_cumulative),
+            self.block_timestamp.wrapping_sub(older.block_timestamp),
+        )
+    }
 }
 
 #[account(zero_copy(unsafe))]
@@ -79,7 +100,8 @@ impl ObservationState {
     /// * `self` - The ObservationState account to write in
     /// * `block_timestamp` - The current timestamp of to update
     ///
-    pub fn update(&mut self, block_timestamp: u32, tick: i32) {
+    /// Returns true if a new observation slot was written
+    pub fn update(&mut self, block_timestamp: u32, tick: i32) -> bool {
         let observation_index = self.observation_index;
         if !self.initialized {
             self.initialized = true;
@@ -87,26 +109,99 @@ impl ObservationState {
             self.observations[observation_index as usize].tick_cumulative = 0;
         } else {
             let last_observation = self.observations[observation_index as usize];
-            let delta_time = block_timestamp.saturating_sub(last_observation.block_timestamp);
+            let delta_time = block_timestamp.wrapping_sub(last_observation.block_timestamp);
             if delta_time < OBSERVATION_UPDATE_DURATION_DEFAULT {
-                return;
+                return false;
             }
 
-            let delta_tick_cumulative = i64::from(tick).checked_mul(delta_time.into()).unwrap();
             let next_observation_index = if observation_index as usize == OBSERVATION_NUM - 1 {
                 0
             } else {
                 observation_index + 1
             };
             self.observations[next_observation_index as usize].block_timestamp = block_timestamp;
-            self.observations[next_observation_index as usize].tick_cumulative = last_observation
-                .tick_cumulative
-                .wrapping_add(delta_tick_cumulative);
+            self.observations[next_observation_index as usize].tick_cumulative =
+                last_observation.next_tick_cumulative(tick, delta_time);
             self.observation_index = next_observation_index;
         }
+        true
+    }
+
+    /// Writes an observation at `tick` if the newest one is at least `min_age` seconds old,
+    /// `tick` must be the pool's tick since the newest observation
+    ///
+    /// Returns true if a new observation slot was written
+    pub fn poke(&mut self, block_timestamp: u32, tick: i32, min_age: u32) -> bool {
+        if self.initialized {
+            let newest = self.observations[usize::from(self.observation_index)];
+            if block_timestamp.wrapping_sub(newest.block_timestamp) < min_age {
+                return false;
+            }
+        }
+        self.update(block_timestamp, tick)
+    }
+
+    /// Returns the time weighted average tick between the newest observation and the newest
+    /// observation at least `window` seconds older than `block_timestamp`, rounded down.
+    /// None if the observations don't cover the window yet.
+    pub fn twap_tick(&self, block_timestamp: u32, window: u32) -> Option<i32> {
+        self.twap(block_timestamp, window).map(|twap| twap.tick)
+    }
+
+    /// Same as `twap_tick`, also returning the number of observations spanned and the age of
+    /// the newest one
+    pub fn twap(&self, block_timestamp: u32, window: u32) -> Option<Twap> {
+        if !self.initialized || window == 0 {
+            return None;
+        }
+        let newest_index = usize::from(self.observation_index);
+        let newest = self.observations[newest_index];
+        for i in 1..OBSERVATION_NUM {
+            let observation =
+                self.observations[(newest_index + OBSERVATION_NUM - i) % OBSERVATION_NUM];
+            if observation.block_timestamp == 0 {
+                return None;
+            }
+            if block_timestamp.wrapping_sub(observation.block_timestamp) >= window {
+                let (delta_tick_cumulative, delta_time) = newest.delta_since(&observation);
+                return Some(Twap {
+                    tick: i32::try_from(delta_tick_cumulative.div_euclid(i64::from(delta_time)))
+                        .ok()?,
+                    observation_count: u16::try_from(i + 1).unwrap(),
+                    staleness: block_timestamp.wrapping_sub(newest.block_timestamp),
+                });
+            }
+        }
+        None
     }
 }
 
+/// A time weighted average tick read from the observations
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Twap {
+    /// The average tick, rounded down
+    pub tick: i32,
+    /// The number of observations the average spans
+    pub observation_count: u16,
+    /// Seconds since the newest observation
+    pub staleness: u32,
+}
+
+/// Emitted when a new observation slot is written, if enabled on the pool
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct ObservationEvent {
+    /// The pool the observation belongs to
+    #[index]
+    pub pool_state: Pubkey,
+    /// The index of the written observation
+    pub observation_index: u16,
+    /// The block timestamp of the observation
+    pub block_timestamp: u32,
+    /// The tick cumulative of the observation
+    pub tick_cumulative: i64,
+}
+
 /// Returns the block timestamp truncated to 32 bits, i.e. mod 2**32
 ///
 pub fn block_timestamp() -> u32 {
@@ -122,6 +217,120 @@ pub fn block_timestamp_mock() -> u64 {
         .as_secs()
 }
 
+#[cfg(test)]
+mod observation_update_test {
+    use super::*;
+    use crate::libraries::tick_math;
+
+    #[test]
+    fn update_only_writes_after_duration() {
+        let mut observation_state = ObservationState::default();
+        assert!(observation_state.update(100, 10));
+        assert_eq!({ observation_state.observation_index }, 0);
+
+        assert!(!observation_state.update(100 + OBSERVATION_UPDATE_DURATION_DEFAULT - 1, 10));
+        assert_eq!({ observation_state.observation_index }, 0);
+
+        assert!(observation_state.update(100 + OBSERVATION_UPDATE_DURATION_DEFAULT, 10));
+        assert_eq!({ observation_state.observation_index }, 1);
+        let observation = observation_state.observations[1];
+        assert_eq!(
+            { observation.tick_cumulative },
+            10 * OBSERVATION_UPDATE_DURATION_DEFAULT as i64
+        );
+    }
+
+    #[test]
+    fn poke_only_writes_stale_observations() {
+        let mut observation_state = ObservationState::default();
+        let min_age = OBSERVATION_POKE_MIN_AGE;
+        assert!(observation_state.poke(1000, 10, min_age));
+        assert!(!observation_state.poke(1000 + min_age - 1, 10, min_age));
+        assert!(observation_state.poke(1000 + min_age, 10, min_age));
+        assert_eq!({ observation_state.observation_index }, 1);
+        // the idle pool's tick is carried into the twap
+        assert_eq!(
+            observation_state.twap_tick(1000 + OBSERVATION_POKE_MIN_AGE, OBSERVATION_POKE_MIN_AGE),
+            Some(10)
+        );
+    }
+
+    #[test]
+    fn twap_tick_test() {
+        let mut observation_state = ObservationState::default();
+        assert_eq!(observation_state.twap_tick(100, 60), None);
+
+        observation_state.update(1000, 0);
+        // tick 10 for 60s then tick -25 for 60s
+        observation_state.update(1060, 10);
+        observation_state.update(1120, -25);
+        assert_eq!(observation_state.twap_tick(1120, 60), Some(-25));
+        assert_eq!(observation_state.twap_tick(1120, 120), Some(-8));
+        // the window reaches before the first observation
+        assert_eq!(observation_state.twap_tick(1120, 121), None);
+
+        assert_eq!(
+            observation_state.twap(1150, 120),
+            Some(Twap {
+                tick: -8,
+                observation_count: 3,
+                staleness: 30,
+            })
+        );
+    }
+
+    #[test]
+    fn tick_cumulative_wraps_around_test() {
+        let mut observation_state = ObservationState::default();
+        observation_state.update(1000, 0);
+        observation_state.observations[0].tick_cumulative = i64::MAX - 100;
+        // crosses i64::MAX
+        observation_state.update(1060, 10);
+        assert_eq!(
+            { observation_state.observations[1].tick_cumulative },
+            i64::MIN + 499
+        );
+        assert_eq!(observation_state.twap_tick(1060, 60), Some(10));
+
+        let mut observation_state = ObservationState::default();
+        observation_state.update(1000, 0);
+        observation_state.observations[0].tick_cumulative = i64::MIN + 100;
+        // crosses i64::MIN
+        observation_state.update(1060, -10);
+        assert_eq!(
+            { observation_state.observations[1].tick_cumulative },
+            i64::MAX - 499
+        );
+        assert_eq!(observation_state.twap_tick(1060, 60), Some(-10));
+        observation_state.update(1120, tick_math::MIN_TICK);
+        assert_eq!(
+            observation_state.twap_tick(1120, 120),
+            Some((tick_math::MIN_TICK - 10).div_euclid(2))
+        );
+    }
+
+    #[test]
+    fn block_timestamp_wraps_around_test() {
+        let mut observation_state = ObservationState::default();
+        observation_state.update(u32::MAX - 29, 0);
+        // the block timestamp wraps to 30
+        assert!(observation_state.update(30, 7));
+        assert_eq!({ observation_state.observation_index }, 1);
+        assert_eq!(
+            { observation_state.observations[1].tick_cumulative },
+            7 * 60
+        );
+        assert_eq!(
+            observation_state.twap(40, 60),
+            Some(Twap {
+                tick: 7,
+                observation_count: 2,
+                staleness: 10,
+            })
+        );
+    }
+}
+
 #[cfg(test)]
 pub mod oracle_layout_test {
     use super::*;
@@ -0,0 +1,103 @@
+use crate::error::ErrorCode;
+use crate::states::{ObservationState, OBSERVATION_UPDATE_DURATION_DEFAULT};
+use anchor_lang::prelude::*;
+
+pub const ORACLE_CONSUMER_SEED: &str = "oracle_consumer";
+
+/// An external protocol using a pool's observations as a price source. While a pool has
+/// consumers, keepers can poke its observations as often as swaps would write them
+///
+/// PDA of `[ORACLE_CONSUMER_SEED, pool_state, consumer]`
+///
+#[account]
+#[derive(Default, Debug)]
+pub struct OracleConsumer {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The pool whose observations are consumed
+    pub pool_id: Pubkey,
+    /// The registering authority, gets the rent back when deregistering
+    pub consumer: Pubkey,
+    /// The oldest the newest observation can be for the consumer's needs, in seconds
+    pub max_staleness: u32,
+    /// The timestamp of the registration
+    pub registered_at: u64,
+    // Unused bytes for future upgrades.
+    pub padding: [u64; 4],
+}
+
+impl OracleConsumer {
+    pub const LEN: usize = 8 + 1 + 32 + 32 + 4 + 8 + 8 * 4;
+
+    pub fn initialize(
+        &mut self,
+        bump: u8,
+        pool_id: Pubkey,
+        consumer: Pubkey,
+        max_staleness: u32,
+        registered_at: u64,
+    ) -> Result<()> {
+        // observations can't be written more often than that
+        require_gte!(
+            max_staleness,
+            OBSERVATION_UPDATE_DURATION_DEFAULT,
+            ErrorCode::InvalidOracleConsumerParam
+        );
+        self.bump = bump;
+        self.pool_id = pool_id;
+        self.consumer = consumer;
+        self.max_staleness = max_staleness;
+        self.registered_at = registered_at;
+        self.padding = [0; 4];
+        Ok(())
+    }
+
+    /// Whether the newest observation is recent enough for the consumer
+    pub fn is_sla_met(&self, observation_state: &ObservationState, block_timestamp: u32) -> bool {
+        if !observation_state.initialized {
+            return false;
+        }
+        let newest =
+            observation_state.observations[usize::from(observation_state.observation_index)];
+        block_timestamp.wrapping_sub(newest.block_timestamp) <= self.max_staleness
+    }
+}
+
+/// Emitted when an oracle consumer registers or deregisters
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct OracleConsumerEvent {
+    /// The pool whose observations are consumed
+    #[index]
+    pub pool_state: Pubkey,
+    /// The consumer's authority
+    pub consumer: Pubkey,
+    /// The consumer's staleness SLA, in seconds
+    pub max_staleness: u32,
+    /// True on registration, false on deregistration
+    pub registered: bool,
+    /// The number of consumers of the pool afterwards
+    pub consumer_count: u32,
+}
+
+#[cfg(test)]
+mod oracle_consumer_test {
+    use super::*;
+
+    #[test]
+    fn is_sla_met_test() {
+        let mut oracle_consumer = OracleConsumer::default();
+        assert!(oracle_consumer
+            .initialize(0, Pubkey::default(), Pubkey::default(), 10, 0)
+            .is_err());
+        oracle_consumer
+            .initialize(0, Pubkey::default(), Pubkey::default(), 60, 0)
+            .unwrap();
+
+        let mut observation_state = ObservationState::default();
+        assert!(!oracle_consumer.is_sla_met(&observation_state, 1000));
+        observation_state.update(1000, 0);
+        assert!(oracle_consumer.is_sla_met(&observation_state, 1060));
+        assert!(!oracle_consumer.is_sla_met(&observation_state, 1061));
+    }
+}
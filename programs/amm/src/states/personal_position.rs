@@ -1,7 +1,9 @@
+use crate::error::ErrorCode;
 use crate::libraries::{big_num::U256, fixed_point_64, full_math::MulDiv};
 use crate::pool::REWARD_NUM;
 use crate::util::get_recent_epoch;
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak;
 
 use super::POSITION_SEED;
 
@@ -42,8 +44,14 @@ pub struct PersonalPositionState {
     pub reward_infos: [PositionRewardInfo; REWARD_NUM],
     // account update recent epoch
     pub recent_epoch: u64,
+    /// The approved incentive program whose campaign the position is enrolled in, default if
+    /// none. A position is enrolled in one campaign at a time
+    pub incentive_program: Pubkey,
+    /// The token_0 and token_1 fees collected or compounded over the position's lifetime
+    pub fees_collected_0: u64,
+    pub fees_collected_1: u64,
     // Unused bytes for future upgrades.
-    pub padding: [u64; 7],
+    pub padding: [u64; 1],
 }
 
 impl PersonalPositionState {
@@ -58,6 +66,44 @@ impl PersonalPositionState {
         ]
     }
 
+    pub fn position_hash(&self) -> [u8; 32] {
+        position_hash(
+            &self.pool_id,
+            self.tick_lower_index,
+            self.tick_upper_index,
+            &self.nft_mint,
+        )
+    }
+
+    pub fn is_enrolled(&self) -> bool {
+        self.incentive_program != Pubkey::default()
+    }
+
+    /// Enrolls the position in the campaign of `incentive_program`, fails if it is already
+    /// enrolled in another one
+    pub fn enroll(&mut self, incentive_program: Pubkey) -> Result<()> {
+        require!(
+            !self.is_enrolled() || self.incentive_program == incentive_program,
+            ErrorCode::PositionAlreadyEnrolled
+        );
+        self.incentive_program = incentive_program;
+        Ok(())
+    }
+
+    /// Whether the position has no liquidity and nothing owed, and has not been touched for
+    /// `grace_epochs` epochs. Liquidity changes and collects all refresh `recent_epoch`
+    pub fn is_sweepable(&self, current_epoch: u64, grace_epochs: u64) -> bool {
+        grace_epochs != 0
+            && self.liquidity == 0
+            && self.token_fees_owed_0 == 0
+            && self.token_fees_owed_1 == 0
+            && self
+                .reward_infos
+                .iter()
+                .all(|reward_info| reward_info.reward_amount_owed == 0)
+            && current_epoch >= self.recent_epoch.saturating_add(grace_epochs)
+    }
+
     pub fn update_rewards(
         &mut self,
         reward_growths_inside: [u128; REWARD_NUM],
@@ -95,6 +141,23 @@ impl PersonalPositionState {
     }
 }
 
+/// Identifies a position in events, it is derived from the position's fixed fields only so
+/// its opens, increases, decreases and close can be correlated after the accounts are closed
+pub fn position_hash(
+    pool_id: &Pubkey,
+    tick_lower_index: i32,
+    tick_upper_index: i32,
+    nft_mint: &Pubkey,
+) -> [u8; 32] {
+    keccak::hashv(&[
+        pool_id.as_ref(),
+        &tick_lower_index.to_be_bytes(),
+        &tick_upper_index.to_be_bytes(),
+        nft_mint.as_ref(),
+    ])
+    .0
+}
+
 #[derive(Copy, Clone, AnchorSerialize, AnchorDeserialize, Default, Debug, PartialEq)]
 pub struct PositionRewardInfo {
     // Q64.64
@@ -142,6 +205,9 @@ pub struct CreatePersonalPositionEvent {
 
     /// The token transfer fee for deposit_amount_1
     pub deposit_amount_1_transfer_fee: u64,
+
+    /// The position identifier, see `position_hash`
+    pub position_hash: [u8; 32],
 }
 
 /// Emitted when liquidity is increased.
@@ -166,6 +232,42 @@ pub struct IncreaseLiquidityEvent {
 
     /// The token transfer fee for amount_1
     pub amount_1_transfer_fee: u64,
+
+    /// The token_0 vault balance before the deposit
+    pub vault_0_balance_before: u64,
+
+    /// The token_1 vault balance before the deposit
+    pub vault_1_balance_before: u64,
+
+    /// The token_0 vault balance after the deposit
+    pub vault_0_balance_after: u64,
+
+    /// The token_1 vault balance after the deposit
+    pub vault_1_balance_after: u64,
+
+    /// The position identifier, see `position_hash`
+    pub position_hash: [u8; 32],
+
+    /// The incentive program the position is enrolled in, default if none
+    pub incentive_program: Pubkey,
+
+    /// The tick_lower and tick_upper of the position
+    pub tick_lower: i32,
+    pub tick_upper: i32,
+
+    /// The position's liquidity after the deposit
+    pub position_liquidity: u128,
+
+    /// The pool's in-range liquidity after the deposit
+    pub pool_liquidity: u128,
+
+    /// The liquidity_net and liquidity_gross of tick_lower after the deposit
+    pub tick_lower_liquidity_net: i128,
+    pub tick_lower_liquidity_gross: u128,
+
+    /// The liquidity_net and liquidity_gross of tick_upper after the deposit
+    pub tick_upper_liquidity_net: i128,
+    pub tick_upper_liquidity_gross: u128,
 }
 
 /// Emitted when liquidity is decreased.
@@ -190,9 +292,132 @@ pub struct DecreaseLiquidityEvent {
     pub transfer_fee_0: u64,
     /// The amount of token_1 transfer fee
     pub transfer_fee_1: u64,
+    /// The token_0 vault balance before the withdrawal
+    pub vault_0_balance_before: u64,
+    /// The token_1 vault balance before the withdrawal
+    pub vault_1_balance_before: u64,
+    /// The token_0 vault balance after the withdrawal
+    pub vault_0_balance_after: u64,
+    /// The token_1 vault balance after the withdrawal
+    pub vault_1_balance_after: u64,
+    /// The position identifier, see `position_hash`
+    pub position_hash: [u8; 32],
+    /// The incentive program the position is enrolled in, default if none
+    pub incentive_program: Pubkey,
+    /// The tick_lower and tick_upper of the position
+    pub tick_lower: i32,
+    pub tick_upper: i32,
+    /// The position's liquidity after the withdrawal
+    pub position_liquidity: u128,
+    /// The pool's in-range liquidity after the withdrawal
+    pub pool_liquidity: u128,
+    /// The liquidity_net and liquidity_gross of tick_lower after the withdrawal
+    pub tick_lower_liquidity_net: i128,
+    pub tick_lower_liquidity_gross: u128,
+    /// The liquidity_net and liquidity_gross of tick_upper after the withdrawal
+    pub tick_upper_liquidity_net: i128,
+    pub tick_upper_liquidity_gross: u128,
+}
+
+/// Emitted when a position is closed
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct ClosePersonalPositionEvent {
+    /// The ID of the token bound to the closed position
+    #[index]
+    pub position_nft_mint: Pubkey,
+    /// The pool of the position
+    pub pool_state: Pubkey,
+    /// The lower tick of the position
+    pub tick_lower_index: i32,
+    /// The upper tick of the position
+    pub tick_upper_index: i32,
+    /// The position identifier, see `position_hash`
+    pub position_hash: [u8; 32],
+}
+
+/// Emitted when a position is moved to a new range
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct RebalancePositionEvent {
+    /// The ID of the token bound to the position
+    #[index]
+    pub position_nft_mint: Pubkey,
+    /// The pool of the position
+    pub pool_state: Pubkey,
+    /// The range before the move
+    pub tick_lower_index: i32,
+    pub tick_upper_index: i32,
+    /// The range after the move
+    pub new_tick_lower_index: i32,
+    pub new_tick_upper_index: i32,
+    /// The liquidity withdrawn from the old range and deposited on the new one
+    pub liquidity_removed: u128,
+    pub liquidity_added: u128,
+    /// The fees settled on the old range
+    pub fee_amount_0: u64,
+    pub fee_amount_1: u64,
+    /// The fees and the withdrawn amounts not needed by the new range, paid to the recipients
+    pub amount_0: u64,
+    pub amount_1: u64,
+}
+
+/// Emitted when an abandoned empty position is swept
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct SweepEmptyPositionEvent {
+    /// The ID of the token bound to the swept position
+    #[index]
+    pub position_nft_mint: Pubkey,
+    /// The pool of the position
+    pub pool_state: Pubkey,
+    /// The account that swept the position
+    pub sweeper: Pubkey,
+    /// The holder of the position nft
+    pub nft_owner: Pubkey,
+    /// The reclaimed rent paid to the sweeper
+    pub sweeper_rent: u64,
+    /// The reclaimed rent paid to the nft holder
+    pub nft_owner_rent: u64,
 }
 
 /// Emitted when liquidity decreased or increase.
+/// Emitted when the admin rewrites the fee growth snapshots of a position
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct RepairPositionCheckpointEvent {
+    /// The ID of the token bound to the repaired position
+    #[index]
+    pub position_nft_mint: Pubkey,
+
+    /// The admin performing the repair
+    pub authority: Pubkey,
+
+    /// The token_0 fee growth snapshot replaced
+    pub old_fee_growth_inside_0_last_x64: u128,
+
+    /// The token_1 fee growth snapshot replaced
+    pub old_fee_growth_inside_1_last_x64: u128,
+
+    /// The token_0 fee growth snapshot written
+    pub fee_growth_inside_0_last_x64: u128,
+
+    /// The token_1 fee growth snapshot written
+    pub fee_growth_inside_1_last_x64: u128,
+
+    /// The token_0 fee growth inside the position's range, the most the snapshot can be set to
+    pub fee_growth_inside_0_x64: u128,
+
+    /// The token_1 fee growth inside the position's range, the most the snapshot can be set to
+    pub fee_growth_inside_1_x64: u128,
+
+    /// The token_0 fees owed to the position, left untouched
+    pub token_fees_owed_0: u64,
+
+    /// The token_1 fees owed to the position, left untouched
+    pub token_fees_owed_1: u64,
+}
+
 #[event]
 #[cfg_attr(feature = "client", derive(Debug))]
 pub struct LiquidityCalculateEvent {
@@ -237,6 +462,78 @@ pub struct CollectPersonalFeeEvent {
     pub amount_1: u64,
 }
 
+/// Emitted when a position's fees are added back to its liquidity
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct CompoundFeesEvent {
+    /// The ID of the token bound to the position
+    #[index]
+    pub position_nft_mint: Pubkey,
+
+    /// The liquidity added to the position
+    pub liquidity: u128,
+
+    /// The amount of token_0 fees deposited
+    pub amount_0: u64,
+
+    /// The amount of token_1 fees deposited
+    pub amount_1: u64,
+
+    /// The token_0 fees still owed to the position
+    pub token_fees_owed_0: u64,
+
+    /// The token_1 fees still owed to the position
+    pub token_fees_owed_1: u64,
+}
+
+/// Emitted when a position's fees and rewards are collected together
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct CollectAllEvent {
+    /// The ID of the token bound to the position
+    #[index]
+    pub position_nft_mint: Pubkey,
+
+    /// The token account that received the collected token_0 fees
+    pub recipient_token_account_0: Pubkey,
+
+    /// The token account that received the collected token_1 fees
+    pub recipient_token_account_1: Pubkey,
+
+    /// The amount of token_0 fees collected
+    pub amount_0: u64,
+
+    /// The amount of token_1 fees collected
+    pub amount_1: u64,
+
+    /// The amount of each reward token collected
+    pub reward_amounts: [u64; REWARD_NUM],
+}
+
+/// Emitted when a position is settled with its owner and its nft handed on
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct TransferPositionEvent {
+    /// The ID of the token bound to the position
+    #[index]
+    pub position_nft_mint: Pubkey,
+
+    /// The owner the fees and rewards were paid to
+    pub previous_owner: Pubkey,
+
+    /// The owner of the account now holding the nft, the previous owner if it was not moved
+    pub new_owner: Pubkey,
+
+    /// The amount of token_0 fees paid to the previous owner
+    pub fee_amount_0: u64,
+
+    /// The amount of token_1 fees paid to the previous owner
+    pub fee_amount_1: u64,
+
+    /// The amount of each reward token paid to the previous owner
+    pub reward_amounts: [u64; REWARD_NUM],
+}
+
 /// Emitted when Reward are updated for a pool
 #[event]
 #[cfg_attr(feature = "client", derive(Debug))]
@@ -244,3 +541,66 @@ pub struct UpdateRewardInfosEvent {
     /// Reward info
     pub reward_growth_global_x64: [u128; REWARD_NUM],
 }
+
+#[cfg(test)]
+mod personal_position_test {
+    use super::*;
+
+    #[test]
+    fn position_hash_test() {
+        let pool_id = Pubkey::new_unique();
+        let nft_mint = Pubkey::new_unique();
+        let hash = position_hash(&pool_id, -10, 10, &nft_mint);
+        let position = PersonalPositionState {
+            pool_id,
+            nft_mint,
+            tick_lower_index: -10,
+            tick_upper_index: 10,
+            liquidity: 100,
+            ..Default::default()
+        };
+        // only the fixed fields are hashed
+        assert_eq!(position.position_hash(), hash);
+        assert_ne!(position_hash(&nft_mint, -10, 10, &pool_id), hash);
+        assert_ne!(position_hash(&pool_id, 10, -10, &nft_mint), hash);
+        assert_ne!(position_hash(&pool_id, -10, 20, &nft_mint), hash);
+    }
+
+    #[test]
+    fn enroll_test() {
+        let mut position = PersonalPositionState::default();
+        let (farm_a, farm_b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        assert!(!position.is_enrolled());
+
+        position.enroll(farm_a).unwrap();
+        assert!(position.is_enrolled());
+        // enrolling again in the same campaign is a no-op, another campaign is double dipping
+        position.enroll(farm_a).unwrap();
+        assert_eq!(
+            position.enroll(farm_b).unwrap_err(),
+            ErrorCode::PositionAlreadyEnrolled.into()
+        );
+        assert_eq!(position.incentive_program, farm_a);
+    }
+
+    #[test]
+    fn is_sweepable_test() {
+        let mut position = PersonalPositionState {
+            recent_epoch: 10,
+            ..Default::default()
+        };
+        assert!(!position.is_sweepable(12, 3));
+        assert!(position.is_sweepable(13, 3));
+        // a grace period of 0 turns sweeping off
+        assert!(!position.is_sweepable(u64::MAX, 0));
+
+        position.reward_infos[1].reward_amount_owed = 1;
+        assert!(!position.is_sweepable(13, 3));
+        position.reward_infos[1].reward_amount_owed = 0;
+        position.token_fees_owed_1 = 1;
+        assert!(!position.is_sweepable(13, 3));
+        position.token_fees_owed_1 = 0;
+        position.liquidity = 1;
+        assert!(!position.is_sweepable(13, 3));
+    }
+}
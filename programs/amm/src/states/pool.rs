@@ -3,7 +3,7 @@ use crate::libraries::{
     big_num::{U1024, U128, U256},
     check_current_tick_array_is_initialized, fixed_point_64,
     full_math::MulDiv,
-    tick_array_bit_map, tick_math,
+    price_math, tick_array_bit_map, tick_math,
 };
 use crate::states::*;
 use crate::util::get_recent_epoch;
@@ -15,11 +15,20 @@ use std::ops::{BitAnd, BitOr, BitXor};
 
 /// Seed to derive account address and signature
 pub const POOL_SEED: &str = "pool";
+/// Replaces `POOL_SEED` for custom fee pools, so they never take the address of the config's
+/// pool of the same pair
+pub const CUSTOM_FEE_POOL_SEED: &str = "custom_fee_pool";
 pub const POOL_VAULT_SEED: &str = "pool_vault";
 pub const POOL_REWARD_VAULT_SEED: &str = "pool_reward_vault";
 pub const POOL_TICK_ARRAY_BITMAP_SEED: &str = "pool_tick_array_bitmap_extension";
 // Number of rewards Token
 pub const REWARD_NUM: usize = 3;
+/// How many tick spacings either side of the current tick count as near for the
+/// liquidity utilization metrics
+pub const NEAR_TICK_SPACINGS: i32 = 10;
+/// The most fee growth a single donation can add, one token per unit of in-range liquidity,
+/// so donations to a thin range can't drive the fee growth to overflow
+pub const MAX_DONATION_FEE_GROWTH_X64: u128 = fixed_point_64::Q64;
 
 #[cfg(feature = "paramset")]
 pub mod reward_period_limit {
@@ -48,9 +57,19 @@ pub enum PoolStatusBitFlag {
     Disable,
 }
 
+/// The orientation in which the pool creator prefers the price to be quoted
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QuoteOrientation {
+    /// Price of token_0 denominated in token_1
+    Token1PerToken0,
+    /// Price of token_1 denominated in token_0
+    Token0PerToken1,
+}
+
 /// The pool state
 ///
-/// PDA of `[POOL_SEED, config, token_mint_0, token_mint_1]`
+/// PDA of `[POOL_SEED, config, token_mint_0, token_mint_1]`, or of
+/// `[CUSTOM_FEE_POOL_SEED, config, token_mint_0, token_mint_1]` for a custom fee pool
 ///
 #[account(zero_copy(unsafe))]
 #[repr(C, packed)]
@@ -112,8 +131,13 @@ pub struct PoolState {
     /// bit3, 1: disable collect reward, 0: normal
     /// bit4, 1: disable swap, 0: normal
     pub status: u8,
+    /// The preferred quote orientation of the pool price
+    /// 0: token_1 per token_0, 1: token_0 per token_1
+    pub quote_orientation: u8,
+    /// Whether to emit an ObservationEvent when a new observation is written, 0: disabled
+    pub observation_event_enabled: u8,
     /// Leave blank for future use
-    pub padding: [u8; 7],
+    pub padding: [u8; 5],
 
     pub reward_infos: [RewardInfo; REWARD_NUM],
 
@@ -135,9 +159,78 @@ pub struct PoolState {
     // account recent update epoch
     pub recent_epoch: u64,
 
+    /// The trade fee set by a whitelisted creator, denominated in hundredths of a bip (10^-6).
+    /// 0 means the pool uses the trade fee of its amm config.
+    pub custom_trade_fee_rate: u32,
+    pub padding5: u32,
+
+    /// The token program owning token_mint_0 and token_mint_1, default for pools
+    /// created before they were recorded
+    pub token_program_0: Pubkey,
+    pub token_program_1: Pubkey,
+
+    /// The partner registered by the admin to receive `partner_fee_rate` of the trade fees,
+    /// default if the pool has none
+    pub partner: Pubkey,
+    /// The partner's share of trade fees, denominated in hundredths of a bip (10^-6)
+    pub partner_fee_rate: u32,
+    pub padding6: u32,
+    /// The amounts of token_0 and token_1 that are owed to the partner
+    pub partner_fees_token_0: u64,
+    pub partner_fees_token_1: u64,
+
+    /// The initialized ticks bounding the current price and their liquidity_gross, refreshed
+    /// whenever a swap crosses a tick. A side's liquidity_gross is 0 if the swap didn't look it up
+    pub near_tick_lower: i32,
+    pub near_tick_upper: i32,
+    pub near_liquidity_gross_lower: u128,
+    pub near_liquidity_gross_upper: u128,
+
+    /// The number of registered oracle consumers, see `OracleConsumer`
+    pub oracle_consumer_count: u32,
+    pub padding7: u32,
+
+    /// The amounts of token_0 and token_1 credited to `ReferralAccount`s and not yet collected
+    pub referral_fees_token_0: u64,
+    pub referral_fees_token_1: u64,
+
+    /// The trade fees and the protocol's share of them charged by swaps so far. Unlike the owed
+    /// amounts above they are never decremented, they wrap around so differences are taken
+    /// with `wrapping_sub`. Swaps made before they were recorded are not counted
+    pub cumulative_trade_fees_token_0: u64,
+    pub cumulative_trade_fees_token_1: u64,
+    pub cumulative_protocol_fees_token_0: u64,
+    pub cumulative_protocol_fees_token_1: u64,
+
+    /// The token_0 volume a trader must swap within an epoch for it to count towards a volume
+    /// rebate tier, 0 disables rebates in the pool
+    pub volume_rebate_threshold: u64,
+    /// The amounts of token_0 and token_1 rebated to `UserStats` and not yet collected
+    pub volume_rebates_token_0: u64,
+    pub volume_rebates_token_1: u64,
+
+    /// The largest price move a single swap may cause, in bips of the price before the swap,
+    /// 0 means no limit
+    pub max_price_impact_bps: u16,
+    pub padding8: [u16; 3],
+
+    /// The program called before and after each swap of the pool, see `invoke_swap_hook`.
+    /// Default means no hook
+    pub swap_hook_program: Pubkey,
+
+    /// The smallest amount of token_0 and token_1 a swap may take in, in each token's base
+    /// units. 0 means no minimum
+    pub min_swap_amount_0: u64,
+    pub min_swap_amount_1: u64,
+
+    /// The cumulative_protocol_fees_token_0/1 already shared with fee distributors, later
+    /// distributions only share the protocol fees charged since
+    pub distributed_protocol_fees_token_0: u64,
+    pub distributed_protocol_fees_token_1: u64,
+
     // Unused bytes for future upgrades.
-    pub padding1: [u64; 24],
-    pub padding2: [u64; 32],
+    pub padding1: [u64; 2],
+    pub padding2: [u64; 14],
 }
 
 impl PoolState {
@@ -167,7 +260,11 @@ impl PoolState {
 
     pub fn seeds(&self) -> [&[u8]; 5] {
         [
-            &POOL_SEED.as_bytes(),
+            if self.custom_trade_fee_rate != 0 {
+                CUSTOM_FEE_POOL_SEED.as_bytes()
+            } else {
+                POOL_SEED.as_bytes()
+            },
             self.amm_config.as_ref(),
             self.token_mint_0.as_ref(),
             self.token_mint_1.as_ref(),
@@ -218,7 +315,9 @@ impl PoolState {
         self.swap_in_amount_token_1 = 0;
         self.swap_out_amount_token_0 = 0;
         self.status = 0;
-        self.padding = [0; 7];
+        self.quote_orientation = QuoteOrientation::Token1PerToken0 as u8;
+        self.observation_event_enabled = 0;
+        self.padding = [0; 5];
         self.tick_array_bitmap = [0; 16];
         self.total_fees_token_0 = 0;
         self.total_fees_claimed_token_0 = 0;
@@ -228,13 +327,303 @@ impl PoolState {
         self.fund_fees_token_1 = 0;
         self.open_time = open_time;
         self.recent_epoch = get_recent_epoch()?;
-        self.padding1 = [0; 24];
-        self.padding2 = [0; 32];
+        self.custom_trade_fee_rate = 0;
+        self.padding5 = 0;
+        self.token_program_0 = *token_mint_0.to_account_info().owner;
+        self.token_program_1 = *token_mint_1.to_account_info().owner;
+        self.partner = Pubkey::default();
+        self.partner_fee_rate = 0;
+        self.padding6 = 0;
+        self.partner_fees_token_0 = 0;
+        self.partner_fees_token_1 = 0;
+        self.near_tick_lower = 0;
+        self.near_tick_upper = 0;
+        self.near_liquidity_gross_lower = 0;
+        self.near_liquidity_gross_upper = 0;
+        self.oracle_consumer_count = 0;
+        self.padding7 = 0;
+        self.referral_fees_token_0 = 0;
+        self.referral_fees_token_1 = 0;
+        self.cumulative_trade_fees_token_0 = 0;
+        self.cumulative_trade_fees_token_1 = 0;
+        self.cumulative_protocol_fees_token_0 = 0;
+        self.cumulative_protocol_fees_token_1 = 0;
+        self.volume_rebate_threshold = 0;
+        self.volume_rebates_token_0 = 0;
+        self.volume_rebates_token_1 = 0;
+        self.max_price_impact_bps = 0;
+        self.padding8 = [0; 3];
+        self.swap_hook_program = Pubkey::default();
+        self.min_swap_amount_0 = 0;
+        self.min_swap_amount_1 = 0;
+        self.distributed_protocol_fees_token_0 = 0;
+        self.distributed_protocol_fees_token_1 = 0;
+        self.padding1 = [0; 2];
+        self.padding2 = [0; 14];
         self.observation_key = observation_state_key;
 
         Ok(())
     }
 
+    /// Whether `token_program` owns the given pool mint, pools that have not recorded
+    /// their token programs accept either program
+    pub fn is_valid_token_program(&self, mint: &Pubkey, token_program: &Pubkey) -> bool {
+        let recorded = if *mint == self.token_mint_0 {
+            self.token_program_0
+        } else if *mint == self.token_mint_1 {
+            self.token_program_1
+        } else {
+            return false;
+        };
+        if recorded == Pubkey::default() {
+            return *token_program == anchor_spl::token::ID
+                || *token_program == anchor_spl::token_2022::ID;
+        }
+        recorded == *token_program
+    }
+
+    /// The trade fee rate charged by swaps in this pool
+    pub fn trade_fee_rate(&self, amm_config: &AmmConfig) -> u32 {
+        if self.custom_trade_fee_rate != 0 {
+            self.custom_trade_fee_rate
+        } else {
+            amm_config.trade_fee_rate
+        }
+    }
+
+    /// The LP fees accrued so far plus the protocol, fund, partner and referral fees and volume
+    /// rebates not yet collected, in token_0 if `token_0` else token_1. Only the difference
+    /// across a swap is meaningful
+    pub fn trade_fees(&self, token_0: bool) -> u64 {
+        if token_0 {
+            self.total_fees_token_0
+                + self.protocol_fees_token_0
+                + self.fund_fees_token_0
+                + self.partner_fees_token_0
+                + self.referral_fees_token_0
+                + self.volume_rebates_token_0
+        } else {
+            self.total_fees_token_1
+                + self.protocol_fees_token_1
+                + self.fund_fees_token_1
+                + self.partner_fees_token_1
+                + self.referral_fees_token_1
+                + self.volume_rebates_token_1
+        }
+    }
+
+    /// The protocol fees not yet collected, in token_0 if `token_0` else token_1
+    pub fn protocol_fees(&self, token_0: bool) -> u64 {
+        if token_0 {
+            self.protocol_fees_token_0
+        } else {
+            self.protocol_fees_token_1
+        }
+    }
+
+    /// Moves `volume_rebate_rate` of the protocol fees accrued since `protocol_fees_before` to
+    /// the volume rebates owed, returns the amount moved
+    pub fn take_volume_rebate(
+        &mut self,
+        token_0: bool,
+        protocol_fees_before: u64,
+        volume_rebate_rate: u32,
+    ) -> u64 {
+        let protocol_fee = self.protocol_fees(token_0) - protocol_fees_before;
+        let rebate = (u128::from(protocol_fee) * u128::from(volume_rebate_rate)
+            / u128::from(FEE_RATE_DENOMINATOR_VALUE)) as u64;
+        if token_0 {
+            self.protocol_fees_token_0 -= rebate;
+            self.volume_rebates_token_0 += rebate;
+        } else {
+            self.protocol_fees_token_1 -= rebate;
+            self.volume_rebates_token_1 += rebate;
+        }
+        rebate
+    }
+
+    /// Takes `share_rate` of the protocol fees charged since the last distribution out of the
+    /// protocol fees owed, at most what is still owed, returns the amount taken
+    pub fn take_distributed_protocol_fee(&mut self, token_0: bool, share_rate: u32) -> u64 {
+        let (cumulative, distributed) = if token_0 {
+            (
+                self.cumulative_protocol_fees_token_0,
+                self.distributed_protocol_fees_token_0,
+            )
+        } else {
+            (
+                self.cumulative_protocol_fees_token_1,
+                self.distributed_protocol_fees_token_1,
+            )
+        };
+        let amount = ((u128::from(cumulative.wrapping_sub(distributed)) * u128::from(share_rate)
+            / u128::from(FEE_RATE_DENOMINATOR_VALUE)) as u64)
+            .min(self.protocol_fees(token_0));
+        if token_0 {
+            self.protocol_fees_token_0 -= amount;
+            self.distributed_protocol_fees_token_0 = cumulative;
+        } else {
+            self.protocol_fees_token_1 -= amount;
+            self.distributed_protocol_fees_token_1 = cumulative;
+        }
+        amount
+    }
+
+    /// The referral fees not yet collected, in token_0 if `token_0` else token_1
+    pub fn referral_fees(&self, token_0: bool) -> u64 {
+        if token_0 {
+            self.referral_fees_token_0
+        } else {
+            self.referral_fees_token_1
+        }
+    }
+
+    /// Registers `partner` for `partner_fee_rate` of the trade fees, the rate must leave room for
+    /// the config's protocol and fund fees. The partner can only be replaced once its fees
+    /// are collected
+    pub fn set_partner(
+        &mut self,
+        partner: Pubkey,
+        partner_fee_rate: u32,
+        amm_config: &AmmConfig,
+    ) -> Result<()> {
+        require!(
+            (partner == Pubkey::default()) == (partner_fee_rate == 0),
+            ErrorCode::InvalidPartnerFeeParam
+        );
+        require_gte!(
+            u64::from(FEE_RATE_DENOMINATOR_VALUE),
+            u64::from(partner_fee_rate)
+                + u64::from(amm_config.protocol_fee_rate)
+                + u64::from(amm_config.fund_fee_rate),
+            ErrorCode::InvalidPartnerFeeParam
+        );
+        require!(
+            partner == self.partner
+                || (self.partner_fees_token_0 == 0 && self.partner_fees_token_1 == 0),
+            ErrorCode::InvalidPartnerFeeParam
+        );
+        self.partner = partner;
+        self.partner_fee_rate = partner_fee_rate;
+        Ok(())
+    }
+
+    /// The price impact ceiling in hundredths of a bip, like `price_impact`, or None if the
+    /// pool has no ceiling
+    pub fn max_price_impact(&self) -> Option<u64> {
+        if self.max_price_impact_bps == 0 {
+            return None;
+        }
+        Some(u64::from(self.max_price_impact_bps) * u64::from(FEE_RATE_DENOMINATOR_VALUE) / 10_000)
+    }
+
+    /// The smallest amount of the input token a swap may take in
+    pub fn min_swap_amount(&self, zero_for_one: bool) -> u64 {
+        if zero_for_one {
+            self.min_swap_amount_0
+        } else {
+            self.min_swap_amount_1
+        }
+    }
+
+    /// The hook program of the pool's swaps, as long as the config still whitelists it
+    pub fn swap_hook(&self, amm_config: &AmmConfig) -> Option<Pubkey> {
+        if self.swap_hook_program == Pubkey::default()
+            || self.swap_hook_program != amm_config.swap_hook_program
+        {
+            return None;
+        }
+        Some(self.swap_hook_program)
+    }
+
+    /// Credits donated amounts to the in-range liquidity as fees, the pool must have some.
+    /// Each amount can be at most the in-range liquidity, see `MAX_DONATION_FEE_GROWTH_X64`
+    pub fn donate(&mut self, amount_0: u64, amount_1: u64) -> Result<()> {
+        require_gt!(self.liquidity, 0, ErrorCode::LiquidityInsufficient);
+        let liquidity = self.liquidity;
+        let fee_growth_delta = |amount: u64| -> Result<u128> {
+            let delta = U128::from(amount)
+                .mul_div_floor(U128::from(fixed_point_64::Q64), U128::from(liquidity))
+                .unwrap()
+                .as_u128();
+            require_gte!(
+                MAX_DONATION_FEE_GROWTH_X64,
+                delta,
+                ErrorCode::DonationTooLarge
+            );
+            Ok(delta)
+        };
+        let (fee_growth_delta_0, fee_growth_delta_1) =
+            (fee_growth_delta(amount_0)?, fee_growth_delta(amount_1)?);
+        self.fee_growth_global_0_x64 = self
+            .fee_growth_global_0_x64
+            .checked_add(fee_growth_delta_0)
+            .unwrap();
+        self.fee_growth_global_1_x64 = self
+            .fee_growth_global_1_x64
+            .checked_add(fee_growth_delta_1)
+            .unwrap();
+        self.total_fees_token_0 = self.total_fees_token_0.checked_add(amount_0).unwrap();
+        self.total_fees_token_1 = self.total_fees_token_1.checked_add(amount_1).unwrap();
+        Ok(())
+    }
+
+    /// Records the bounding ticks after a swap crossed `crossed_tick`. `next_tick` is the next
+    /// initialized tick in the swap direction if the swap stopped short of it
+    pub fn update_near_ticks(
+        &mut self,
+        zero_for_one: bool,
+        crossed_tick: &TickState,
+        next_tick: Option<&TickState>,
+    ) {
+        let (next_tick_index, next_liquidity_gross) = match next_tick {
+            Some(tick_state) => (tick_state.tick, tick_state.liquidity_gross),
+            None => (crossed_tick.tick, 0),
+        };
+        if zero_for_one {
+            self.near_tick_lower = next_tick_index;
+            self.near_liquidity_gross_lower = next_liquidity_gross;
+            self.near_tick_upper = crossed_tick.tick;
+            self.near_liquidity_gross_upper = crossed_tick.liquidity_gross;
+        } else {
+            self.near_tick_lower = crossed_tick.tick;
+            self.near_liquidity_gross_lower = crossed_tick.liquidity_gross;
+            self.near_tick_upper = next_tick_index;
+            self.near_liquidity_gross_upper = next_liquidity_gross;
+        }
+    }
+
+    /// The liquidity_gross of the bounding ticks within `NEAR_TICK_SPACINGS` of the current tick
+    pub fn near_liquidity_gross(&self) -> u128 {
+        let window = NEAR_TICK_SPACINGS * i32::from(self.tick_spacing);
+        [
+            (self.near_tick_lower, self.near_liquidity_gross_lower),
+            (self.near_tick_upper, self.near_liquidity_gross_upper),
+        ]
+        .iter()
+        .filter(|(tick, _)| (*tick - self.tick_current).abs() <= window)
+        .fold(0u128, |total, (_, liquidity_gross)| {
+            total.saturating_add(*liquidity_gross)
+        })
+    }
+
+    /// The in range liquidity relative to `near_liquidity_gross`, denominated in hundredths of
+    /// a bip (10^-6). Low values mean much of the depth ends close to the price, 0 if no
+    /// bounding tick is near
+    pub fn liquidity_utilization(&self) -> u64 {
+        let near_liquidity_gross = self.near_liquidity_gross();
+        if near_liquidity_gross == 0 {
+            return 0;
+        }
+        match U256::from(self.liquidity).mul_div_floor(
+            U256::from(FEE_RATE_DENOMINATOR_VALUE),
+            U256::from(near_liquidity_gross),
+        ) {
+            Some(utilization) if utilization <= U256::from(u64::MAX) => utilization.as_u64(),
+            _ => u64::MAX,
+        }
+    }
+
     pub fn initialize_reward(
         &mut self,
         open_time: u64,
@@ -245,6 +634,7 @@ impl PoolState {
         authority: &Pubkey,
         operation_state: &OperationState,
     ) -> Result<()> {
+        require_gt!(end_time, open_time, ErrorCode::InvalidRewardInitParam);
         let reward_infos = self.reward_infos;
         let lowest_index = match reward_infos.iter().position(|r| !r.initialized()) {
             Some(lowest_index) => lowest_index,
@@ -327,9 +717,11 @@ impl PoolState {
 
             if self.liquidity != 0 {
                 require_gte!(latest_update_timestamp, reward_info.last_update_time);
+                // only time inside the campaign is integrated, whatever the last update time was
+                // left at, and never more than the campaign period in a single update
                 let time_delta = latest_update_timestamp
-                    .checked_sub(reward_info.last_update_time)
-                    .unwrap();
+                    .saturating_sub(reward_info.last_update_time.max(reward_info.open_time))
+                    .min(reward_info.end_time.saturating_sub(reward_info.open_time));
 
                 let reward_growth_delta = U256::from(time_delta)
                     .mul_div_floor(
@@ -530,6 +922,12 @@ impl PoolState {
         self.status = status
     }
 
+    /// Disables the operations of the withdraw-only mode, the operations already disabled stay
+    /// disabled
+    pub fn set_withdraw_only(&mut self) {
+        self.status = self.status.bitor(WITHDRAW_ONLY_STATUS);
+    }
+
     pub fn set_status_by_bit(&mut self, bit: PoolStatusBitIndex, flag: PoolStatusBitFlag) {
         let s = u8::from(1) << (bit as u8);
         if flag == PoolStatusBitFlag::Disable {
@@ -546,6 +944,38 @@ impl PoolState {
         self.status.bitand(status) == 0
     }
 
+    pub fn is_observation_event_enabled(&self) -> bool {
+        self.observation_event_enabled != 0
+    }
+
+    /// The age the newest observation must reach before it can be poked. Pools used as price
+    /// sources can be poked as often as swaps write observations
+    pub fn observation_poke_min_age(&self) -> u32 {
+        if self.oracle_consumer_count > 0 {
+            OBSERVATION_UPDATE_DURATION_DEFAULT
+        } else {
+            OBSERVATION_POKE_MIN_AGE
+        }
+    }
+
+    pub fn get_quote_orientation(&self) -> QuoteOrientation {
+        if self.quote_orientation == QuoteOrientation::Token0PerToken1 as u8 {
+            QuoteOrientation::Token0PerToken1
+        } else {
+            QuoteOrientation::Token1PerToken0
+        }
+    }
+
+    /// The decimal adjusted price as a Q64.64 in the given quote orientation
+    pub fn get_price_x64(&self, quote_orientation: QuoteOrientation) -> Result<u128> {
+        price_math::get_price_x64_with_decimals(
+            self.sqrt_price_x64,
+            self.mint_decimals_0,
+            self.mint_decimals_1,
+            quote_orientation == QuoteOrientation::Token0PerToken1,
+        )
+    }
+
     pub fn is_overflow_default_tickarray_bitmap(&self, tick_indexs: Vec<i32>) -> bool {
         let (min_tick_array_start_index_boundary, max_tick_array_index_boundary) =
             self.tick_array_start_index_range();
@@ -698,8 +1128,74 @@ pub struct CollectProtocolFeeEvent {
     /// The amount of token_0 protocol fees that is withdrawn
     pub amount_0: u64,
 
-    /// The amount of token_0 protocol fees that is withdrawn
+    /// The amount of token_1 protocol fees that is withdrawn
+    pub amount_1: u64,
+
+    /// The amount of token_0 fees requested by the caller
+    pub amount_0_requested: u64,
+
+    /// The amount of token_1 fees requested by the caller
+    pub amount_1_requested: u64,
+
+    /// The token_0 fees still accrued in the pool after collection
+    pub remaining_fees_token_0: u64,
+
+    /// The token_1 fees still accrued in the pool after collection
+    pub remaining_fees_token_1: u64,
+
+    /// The token_0 vault balance after collection
+    pub vault_0_balance: u64,
+
+    /// The token_1 vault balance after collection
+    pub vault_1_balance: u64,
+}
+
+/// Emitted when protocol fees are swapped into the treasury token by the admin
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct ConvertProtocolFeeEvent {
+    /// The pool whose protocol fee is converted
+    #[index]
+    pub fee_pool_state: Pubkey,
+
+    /// The pool the fee is swapped through
+    pub swap_pool_state: Pubkey,
+
+    /// The address that receives the treasury token
+    pub treasury_token_account: Pubkey,
+
+    /// The amount of fee token swapped
+    pub amount_in: u64,
+
+    /// The amount of treasury token received
+    pub amount_out: u64,
+
+    /// The twap tick of the swap pool the output is bounded by
+    pub twap_tick: i32,
+
+    /// The minimum output derived from the twap price
+    pub amount_out_minimum: u64,
+}
+
+/// Emitted when tokens are donated to the liquidity providers of a pool
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct DonateEvent {
+    /// The pool receiving the donation
+    #[index]
+    pub pool_state: Pubkey,
+
+    /// The donor
+    pub sender: Pubkey,
+
+    /// The amount of token_0 credited to fees, excluding the transfer fee
+    pub amount_0: u64,
+
+    /// The amount of token_1 credited to fees, excluding the transfer fee
     pub amount_1: u64,
+
+    /// The in-range liquidity the donation is shared across
+    pub liquidity: u128,
 }
 
 /// Emitted by when a swap is performed for a pool
@@ -724,6 +1220,10 @@ pub struct SwapEvent {
     #[index]
     pub token_account_1: Pubkey,
 
+    /// The owner of the token account that received the output, the sender unless the swap
+    /// directed it elsewhere
+    pub recipient: Pubkey,
+
     /// The real delta amount of the token_0 of the pool or user
     pub amount_0: u64,
 
@@ -773,6 +1273,31 @@ pub struct LiquidityChangeEvent {
     pub liquidity_after: u128,
 }
 
+/// Emitted when a pool's derived state is recomputed by `validate_pool`
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct ValidatePoolEvent {
+    /// The validated pool
+    #[index]
+    pub pool_state: Pubkey,
+    /// The tick and sqrt price of the pool
+    pub tick_current: i32,
+    pub sqrt_price_x64: u128,
+    /// Whether the tick is the tick of the sqrt price
+    pub tick_consistent: bool,
+    /// The pool liquidity before any repair
+    pub liquidity: u128,
+    /// Whether the tick arrays given covered the whole range below the current tick
+    pub liquidity_checked: bool,
+    /// The liquidity summed from the ticks, 0 when not checked
+    pub expected_liquidity: u128,
+    /// Whether the pool liquidity was overwritten with the expected liquidity
+    pub liquidity_repaired: bool,
+    /// The number of tick arrays recounted, and of those whose initialized tick count was wrong
+    pub tick_arrays_checked: u8,
+    pub tick_counts_repaired: u8,
+}
+
 // /// Emitted when price move in a swap step
 // #[event]
 // #[cfg_attr(feature = "client", derive(Debug))]
@@ -838,6 +1363,45 @@ pub mod pool_test {
         RefCell::new(new_pool)
     }
 
+    #[test]
+    fn take_volume_rebate_test() {
+        let mut pool_state = PoolState {
+            protocol_fees_token_0: 1_000,
+            ..Default::default()
+        };
+        // only the protocol fee accrued since the snapshot is rebated
+        let rebate = pool_state.take_volume_rebate(true, 600, 250_000);
+        assert_eq!(rebate, 100);
+        assert_eq!({ pool_state.protocol_fees_token_0 }, 900);
+        assert_eq!({ pool_state.volume_rebates_token_0 }, 100);
+        // the owed fees are unchanged overall
+        assert_eq!(pool_state.trade_fees(true), 1_000);
+
+        assert_eq!(pool_state.take_volume_rebate(false, 0, 250_000), 0);
+        assert_eq!({ pool_state.volume_rebates_token_1 }, 0);
+    }
+
+    #[test]
+    fn take_distributed_protocol_fee_test() {
+        let mut pool_state = PoolState {
+            protocol_fees_token_0: 1_000,
+            cumulative_protocol_fees_token_0: 1_000,
+            ..Default::default()
+        };
+        assert_eq!(pool_state.take_distributed_protocol_fee(true, 500_000), 500);
+        assert_eq!({ pool_state.protocol_fees_token_0 }, 500);
+        // repeated calls don't share the same fees again
+        assert_eq!(pool_state.take_distributed_protocol_fee(true, 500_000), 0);
+        assert_eq!({ pool_state.protocol_fees_token_0 }, 500);
+
+        // only the fees charged since are shared, at most what is still owed
+        pool_state.cumulative_protocol_fees_token_0 += 400;
+        pool_state.protocol_fees_token_0 = 100;
+        assert_eq!(pool_state.take_distributed_protocol_fee(true, 500_000), 100);
+        assert_eq!({ pool_state.protocol_fees_token_0 }, 0);
+        assert_eq!({ pool_state.distributed_protocol_fees_token_0 }, 1_400);
+    }
+
     mod tick_array_bitmap_test {
 
         use super::*;
@@ -999,6 +1563,267 @@ pub mod pool_test {
                 false
             );
         }
+
+        #[test]
+        fn set_withdraw_only_test() {
+            let mut pool_state = PoolState::default();
+            pool_state.set_withdraw_only();
+            assert_eq!({ pool_state.status }, WITHDRAW_ONLY_STATUS);
+            assert!(
+                !pool_state.get_status_by_bit(PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity)
+            );
+            assert!(!pool_state.get_status_by_bit(PoolStatusBitIndex::Swap));
+            assert!(pool_state.get_status_by_bit(PoolStatusBitIndex::DecreaseLiquidity));
+            assert!(pool_state.get_status_by_bit(PoolStatusBitIndex::CollectFee));
+            assert!(pool_state.get_status_by_bit(PoolStatusBitIndex::CollectReward));
+
+            // a frozen pool stays frozen
+            pool_state.set_status(255);
+            pool_state.set_withdraw_only();
+            assert_eq!({ pool_state.status }, 255);
+
+            // as do the operations the admin disabled
+            pool_state.set_status(1 << PoolStatusBitIndex::CollectReward as u8);
+            pool_state.set_withdraw_only();
+            assert!(!pool_state.get_status_by_bit(PoolStatusBitIndex::Swap));
+            assert!(pool_state.get_status_by_bit(PoolStatusBitIndex::DecreaseLiquidity));
+            assert!(!pool_state.get_status_by_bit(PoolStatusBitIndex::CollectReward));
+
+            // and keep so once the admin enables the withdraw-only operations again
+            pool_state.set_status_by_bit(
+                PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity,
+                PoolStatusBitFlag::Enable,
+            );
+            pool_state.set_status_by_bit(PoolStatusBitIndex::Swap, PoolStatusBitFlag::Enable);
+            assert!(
+                pool_state.get_status_by_bit(PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity)
+            );
+            assert!(pool_state.get_status_by_bit(PoolStatusBitIndex::Swap));
+            assert!(pool_state.get_status_by_bit(PoolStatusBitIndex::DecreaseLiquidity));
+            assert!(!pool_state.get_status_by_bit(PoolStatusBitIndex::CollectReward));
+        }
+    }
+
+    mod token_program_test {
+        use super::*;
+
+        #[test]
+        fn is_valid_token_program_test() {
+            let mut pool_state = PoolState::default();
+            pool_state.token_mint_0 = Pubkey::new_unique();
+            pool_state.token_mint_1 = Pubkey::new_unique();
+            let mint_0 = pool_state.token_mint_0;
+            let mint_1 = pool_state.token_mint_1;
+
+            // not recorded, either token program is accepted
+            assert!(pool_state.is_valid_token_program(&mint_0, &anchor_spl::token::ID));
+            assert!(pool_state.is_valid_token_program(&mint_1, &anchor_spl::token_2022::ID));
+            assert!(!pool_state.is_valid_token_program(&mint_0, &Pubkey::new_unique()));
+
+            pool_state.token_program_0 = anchor_spl::token::ID;
+            pool_state.token_program_1 = anchor_spl::token_2022::ID;
+            assert!(pool_state.is_valid_token_program(&mint_0, &anchor_spl::token::ID));
+            assert!(!pool_state.is_valid_token_program(&mint_0, &anchor_spl::token_2022::ID));
+            assert!(pool_state.is_valid_token_program(&mint_1, &anchor_spl::token_2022::ID));
+            assert!(!pool_state.is_valid_token_program(&mint_1, &anchor_spl::token::ID));
+
+            assert!(
+                !pool_state.is_valid_token_program(&Pubkey::new_unique(), &anchor_spl::token::ID)
+            );
+        }
+    }
+
+    mod custom_trade_fee_test {
+        use super::*;
+
+        #[test]
+        fn trade_fee_rate_test() {
+            let mut amm_config = AmmConfig::default();
+            amm_config.trade_fee_rate = 2500;
+            let mut pool_state = PoolState::default();
+            assert_eq!(pool_state.trade_fee_rate(&amm_config), 2500);
+
+            pool_state.custom_trade_fee_rate = 100;
+            assert_eq!(pool_state.trade_fee_rate(&amm_config), 100);
+        }
+
+        #[test]
+        fn max_price_impact_test() {
+            let mut pool_state = PoolState::default();
+            assert_eq!(pool_state.max_price_impact(), None);
+            pool_state.max_price_impact_bps = 250;
+            assert_eq!(pool_state.max_price_impact(), Some(25_000));
+            pool_state.max_price_impact_bps = u16::MAX;
+            assert_eq!(pool_state.max_price_impact(), Some(6_553_500));
+        }
+
+        #[test]
+        fn swap_hook_test() {
+            let hook_program = Pubkey::new_unique();
+            let mut amm_config = AmmConfig::default();
+            let mut pool_state = PoolState::default();
+            assert_eq!(pool_state.swap_hook(&amm_config), None);
+
+            pool_state.swap_hook_program = hook_program;
+            assert_eq!(pool_state.swap_hook(&amm_config), None);
+            amm_config.swap_hook_program = hook_program;
+            assert_eq!(pool_state.swap_hook(&amm_config), Some(hook_program));
+            // whitelisting another hook revokes the pool's
+            amm_config.swap_hook_program = Pubkey::new_unique();
+            assert_eq!(pool_state.swap_hook(&amm_config), None);
+        }
+
+        #[test]
+        fn set_partner_test() {
+            let amm_config = AmmConfig {
+                protocol_fee_rate: 120_000,
+                fund_fee_rate: 40_000,
+                ..Default::default()
+            };
+            let mut pool_state = PoolState::default();
+            let partner = Pubkey::new_unique();
+
+            // a partner needs a rate and the other way round
+            assert!(pool_state.set_partner(partner, 0, &amm_config).is_err());
+            assert!(pool_state
+                .set_partner(Pubkey::default(), 100, &amm_config)
+                .is_err());
+            // no room left for the protocol and fund fees
+            assert!(pool_state
+                .set_partner(partner, 840_001, &amm_config)
+                .is_err());
+            pool_state
+                .set_partner(partner, 840_000, &amm_config)
+                .unwrap();
+            assert_eq!({ pool_state.partner_fee_rate }, 840_000);
+
+            // the partner can't be replaced while its fees are owed
+            pool_state.partner_fees_token_1 = 1;
+            pool_state
+                .set_partner(partner, 100_000, &amm_config)
+                .unwrap();
+            assert!(pool_state
+                .set_partner(Pubkey::default(), 0, &amm_config)
+                .is_err());
+            pool_state.partner_fees_token_1 = 0;
+            pool_state
+                .set_partner(Pubkey::default(), 0, &amm_config)
+                .unwrap();
+            assert_eq!({ pool_state.partner }, Pubkey::default());
+        }
+
+        #[test]
+        fn donate_test() {
+            let mut pool_state = PoolState::default();
+            // nobody to donate to
+            assert!(pool_state.donate(100, 100).is_err());
+
+            pool_state.liquidity = 256;
+            pool_state.donate(128, 1).unwrap();
+            assert_eq!(
+                { pool_state.fee_growth_global_0_x64 },
+                fixed_point_64::Q64 / 2
+            );
+            assert_eq!(
+                { pool_state.fee_growth_global_1_x64 },
+                fixed_point_64::Q64 / 256
+            );
+            assert_eq!({ pool_state.total_fees_token_0 }, 128);
+            assert_eq!({ pool_state.total_fees_token_1 }, 1);
+
+            // at most one token per unit of in-range liquidity
+            pool_state.donate(256, 0).unwrap();
+            assert!(pool_state.donate(0, 257).is_err());
+        }
+
+        #[test]
+        fn liquidity_utilization_test() {
+            let mut pool_state = PoolState {
+                tick_spacing: 10,
+                liquidity: 1000,
+                ..Default::default()
+            };
+            // nothing crossed yet
+            assert_eq!(pool_state.liquidity_utilization(), 0);
+
+            let crossed_tick = TickState {
+                tick: 0,
+                liquidity_gross: 500,
+                ..Default::default()
+            };
+            let next_tick = TickState {
+                tick: -50,
+                liquidity_gross: 1500,
+                ..Default::default()
+            };
+            pool_state.tick_current = -1;
+            pool_state.update_near_ticks(true, &crossed_tick, Some(&next_tick));
+            assert_eq!({ pool_state.near_tick_lower }, -50);
+            assert_eq!({ pool_state.near_tick_upper }, 0);
+            assert_eq!(pool_state.near_liquidity_gross(), 2000);
+            assert_eq!(pool_state.liquidity_utilization(), 500_000);
+
+            // the lower tick drifts out of the window
+            pool_state.tick_current = 60;
+            assert_eq!(pool_state.near_liquidity_gross(), 500);
+            assert_eq!(pool_state.liquidity_utilization(), 2_000_000);
+
+            // the swap ended on the crossed tick, the next one is unknown
+            pool_state.tick_current = 0;
+            pool_state.update_near_ticks(false, &crossed_tick, None);
+            assert_eq!({ pool_state.near_liquidity_gross_upper }, 0);
+            assert_eq!(pool_state.near_liquidity_gross(), 500);
+        }
+
+        #[test]
+        fn check_custom_trade_fee_rate_test() {
+            let mut amm_config = AmmConfig::default();
+            // disabled by default
+            assert!(amm_config.check_custom_trade_fee_rate(100).is_err());
+
+            amm_config.custom_fee_min_rate = 50;
+            amm_config.custom_fee_max_rate = 500;
+            assert!(amm_config.check_custom_trade_fee_rate(49).is_err());
+            assert!(amm_config.check_custom_trade_fee_rate(50).is_ok());
+            assert!(amm_config.check_custom_trade_fee_rate(500).is_ok());
+            assert!(amm_config.check_custom_trade_fee_rate(501).is_err());
+
+            amm_config.custom_fee_min_rate = 0;
+            assert!(amm_config.check_custom_trade_fee_rate(0).is_err());
+        }
+
+        #[test]
+        fn custom_fee_pool_seeds_test() {
+            let mut pool_state = PoolState::default();
+            pool_state.amm_config = Pubkey::new_unique();
+            pool_state.token_mint_0 = Pubkey::new_unique();
+            pool_state.token_mint_1 = Pubkey::new_unique();
+            let pool_seeds = [
+                POOL_SEED.as_bytes(),
+                pool_state.amm_config.as_ref(),
+                pool_state.token_mint_0.as_ref(),
+                pool_state.token_mint_1.as_ref(),
+            ];
+            let (pool_id, bump) = Pubkey::find_program_address(&pool_seeds, &crate::id());
+            pool_state.bump = [bump];
+            assert_eq!(pool_state.key(), pool_id);
+
+            // a custom fee pool of the same pair lives at another address
+            pool_state.custom_trade_fee_rate = 100;
+            assert_eq!(pool_state.seeds()[0], CUSTOM_FEE_POOL_SEED.as_bytes());
+            let (custom_fee_pool_id, bump) = Pubkey::find_program_address(
+                &[
+                    CUSTOM_FEE_POOL_SEED.as_bytes(),
+                    &pool_seeds[1],
+                    &pool_seeds[2],
+                    &pool_seeds[3],
+                ],
+                &crate::id(),
+            );
+            pool_state.bump = [bump];
+            assert_eq!(pool_state.key(), custom_fee_pool_id);
+            assert_ne!(custom_fee_pool_id, pool_id);
+        }
     }
 
     mod update_reward_infos_test {
@@ -1056,6 +1881,56 @@ pub mod pool_test {
                 1666069200
             );
         }
+
+        #[test]
+        fn reward_accrual_bounded_by_campaign_test() {
+            let pool_state = &mut PoolState::default();
+            let operation_state = OperationState {
+                bump: 0,
+                operation_owners: [Pubkey::default(); OPERATION_SIZE_USIZE],
+                whitelist_mints: [Pubkey::default(); WHITE_MINT_SIZE_USIZE],
+            };
+            assert!(pool_state
+                .initialize_reward(
+                    1000,
+                    1000,
+                    10,
+                    &Pubkey::new_unique(),
+                    &Pubkey::default(),
+                    &Pubkey::default(),
+                    &operation_state,
+                )
+                .is_err());
+            pool_state
+                .initialize_reward(
+                    1000,
+                    2000,
+                    10,
+                    &Pubkey::new_unique(),
+                    &Pubkey::default(),
+                    &Pubkey::default(),
+                    &operation_state,
+                )
+                .unwrap();
+            pool_state.liquidity = 10;
+
+            // a stale last update time before the open time doesn't accrue the gap
+            pool_state.reward_infos[0].last_update_time = 0;
+            let updated_reward_infos = pool_state.update_reward_infos(1500).unwrap();
+            assert_eq!(
+                identity(updated_reward_infos[0].reward_growth_global_x64),
+                500
+            );
+
+            // an end time before the open time never emits
+            pool_state.reward_infos[0].end_time = 900;
+            pool_state.reward_infos[0].last_update_time = 0;
+            let updated_reward_infos = pool_state.update_reward_infos(3000).unwrap();
+            assert_eq!(
+                identity(updated_reward_infos[0].reward_growth_global_x64),
+                500
+            );
+        }
     }
 
     mod use_tickarray_bitmap_extension_test {
@@ -1592,7 +2467,9 @@ pub mod pool_test {
             let swap_in_amount_token_1: u128 = 0x11223344556677008899aabbccddeeff;
             let swap_out_amount_token_0: u128 = 0x11223344556677880099aabbccddeeff;
             let status: u8 = 0x1b;
-            let padding: [u8; 7] = [0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18];
+            let quote_orientation: u8 = 0x1d;
+            let observation_event_enabled: u8 = 0x1e;
+            let padding: [u8; 5] = [0x12, 0x13, 0x14, 0x15, 0x16];
             // RewardInfo
             let reward_state: u8 = 0x1c;
             let open_time: u64 = 0x123456789abc0def;
@@ -1663,20 +2540,51 @@ pub mod pool_test {
             let fund_fees_token_1: u64 = 0x1230456789abcdef;
             let pool_open_time: u64 = 0x1203456789abcdef;
             let recent_epoch: u64 = 0x1023456789abcdef;
-            let mut padding1: [u64; 24] = [0u64; 24];
-            let mut padding1_data = [0u8; 8 * 24];
+            let custom_trade_fee_rate: u32 = 0x12345678;
+            let padding5: u32 = 0x87654321;
+            let token_program_0 = Pubkey::new_unique();
+            let token_program_1 = Pubkey::new_unique();
+            let partner = Pubkey::new_unique();
+            let partner_fee_rate: u32 = 0x12340678;
+            let padding6: u32 = 0x87604321;
+            let partner_fees_token_0: u64 = 0x1203456789abcdef;
+            let partner_fees_token_1: u64 = 0x1234567890abcdef;
+            let near_tick_lower: i32 = -0x1234567;
+            let near_tick_upper: i32 = 0x1234567;
+            let near_liquidity_gross_lower: u128 = 0x11002233445566778899aabbccddeeff;
+            let near_liquidity_gross_upper: u128 = 0x11220033445566778899aabbccddeeff;
+            let oracle_consumer_count: u32 = 0x12345078;
+            let padding7: u32 = 0x87654021;
+            let referral_fees_token_0: u64 = 0x1234567809abcdef;
+            let referral_fees_token_1: u64 = 0x123456789a0bcdef;
+            let cumulative_trade_fees_token_0: u64 = 0x123456789ab0cdef;
+            let cumulative_trade_fees_token_1: u64 = 0x123456789abc0def;
+            let cumulative_protocol_fees_token_0: u64 = 0x123456789abcd0ef;
+            let cumulative_protocol_fees_token_1: u64 = 0x123456789abcde0f;
+            let volume_rebate_threshold: u64 = 0x1234567890abcdef;
+            let volume_rebates_token_0: u64 = 0x0123456789abcdef;
+            let volume_rebates_token_1: u64 = 0x1023456789abcdef;
+            let max_price_impact_bps: u16 = 0x1234;
+            let padding8: [u16; 3] = [0x2345, 0x3456, 0x4567];
+            let swap_hook_program = Pubkey::new_unique();
+            let min_swap_amount_0: u64 = 0x123456789abcdef0;
+            let min_swap_amount_1: u64 = 0x23456789abcdef01;
+            let distributed_protocol_fees_token_0: u64 = 0x3456789abcdef012;
+            let distributed_protocol_fees_token_1: u64 = 0x456789abcdef0123;
+            let mut padding1: [u64; 2] = [0u64; 2];
+            let mut padding1_data = [0u8; 8 * 2];
             let mut offset = 0;
-            for i in 0..24 {
+            for i in 0..2 {
                 padding1[i] = u64::MAX - i as u64;
                 padding1_data[offset..offset + 8].copy_from_slice(&padding1[i].to_le_bytes());
                 offset += 8;
             }
-            let mut padding2: [u64; 32] = [0u64; 32];
-            let mut padding2_data = [0u8; 8 * 32];
+            let mut padding2: [u64; 14] = [0u64; 14];
+            let mut padding2_data = [0u8; 8 * 14];
             let mut offset = 0;
-            for i in 24..(24 + 32) {
-                padding2[i - 24] = u64::MAX - i as u64;
-                padding2_data[offset..offset + 8].copy_from_slice(&padding2[i - 24].to_le_bytes());
+            for i in 28..(28 + 14) {
+                padding2[i - 28] = u64::MAX - i as u64;
+                padding2_data[offset..offset + 8].copy_from_slice(&padding2[i - 28].to_le_bytes());
                 offset += 8;
             }
             // serialize original data
@@ -1734,8 +2642,12 @@ pub mod pool_test {
             offset += 16;
             pool_data[offset..offset + 1].copy_from_slice(&status.to_le_bytes());
             offset += 1;
-            pool_data[offset..offset + 7].copy_from_slice(&padding);
-            offset += 7;
+            pool_data[offset..offset + 1].copy_from_slice(&quote_orientation.to_le_bytes());
+            offset += 1;
+            pool_data[offset..offset + 1].copy_from_slice(&observation_event_enabled.to_le_bytes());
+            offset += 1;
+            pool_data[offset..offset + 5].copy_from_slice(&padding);
+            offset += 5;
             pool_data[offset..offset + RewardInfo::LEN * REWARD_NUM]
                 .copy_from_slice(&reward_info_datas);
             offset += RewardInfo::LEN * REWARD_NUM;
@@ -1759,10 +2671,82 @@ pub mod pool_test {
             offset += 8;
             pool_data[offset..offset + 8].copy_from_slice(&recent_epoch.to_le_bytes());
             offset += 8;
-            pool_data[offset..offset + 8 * 24].copy_from_slice(&padding1_data);
-            offset += 8 * 24;
-            pool_data[offset..offset + 8 * 32].copy_from_slice(&padding2_data);
-            offset += 8 * 32;
+            pool_data[offset..offset + 4].copy_from_slice(&custom_trade_fee_rate.to_le_bytes());
+            offset += 4;
+            pool_data[offset..offset + 4].copy_from_slice(&padding5.to_le_bytes());
+            offset += 4;
+            pool_data[offset..offset + 32].copy_from_slice(&token_program_0.to_bytes());
+            offset += 32;
+            pool_data[offset..offset + 32].copy_from_slice(&token_program_1.to_bytes());
+            offset += 32;
+            pool_data[offset..offset + 32].copy_from_slice(&partner.to_bytes());
+            offset += 32;
+            pool_data[offset..offset + 4].copy_from_slice(&partner_fee_rate.to_le_bytes());
+            offset += 4;
+            pool_data[offset..offset + 4].copy_from_slice(&padding6.to_le_bytes());
+            offset += 4;
+            pool_data[offset..offset + 8].copy_from_slice(&partner_fees_token_0.to_le_bytes());
+            offset += 8;
+            pool_data[offset..offset + 8].copy_from_slice(&partner_fees_token_1.to_le_bytes());
+            offset += 8;
+            pool_data[offset..offset + 4].copy_from_slice(&near_tick_lower.to_le_bytes());
+            offset += 4;
+            pool_data[offset..offset + 4].copy_from_slice(&near_tick_upper.to_le_bytes());
+            offset += 4;
+            pool_data[offset..offset + 16]
+                .copy_from_slice(&near_liquidity_gross_lower.to_le_bytes());
+            offset += 16;
+            pool_data[offset..offset + 16]
+                .copy_from_slice(&near_liquidity_gross_upper.to_le_bytes());
+            offset += 16;
+            pool_data[offset..offset + 4].copy_from_slice(&oracle_consumer_count.to_le_bytes());
+            offset += 4;
+            pool_data[offset..offset + 4].copy_from_slice(&padding7.to_le_bytes());
+            offset += 4;
+            pool_data[offset..offset + 8].copy_from_slice(&referral_fees_token_0.to_le_bytes());
+            offset += 8;
+            pool_data[offset..offset + 8].copy_from_slice(&referral_fees_token_1.to_le_bytes());
+            offset += 8;
+            pool_data[offset..offset + 8]
+                .copy_from_slice(&cumulative_trade_fees_token_0.to_le_bytes());
+            offset += 8;
+            pool_data[offset..offset + 8]
+                .copy_from_slice(&cumulative_trade_fees_token_1.to_le_bytes());
+            offset += 8;
+            pool_data[offset..offset + 8]
+                .copy_from_slice(&cumulative_protocol_fees_token_0.to_le_bytes());
+            offset += 8;
+            pool_data[offset..offset + 8]
+                .copy_from_slice(&cumulative_protocol_fees_token_1.to_le_bytes());
+            offset += 8;
+            pool_data[offset..offset + 8].copy_from_slice(&volume_rebate_threshold.to_le_bytes());
+            offset += 8;
+            pool_data[offset..offset + 8].copy_from_slice(&volume_rebates_token_0.to_le_bytes());
+            offset += 8;
+            pool_data[offset..offset + 8].copy_from_slice(&volume_rebates_token_1.to_le_bytes());
+            offset += 8;
+            pool_data[offset..offset + 2].copy_from_slice(&max_price_impact_bps.to_le_bytes());
+            offset += 2;
+            for padding in padding8 {
+                pool_data[offset..offset + 2].copy_from_slice(&padding.to_le_bytes());
+                offset += 2;
+            }
+            pool_data[offset..offset + 32].copy_from_slice(&swap_hook_program.to_bytes());
+            offset += 32;
+            pool_data[offset..offset + 8].copy_from_slice(&min_swap_amount_0.to_le_bytes());
+            offset += 8;
+            pool_data[offset..offset + 8].copy_from_slice(&min_swap_amount_1.to_le_bytes());
+            offset += 8;
+            pool_data[offset..offset + 8]
+                .copy_from_slice(&distributed_protocol_fees_token_0.to_le_bytes());
+            offset += 8;
+            pool_data[offset..offset + 8]
+                .copy_from_slice(&distributed_protocol_fees_token_1.to_le_bytes());
+            offset += 8;
+            pool_data[offset..offset + 8 * 2].copy_from_slice(&padding1_data);
+            offset += 8 * 2;
+            pool_data[offset..offset + 8 * 14].copy_from_slice(&padding2_data);
+            offset += 8 * 14;
 
             // len check
             assert_eq!(offset, pool_data.len());
@@ -1823,6 +2807,10 @@ pub mod pool_test {
             assert_eq!(unpack_swap_out_amount_token_0, swap_out_amount_token_0);
             let unpack_status = unpack_data.status;
             assert_eq!(unpack_status, status);
+            let unpack_quote_orientation = unpack_data.quote_orientation;
+            assert_eq!(unpack_quote_orientation, quote_orientation);
+            let unpack_observation_event_enabled = unpack_data.observation_event_enabled;
+            assert_eq!(unpack_observation_event_enabled, observation_event_enabled);
             let unpack_padding = unpack_data.padding;
             assert_eq!(unpack_padding, padding);
 
@@ -1873,6 +2861,96 @@ pub mod pool_test {
             assert_eq!(unpack_open_time, pool_open_time);
             let unpack_recent_epoch = unpack_data.recent_epoch;
             assert_eq!(unpack_recent_epoch, recent_epoch);
+            let unpack_custom_trade_fee_rate = unpack_data.custom_trade_fee_rate;
+            assert_eq!(unpack_custom_trade_fee_rate, custom_trade_fee_rate);
+            let unpack_padding5 = unpack_data.padding5;
+            assert_eq!(unpack_padding5, padding5);
+            let unpack_token_program_0 = unpack_data.token_program_0;
+            assert_eq!(unpack_token_program_0, token_program_0);
+            let unpack_token_program_1 = unpack_data.token_program_1;
+            assert_eq!(unpack_token_program_1, token_program_1);
+            let unpack_partner = unpack_data.partner;
+            assert_eq!(unpack_partner, partner);
+            let unpack_partner_fee_rate = unpack_data.partner_fee_rate;
+            assert_eq!(unpack_partner_fee_rate, partner_fee_rate);
+            let unpack_padding6 = unpack_data.padding6;
+            assert_eq!(unpack_padding6, padding6);
+            let unpack_partner_fees_token_0 = unpack_data.partner_fees_token_0;
+            assert_eq!(unpack_partner_fees_token_0, partner_fees_token_0);
+            let unpack_partner_fees_token_1 = unpack_data.partner_fees_token_1;
+            assert_eq!(unpack_partner_fees_token_1, partner_fees_token_1);
+            let unpack_near_tick_lower = unpack_data.near_tick_lower;
+            assert_eq!(unpack_near_tick_lower, near_tick_lower);
+            let unpack_near_tick_upper = unpack_data.near_tick_upper;
+            assert_eq!(unpack_near_tick_upper, near_tick_upper);
+            let unpack_near_liquidity_gross_lower = unpack_data.near_liquidity_gross_lower;
+            assert_eq!(
+                unpack_near_liquidity_gross_lower,
+                near_liquidity_gross_lower
+            );
+            let unpack_near_liquidity_gross_upper = unpack_data.near_liquidity_gross_upper;
+            assert_eq!(
+                unpack_near_liquidity_gross_upper,
+                near_liquidity_gross_upper
+            );
+            let unpack_oracle_consumer_count = unpack_data.oracle_consumer_count;
+            assert_eq!(unpack_oracle_consumer_count, oracle_consumer_count);
+            let unpack_padding7 = unpack_data.padding7;
+            assert_eq!(unpack_padding7, padding7);
+            let unpack_referral_fees_token_0 = unpack_data.referral_fees_token_0;
+            assert_eq!(unpack_referral_fees_token_0, referral_fees_token_0);
+            let unpack_referral_fees_token_1 = unpack_data.referral_fees_token_1;
+            assert_eq!(unpack_referral_fees_token_1, referral_fees_token_1);
+            let unpack_cumulative_trade_fees_token_0 = unpack_data.cumulative_trade_fees_token_0;
+            assert_eq!(
+                unpack_cumulative_trade_fees_token_0,
+                cumulative_trade_fees_token_0
+            );
+            let unpack_cumulative_trade_fees_token_1 = unpack_data.cumulative_trade_fees_token_1;
+            assert_eq!(
+                unpack_cumulative_trade_fees_token_1,
+                cumulative_trade_fees_token_1
+            );
+            let unpack_cumulative_protocol_fees_token_0 =
+                unpack_data.cumulative_protocol_fees_token_0;
+            assert_eq!(
+                unpack_cumulative_protocol_fees_token_0,
+                cumulative_protocol_fees_token_0
+            );
+            let unpack_cumulative_protocol_fees_token_1 =
+                unpack_data.cumulative_protocol_fees_token_1;
+            assert_eq!(
+                unpack_cumulative_protocol_fees_token_1,
+                cumulative_protocol_fees_token_1
+            );
+            let unpack_volume_rebate_threshold = unpack_data.volume_rebate_threshold;
+            assert_eq!(unpack_volume_rebate_threshold, volume_rebate_threshold);
+            let unpack_volume_rebates_token_0 = unpack_data.volume_rebates_token_0;
+            assert_eq!(unpack_volume_rebates_token_0, volume_rebates_token_0);
+            let unpack_volume_rebates_token_1 = unpack_data.volume_rebates_token_1;
+            assert_eq!(unpack_volume_rebates_token_1, volume_rebates_token_1);
+            let unpack_max_price_impact_bps = unpack_data.max_price_impact_bps;
+            assert_eq!(unpack_max_price_impact_bps, max_price_impact_bps);
+            let unpack_padding8 = unpack_data.padding8;
+            assert_eq!(unpack_padding8, padding8);
+            let unpack_swap_hook_program = unpack_data.swap_hook_program;
+            assert_eq!(unpack_swap_hook_program, swap_hook_program);
+            let unpack_min_swap_amount_0 = unpack_data.min_swap_amount_0;
+            assert_eq!(unpack_min_swap_amount_0, min_swap_amount_0);
+            let unpack_min_swap_amount_1 = unpack_data.min_swap_amount_1;
+            assert_eq!(unpack_min_swap_amount_1, min_swap_amount_1);
+            let unpack_distributed_protocol_fees_token_0 =
+                unpack_data.distributed_protocol_fees_token_0;
+            assert_eq!(
+                unpack_distributed_protocol_fees_token_0,
+                distributed_protocol_fees_token_0
+            );
+            let unpack_distributed_protocol_fees_token_1 =
+                unpack_data.distributed_protocol_fees_token_1;
+            assert_eq!(
+                unpack_distributed_protocol_fees_token_1,
+                distributed_protocol_fees_token_1
+            );
             let unpack_padding1 = unpack_data.padding1;
             assert_eq!(unpack_padding1, padding1);
             let unpack_padding2 = unpack_data.padding2;
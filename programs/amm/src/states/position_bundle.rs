@@ -0,0 +1,128 @@
+use crate::error::ErrorCode;
+use anchor_lang::prelude::*;
+
+pub const POSITION_BUNDLE_SEED: &str = "position_bundle";
+/// The number of position slots held by one bundle
+pub const POSITION_BUNDLE_SIZE: u16 = 256;
+
+/// Many positions held under a single Token2022 NFT, so one mint and one token account
+/// back up to `POSITION_BUNDLE_SIZE` positions.
+///
+/// Each bundled position is a regular `PersonalPositionState` whose `nft_mint` is the bundle
+/// mint, at PDA `[POSITION_SEED, position_bundle_mint, bundle_index]`, so liquidity, fees and
+/// rewards are managed through the usual position instructions by the bundle NFT holder.
+///
+/// PDA of `[POSITION_BUNDLE_SEED, position_bundle_mint]`
+///
+#[account]
+#[derive(Default, Debug)]
+pub struct PositionBundle {
+    /// Bump to identify PDA
+    pub bump: [u8; 1],
+    /// The NFT mint held by the bundle owner
+    pub position_bundle_mint: Pubkey,
+    /// One bit per slot, set while a position is open in the slot
+    pub position_bitmap: [u8; 32],
+    // Unused bytes for future upgrades.
+    pub padding: [u64; 8],
+}
+
+impl PositionBundle {
+    pub const LEN: usize = 8 + 1 + 32 + 32 + 8 * 8;
+
+    pub fn seeds(&self) -> [&[u8]; 3] {
+        [
+            POSITION_BUNDLE_SEED.as_bytes(),
+            self.position_bundle_mint.as_ref(),
+            self.bump.as_ref(),
+        ]
+    }
+
+    pub fn is_open(&self, bundle_index: u16) -> bool {
+        let index = usize::from(bundle_index);
+        index < usize::from(POSITION_BUNDLE_SIZE)
+            && self.position_bitmap[index / 8] & (1 << (index % 8)) != 0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.position_bitmap.iter().all(|b| *b == 0)
+    }
+
+    pub fn open_slot(&mut self, bundle_index: u16) -> Result<()> {
+        require!(
+            bundle_index < POSITION_BUNDLE_SIZE && !self.is_open(bundle_index),
+            ErrorCode::InvalidBundleIndex
+        );
+        let index = usize::from(bundle_index);
+        self.position_bitmap[index / 8] |= 1 << (index % 8);
+        Ok(())
+    }
+
+    pub fn close_slot(&mut self, bundle_index: u16) -> Result<()> {
+        require!(self.is_open(bundle_index), ErrorCode::InvalidBundleIndex);
+        let index = usize::from(bundle_index);
+        self.position_bitmap[index / 8] &= !(1 << (index % 8));
+        Ok(())
+    }
+}
+
+/// Emitted when a bundled position is moved to a new range by `rebalance_bundle`
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct RebalanceBundledPositionEvent {
+    /// The bundle holding the position
+    pub position_bundle: Pubkey,
+    /// The slot of the position in the bundle
+    pub bundle_index: u16,
+    /// The pool of the position
+    pub pool_state: Pubkey,
+    /// The range the liquidity was removed from
+    pub tick_lower_index: i32,
+    pub tick_upper_index: i32,
+    /// The range the liquidity was added to
+    pub new_tick_lower_index: i32,
+    pub new_tick_upper_index: i32,
+    /// The liquidity removed from the old range
+    pub liquidity_removed: u128,
+    /// The liquidity added to the new range
+    pub liquidity_added: u128,
+    /// The principal withdrawn from the old range
+    pub amount_0: u64,
+    pub amount_1: u64,
+    /// The fees collected from the old range
+    pub fee_amount_0: u64,
+    pub fee_amount_1: u64,
+    /// The position identifier before and after the move, see `position_hash`
+    pub position_hash: [u8; 32],
+    pub new_position_hash: [u8; 32],
+}
+
+#[cfg(test)]
+mod position_bundle_test {
+    use super::*;
+
+    #[test]
+    fn slot_test() {
+        let mut bundle = PositionBundle::default();
+        assert!(bundle.is_empty());
+        bundle.open_slot(0).unwrap();
+        bundle.open_slot(9).unwrap();
+        bundle.open_slot(POSITION_BUNDLE_SIZE - 1).unwrap();
+        assert!(bundle.is_open(9));
+        assert!(!bundle.is_open(8));
+        assert_eq!(bundle.position_bitmap[1], 0b10);
+        assert_eq!(bundle.position_bitmap[31], 0b1000_0000);
+
+        // already open or out of range
+        assert!(bundle.open_slot(9).is_err());
+        assert!(bundle.open_slot(POSITION_BUNDLE_SIZE).is_err());
+        assert!(!bundle.is_open(POSITION_BUNDLE_SIZE));
+
+        bundle.close_slot(0).unwrap();
+        bundle.close_slot(9).unwrap();
+        assert!(bundle.close_slot(9).is_err());
+        assert!(!bundle.is_empty());
+        bundle.close_slot(POSITION_BUNDLE_SIZE - 1).unwrap();
+        assert!(bundle.is_empty());
+    }
+}
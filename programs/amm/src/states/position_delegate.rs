@@ -0,0 +1,91 @@
+use crate::error::ErrorCode;
+use anchor_lang::prelude::*;
+
+pub const POSITION_DELEGATE_SEED: &str = "position_delegate";
+
+/// Lets the operator collect the fees and rewards of the owner's positions
+pub const POSITION_DELEGATE_SCOPE_COLLECT: u8 = 1 << 0;
+/// Lets the operator decrease the liquidity of the owner's positions
+pub const POSITION_DELEGATE_SCOPE_DECREASE: u8 = 1 << 1;
+/// Lets the operator compound the fees of the owner's positions
+pub const POSITION_DELEGATE_SCOPE_COMPOUND: u8 = 1 << 2;
+pub const POSITION_DELEGATE_SCOPE_ALL: u8 = POSITION_DELEGATE_SCOPE_COLLECT
+    | POSITION_DELEGATE_SCOPE_DECREASE
+    | POSITION_DELEGATE_SCOPE_COMPOUND;
+
+/// An operator the owner lets manage every position nft it holds, within a scope. Unlike an
+/// spl token delegate it survives approvals of the nft, and it stops applying to a position as
+/// soon as the nft changes hands. The tokens an operator withdraws always go to the owner
+///
+/// PDA of `[POSITION_DELEGATE_SEED, owner, operator]`
+///
+#[account]
+#[derive(Default, Debug)]
+pub struct PositionDelegate {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The holder of the positions, paid the rent back on revocation
+    pub owner: Pubkey,
+    /// The keeper acting on the owner's positions
+    pub operator: Pubkey,
+    /// The `POSITION_DELEGATE_SCOPE_*` bits granted to the operator
+    pub scope: u8,
+    // Unused bytes for future upgrades.
+    pub padding: [u64; 4],
+}
+
+impl PositionDelegate {
+    pub const LEN: usize = 8 + 1 + 32 + 32 + 1 + 8 * 4;
+
+    pub fn check_scope(scope: u8) -> Result<()> {
+        require!(
+            scope & !POSITION_DELEGATE_SCOPE_ALL == 0,
+            ErrorCode::InvalidPositionDelegateScope
+        );
+        Ok(())
+    }
+
+    /// Whether `operator` may act on the positions of `owner` with all the bits of `scope`
+    pub fn allows(&self, owner: &Pubkey, operator: &Pubkey, scope: u8) -> bool {
+        self.owner == *owner && self.operator == *operator && self.scope & scope == scope
+    }
+}
+
+/// Emitted when an owner sets or revokes an operator
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct SetPositionOperatorEvent {
+    /// The holder of the positions
+    #[index]
+    pub owner: Pubkey,
+    /// The keeper acting on the owner's positions
+    pub operator: Pubkey,
+    /// The scope granted, 0 on revocation
+    pub scope: u8,
+}
+
+#[cfg(test)]
+mod position_delegate_test {
+    use super::*;
+
+    #[test]
+    fn allows_test() {
+        let (owner, operator) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let position_delegate = PositionDelegate {
+            owner,
+            operator,
+            scope: POSITION_DELEGATE_SCOPE_COLLECT | POSITION_DELEGATE_SCOPE_COMPOUND,
+            ..Default::default()
+        };
+        assert!(position_delegate.allows(&owner, &operator, POSITION_DELEGATE_SCOPE_COLLECT));
+        assert!(position_delegate.allows(&owner, &operator, POSITION_DELEGATE_SCOPE_COMPOUND));
+        assert!(!position_delegate.allows(&owner, &operator, POSITION_DELEGATE_SCOPE_DECREASE));
+        assert!(!position_delegate.allows(&owner, &operator, POSITION_DELEGATE_SCOPE_ALL));
+        // the delegation is bound to the owner and the operator
+        assert!(!position_delegate.allows(&operator, &operator, POSITION_DELEGATE_SCOPE_COLLECT));
+        assert!(!position_delegate.allows(&owner, &owner, POSITION_DELEGATE_SCOPE_COLLECT));
+
+        assert!(PositionDelegate::check_scope(POSITION_DELEGATE_SCOPE_ALL).is_ok());
+        assert!(PositionDelegate::check_scope(1 << 3).is_err());
+    }
+}
@@ -0,0 +1,91 @@
+use anchor_lang::prelude::*;
+
+pub const POSITION_LEASE_SEED: &str = "position_lease";
+pub const POSITION_LEASE_VAULT_SEED: &str = "position_lease_vault";
+
+/// Escrow record of a position NFT leased to a lessee, who can collect the fees and rewards the
+/// position earns from the lease start until `expires_at`. The owner keeps the principal and
+/// takes the nft back once the lease expires
+///
+/// PDA of `[POSITION_LEASE_SEED, position_nft_mint]`
+///
+#[account]
+#[derive(Default, Debug)]
+pub struct PositionLeaseState {
+    /// Bump to identify PDA
+    pub bump: [u8; 1],
+    /// The owner of the position, who gets the nft back after `expires_at`
+    pub owner: Pubkey,
+    /// The lessee, who can collect fees and rewards while the lease is active
+    pub lessee: Pubkey,
+    /// Mint address of the leased position nft
+    pub position_nft_mint: Pubkey,
+    /// The personal position bound to the nft
+    pub personal_position: Pubkey,
+    /// The escrow token account holding the position nft
+    pub leased_nft_account: Pubkey,
+    /// The timestamp the lessee's rights end at
+    pub expires_at: u64,
+    // Unused bytes for future upgrades.
+    pub padding: [u64; 4],
+}
+
+impl PositionLeaseState {
+    pub const LEN: usize = 8 + 1 + 32 * 5 + 8 + 8 * 4;
+
+    pub fn seeds(&self) -> [&[u8]; 3] {
+        [
+            POSITION_LEASE_SEED.as_bytes(),
+            self.position_nft_mint.as_ref(),
+            self.bump.as_ref(),
+        ]
+    }
+
+    pub fn is_active(&self, block_timestamp: u64) -> bool {
+        block_timestamp < self.expires_at
+    }
+}
+
+/// Emitted when a position is leased
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct LeasePositionEvent {
+    /// The leased position nft mint
+    #[index]
+    pub position_nft_mint: Pubkey,
+    /// The owner of the leased position
+    pub owner: Pubkey,
+    /// The lessee collecting the position's fees and rewards
+    pub lessee: Pubkey,
+    /// The timestamp the lease expires at
+    pub expires_at: u64,
+}
+
+/// Emitted when an expired lease is ended and the nft is returned to the owner
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct EndPositionLeaseEvent {
+    /// The position nft mint
+    #[index]
+    pub position_nft_mint: Pubkey,
+    /// The owner who receives the position nft
+    pub owner: Pubkey,
+    /// The lessee of the ended lease
+    pub lessee: Pubkey,
+}
+
+#[cfg(test)]
+mod position_lease_test {
+    use super::*;
+
+    #[test]
+    fn is_active_test() {
+        let lease = PositionLeaseState {
+            expires_at: 100,
+            ..Default::default()
+        };
+        assert!(lease.is_active(99));
+        assert!(!lease.is_active(100));
+        assert!(!lease.is_active(101));
+    }
+}
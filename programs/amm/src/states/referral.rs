@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+
+pub const REFERRAL_SEED: &str = "referral";
+
+/// The trade fees a referrer earned on the swaps it brought to a pool, the tokens stay in the
+/// pool vaults until `collect_referral_fee`
+///
+/// PDA of `[REFERRAL_SEED, pool_state, referrer]`
+///
+#[account]
+#[derive(Default, Debug)]
+pub struct ReferralAccount {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The pool the referred swaps are made in
+    pub pool_id: Pubkey,
+    /// The only one able to collect the fees
+    pub referrer: Pubkey,
+    /// The amounts of token_0 and token_1 that are owed to the referrer
+    pub fees_token_0: u64,
+    pub fees_token_1: u64,
+    // Unused bytes for future upgrades.
+    pub padding: [u64; 4],
+}
+
+impl ReferralAccount {
+    pub const LEN: usize = 8 + 1 + 32 + 32 + 8 + 8 + 8 * 4;
+
+    pub fn initialize(&mut self, bump: u8, pool_id: Pubkey, referrer: Pubkey) {
+        self.bump = bump;
+        self.pool_id = pool_id;
+        self.referrer = referrer;
+        self.fees_token_0 = 0;
+        self.fees_token_1 = 0;
+        self.padding = [0; 4];
+    }
+
+    /// Credits the referral cut of a swap, taken in the swap's input token
+    pub fn credit(&mut self, zero_for_one: bool, referral_fee: u64) {
+        if zero_for_one {
+            self.fees_token_0 = self.fees_token_0.checked_add(referral_fee).unwrap();
+        } else {
+            self.fees_token_1 = self.fees_token_1.checked_add(referral_fee).unwrap();
+        }
+    }
+}
+
+#[cfg(test)]
+mod referral_test {
+    use super::*;
+
+    #[test]
+    fn credit_test() {
+        let mut referral_account = ReferralAccount::default();
+        referral_account.credit(true, 10);
+        referral_account.credit(false, 3);
+        referral_account.credit(true, 0);
+        assert_eq!(referral_account.fees_token_0, 10);
+        assert_eq!(referral_account.fees_token_1, 3);
+    }
+}
@@ -0,0 +1,134 @@
+use super::ObservationState;
+use crate::error::ErrorCode;
+use anchor_lang::prelude::*;
+
+pub const STOP_ORDER_SEED: &str = "stop_order";
+/// Upper bound of the lamports an owner can escrow to compensate the settling keeper
+pub const MAX_STOP_ORDER_KEEPER_REWARD: u64 = 10_000_000;
+
+/// A stop-loss on a position: once the pool's time weighted average tick crosses `stop_tick`,
+/// anyone can settle it, removing all liquidity to the owner and earning `keeper_reward` lamports
+///
+/// PDA of `[STOP_ORDER_SEED, personal_position]`
+///
+#[account]
+#[derive(Default, Debug)]
+pub struct StopOrderState {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The owner of the position, who receives the withdrawn tokens
+    pub owner: Pubkey,
+    /// The personal position to close when triggered
+    pub personal_position: Pubkey,
+    /// The tick that triggers the order
+    pub stop_tick: i32,
+    /// Triggers when the pool tick is at or below `stop_tick` if true,
+    /// at or above otherwise
+    pub trigger_below: bool,
+    /// Minimum amount of token_0 to withdraw on settlement
+    pub amount_0_min: u64,
+    /// Minimum amount of token_1 to withdraw on settlement
+    pub amount_1_min: u64,
+    /// Lamports escrowed in this account paid to the settling keeper
+    pub keeper_reward: u64,
+    /// The seconds of pool history the trigger tick is averaged over
+    pub twap_window: u32,
+    // Unused bytes for future upgrades.
+    pub padding: [u64; 4],
+}
+
+impl StopOrderState {
+    pub const LEN: usize = 8 + 1 + 32 * 2 + 4 + 1 + 8 * 3 + 4 + 8 * 4;
+
+    pub fn is_triggered(&self, tick_current: i32) -> bool {
+        if self.trigger_below {
+            tick_current <= self.stop_tick
+        } else {
+            tick_current >= self.stop_tick
+        }
+    }
+
+    /// Returns the pool's time weighted average tick over `twap_window` if it has crossed
+    /// `stop_tick`, so moving the spot price within a transaction can't trigger the order
+    pub fn triggered_tick(
+        &self,
+        observation_state: &ObservationState,
+        block_timestamp: u32,
+    ) -> Result<i32> {
+        let twap_tick = observation_state
+            .twap_tick(block_timestamp, self.twap_window)
+            .ok_or(ErrorCode::ObservationNotEnough)?;
+        require!(
+            self.is_triggered(twap_tick),
+            ErrorCode::StopOrderNotTriggered
+        );
+        Ok(twap_tick)
+    }
+}
+
+/// Emitted when a stop order is settled
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct SettleStopOrderEvent {
+    /// The settled position
+    #[index]
+    pub personal_position: Pubkey,
+    /// The owner of the position
+    pub owner: Pubkey,
+    /// The keeper who settled the order
+    pub keeper: Pubkey,
+    /// The time weighted average pool tick that triggered the order
+    pub twap_tick: i32,
+    /// The liquidity removed from the position
+    pub liquidity: u128,
+    /// The lamports paid to the keeper
+    pub keeper_reward: u64,
+}
+
+#[cfg(test)]
+mod stop_order_test {
+    use super::*;
+
+    #[test]
+    fn is_triggered_test() {
+        let mut stop_order = StopOrderState {
+            stop_tick: -100,
+            trigger_below: true,
+            ..Default::default()
+        };
+        assert!(!stop_order.is_triggered(-99));
+        assert!(stop_order.is_triggered(-100));
+        assert!(stop_order.is_triggered(-101));
+
+        stop_order.trigger_below = false;
+        assert!(stop_order.is_triggered(-99));
+        assert!(stop_order.is_triggered(-100));
+        assert!(!stop_order.is_triggered(-101));
+    }
+
+    #[test]
+    fn triggered_tick_test() {
+        let stop_order = StopOrderState {
+            stop_tick: -100,
+            trigger_below: true,
+            twap_window: 120,
+            ..Default::default()
+        };
+        let mut observation_state = ObservationState::default();
+        observation_state.update(1000, 0);
+        observation_state.update(1060, 0);
+        // the window isn't covered yet
+        assert!(stop_order.triggered_tick(&observation_state, 1060).is_err());
+
+        // tick -150 for the last 60s crosses the stop tick, the average over the window doesn't
+        observation_state.update(1120, -150);
+        assert!(stop_order.is_triggered(-150));
+        assert!(stop_order.triggered_tick(&observation_state, 1120).is_err());
+
+        observation_state.update(1180, -150);
+        assert_eq!(
+            stop_order.triggered_tick(&observation_state, 1180).unwrap(),
+            -150
+        );
+    }
+}
@@ -0,0 +1,153 @@
+use crate::error::ErrorCode;
+use crate::libraries::tick_math;
+use anchor_lang::prelude::*;
+
+pub const STRATEGY_PRESET_SEED: &str = "strategy_preset";
+/// The max number of positions opened by one ladder, also the max number of preset weights
+pub const LADDER_MAX_RUNGS: usize = 8;
+
+/// Bots holding a position created from the preset should compound its fees
+pub const STRATEGY_FLAG_AUTO_COMPOUND: u8 = 1 << 0;
+
+/// A named range recipe: a band of `tick_width` ticks centered on the pool's current tick,
+/// split into one rung per weight
+///
+/// PDA of `[STRATEGY_PRESET_SEED, authority, name]`
+///
+#[account]
+#[derive(Default, Debug)]
+pub struct StrategyPreset {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The creator of the preset, who can close it
+    pub authority: Pubkey,
+    /// The preset name, utf8 padded with zeros
+    pub name: [u8; 32],
+    /// The total width in ticks of the band around the current tick
+    pub tick_width: i32,
+    /// The number of rungs in use in `weights`
+    pub weight_count: u8,
+    /// The liquidity share of each rung, from the lowest range to the highest
+    pub weights: [u32; LADDER_MAX_RUNGS],
+    /// Bit flags for off-chain executors, see `STRATEGY_FLAG_AUTO_COMPOUND`
+    pub flags: u8,
+    // Unused bytes for future upgrades.
+    pub padding: [u64; 8],
+}
+
+impl StrategyPreset {
+    pub const LEN: usize = 8 + 1 + 32 + 32 + 4 + 1 + 4 * LADDER_MAX_RUNGS + 1 + 8 * 8;
+
+    pub fn initialize(
+        &mut self,
+        bump: u8,
+        authority: Pubkey,
+        name: [u8; 32],
+        tick_width: i32,
+        weights: &[u32],
+        flags: u8,
+    ) -> Result<()> {
+        require!(
+            !weights.is_empty()
+                && weights.len() <= LADDER_MAX_RUNGS
+                && weights.iter().all(|w| *w > 0)
+                && tick_width > 0
+                && tick_width <= tick_math::MAX_TICK,
+            ErrorCode::InvalidLadderParam
+        );
+        self.bump = bump;
+        self.authority = authority;
+        self.name = name;
+        self.tick_width = tick_width;
+        self.weight_count = weights.len() as u8;
+        self.weights = [0; LADDER_MAX_RUNGS];
+        self.weights[..weights.len()].copy_from_slice(weights);
+        self.flags = flags;
+        Ok(())
+    }
+
+    pub fn weights(&self) -> Vec<u32> {
+        self.weights[..usize::from(self.weight_count)].to_vec()
+    }
+
+    pub fn is_auto_compound(&self) -> bool {
+        self.flags & STRATEGY_FLAG_AUTO_COMPOUND != 0
+    }
+
+    /// Returns the lowest tick and the per rung width of the ladder centered on `tick_current`.
+    /// The rung width is rounded down to a multiple of `tick_spacing`, and the lower tick to the
+    /// initializable tick at or below the band's lower edge.
+    pub fn ladder_range(&self, tick_current: i32, tick_spacing: u16) -> Result<(i32, i32)> {
+        let tick_spacing = i32::from(tick_spacing);
+        let rungs = i32::from(self.weight_count);
+        let rung_tick_width = self.tick_width / rungs / tick_spacing * tick_spacing;
+        require_gt!(rung_tick_width, 0, ErrorCode::InvalidLadderParam);
+
+        let band_lower = tick_current - rung_tick_width * rungs / 2;
+        let tick_lower_index = band_lower.div_euclid(tick_spacing) * tick_spacing;
+        require!(
+            tick_lower_index >= tick_math::MIN_TICK
+                && tick_lower_index + rung_tick_width * rungs <= tick_math::MAX_TICK,
+            ErrorCode::InvalidLadderParam
+        );
+        Ok((tick_lower_index, rung_tick_width))
+    }
+}
+
+#[cfg(test)]
+mod strategy_preset_test {
+    use super::*;
+
+    fn preset(tick_width: i32, weights: &[u32]) -> StrategyPreset {
+        let mut preset = StrategyPreset::default();
+        preset
+            .initialize(255, Pubkey::default(), [0; 32], tick_width, weights, 0)
+            .unwrap();
+        preset
+    }
+
+    #[test]
+    fn ladder_range_test() {
+        // 5 rungs of 200 ticks centered on tick 0
+        let preset = preset(1000, &[1, 2, 4, 2, 1]);
+        assert_eq!(preset.weights(), vec![1, 2, 4, 2, 1]);
+        assert_eq!(preset.ladder_range(0, 10).unwrap(), (-500, 200));
+        // the lower tick rounds down to the tick spacing
+        assert_eq!(preset.ladder_range(-3, 10).unwrap(), (-510, 200));
+        // the rung width rounds down to the tick spacing
+        assert_eq!(preset.ladder_range(0, 60).unwrap(), (-480, 180));
+        // band narrower than a tick spacing per rung
+        assert!(preset.ladder_range(0, 256).is_err());
+        // band past the max tick
+        assert!(preset.ladder_range(tick_math::MAX_TICK - 100, 10).is_err());
+    }
+
+    #[test]
+    fn initialize_test() {
+        let mut preset = StrategyPreset::default();
+        assert!(preset
+            .initialize(0, Pubkey::default(), [0; 32], 100, &[], 0)
+            .is_err());
+        assert!(preset
+            .initialize(0, Pubkey::default(), [0; 32], 100, &[1; 9], 0)
+            .is_err());
+        assert!(preset
+            .initialize(0, Pubkey::default(), [0; 32], 100, &[1, 0], 0)
+            .is_err());
+        assert!(preset
+            .initialize(0, Pubkey::default(), [0; 32], 0, &[1], 0)
+            .is_err());
+        preset
+            .initialize(
+                0,
+                Pubkey::default(),
+                [0; 32],
+                100,
+                &[1; 8],
+                STRATEGY_FLAG_AUTO_COMPOUND,
+            )
+            .unwrap();
+        assert!(preset.is_auto_compound());
+        assert_eq!(preset.weights().len(), LADDER_MAX_RUNGS);
+    }
+}
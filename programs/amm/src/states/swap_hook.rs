@@ -0,0 +1,148 @@
+use crate::error::ErrorCode;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    hash::hash,
+    instruction::{AccountMeta, Instruction},
+    program::invoke,
+};
+
+/// The most accounts a swap can pass to its pool's hook, besides the hook program
+pub const MAX_SWAP_HOOK_ACCOUNTS: usize = 4;
+
+/// The hook instruction called before the swap, it can fail the swap
+pub const BEFORE_SWAP_HOOK: &str = "before_swap";
+/// The hook instruction called once the swap and its transfers are done
+pub const AFTER_SWAP_HOOK: &str = "after_swap";
+
+/// The arguments of the hook's `before_swap` instruction
+#[derive(Copy, Clone, AnchorSerialize, AnchorDeserialize, Debug, PartialEq)]
+pub struct BeforeSwapArgs {
+    pub zero_for_one: bool,
+    pub is_base_input: bool,
+    /// The amount of the swap as specified by the trader
+    pub amount_specified: u64,
+    /// The Q64.64 sqrt price limit of the swap, 0 for none
+    pub sqrt_price_limit_x64: u128,
+}
+
+/// The arguments of the hook's `after_swap` instruction
+#[derive(Copy, Clone, AnchorSerialize, AnchorDeserialize, Debug, PartialEq)]
+pub struct AfterSwapArgs {
+    pub zero_for_one: bool,
+    pub is_base_input: bool,
+    /// The amounts of token_0 and token_1 swapped, before transfer fees
+    pub amount_0: u64,
+    pub amount_1: u64,
+    /// The pool's sqrt price and tick after the swap
+    pub sqrt_price_x64: u128,
+    pub tick: i32,
+}
+
+/// Splits a swap's remaining accounts into its own and the hook's: the hook program and the
+/// accounts after it. Swaps of a pool with a hook must pass it
+pub fn split_swap_hook_accounts<'a, 'info, T: AsRef<AccountInfo<'info>>>(
+    remaining_accounts: &'a [T],
+    swap_hook_program: Option<Pubkey>,
+) -> Result<(&'a [T], &'a [T])> {
+    let swap_hook_program = match swap_hook_program {
+        Some(swap_hook_program) => swap_hook_program,
+        None => return Ok((remaining_accounts, &[])),
+    };
+    let start = remaining_accounts
+        .iter()
+        .position(|account_info| account_info.as_ref().key() == swap_hook_program)
+        .ok_or(ErrorCode::InvalidSwapHookAccounts)?;
+    let (swap_accounts, hook_accounts) = remaining_accounts.split_at(start);
+    require_gte!(
+        MAX_SWAP_HOOK_ACCOUNTS + 1,
+        hook_accounts.len(),
+        ErrorCode::InvalidSwapHookAccounts
+    );
+    Ok((swap_accounts, hook_accounts))
+}
+
+/// Calls `instruction` of the hook with the Anchor discriminator and `args`, passing the pool,
+/// the trader and the hook accounts. None of them sign for the hook, and the pool and trader
+/// are read-only. Does nothing without hook accounts.
+///
+/// The hook has no compute unit ceiling of its own: the runtime can't cap a CPI's budget, and
+/// `sol_remaining_compute_units` only comes with solana-program 1.17, so an expensive hook
+/// can't be told apart from an expensive swap. The hook spends the trader's transaction budget,
+/// which bounds it to failing the swap, and only the program allowed by the pool's amm config
+/// can be set as hook
+pub fn invoke_swap_hook<'info, T: AsRef<AccountInfo<'info>>>(
+    hook_accounts: &[T],
+    pool_state: &AccountInfo<'info>,
+    trader: &AccountInfo<'info>,
+    instruction: &str,
+    args: impl AnchorSerialize,
+) -> Result<()> {
+    let (swap_hook_program, accounts) = match hook_accounts.split_first() {
+        Some((swap_hook_program, accounts)) => (swap_hook_program.as_ref(), accounts),
+        None => return Ok(()),
+    };
+    let mut data = hash(format!("global:{}", instruction).as_bytes()).to_bytes()[..8].to_vec();
+    args.serialize(&mut data)?;
+
+    let mut account_metas = vec![
+        AccountMeta::new_readonly(pool_state.key(), false),
+        AccountMeta::new_readonly(trader.key(), false),
+    ];
+    let mut account_infos = vec![pool_state.clone(), trader.clone()];
+    for account_info in accounts {
+        let account_info = account_info.as_ref();
+        account_metas.push(if account_info.is_writable {
+            AccountMeta::new(account_info.key(), false)
+        } else {
+            AccountMeta::new_readonly(account_info.key(), false)
+        });
+        account_infos.push(account_info.clone());
+    }
+    account_infos.push(swap_hook_program.clone());
+    invoke(
+        &Instruction {
+            program_id: swap_hook_program.key(),
+            accounts: account_metas,
+            data,
+        },
+        &account_infos,
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod swap_hook_test {
+    use super::*;
+
+    #[test]
+    fn split_swap_hook_accounts_test() {
+        let keys: Vec<Pubkey> = (0..7).map(|_| Pubkey::new_unique()).collect();
+        let owner = Pubkey::new_unique();
+        let mut lamports = vec![0u64; keys.len()];
+        let mut data = vec![vec![0u8; 0]; keys.len()];
+        let account_infos: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .map(|((key, lamports), data)| {
+                AccountInfo::new(key, false, false, lamports, data, &owner, false, 0)
+            })
+            .collect();
+
+        let (swap_accounts, hook_accounts) =
+            split_swap_hook_accounts(&account_infos, None).unwrap();
+        assert_eq!(swap_accounts.len(), 7);
+        assert!(hook_accounts.is_empty());
+
+        let (swap_accounts, hook_accounts) =
+            split_swap_hook_accounts(&account_infos, Some(keys[2])).unwrap();
+        assert_eq!(swap_accounts.len(), 2);
+        assert_eq!(hook_accounts.len(), 5);
+        assert_eq!(hook_accounts[0].key(), keys[2]);
+
+        // the hook takes at most MAX_SWAP_HOOK_ACCOUNTS accounts
+        assert!(split_swap_hook_accounts(&account_infos, Some(keys[1])).is_err());
+        // a pool with a hook can't be swapped without it
+        assert!(split_swap_hook_accounts(&account_infos, Some(owner)).is_err());
+    }
+}
@@ -15,6 +15,7 @@ pub const TICK_ARRAY_SIZE: i32 = 60;
 // pub const MAX_TICK_ARRAY_START_INDEX: i32 = 306600;
 #[account(zero_copy(unsafe))]
 #[repr(C, packed)]
+#[cfg_attr(feature = "client", derive(Debug))]
 pub struct TickArrayState {
     pub pool_id: Pubkey,
     pub start_tick_index: i32,
@@ -130,6 +131,12 @@ impl TickArrayState {
         Ok(&mut self.ticks[offset_in_array])
     }
 
+    /// A copy of the tick state at `tick_index`, which must be within the array
+    pub fn get_tick_state(&self, tick_index: i32, tick_spacing: u16) -> Result<TickState> {
+        let offset_in_array = self.get_tick_offset_in_array(tick_index, tick_spacing)?;
+        Ok(self.ticks[offset_in_array])
+    }
+
     pub fn update_tick_state(
         &mut self,
         tick_index: i32,
@@ -644,6 +651,29 @@ pub mod tick_array_test {
             );
         }
 
+        #[test]
+        fn get_tick_state_test() {
+            let tick_spacing = 10;
+            let tick_array_ref = build_tick_array_with_tick_states(
+                Pubkey::default(),
+                0,
+                tick_spacing,
+                vec![*build_tick(100, 20, -20).borrow()],
+            );
+            let tick_state = tick_array_ref
+                .borrow()
+                .get_tick_state(100, tick_spacing)
+                .unwrap();
+            assert_eq!(
+                (tick_state.liquidity_net, tick_state.liquidity_gross),
+                (-20, 20)
+            );
+            assert!(tick_array_ref
+                .borrow()
+                .get_tick_state(-100, tick_spacing)
+                .is_err());
+        }
+
         #[test]
         fn get_tick_offset_in_array_test() {
             let tick_spacing = 4;
@@ -0,0 +1,130 @@
+use anchor_lang::prelude::*;
+
+pub const USER_STATS_SEED: &str = "user_stats";
+/// The highest volume rebate tier, reached after `MAX_VOLUME_REBATE_TIER` times the config's
+/// `volume_rebate_min_epochs` consecutive qualifying epochs
+pub const MAX_VOLUME_REBATE_TIER: u32 = 3;
+
+/// A trader's volume in a pool, per epoch, and the fee rebates it earned. A rebate tier is only
+/// unlocked by consecutive past epochs each above the pool's `volume_rebate_threshold`, so a
+/// burst of wash trades within one epoch earns nothing by itself
+///
+/// PDA of `[USER_STATS_SEED, pool_state, owner]`
+///
+#[account]
+#[derive(Default, Debug)]
+pub struct UserStats {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The pool the volume is traded in
+    pub pool_id: Pubkey,
+    /// The trader, the only one whose swaps count and who can collect the rebates
+    pub owner: Pubkey,
+    /// The epoch `epoch_volume` is accumulated over
+    pub epoch: u64,
+    /// The token_0 volume swapped in `epoch`
+    pub epoch_volume: u64,
+    /// The number of consecutive qualifying epochs ending right before `epoch`
+    pub qualifying_epochs: u32,
+    pub padding_u32: u32,
+    /// The amounts of token_0 and token_1 rebated to the trader and not yet collected
+    pub rebates_token_0: u64,
+    pub rebates_token_1: u64,
+    // Unused bytes for future upgrades.
+    pub padding: [u64; 4],
+}
+
+impl UserStats {
+    pub const LEN: usize = 8 + 1 + 32 + 32 + 8 + 8 + 4 + 4 + 8 + 8 + 8 * 4;
+
+    pub fn initialize(&mut self, bump: u8, pool_id: Pubkey, owner: Pubkey, epoch: u64) {
+        self.bump = bump;
+        self.pool_id = pool_id;
+        self.owner = owner;
+        self.epoch = epoch;
+        self.epoch_volume = 0;
+        self.qualifying_epochs = 0;
+        self.padding_u32 = 0;
+        self.rebates_token_0 = 0;
+        self.rebates_token_1 = 0;
+        self.padding = [0; 4];
+    }
+
+    /// Closes the recorded epoch if `epoch` is a later one. The streak grows if it was the
+    /// previous epoch and reached `threshold`, and is reset otherwise
+    pub fn roll(&mut self, epoch: u64, threshold: u64) {
+        if epoch <= self.epoch {
+            return;
+        }
+        let qualified = threshold > 0 && self.epoch_volume >= threshold;
+        self.qualifying_epochs = if qualified && epoch == self.epoch + 1 {
+            self.qualifying_epochs.saturating_add(1)
+        } else {
+            0
+        };
+        self.epoch = epoch;
+        self.epoch_volume = 0;
+    }
+
+    pub fn record_volume(&mut self, volume: u64) {
+        self.epoch_volume = self.epoch_volume.saturating_add(volume);
+    }
+
+    /// The rebate tier reached, one per `min_epochs` consecutive qualifying epochs
+    pub fn tier(&self, min_epochs: u32) -> u32 {
+        if min_epochs == 0 {
+            return 0;
+        }
+        (self.qualifying_epochs / min_epochs).min(MAX_VOLUME_REBATE_TIER)
+    }
+
+    /// Credits a rebate, taken from the protocol fee in the swap's input token
+    pub fn credit(&mut self, zero_for_one: bool, rebate: u64) {
+        if zero_for_one {
+            self.rebates_token_0 = self.rebates_token_0.checked_add(rebate).unwrap();
+        } else {
+            self.rebates_token_1 = self.rebates_token_1.checked_add(rebate).unwrap();
+        }
+    }
+}
+
+#[cfg(test)]
+mod user_stats_test {
+    use super::*;
+
+    #[test]
+    fn roll_test() {
+        let mut user_stats = UserStats::default();
+        user_stats.initialize(0, Pubkey::default(), Pubkey::default(), 10);
+
+        // a single epoch burst unlocks nothing until the epoch is over
+        user_stats.record_volume(1_000_000);
+        assert_eq!(user_stats.tier(2), 0);
+        user_stats.roll(11, 100);
+        assert_eq!(user_stats.qualifying_epochs, 1);
+        assert_eq!(user_stats.tier(2), 0);
+
+        // rolling within the same epoch keeps the volume
+        user_stats.record_volume(60);
+        user_stats.roll(11, 100);
+        user_stats.record_volume(40);
+        user_stats.roll(12, 100);
+        assert_eq!(user_stats.qualifying_epochs, 2);
+        assert_eq!(user_stats.tier(2), 1);
+
+        // skipping an epoch breaks the streak, even after a qualifying one
+        user_stats.record_volume(100);
+        user_stats.roll(14, 100);
+        assert_eq!(user_stats.qualifying_epochs, 0);
+
+        // tiers are capped, and disabled without min epochs
+        user_stats.qualifying_epochs = 100;
+        assert_eq!(user_stats.tier(2), MAX_VOLUME_REBATE_TIER);
+        assert_eq!(user_stats.tier(0), 0);
+
+        // a threshold of 0 disables qualification
+        user_stats.record_volume(100);
+        user_stats.roll(15, 0);
+        assert_eq!(user_stats.qualifying_epochs, 0);
+    }
+}
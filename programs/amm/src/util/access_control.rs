@@ -0,0 +1,399 @@
+use crate::error::ErrorCode;
+use crate::states::{PoolState, PositionDelegate};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::StateWithExtensions,
+    state::{Account as TokenAccountState, Mint as MintState},
+};
+
+/// Checks `authority` may act on the position held by the token account: the account must
+/// hold exactly the position nft, must not be frozen, and `authority` must be either its
+/// owner or a delegate approved for the nft. Works for both spl token and token 2022 accounts
+pub fn is_authorized_for_token(
+    authority: &Pubkey,
+    token_account: &AccountInfo,
+    position_nft_mint: &Pubkey,
+) -> bool {
+    if *token_account.owner != anchor_spl::token::ID && *token_account.owner != spl_token_2022::ID {
+        return false;
+    }
+    let Ok(data) = token_account.try_borrow_data() else {
+        return false;
+    };
+    match StateWithExtensions::<TokenAccountState>::unpack(&data) {
+        Ok(account) => is_authorized_for_token_state(authority, &account.base, position_nft_mint),
+        Err(_) => false,
+    }
+}
+
+fn is_authorized_for_token_state(
+    authority: &Pubkey,
+    account: &TokenAccountState,
+    position_nft_mint: &Pubkey,
+) -> bool {
+    if account.mint != *position_nft_mint || account.amount != 1 || account.is_frozen() {
+        return false;
+    }
+    if account.owner == *authority {
+        return true;
+    }
+    // a revoked delegate is cleared to None together with delegated_amount
+    match account.delegate {
+        COption::Some(delegate) => delegate == *authority && account.delegated_amount >= 1,
+        COption::None => false,
+    }
+}
+
+/// Checks `authority` may act on the position held by the token account as in
+/// `is_authorized_for_token`, or as an operator the holder granted `scope` to in
+/// `position_delegate`
+pub fn is_authorized_for_position(
+    authority: &Pubkey,
+    token_account: &AccountInfo,
+    position_nft_mint: &Pubkey,
+    position_delegate: Option<&PositionDelegate>,
+    scope: u8,
+) -> bool {
+    if *token_account.owner != anchor_spl::token::ID && *token_account.owner != spl_token_2022::ID {
+        return false;
+    }
+    let Ok(data) = token_account.try_borrow_data() else {
+        return false;
+    };
+    match StateWithExtensions::<TokenAccountState>::unpack(&data) {
+        Ok(account) => is_authorized_for_position_state(
+            authority,
+            &account.base,
+            position_nft_mint,
+            position_delegate,
+            scope,
+        ),
+        Err(_) => false,
+    }
+}
+
+fn is_authorized_for_position_state(
+    authority: &Pubkey,
+    account: &TokenAccountState,
+    position_nft_mint: &Pubkey,
+    position_delegate: Option<&PositionDelegate>,
+    scope: u8,
+) -> bool {
+    if is_authorized_for_token_state(authority, account, position_nft_mint) {
+        return true;
+    }
+    // the nft must still be held, unfrozen, for an operator too
+    if account.mint != *position_nft_mint || account.amount != 1 || account.is_frozen() {
+        return false;
+    }
+    position_delegate
+        .is_some_and(|position_delegate| position_delegate.allows(&account.owner, authority, scope))
+}
+
+/// Checks every token account among `accounts` that is not one of the pool's vaults belongs to
+/// `holder`, so an operator can move a position's tokens out of the pool but never take them
+pub fn check_payouts_to_holder<'a, 'info: 'a>(
+    holder: &Pubkey,
+    pool_state_key: &Pubkey,
+    accounts: impl IntoIterator<Item = &'a AccountInfo<'info>>,
+) -> Result<()> {
+    for account_info in accounts {
+        if *account_info.owner != anchor_spl::token::ID && *account_info.owner != spl_token_2022::ID
+        {
+            continue;
+        }
+        let data = account_info.try_borrow_data()?;
+        // mints fail to unpack as token accounts
+        let Ok(account) = StateWithExtensions::<TokenAccountState>::unpack(&data) else {
+            continue;
+        };
+        require!(
+            account.base.owner == *holder || account.base.owner == *pool_state_key,
+            ErrorCode::NotApproved
+        );
+    }
+    Ok(())
+}
+
+/// Checks the position nft mint can never back more than one position:
+/// supply is 1, decimals is 0, and there is neither mint authority nor freeze authority
+pub fn check_position_nft_mint(position_nft_mint: &AccountInfo) -> Result<()> {
+    let data = position_nft_mint.try_borrow_data()?;
+    let mint = StateWithExtensions::<MintState>::unpack(&data)?;
+    require!(
+        mint.base.supply == 1
+            && mint.base.decimals == 0
+            && mint.base.mint_authority.is_none()
+            && mint.base.freeze_authority.is_none(),
+        ErrorCode::InvalidPositionNftMint
+    );
+    Ok(())
+}
+
+/// Checks the vault is one of the pool's token or reward vaults, its authority is the pool,
+/// and it carries neither a delegate nor a close authority that could move funds
+/// out of the pool
+pub fn verify_vault(
+    pool_state_key: &Pubkey,
+    pool_state: &PoolState,
+    vault: &AccountInfo,
+) -> Result<()> {
+    let vault_key = vault.key();
+    let reward_infos = pool_state.reward_infos;
+    require!(
+        vault_key == pool_state.token_vault_0
+            || vault_key == pool_state.token_vault_1
+            || reward_infos.iter().any(
+                |reward_info| reward_info.initialized() && reward_info.token_vault == vault_key
+            ),
+        ErrorCode::InvalidPoolVault
+    );
+    require!(
+        *vault.owner == anchor_spl::token::ID || *vault.owner == spl_token_2022::ID,
+        ErrorCode::InvalidPoolVault
+    );
+    let data = vault.try_borrow_data()?;
+    let account = StateWithExtensions::<TokenAccountState>::unpack(&data)?;
+    require!(
+        account.base.owner == *pool_state_key
+            && account.base.delegate.is_none()
+            && account.base.close_authority.is_none(),
+        ErrorCode::InvalidPoolVault
+    );
+    Ok(())
+}
+
+/// Checks both token vaults of the pool, see `verify_vault`
+pub fn verify_vaults(
+    pool_state_key: &Pubkey,
+    pool_state: &PoolState,
+    token_vault_0: &AccountInfo,
+    token_vault_1: &AccountInfo,
+) -> Result<()> {
+    require_keys_eq!(
+        token_vault_0.key(),
+        pool_state.token_vault_0,
+        ErrorCode::InvalidPoolVault
+    );
+    require_keys_eq!(
+        token_vault_1.key(),
+        pool_state.token_vault_1,
+        ErrorCode::InvalidPoolVault
+    );
+    verify_vault(pool_state_key, pool_state, token_vault_0)?;
+    verify_vault(pool_state_key, pool_state, token_vault_1)
+}
+
+/// Returns the program this instruction is invoked from through CPI, if that program is the
+/// top level instruction being executed. The instructions sysvar only holds top level
+/// instructions, so None is returned for deeper CPI where the direct caller is unknown
+pub fn get_cpi_caller(instructions_sysvar: &AccountInfo) -> Result<Option<Pubkey>> {
+    if instruction::get_stack_height() != instruction::TRANSACTION_LEVEL_STACK_HEIGHT + 1 {
+        return Ok(None);
+    }
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+    let current_instruction =
+        load_instruction_at_checked(usize::from(current_index), instructions_sysvar)?;
+    if current_instruction.program_id == crate::id() {
+        Ok(None)
+    } else {
+        Ok(Some(current_instruction.program_id))
+    }
+}
+
+/// Rejects the instruction unless it is a top level instruction of the transaction, so a
+/// compromised program holding an admin signer can't drive admin instructions through CPI.
+/// Builds with the `allow-admin-cpi` feature skip the check. It is a build switch rather than
+/// a config flag as most admin instructions take no account such a flag could be read from
+pub fn require_not_cpi() -> Result<()> {
+    #[cfg(not(feature = "allow-admin-cpi"))]
+    require_eq!(
+        instruction::get_stack_height(),
+        instruction::TRANSACTION_LEVEL_STACK_HEIGHT,
+        ErrorCode::CpiNotAllowed
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::states::{POSITION_DELEGATE_SCOPE_COLLECT, POSITION_DELEGATE_SCOPE_DECREASE};
+    use anchor_spl::token_2022::spl_token_2022::state::AccountState;
+
+    fn position_account(owner: Pubkey, mint: Pubkey) -> TokenAccountState {
+        TokenAccountState {
+            mint,
+            owner,
+            amount: 1,
+            state: AccountState::Initialized,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn owner_is_authorized() {
+        let owner = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let account = position_account(owner, mint);
+        assert!(is_authorized_for_token_state(&owner, &account, &mint));
+        assert!(!is_authorized_for_token_state(
+            &Pubkey::new_unique(),
+            &account,
+            &mint
+        ));
+    }
+
+    #[test]
+    fn wrong_mint_is_rejected() {
+        let owner = Pubkey::new_unique();
+        let account = position_account(owner, Pubkey::new_unique());
+        assert!(!is_authorized_for_token_state(
+            &owner,
+            &account,
+            &Pubkey::new_unique()
+        ));
+    }
+
+    #[test]
+    fn empty_account_is_rejected() {
+        let owner = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let mut account = position_account(owner, mint);
+        account.amount = 0;
+        assert!(!is_authorized_for_token_state(&owner, &account, &mint));
+    }
+
+    #[test]
+    fn frozen_account_is_rejected() {
+        let owner = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let mut account = position_account(owner, mint);
+        account.state = AccountState::Frozen;
+        assert!(!is_authorized_for_token_state(&owner, &account, &mint));
+    }
+
+    #[test]
+    fn approved_delegate_is_authorized() {
+        let delegate = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let mut account = position_account(Pubkey::new_unique(), mint);
+        account.delegate = COption::Some(delegate);
+        account.delegated_amount = 1;
+        assert!(is_authorized_for_token_state(&delegate, &account, &mint));
+    }
+
+    #[test]
+    fn spent_delegate_is_rejected() {
+        let delegate = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let mut account = position_account(Pubkey::new_unique(), mint);
+        account.delegate = COption::Some(delegate);
+        account.delegated_amount = 0;
+        assert!(!is_authorized_for_token_state(&delegate, &account, &mint));
+    }
+
+    #[test]
+    fn revoked_delegate_is_rejected() {
+        let delegate = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let mut account = position_account(Pubkey::new_unique(), mint);
+        account.delegate = COption::None;
+        account.delegated_amount = 1;
+        assert!(!is_authorized_for_token_state(&delegate, &account, &mint));
+    }
+
+    #[test]
+    fn other_delegate_is_rejected() {
+        let mint = Pubkey::new_unique();
+        let mut account = position_account(Pubkey::new_unique(), mint);
+        account.delegate = COption::Some(Pubkey::new_unique());
+        account.delegated_amount = 1;
+        assert!(!is_authorized_for_token_state(
+            &Pubkey::new_unique(),
+            &account,
+            &mint
+        ));
+    }
+
+    #[test]
+    fn position_operator_is_authorized_within_scope() {
+        let (owner, operator) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mint = Pubkey::new_unique();
+        let mut account = position_account(owner, mint);
+        let position_delegate = PositionDelegate {
+            owner,
+            operator,
+            scope: POSITION_DELEGATE_SCOPE_COLLECT,
+            ..Default::default()
+        };
+        let is_authorized = |account: &TokenAccountState, scope| {
+            is_authorized_for_position_state(
+                &operator,
+                account,
+                &mint,
+                Some(&position_delegate),
+                scope,
+            )
+        };
+        assert!(is_authorized(&account, POSITION_DELEGATE_SCOPE_COLLECT));
+        assert!(!is_authorized(&account, POSITION_DELEGATE_SCOPE_DECREASE));
+        assert!(!is_authorized_for_position_state(
+            &operator,
+            &account,
+            &mint,
+            None,
+            POSITION_DELEGATE_SCOPE_COLLECT
+        ));
+        // the owner keeps its own rights without a delegation
+        assert!(is_authorized_for_position_state(
+            &owner,
+            &account,
+            &mint,
+            None,
+            POSITION_DELEGATE_SCOPE_DECREASE
+        ));
+
+        // the delegation stops applying once the nft leaves the owner
+        account.owner = Pubkey::new_unique();
+        assert!(!is_authorized(&account, POSITION_DELEGATE_SCOPE_COLLECT));
+    }
+
+    #[test]
+    fn payouts_to_other_wallets_are_rejected() {
+        use anchor_lang::solana_program::program_pack::Pack;
+
+        let (holder, pool_state_key) = (Pubkey::new_unique(), Pubkey::new_unique());
+        // a reward vault of the pool, a reward recipient of the holder and one of someone else
+        let owners = [pool_state_key, holder, Pubkey::new_unique()];
+        let keys: Vec<Pubkey> = owners.iter().map(|_| Pubkey::new_unique()).collect();
+        let mut lamports = vec![0u64; owners.len()];
+        let mut data: Vec<Vec<u8>> = owners
+            .iter()
+            .map(|owner| {
+                let mut data = vec![0u8; TokenAccountState::LEN];
+                TokenAccountState::pack(position_account(*owner, Pubkey::new_unique()), &mut data)
+                    .unwrap();
+                data
+            })
+            .collect();
+        let token_program = anchor_spl::token::ID;
+        let account_infos: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .map(|((key, lamports), data)| {
+                AccountInfo::new(key, false, true, lamports, data, &token_program, false, 0)
+            })
+            .collect();
+
+        assert!(check_payouts_to_holder(&holder, &pool_state_key, &account_infos[..2]).is_ok());
+        assert!(check_payouts_to_holder(&holder, &pool_state_key, &account_infos).is_err());
+    }
+}
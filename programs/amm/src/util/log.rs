@@ -0,0 +1,20 @@
+/// Logs a tag followed by machine-parsable `key=value` pairs, e.g.
+/// `log_kv!("swap_step", tick = state.tick, amount_in = step.amount_in)` logs
+/// `swap_step tick=-10 amount_in=100`.
+///
+/// Values are copied before formatting so packed account fields can be logged directly.
+/// Nothing is logged unless the `verbose-logs` feature is enabled.
+#[macro_export]
+macro_rules! log_kv {
+    ($tag:expr $(, $key:ident = $value:expr)* $(,)?) => {{
+        #[cfg(feature = "verbose-logs")]
+        anchor_lang::prelude::msg!(
+            concat!($tag $(, " ", stringify!($key), "={}")*)
+            $(, { $value })*
+        );
+        #[cfg(not(feature = "verbose-logs"))]
+        {
+            $(let _ = $value;)*
+        }
+    }};
+}
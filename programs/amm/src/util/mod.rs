@@ -6,3 +6,8 @@ pub use system::*;
 
 pub mod account_load;
 pub use account_load::*;
+
+pub mod access_control;
+pub use access_control::*;
+
+pub mod log;
@@ -55,6 +55,26 @@ pub fn create_or_allocate_account<'a>(
     Ok(())
 }
 
+/// Fails once the current unix timestamp is past `deadline`
+pub fn check_deadline(deadline: u64) -> Result<()> {
+    require_gte!(
+        deadline,
+        Clock::get()?.unix_timestamp as u64,
+        crate::error::ErrorCode::TransactionTooOld
+    );
+    Ok(())
+}
+
+/// Fails once the current slot is past `max_slot`
+pub fn check_max_slot(max_slot: u64) -> Result<()> {
+    require_gte!(
+        max_slot,
+        Clock::get()?.slot,
+        crate::error::ErrorCode::TransactionTooOld
+    );
+    Ok(())
+}
+
 #[cfg(not(test))]
 pub fn get_recent_epoch() -> Result<u64> {
     Ok(Clock::get()?.epoch)
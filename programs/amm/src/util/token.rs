@@ -3,9 +3,9 @@ use crate::error::ErrorCode;
 use crate::states::*;
 use anchor_lang::{
     prelude::*,
-    system_program::{create_account, CreateAccount},
+    system_program::{create_account, transfer, CreateAccount, Transfer},
 };
-use anchor_spl::token::{self, Token};
+use anchor_spl::token::{self, spl_token::native_mint, Token, TokenAccount};
 use anchor_spl::token_2022::{
     self,
     spl_token_2022::{
@@ -21,6 +21,17 @@ use anchor_spl::token_2022::{
 use anchor_spl::token_interface::{initialize_mint2, InitializeMint2, Mint};
 use std::collections::HashSet;
 
+/// The token 2022 mint extensions a pool or reward mint may have, anything else such as a
+/// transfer hook or confidential transfers would let the mint move or hide vault tokens
+/// outside of the pool's accounting
+const SUPPORTED_MINT_EXTENSIONS: [ExtensionType; 5] = [
+    ExtensionType::TransferFeeConfig,
+    ExtensionType::MetadataPointer,
+    ExtensionType::TokenMetadata,
+    ExtensionType::InterestBearingConfig,
+    ExtensionType::MintCloseAuthority,
+];
+
 const MINT_WHITELIST: [&'static str; 5] = [
     "HVbpJAQGNpkgBaYBZQBR1t7yFdvaYVp2vCQQfKKEN4tM",
     "Crn4x1Y2HUKko7ox2EZMT6N2t2ZyH7eKtwkBGVnhEq1g",
@@ -54,7 +65,8 @@ pub fn transfer_from_user_to_pool_vault<'info>(
     let from_token_info = from.to_account_info();
     match (mint, token_program_2022) {
         (Some(mint), Some(token_program_2022)) => {
-            if from_token_info.owner == token_program_2022.key {
+            // the mint decides which token program performs the transfer
+            if mint.to_account_info().owner == token_program_2022.key {
                 token_program_info = token_program_2022.to_account_info()
             }
             token_2022::transfer_checked(
@@ -97,11 +109,17 @@ pub fn transfer_from_pool_vault_to_user<'info>(
     if amount == 0 {
         return Ok(());
     }
+    super::verify_vault(
+        &pool_state_loader.key(),
+        &*pool_state_loader.load()?,
+        from_vault,
+    )?;
     let mut token_program_info = token_program.to_account_info();
     let from_vault_info = from_vault.to_account_info();
     match (mint, token_program_2022) {
         (Some(mint), Some(token_program_2022)) => {
-            if from_vault_info.owner == token_program_2022.key {
+            // the mint decides which token program performs the transfer
+            if mint.to_account_info().owner == token_program_2022.key {
                 token_program_info = token_program_2022.to_account_info()
             }
             token_2022::transfer_checked(
@@ -134,6 +152,82 @@ pub fn transfer_from_pool_vault_to_user<'info>(
     }
 }
 
+pub fn transfer_from_distributor_vault_to_user<'info>(
+    fee_distributor: &Account<'info, FeeDistributorState>,
+    from_vault: &AccountInfo<'info>,
+    to: &AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    token_program: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    token_2022::transfer_checked(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            token_2022::TransferChecked {
+                from: from_vault.to_account_info(),
+                to: to.to_account_info(),
+                authority: fee_distributor.to_account_info(),
+                mint: mint.to_account_info(),
+            },
+            &[&fee_distributor.seeds()],
+        ),
+        amount,
+        mint.decimals,
+    )
+}
+
+pub fn transfer_position_nft<'info>(
+    authority: &AccountInfo<'info>,
+    from: &AccountInfo<'info>,
+    to: &AccountInfo<'info>,
+    position_nft_mint: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    signers_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    token_2022::transfer_checked(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            token_2022::TransferChecked {
+                from: from.to_account_info(),
+                to: to.to_account_info(),
+                authority: authority.to_account_info(),
+                mint: position_nft_mint.to_account_info(),
+            },
+            signers_seeds,
+        ),
+        1,
+        0,
+    )
+}
+
+pub fn burn_from_vault<'info>(
+    authority: &AccountInfo<'info>,
+    vault: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    signers_seeds: &[&[&[u8]]],
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    token_2022::burn(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            token_2022::Burn {
+                mint: mint.to_account_info(),
+                from: vault.to_account_info(),
+                authority: authority.to_account_info(),
+            },
+            signers_seeds,
+        ),
+        amount,
+    )
+}
+
 pub fn close_spl_account<'a, 'b, 'c, 'info>(
     owner: &AccountInfo<'info>,
     destination: &AccountInfo<'info>,
@@ -152,6 +246,67 @@ pub fn close_spl_account<'a, 'b, 'c, 'info>(
     ))
 }
 
+/// Whether `token_account` holds wrapped SOL on behalf of `owner`
+fn is_native_account_of(owner: &AccountInfo, token_account: &Account<TokenAccount>) -> bool {
+    token_account.mint == native_mint::id() && token_account.owner == owner.key()
+}
+
+/// Tops up a native mint token account of `owner` with lamports from `owner` until it holds
+/// `amount` wrapped SOL. Other token accounts are left untouched. The account is not created
+/// here, the client has to create it earlier in the transaction, e.g. the owner's associated
+/// token account of the native mint created idempotently
+pub fn wrap_sol<'info>(
+    owner: &AccountInfo<'info>,
+    token_account: &Account<'info, TokenAccount>,
+    token_program: &Program<'info, Token>,
+    system_program: &Program<'info, System>,
+    amount: u64,
+) -> Result<()> {
+    if !is_native_account_of(owner, token_account) {
+        return Ok(());
+    }
+    let lamports = amount.saturating_sub(token_account.amount);
+    if lamports == 0 {
+        return Ok(());
+    }
+    transfer(
+        CpiContext::new(
+            system_program.to_account_info(),
+            Transfer {
+                from: owner.clone(),
+                to: token_account.to_account_info(),
+            },
+        ),
+        lamports,
+    )?;
+    token::sync_native(CpiContext::new(
+        token_program.to_account_info(),
+        token::SyncNative {
+            account: token_account.to_account_info(),
+        },
+    ))
+}
+
+/// Closes a native mint token account of `owner`, returning its wrapped SOL and rent to `owner`.
+/// Other token accounts are left untouched. Together with `wrap_sol`, this lets a client use a
+/// native mint account created in the same transaction as a temporary one
+pub fn unwrap_sol<'info>(
+    owner: &AccountInfo<'info>,
+    token_account: &Account<'info, TokenAccount>,
+    token_program: &Program<'info, Token>,
+) -> Result<()> {
+    if !is_native_account_of(owner, token_account) {
+        return Ok(());
+    }
+    close_spl_account(
+        owner,
+        owner,
+        &token_account.to_account_info(),
+        &token_program.to_account_info(),
+        &[],
+    )
+}
+
 pub fn burn<'a, 'b, 'c, 'info>(
     owner: &Signer<'info>,
     mint: &AccountInfo<'info>,
@@ -176,33 +331,29 @@ pub fn burn<'a, 'b, 'c, 'info>(
     )
 }
 
+/// The transfer fee config of a token 2022 mint, None if transfers of the mint are not charged
+pub fn get_transfer_fee_config(mint_info: &AccountInfo) -> Result<Option<TransferFeeConfig>> {
+    if *mint_info.owner == Token::id() {
+        return Ok(None);
+    }
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    Ok(mint.get_extension::<TransferFeeConfig>().ok().copied())
+}
+
 /// Calculate the fee for output amount
 pub fn get_transfer_inverse_fee(
     mint_account: Box<InterfaceAccount<Mint>>,
     post_fee_amount: u64,
 ) -> Result<u64> {
-    let mint_info = mint_account.to_account_info();
-    if *mint_info.owner == Token::id() {
-        return Ok(0);
+    match get_transfer_fee_config(&mint_account.to_account_info())? {
+        Some(transfer_fee_config) => Ok(transfer_inverse_fee(
+            &transfer_fee_config,
+            get_recent_epoch()?,
+            post_fee_amount,
+        )),
+        None => Ok(0),
     }
-    let mint_data = mint_info.try_borrow_data()?;
-    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
-
-    let fee = if let Ok(transfer_fee_config) = mint.get_extension::<TransferFeeConfig>() {
-        let epoch = get_recent_epoch()?;
-
-        let transfer_fee = transfer_fee_config.get_epoch_fee(epoch);
-        if u16::from(transfer_fee.transfer_fee_basis_points) == MAX_FEE_BASIS_POINTS {
-            u64::from(transfer_fee.maximum_fee)
-        } else {
-            transfer_fee_config
-                .calculate_inverse_epoch_fee(epoch, post_fee_amount)
-                .unwrap()
-        }
-    } else {
-        0
-    };
-    Ok(fee)
 }
 
 /// Calculate the fee for input amount
@@ -210,21 +361,36 @@ pub fn get_transfer_fee(
     mint_account: Box<InterfaceAccount<Mint>>,
     pre_fee_amount: u64,
 ) -> Result<u64> {
-    let mint_info = mint_account.to_account_info();
-    if *mint_info.owner == Token::id() {
-        return Ok(0);
+    match get_transfer_fee_config(&mint_account.to_account_info())? {
+        Some(transfer_fee_config) => Ok(transfer_fee_config
+            .calculate_epoch_fee(get_recent_epoch()?, pre_fee_amount)
+            .unwrap()),
+        None => Ok(0),
     }
-    let mint_data = mint_info.try_borrow_data()?;
-    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+}
 
-    let fee = if let Ok(transfer_fee_config) = mint.get_extension::<TransferFeeConfig>() {
+/// The fee to add to `post_fee_amount` so that `post_fee_amount` is received. A mint charging
+/// 100% only ever takes its maximum fee
+fn transfer_inverse_fee(
+    transfer_fee_config: &TransferFeeConfig,
+    epoch: u64,
+    post_fee_amount: u64,
+) -> u64 {
+    let transfer_fee = transfer_fee_config.get_epoch_fee(epoch);
+    if u16::from(transfer_fee.transfer_fee_basis_points) == MAX_FEE_BASIS_POINTS {
+        u64::from(transfer_fee.maximum_fee)
+    } else {
         transfer_fee_config
-            .calculate_epoch_fee(get_recent_epoch()?, pre_fee_amount)
+            .calculate_inverse_epoch_fee(epoch, post_fee_amount)
             .unwrap()
-    } else {
-        0
-    };
-    Ok(fee)
+    }
+}
+
+/// Read the amount of a spl token or token 2022 account
+pub fn get_token_account_amount(token_account: &AccountInfo) -> Result<u64> {
+    let data = token_account.try_borrow_data()?;
+    let account = StateWithExtensions::<spl_token_2022::state::Account>::unpack(&data)?;
+    Ok(account.base.amount)
 }
 
 pub fn is_supported_mint(mint_account: &InterfaceAccount<Mint>) -> Result<bool> {
@@ -238,18 +404,13 @@ pub fn is_supported_mint(mint_account: &InterfaceAccount<Mint>) -> Result<bool>
     }
     let mint_data = mint_info.try_borrow_data()?;
     let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
-    let extensions = mint.get_extension_types()?;
-    for e in extensions {
-        if e != ExtensionType::TransferFeeConfig
-            && e != ExtensionType::MetadataPointer
-            && e != ExtensionType::TokenMetadata
-            && e != ExtensionType::InterestBearingConfig
-            && e != ExtensionType::MintCloseAuthority
-        {
-            return Ok(false);
-        }
-    }
-    Ok(true)
+    Ok(are_supported_mint_extensions(&mint.get_extension_types()?))
+}
+
+fn are_supported_mint_extensions(extensions: &[ExtensionType]) -> bool {
+    extensions
+        .iter()
+        .all(|extension| SUPPORTED_MINT_EXTENSIONS.contains(extension))
 }
 
 pub fn create_position_nft_mint_with_extensions<'info>(
@@ -270,8 +431,30 @@ pub fn create_position_nft_mint_with_extensions<'info>(
     } else {
         [ExtensionType::MintCloseAuthority].to_vec()
     };
+    create_mint_with_extensions(
+        payer,
+        position_nft_mint,
+        mint_authority,
+        mint_close_authority,
+        system_program,
+        token_2022_program,
+        &extensions,
+    )
+}
+
+/// Creates and initializes a token 2022 mint with zero decimals and the given extensions,
+/// only the extensions used by position nfts and their receipts are supported
+pub fn create_mint_with_extensions<'info>(
+    payer: &Signer<'info>,
+    mint: &AccountInfo<'info>,
+    mint_authority: &AccountInfo<'info>,
+    mint_close_authority: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    token_2022_program: &Program<'info, Token2022>,
+    extensions: &[ExtensionType],
+) -> Result<()> {
     let space =
-        ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&extensions)?;
+        ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(extensions)?;
 
     let lamports = Rent::get()?.minimum_balance(space);
 
@@ -281,7 +464,7 @@ pub fn create_position_nft_mint_with_extensions<'info>(
             system_program.to_account_info(),
             CreateAccount {
                 from: payer.to_account_info(),
-                to: position_nft_mint.to_account_info(),
+                to: mint.to_account_info(),
             },
         ),
         lamports,
@@ -291,34 +474,38 @@ pub fn create_position_nft_mint_with_extensions<'info>(
 
     // initialize token extensions
     for e in extensions {
-        match e {
+        match *e {
             ExtensionType::MetadataPointer => {
                 let ix = metadata_pointer::instruction::initialize(
                     token_2022_program.key,
-                    position_nft_mint.key,
+                    mint.key,
                     None,
-                    Some(position_nft_mint.key()),
+                    Some(mint.key()),
                 )?;
                 solana_program::program::invoke(
                     &ix,
-                    &[
-                        token_2022_program.to_account_info(),
-                        position_nft_mint.to_account_info(),
-                    ],
+                    &[token_2022_program.to_account_info(), mint.to_account_info()],
                 )?;
             }
             ExtensionType::MintCloseAuthority => {
                 let ix = spl_token_2022::instruction::initialize_mint_close_authority(
                     token_2022_program.key,
-                    position_nft_mint.key,
+                    mint.key,
                     Some(mint_close_authority.key),
                 )?;
                 solana_program::program::invoke(
                     &ix,
-                    &[
-                        token_2022_program.to_account_info(),
-                        position_nft_mint.to_account_info(),
-                    ],
+                    &[token_2022_program.to_account_info(), mint.to_account_info()],
+                )?;
+            }
+            ExtensionType::NonTransferable => {
+                let ix = spl_token_2022::instruction::initialize_non_transferable_mint(
+                    token_2022_program.key,
+                    mint.key,
+                )?;
+                solana_program::program::invoke(
+                    &ix,
+                    &[token_2022_program.to_account_info(), mint.to_account_info()],
                 )?;
             }
             _ => {
@@ -332,7 +519,7 @@ pub fn create_position_nft_mint_with_extensions<'info>(
         CpiContext::new(
             token_2022_program.to_account_info(),
             InitializeMint2 {
-                mint: position_nft_mint.to_account_info(),
+                mint: mint.to_account_info(),
             },
         ),
         0,
@@ -340,3 +527,64 @@ pub fn create_position_nft_mint_with_extensions<'info>(
         None,
     )
 }
+
+#[cfg(test)]
+mod token_test {
+    use super::*;
+    use anchor_spl::token_2022::spl_token_2022::extension::transfer_fee::TransferFee;
+
+    fn transfer_fee_config(transfer_fee_basis_points: u16, maximum_fee: u64) -> TransferFeeConfig {
+        let transfer_fee = TransferFee {
+            epoch: 0.into(),
+            maximum_fee: maximum_fee.into(),
+            transfer_fee_basis_points: transfer_fee_basis_points.into(),
+        };
+        TransferFeeConfig {
+            older_transfer_fee: transfer_fee,
+            newer_transfer_fee: transfer_fee,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn supported_mint_extensions_test() {
+        assert!(are_supported_mint_extensions(&[]));
+        assert!(are_supported_mint_extensions(&[
+            ExtensionType::TransferFeeConfig,
+            ExtensionType::MetadataPointer,
+            ExtensionType::TokenMetadata,
+        ]));
+        for extension in [
+            ExtensionType::TransferHook,
+            ExtensionType::ConfidentialTransferMint,
+            ExtensionType::PermanentDelegate,
+            ExtensionType::NonTransferable,
+            ExtensionType::DefaultAccountState,
+        ] {
+            assert!(!are_supported_mint_extensions(&[
+                ExtensionType::TransferFeeConfig,
+                extension
+            ]));
+        }
+    }
+
+    #[test]
+    fn transfer_inverse_fee_test() {
+        // 1%, the gross amount nets exactly the wanted amount
+        let config = transfer_fee_config(100, u64::MAX);
+        for post_fee_amount in [1, 99, 100, 12345, 1_000_000] {
+            let fee = transfer_inverse_fee(&config, 0, post_fee_amount);
+            let pre_fee_amount = post_fee_amount + fee;
+            assert_eq!(
+                pre_fee_amount - config.calculate_epoch_fee(0, pre_fee_amount).unwrap(),
+                post_fee_amount
+            );
+        }
+        // capped by the maximum fee
+        let config = transfer_fee_config(100, 5);
+        assert_eq!(transfer_inverse_fee(&config, 0, 1_000_000), 5);
+        // 100% takes the maximum fee whatever the amount
+        let config = transfer_fee_config(MAX_FEE_BASIS_POINTS, 7);
+        assert_eq!(transfer_inverse_fee(&config, 0, 1_000_000), 7);
+    }
+}